@@ -0,0 +1,122 @@
+use ktensor::TensorError;
+
+/// Error of the fallible `try_*` functions of kdezero
+/// 
+/// The panicking functions panic with the message of this error.
+/// 
+/// # Variants
+/// 
+/// * `InvalidVariableId` - The variable is not in the graph
+/// * `InvalidFunctionId` - The function is not in the graph
+/// * `InvalidInputCount` - The function got the wrong number of inputs
+/// * `InvalidInputId` - An input of the function is not in the graph
+/// * `InvalidInput` - The tensor operation failed on an input of the function
+/// * `InvalidLabel` - A class label is not an integer in the range of the classes
+/// * `InvalidOutputCount` - The function has the wrong number of outputs
+/// * `InputsNotFound` - The inputs of the function were not recorded by forward
+/// * `OutputsNotFound` - The outputs of the function were not recorded by forward
+/// * `GradNotFound` - An output of the function has no gradient in backward
+/// * `GenerationOverflow` - The generation of the function overflows
+/// * `BackwardFailed` - The backward of the function failed
+#[derive(Debug, Clone, PartialEq)]
+pub enum KdezeroError {
+    InvalidVariableId { id: usize },
+    InvalidFunctionId { id: usize },
+    InvalidInputCount { function: String, expected: usize, actual: usize },
+    InvalidInputId { function: String, index: usize, id: usize },
+    InvalidInput { function: String, index: usize, shape: Vec<usize>, error: TensorError },
+    InvalidLabel { function: String, index: usize, label: f64, classes: usize },
+    InvalidOutputCount { function: String, expected: usize, actual: usize },
+    InputsNotFound { function: String },
+    OutputsNotFound { function: String },
+    GradNotFound { function: String, id: usize },
+    GenerationOverflow { function: String },
+    BackwardFailed { function: String, error: Box<KdezeroError> },
+}
+
+impl KdezeroError {
+    /// Create an `InvalidInput` error.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `function` - Function name
+    /// * `index` - Index of the input in the inputs of the function
+    /// * `shape` - Shape of the input
+    /// * `error` - Error of the tensor operation
+    pub fn invalid_input(function: &str, index: usize, shape: &[usize], error: TensorError) -> Self {
+        KdezeroError::InvalidInput { function: function.to_string(), index, shape: shape.to_vec(), error }
+    }
+}
+
+impl std::fmt::Display for KdezeroError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            KdezeroError::InvalidVariableId { id } =>
+                write!(f, "Invalid variable id: {}", id),
+            KdezeroError::InvalidFunctionId { id } =>
+                write!(f, "Invalid function id: {}", id),
+            KdezeroError::InvalidInputCount { function, expected, actual } =>
+                write!(f, "{} function must have {} inputs, but got {} inputs.", function, expected, actual),
+            KdezeroError::InvalidInputId { function, index, id } =>
+                write!(f, "Invalid variable id: {} at input {} of {} function", id, index, function),
+            KdezeroError::InvalidInput { function, index, shape, error } =>
+                write!(f, "{} at input {} with the shape {:?} of {} function", error, index, shape, function),
+            KdezeroError::InvalidLabel { function, index, label, classes } =>
+                write!(f, "Invalid label: {} at index {} is not a class of {} classes of {} function", label, index, classes, function),
+            KdezeroError::InvalidOutputCount { function, expected, actual } =>
+                write!(f, "{} function must have {} outputs, but got {} outputs.", function, expected, actual),
+            KdezeroError::InputsNotFound { function } =>
+                write!(f, "Input not found: {} function has not recorded its inputs", function),
+            KdezeroError::OutputsNotFound { function } =>
+                write!(f, "Output not found: {} function has not recorded its outputs", function),
+            KdezeroError::GradNotFound { function, id } =>
+                write!(f, "Output grad id not found: variable {} of {} function", id, function),
+            KdezeroError::GenerationOverflow { function } =>
+                write!(f, "Generation overflow in {} function", function),
+            KdezeroError::BackwardFailed { function, error } =>
+                write!(f, "{} in the backward of {} function", error, function),
+        }
+    }
+}
+
+impl std::error::Error for KdezeroError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            KdezeroError::InvalidInput { error, .. } => Some(error),
+            KdezeroError::BackwardFailed { error, .. } => Some(error.as_ref()),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::error::Error;
+
+    #[test]
+    fn display_normal() {
+        let error = KdezeroError::InvalidInputCount { function: "Mul".to_string(), expected: 2, actual: 1 };
+        assert_eq!(error.to_string(), "Mul function must have 2 inputs, but got 1 inputs.");
+        let error = KdezeroError::invalid_input("Reshape", 0, &[2, 3],
+            TensorError::SizeMismatch { size: 6, shape: vec![4] });
+        assert_eq!(error.to_string(),
+            "Shape mismatch: 6 elements cannot have the shape [4] at input 0 with the shape [2, 3] of Reshape function");
+        let error = KdezeroError::InvalidLabel { function: "SoftmaxCrossEntropy".to_string(), index: 1, label: 3.0, classes: 3 };
+        assert_eq!(error.to_string(),
+            "Invalid label: 3 at index 1 is not a class of 3 classes of SoftmaxCrossEntropy function");
+    }
+
+    #[test]
+    fn source_normal() {
+        let tensor_error = TensorError::RepeatedAxis { axis: 0 };
+        let error = KdezeroError::invalid_input("Permute", 0, &[2, 2], tensor_error.clone());
+        assert_eq!(error.source().unwrap().to_string(), tensor_error.to_string());
+        assert!(KdezeroError::InvalidVariableId { id: 0 }.source().is_none());
+
+        let error = KdezeroError::BackwardFailed { function: "Exp".to_string(),
+            error: Box::new(KdezeroError::InvalidVariableId { id: 3 }) };
+        assert_eq!(error.to_string(), "Invalid variable id: 3 in the backward of Exp function");
+        assert_eq!(error.source().unwrap().to_string(), "Invalid variable id: 3");
+    }
+}
@@ -5,7 +5,10 @@ pub mod function;
 pub use function_table::FunctionTable;
 
 use std::any::Any;
+use ktensor::{Tensor, TensorError};
 use crate::variable::VariableTable;
+use crate::graph::Graph;
+use crate::error::KdezeroError;
 
 /// Function information
 /// 
@@ -66,6 +69,11 @@ impl Function {
         self.info.id
     }
 
+    /// Get the function name.
+    pub fn get_name(&self) -> &str {
+        self.function.name()
+    }
+
     /// Get the function inputs.
     pub fn get_inputs(&self) -> Option<&Vec<usize>> {
         self.info.inputs.as_ref()
@@ -76,6 +84,24 @@ impl Function {
         self.info.outputs.as_ref()
     }
 
+    /// Get the function inputs for backward.
+    /// 
+    /// # Errors
+    /// 
+    /// * `InputsNotFound` - If forward has not recorded the inputs
+    pub fn try_get_inputs(&self) -> Result<&Vec<usize>, KdezeroError> {
+        self.info.inputs.as_ref().ok_or_else(|| KdezeroError::InputsNotFound { function: self.get_name().to_string() })
+    }
+
+    /// Get the function outputs for backward.
+    /// 
+    /// # Errors
+    /// 
+    /// * `OutputsNotFound` - If forward has not recorded the outputs
+    pub fn try_get_outputs(&self) -> Result<&Vec<usize>, KdezeroError> {
+        self.info.outputs.as_ref().ok_or_else(|| KdezeroError::OutputsNotFound { function: self.get_name().to_string() })
+    }
+
     /// Set the function outputs.
     pub fn set_outputs(&mut self, outputs: Vec<usize>) {
         self.info.outputs = Some(outputs);
@@ -117,26 +143,54 @@ impl Function {
     /// * `Inputs are empty` - If the inputs are empty
     /// * `Generation overflow` - If the generation overflows
     pub fn forward(&mut self, inputs: Vec<usize>, variable_table: &mut VariableTable, no_grad: bool) -> Vec<usize> {
-        let outputs = self.function.forward(&self.info, &inputs, variable_table);
+        self.try_forward(inputs, variable_table, no_grad).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Fallible forward
+    /// 
+    /// # Arguments
+    /// 
+    /// * `inputs` - Input variable IDs
+    /// * `variable_table` - Variable table
+    /// * `no_grad` - Whether to calculate gradients
+    /// 
+    /// # Returns
+    /// 
+    /// * `outputs` - Output variable IDs
+    /// 
+    /// # Errors
+    /// 
+    /// * `InvalidInputId` - If an input variable is not in the variable table
+    /// * `InvalidInputCount` - If the number of inputs is wrong, including no inputs
+    /// * `InvalidInput` - If the function cannot be applied to the shape of an input
+    /// * `GenerationOverflow` - If the generation overflows
+    pub fn try_forward(&mut self, inputs: Vec<usize>, variable_table: &mut VariableTable, no_grad: bool) -> Result<Vec<usize>, KdezeroError> {
+        let outputs = self.function.try_forward(&self.info, &inputs, variable_table)?;
         if !no_grad {
+            let name = self.function.name();
+            let mut generation = None;
+            for (index, &id) in inputs.iter().enumerate() {
+                let variable = variable_table.get(id)
+                    .ok_or_else(|| KdezeroError::InvalidInputId { function: name.to_string(), index, id })?;
+                generation = generation.max(Some(variable.get_generation()));
+            }
+            let generation = generation
+                .ok_or_else(|| KdezeroError::InvalidInputCount { function: name.to_string(), expected: 1, actual: 0 })?;
+            let new_generation = generation.checked_add(1)
+                .ok_or_else(|| KdezeroError::GenerationOverflow { function: name.to_string() })?;
             self.info.inputs = Some(inputs);
             self.info.outputs = Some(outputs.clone());
-            self.info.generation = self.info.inputs.as_ref().unwrap()
-                .iter().map(
-                    |&id| variable_table.get(id).expect("Invalid variable id")
-                    .get_generation())
-                .max().expect("Inputs are empty");
-            let new_generation = self.info.generation.checked_add(1).expect("Generation overflow");
+            self.info.generation = generation;
             for output in &outputs {
                 variable_table.get_mut(*output).expect("Invalid variable id")
                     .set_creator(self.info.id, new_generation);
             }
         }
-        outputs
+        Ok(outputs)
     }
 
     /// Get the backward function.
-    pub fn get_backward(&self) -> fn(usize, &mut FunctionTable, &mut VariableTable) -> Vec<usize> {
+    pub fn get_backward(&self) -> fn(usize, &mut Graph) -> Result<Vec<usize>, KdezeroError> {
         self.function.get_backward()
     }
 
@@ -162,5 +216,102 @@ pub trait FunctionContents {
     fn as_any(&self) -> &dyn Any;
     fn name(&self) -> &str;
     fn forward(&self, info: &FunctionInfo, inputs: &Vec<usize>, variable_table: &mut VariableTable) -> Vec<usize>;
-    fn get_backward(&self) -> fn(usize, &mut FunctionTable, &mut VariableTable) -> Vec<usize>;
+
+    /// Fallible forward
+    /// 
+    /// The default is for functions with one input that cannot fail on its shape.
+    /// It checks that there is exactly one input in the variable table and calls `forward`.
+    /// Functions with another number of inputs or that can fail on the shapes of their inputs
+    /// override it and implement `forward` by it.
+    /// 
+    /// # Errors
+    /// 
+    /// * `InvalidInputCount` - If the number of inputs is not 1
+    /// * `InvalidInputId` - If an input variable is not in the variable table
+    fn try_forward(&self, info: &FunctionInfo, inputs: &Vec<usize>, variable_table: &mut VariableTable) -> Result<Vec<usize>, KdezeroError> {
+        get_inputs_f64::<1>(self.name(), inputs, variable_table)?;
+        Ok(self.forward(info, inputs, variable_table))
+    }
+
+    /// Get the backward function.
+    /// 
+    /// The backward function takes the function ID and the graph,
+    /// accumulates the gradients of the inputs
+    /// and returns the input variable IDs whose gradients were updated.
+    /// 
+    /// # Errors
+    /// 
+    /// * `InvalidFunctionId` - If the function is not in the graph
+    /// * `InputsNotFound` - If the function has not recorded its inputs
+    /// * `OutputsNotFound` - If the function has not recorded its outputs
+    /// * `InvalidInputCount` - If the function has the wrong number of inputs
+    /// * `InvalidOutputCount` - If the function has the wrong number of outputs
+    /// * `GradNotFound` - If an output of the function has no gradient
+    /// * `InvalidVariableId` - If an input of the function has been released
+    /// * `InvalidInput` - If the gradient cannot be computed from the shapes of the inputs and the gradients
+    fn get_backward(&self) -> fn(usize, &mut Graph) -> Result<Vec<usize>, KdezeroError>;
+}
+
+/// Get the f64 contents of the inputs of a function.
+/// 
+/// # Arguments
+/// 
+/// * `name` - Function name
+/// * `inputs` - Input variable IDs
+/// * `variable_table` - Variable table
+/// 
+/// # Errors
+/// 
+/// * `InvalidInputCount` - If the number of inputs is not `N`
+/// * `InvalidInputId` - If an input variable is not in the variable table
+/// * `InvalidInput` - If an input variable does not have f64 contents
+pub(crate) fn get_inputs_f64<'a, const N: usize>(name: &str, inputs: &[usize], variable_table: &'a VariableTable) -> Result<[&'a Tensor<f64>; N], KdezeroError> {
+    if inputs.len() != N {
+        return Err(KdezeroError::InvalidInputCount { function: name.to_string(), expected: N, actual: inputs.len() });
+    }
+    let mut contents = Vec::with_capacity(N);
+    for (index, &id) in inputs.iter().enumerate() {
+        contents.push(get_input_f64(name, index, id, variable_table)?);
+    }
+    Ok(contents.try_into().unwrap_or_else(|_| unreachable!()))
+}
+
+/// Get the f64 contents of any number of inputs of a function.
+/// 
+/// # Arguments
+/// 
+/// * `name` - Function name
+/// * `inputs` - Input variable IDs
+/// * `variable_table` - Variable table
+/// 
+/// # Errors
+/// 
+/// * `InvalidInputCount` - If there are no inputs
+/// * `InvalidInputId` - If an input variable is not in the variable table
+/// * `InvalidInput` - If an input variable does not have f64 contents
+pub(crate) fn get_all_inputs_f64<'a>(name: &str, inputs: &[usize], variable_table: &'a VariableTable) -> Result<Vec<&'a Tensor<f64>>, KdezeroError> {
+    if inputs.is_empty() {
+        return Err(KdezeroError::InvalidInputCount { function: name.to_string(), expected: 1, actual: 0 });
+    }
+    inputs.iter().enumerate().map(|(index, &id)| get_input_f64(name, index, id, variable_table)).collect()
+}
+
+/// Get the f64 contents of an input of a function.
+/// 
+/// # Arguments
+/// 
+/// * `name` - Function name
+/// * `index` - Index of the input in the inputs of the function
+/// * `id` - Input variable ID
+/// * `variable_table` - Variable table
+/// 
+/// # Errors
+/// 
+/// * `InvalidInputId` - If the input variable is not in the variable table
+/// * `InvalidInput` - If the input variable does not have f64 contents
+pub(crate) fn get_input_f64<'a>(name: &str, index: usize, id: usize, variable_table: &'a VariableTable) -> Result<&'a Tensor<f64>, KdezeroError> {
+    let variable = variable_table.get(id)
+        .ok_or_else(|| KdezeroError::InvalidInputId { function: name.to_string(), index, id })?;
+    variable.to_f64_tensor().ok_or_else(|| KdezeroError::invalid_input(name, index, variable.shape(),
+        TensorError::DTypeMismatch { expected: "f64".to_string(), actual: variable.data_type().to_string() }))
 }
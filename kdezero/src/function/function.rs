@@ -1,5 +1,9 @@
 pub mod linear;
 pub mod sigmoid;
+pub mod relu;
+pub mod accuracy;
 
 pub use linear::linear;
 pub use sigmoid::sigmoid;
+pub use relu::relu;
+pub use accuracy::accuracy;
@@ -0,0 +1,49 @@
+use crate::graph::Graph;
+
+/// Ratio of the samples whose predicted class matches the label.
+/// 
+/// The predicted class is the index of the maximum score along the last axis.
+/// No function is added to the graph.
+/// 
+/// # Arguments
+/// 
+/// * `y_id` - Scores with the shape (N, C)
+/// * `t_id` - Class labels with the shape (N)
+/// 
+/// # Panics
+/// 
+/// Panics if the variables are not f64 or the number of the labels is not N.
+pub fn accuracy(y_id: usize, t_id: usize, graph: &Graph) -> f64 {
+    let y = graph.get_variable_contents_f64(y_id).expect("Invalid variable id");
+    let t = graph.get_variable_contents_f64(t_id).expect("Invalid variable id");
+    let pred = y.argmax(Some(y.ndim().saturating_sub(1)), false);
+    if pred.size() != t.size() {
+        panic!("The number of the labels must be {}, but got {}.", pred.size(), t.size());
+    }
+    if t.size() == 0 {
+        return 0.0;
+    }
+    let correct = pred.iter().zip(t.iter())
+        .filter(|(p, t)| *p.data() as f64 == *t.data())
+        .count();
+    correct as f64 / t.size() as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ktensor::Tensor;
+
+    #[test]
+    fn accuracy_normal() {
+        let mut graph = Graph::new();
+
+        let y_id = graph.generate_variable_from_f64_tensor(
+            Tensor::new_from_num_vec(vec![0.2, 0.8, 0.0, 0.1, 0.9, 0.0, 0.8, 0.1, 0.1, 0.0, 0.4, 0.6], vec![4, 3]), "y");
+        let t_id = graph.generate_variable_from_f64_tensor(
+            Tensor::new_from_num_vec(vec![1.0, 2.0, 0.0, 0.0], vec![4]), "t");
+
+        assert_eq!(accuracy(y_id, t_id, &graph), 0.5);
+        assert_eq!(graph.function_table().len(), 0);
+    }
+}
@@ -0,0 +1,30 @@
+use super::super::operator::ReLU;
+use crate::graph::Graph;
+
+/// Apply the rectified linear unit to the variable.
+pub fn relu(x_id: usize, graph: &mut Graph) -> usize {
+    let relu_id = graph.generate_function_from_function_contents(Box::new(ReLU::new()));
+    graph.forward(relu_id, vec![x_id])[0]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ktensor::Tensor;
+
+    #[test]
+    fn backward_normal() {
+        let mut graph = Graph::new();
+
+        let id0 = graph.generate_variable_from_f64_tensor(
+            Tensor::new_from_num_vec(vec![-1.0, 0.5, 2.0], vec![3]), "x");
+
+        let output_id = relu(id0, &mut graph);
+        graph.backward(vec![output_id], false, false);
+
+        let output = graph.get_variable_contents_f64(output_id).unwrap();
+        assert_eq!(output, &Tensor::new_from_num_vec(vec![0.0, 0.5, 2.0], vec![3]));
+        let grad = graph.get_variable_grad_contents_f64(id0).unwrap();
+        assert_eq!(grad, &Tensor::new_from_num_vec(vec![0.0, 1.0, 1.0], vec![3]));
+    }
+}
@@ -1,22 +1,23 @@
 use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use super::{Function, FunctionContents};
-use crate::variable::VariableTable;
+
+/// The next function id to adopt, shared by all tables so that ids never collide across graphs
+static NEXT_FUNCTION_ID: AtomicUsize = AtomicUsize::new(0);
 
 /// Function table
 /// 
 /// # Fields
 /// 
 /// * `table` - Function table
-/// * `id_max` - The next id to adopt
 pub struct FunctionTable {
     table: HashMap<usize, Box<Function>>,
-    id_max: usize,
 }
 
 impl FunctionTable {
     /// Create a new FunctionTable instance.
     pub fn new() -> Self {
-        Self { table: HashMap::new(), id_max: 0 }
+        Self { table: HashMap::new() }
     }
 
     /// Insert a new function into the table.
@@ -25,8 +26,7 @@ impl FunctionTable {
     /// 
     /// * `function` - Function
     fn insert(&mut self, function: Function) -> usize {
-        let id = self.id_max;
-        self.id_max += 1;
+        let id = function.get_id();
         self.table.insert(id, Box::new(function));
         id
     }
@@ -41,41 +41,27 @@ impl FunctionTable {
         self.table.get_mut(&id).map(|v| v.as_mut())
     }
 
-    /// Generate a new function from the specified data and insert it into the table.
-    pub fn generate_function_from_function_contents(&mut self, function_contents: Box<dyn FunctionContents>) -> usize {
-        self.insert(Function::new(self.id_max, function_contents))
+    /// Get the number of functions in the table.
+    pub fn len(&self) -> usize {
+        self.table.len()
     }
 
-    /// Forward the function with the specified id.
-    /// 
-    /// # Arguments
-    /// 
-    /// * `id` - Function ID
-    /// * `inputs` - Input variable IDs
-    /// * `variable_table` - Variable table
-    /// * `no_grad` - Whether to calculate the gradient
-    /// 
-    /// # Returns
-    /// 
-    /// * Output variable IDs
-    pub fn forward(&mut self, id: usize, inputs: Vec<usize>, variable_table: &mut VariableTable, no_grad: bool) -> Vec<usize> {
-        let function = self.get_mut(id).expect("Invalid function id");
-        function.forward(inputs, variable_table, no_grad)
+    /// Check if the table has no functions.
+    pub fn is_empty(&self) -> bool {
+        self.table.is_empty()
     }
 
-    /// Backward the function with the specified id.
+    /// Retain only the functions whose id satisfies the predicate.
     /// 
     /// # Arguments
     /// 
-    /// * `id` - Function ID
-    /// * `variable_table` - Variable table
-    /// 
-    /// # Returns
-    /// 
-    /// * Input variable IDs
-    pub fn backward(&mut self, id: usize, variable_table: &mut VariableTable) -> Vec<usize> {
-        let function = self.get(id).expect("Invalid function id");
-        let backward = function.get_backward();
-        backward(id, self, variable_table)
+    /// * `f` - Predicate on the function ID
+    pub fn retain<F: FnMut(usize) -> bool>(&mut self, mut f: F) {
+        self.table.retain(|&id, _| f(id));
+    }
+
+    /// Generate a new function from the specified data and insert it into the table.
+    pub fn generate_function_from_function_contents(&mut self, function_contents: Box<dyn FunctionContents>) -> usize {
+        self.insert(Function::new(NEXT_FUNCTION_ID.fetch_add(1, Ordering::Relaxed), function_contents))
     }
 }
@@ -9,13 +9,50 @@ pub mod pow;
 pub mod sin;
 pub mod cos;
 pub mod tanh;
+pub mod log;
+pub mod sqrt;
+pub mod abs;
+pub mod clip;
+pub mod maximum;
+pub mod minimum;
+pub mod erf;
+pub mod sigmoid;
+pub mod relu;
+pub mod leaky_relu;
+pub mod gelu;
+pub mod silu;
+pub mod elu;
+pub mod softplus;
+pub mod softmax;
+pub mod log_softmax;
+pub mod softmax_cross_entropy;
+pub mod binary_cross_entropy_with_logits;
 pub mod reshape;
 pub mod transpose;
+pub mod permute;
 pub mod sum;
+pub mod mean;
+pub mod max;
+pub mod min;
+pub mod var;
 pub mod broadcast_to;
 pub mod sum_to;
 pub mod matmul;
+pub mod get_item;
+pub mod get_item_grad;
+pub mod index_select;
+pub mod gather;
+pub mod scatter_add;
+pub mod concat;
+pub mod stack;
+pub mod split;
+pub mod reduction;
 pub mod mean_squared_error;
+pub mod mean_absolute_error;
+pub mod huber_loss;
+pub mod smooth_l1_loss;
+pub mod kl_divergence;
+pub mod cosine_embedding_loss;
 
 pub use square::Square;
 pub use mul::Mul;
@@ -28,10 +65,47 @@ pub use pow::Pow;
 pub use sin::Sin;
 pub use cos::Cos;
 pub use tanh::Tanh;
+pub use log::Log;
+pub use sqrt::Sqrt;
+pub use abs::Abs;
+pub use clip::Clip;
+pub use maximum::Maximum;
+pub use minimum::Minimum;
+pub use erf::Erf;
+pub use sigmoid::Sigmoid;
+pub use relu::ReLU;
+pub use leaky_relu::LeakyReLU;
+pub use gelu::GELU;
+pub use silu::SiLU;
+pub use elu::ELU;
+pub use softplus::Softplus;
+pub use softmax::Softmax;
+pub use log_softmax::LogSoftmax;
+pub use softmax_cross_entropy::SoftmaxCrossEntropy;
+pub use binary_cross_entropy_with_logits::BinaryCrossEntropyWithLogits;
 pub use reshape::Reshape;
 pub use transpose::Transpose;
+pub use permute::Permute;
 pub use sum::Sum;
+pub use mean::Mean;
+pub use max::Max;
+pub use min::Min;
+pub use var::Var;
 pub use broadcast_to::BroadcastTo;
 pub use sum_to::SumTo;
 pub use matmul::MatMul;
+pub use get_item::GetItem;
+pub use get_item_grad::GetItemGrad;
+pub use index_select::IndexSelect;
+pub use gather::Gather;
+pub use scatter_add::ScatterAdd;
+pub use concat::Concat;
+pub use stack::Stack;
+pub use split::Split;
+pub use reduction::Reduction;
 pub use mean_squared_error::MeanSquaredError;
+pub use mean_absolute_error::MeanAbsoluteError;
+pub use huber_loss::HuberLoss;
+pub use smooth_l1_loss::SmoothL1Loss;
+pub use kl_divergence::KLDivergence;
+pub use cosine_embedding_loss::CosineEmbeddingLoss;
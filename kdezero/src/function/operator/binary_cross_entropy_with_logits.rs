@@ -0,0 +1,181 @@
+use std::any::Any;
+use super::{Sub, Mul, Neg, Sigmoid};
+use super::super::{FunctionContents, get_inputs_f64};
+use ktensor::{Tensor, TensorError};
+use crate::error::KdezeroError;
+use crate::variable::VariableTable;
+use crate::graph::Graph;
+
+/// Mean binary cross entropy of the sigmoid of the logits and the targets
+/// 
+/// The inputs are the logits and the targets of the same shape.
+/// Each element is computed as `max(x, 0) - x * t + log(1 + exp(-|x|))`,
+/// so that it stays finite for large logits.
+#[derive(Debug, Clone)]
+pub struct BinaryCrossEntropyWithLogits {}
+
+impl BinaryCrossEntropyWithLogits {
+    pub fn new() -> Self {
+        Self {}
+    }
+
+    fn input_check(inputs: &Vec<usize>) -> Result<(), KdezeroError> {
+        if inputs.len() != 2 {
+            return Err(KdezeroError::InvalidInputCount { function: "BinaryCrossEntropyWithLogits".to_string(), expected: 2, actual: inputs.len() });
+        }
+        Ok(())
+    }
+
+    fn output_check(outputs: &Vec<usize>) -> Result<(), KdezeroError> {
+        if outputs.len() != 1 {
+            return Err(KdezeroError::InvalidOutputCount { function: "BinaryCrossEntropyWithLogits".to_string(), expected: 1, actual: outputs.len() });
+        }
+        Ok(())
+    }
+}
+
+impl FunctionContents for BinaryCrossEntropyWithLogits {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "BinaryCrossEntropyWithLogits"
+    }
+
+    fn forward(&self, info: &crate::function::FunctionInfo, inputs: &Vec<usize>, variable_table: &mut VariableTable) -> Vec<usize> {
+        self.try_forward(info, inputs, variable_table).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    fn try_forward(&self, _info: &crate::function::FunctionInfo, inputs: &Vec<usize>, variable_table: &mut VariableTable) -> Result<Vec<usize>, KdezeroError> {
+        let [x, t] = get_inputs_f64(self.name(), inputs, variable_table)?;
+        if x.shape() != t.shape() {
+            return Err(KdezeroError::invalid_input(self.name(), 1, t.shape(),
+                TensorError::ShapeMismatch { left: x.shape().clone(), right: t.shape().clone() }));
+        }
+
+        let total = x.iter().zip(t.iter())
+            .map(|(x, t)| {
+                let (x, t) = (*x.data(), *t.data());
+                x.max(0.0) - x * t + (-x.abs()).exp().ln_1p()
+            })
+            .sum::<f64>();
+        let output = Tensor::full(total / x.size().max(1) as f64, vec![]);
+
+        let output_id = variable_table.generate_variable_from_f64_tensor(output, "");
+        Ok(vec![output_id])
+    }
+
+    fn get_backward(&self) -> fn(usize, &mut Graph) -> Result<Vec<usize>, KdezeroError> {
+        |function_id, graph| {
+            let function = graph.get_function(function_id).ok_or(KdezeroError::InvalidFunctionId { id: function_id })?;
+            let inputs = function.try_get_inputs()?;
+            let outputs = function.try_get_outputs()?;
+            BinaryCrossEntropyWithLogits::input_check(inputs)?;
+            BinaryCrossEntropyWithLogits::output_check(outputs)?;
+            let input_ids = inputs.clone();
+            let output_id = outputs[0];
+            let output_grad_id = graph.get_variable_grad_id(output_id).ok_or_else(|| KdezeroError::GradNotFound { function: "BinaryCrossEntropyWithLogits".to_string(), id: output_id })?;
+
+            let size = graph.get_variable_contents_f64(input_ids[0]).ok_or(KdezeroError::InvalidVariableId { id: input_ids[0] })?.size();
+            let scale_id = graph.generate_variable_from_f64_tensor(Tensor::full(1.0 / size.max(1) as f64, vec![]), "");
+            let mul_id = graph.generate_function_from_function_contents(Box::new(Mul::new()));
+            let scaled_grad_id = graph.try_forward(mul_id, vec![output_grad_id, scale_id])?[0];
+
+            // (sigmoid(x) - t) * grad / N
+            let sigmoid_id = graph.generate_function_from_function_contents(Box::new(Sigmoid::new()));
+            let temp_id = graph.try_forward(sigmoid_id, vec![input_ids[0]])?[0];
+            let sub_id = graph.generate_function_from_function_contents(Box::new(Sub::new()));
+            let temp_id = graph.try_forward(sub_id, vec![temp_id, input_ids[1]])?[0];
+            let mul_id = graph.generate_function_from_function_contents(Box::new(Mul::new()));
+            let grad_x_id = graph.try_forward(mul_id, vec![temp_id, scaled_grad_id])?[0];
+
+            // -x * grad / N
+            let neg_id = graph.generate_function_from_function_contents(Box::new(Neg::new()));
+            let temp_id = graph.try_forward(neg_id, vec![input_ids[0]])?[0];
+            let mul_id = graph.generate_function_from_function_contents(Box::new(Mul::new()));
+            let grad_t_id = graph.try_forward(mul_id, vec![temp_id, scaled_grad_id])?[0];
+
+            graph.try_update_grad(input_ids[0], grad_x_id)?;
+            graph.try_update_grad(input_ids[1], grad_t_id)?;
+
+            Ok(input_ids)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ktensor::utility::assert_approx_eq;
+
+    #[test]
+    fn forward_normal() {
+        let mut graph = Graph::new();
+
+        let x = [-1.0, 0.5, 2.0_f64];
+        let t = [0.0, 1.0, 0.3];
+        let bce_id = graph.generate_function_from_function_contents(Box::new(BinaryCrossEntropyWithLogits::new()));
+        let x_id = graph.generate_variable_from_f64_tensor(Tensor::new_from_num_vec(x, vec![3]), "x");
+        let t_id = graph.generate_variable_from_f64_tensor(Tensor::new_from_num_vec(t, vec![3]), "t");
+        let y_id = graph.forward(bce_id, vec![x_id, t_id]);
+
+        let expected = x.iter().zip(t.iter())
+            .map(|(x, t)| {
+                let p = 1.0 / (1.0 + (-x).exp());
+                -(t * p.ln() + (1.0 - t) * (1.0 - p).ln())
+            })
+            .sum::<f64>() / 3.0;
+        let y = graph.get_variable_contents_f64(y_id[0]).unwrap();
+        assert_approx_eq(*y.at(&[]).data(), expected, 1e-12);
+    }
+
+    #[test]
+    fn forward_large() {
+        let mut graph = Graph::new();
+
+        let bce_id = graph.generate_function_from_function_contents(Box::new(BinaryCrossEntropyWithLogits::new()));
+        let x_id = graph.generate_variable_from_f64_tensor(Tensor::new_from_num_vec([1000.0, -1000.0], vec![2]), "x");
+        let t_id = graph.generate_variable_from_f64_tensor(Tensor::new_from_num_vec([0.0, 0.0], vec![2]), "t");
+        let y_id = graph.forward(bce_id, vec![x_id, t_id]);
+
+        let y = graph.get_variable_contents_f64(y_id[0]).unwrap();
+        assert_eq!(y, &Tensor::full(500.0, vec![]));
+    }
+
+    #[test]
+    fn backward_normal() {
+        let mut graph = Graph::new();
+
+        let x = Tensor::new_from_num_vec([-1.0, 0.5, 2.0, 0.0], vec![2, 2]);
+        let t = Tensor::new_from_num_vec([0.0, 1.0, 0.3, 1.0], vec![2, 2]);
+        let bce_id = graph.generate_function_from_function_contents(Box::new(BinaryCrossEntropyWithLogits::new()));
+        let x_id = graph.generate_variable_from_f64_tensor(x.clone(), "x");
+        let t_id = graph.generate_variable_from_f64_tensor(t.clone(), "t");
+        let y_ids = graph.forward(bce_id, vec![x_id, t_id]);
+
+        graph.backward(y_ids, false, false);
+
+        let x_grad = graph.get_variable_grad_contents_f64(x_id).unwrap();
+        let expected = (&x.sigmoid() - &t).scalar_div(4.0.into());
+        expected.data().iter().zip(x_grad.data().iter())
+            .for_each(|(x, y)| assert_approx_eq(*x.data(), *y.data(), 1e-12));
+        let t_grad = graph.get_variable_grad_contents_f64(t_id).unwrap();
+        let expected = x.map(|x| (-x.data() / 4.0).into());
+        expected.data().iter().zip(t_grad.data().iter())
+            .for_each(|(x, y)| assert_approx_eq(*x.data(), *y.data(), 1e-12));
+    }
+
+    #[test]
+    fn try_forward_error_shape() {
+        let mut graph = Graph::new();
+
+        let bce_id = graph.generate_function_from_function_contents(Box::new(BinaryCrossEntropyWithLogits::new()));
+        let x_id = graph.generate_variable_from_f64_tensor(Tensor::full(0.0, vec![2, 3]), "x");
+        let t_id = graph.generate_variable_from_f64_tensor(Tensor::full(0.0, vec![3]), "t");
+        let error = graph.try_forward(bce_id, vec![x_id, t_id]).unwrap_err();
+
+        assert_eq!(error, KdezeroError::invalid_input("BinaryCrossEntropyWithLogits", 1, &[3],
+            TensorError::ShapeMismatch { left: vec![2, 3], right: vec![3] }));
+    }
+}
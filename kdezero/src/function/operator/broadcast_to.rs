@@ -1,7 +1,9 @@
 use std::any::Any;
 use super::SumTo;
-use super::super::{FunctionContents, FunctionTable};
+use super::super::{FunctionContents, get_inputs_f64};
+use crate::error::KdezeroError;
 use crate::variable::VariableTable;
+use crate::graph::Graph;
 
 #[derive(Debug, Clone)]
 pub struct BroadcastTo {
@@ -13,16 +15,18 @@ impl BroadcastTo {
         Self { shape }
     }
 
-    fn input_check(inputs: &Vec<usize>) {
+    fn input_check(inputs: &Vec<usize>) -> Result<(), KdezeroError> {
         if inputs.len() != 1 {
-            panic!("BroadcastTo function must have only one input, but got {} inputs.", inputs.len());
+            return Err(KdezeroError::InvalidInputCount { function: "BroadcastTo".to_string(), expected: 1, actual: inputs.len() });
         }
+        Ok(())
     }
 
-    fn output_check(outputs: &Vec<usize>) {
+    fn output_check(outputs: &Vec<usize>) -> Result<(), KdezeroError> {
         if outputs.len() != 1 {
-            panic!("BroadcastTo function must have only one output, but got {} outputs.", outputs.len());
+            return Err(KdezeroError::InvalidOutputCount { function: "BroadcastTo".to_string(), expected: 1, actual: outputs.len() });
         }
+        Ok(())
     }
 }
 
@@ -35,34 +39,38 @@ impl FunctionContents for BroadcastTo {
         "BroadcastTo"
     }
 
-    fn forward(&self, _info: &crate::function::FunctionInfo, inputs: &Vec<usize>, variable_table: &mut VariableTable) -> Vec<usize> {
-        BroadcastTo::input_check(inputs);
-        let x = variable_table.get_variable_contents_f64(inputs[0]).expect("Invalid variable id");
+    fn forward(&self, info: &crate::function::FunctionInfo, inputs: &Vec<usize>, variable_table: &mut VariableTable) -> Vec<usize> {
+        self.try_forward(info, inputs, variable_table).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    fn try_forward(&self, _info: &crate::function::FunctionInfo, inputs: &Vec<usize>, variable_table: &mut VariableTable) -> Result<Vec<usize>, KdezeroError> {
+        let [x] = get_inputs_f64(self.name(), inputs, variable_table)?;
 
-        let output = x.broadcast_to(&self.shape);
+        let output = x.try_broadcast_to(&self.shape)
+            .map_err(|e| KdezeroError::invalid_input(self.name(), 0, x.shape(), e))?;
 
         let output_id = variable_table.generate_variable_from_f64_tensor(output, "");
-        vec![output_id]
+        Ok(vec![output_id])
     }
 
-    fn get_backward(&self) -> fn(usize, &mut FunctionTable, &mut VariableTable) -> Vec<usize> {
-        |function_id, function_table, variable_table| {
-            let function = function_table.get(function_id).expect("Invalid function id");
-            let inputs = function.get_inputs().expect("Invalid inputs");
-            let outputs = function.get_outputs().expect("Invalid outputs");
-            BroadcastTo::input_check(inputs);
-            BroadcastTo::output_check(outputs);
+    fn get_backward(&self) -> fn(usize, &mut Graph) -> Result<Vec<usize>, KdezeroError> {
+        |function_id, graph| {
+            let function = graph.get_function(function_id).ok_or(KdezeroError::InvalidFunctionId { id: function_id })?;
+            let inputs = function.try_get_inputs()?;
+            let outputs = function.try_get_outputs()?;
+            BroadcastTo::input_check(inputs)?;
+            BroadcastTo::output_check(outputs)?;
             let input_id = inputs[0];
-            let input_shape = variable_table.get(input_id).expect("Invalid variable id").shape();
+            let input_shape = graph.get_variable(input_id).ok_or(KdezeroError::InvalidVariableId { id: input_id })?.shape();
             let output_id = outputs[0];
-            let output_grad_id = variable_table.get_variable_grad_id(output_id).expect("Output grad id not found");
+            let output_grad_id = graph.get_variable_grad_id(output_id).ok_or_else(|| KdezeroError::GradNotFound { function: "BroadcastTo".to_string(), id: output_id })?;
 
-            let sum_to_id = function_table.generate_function_from_function_contents(Box::new(SumTo::new(input_shape.clone())));
-            let grad_id = function_table.forward(sum_to_id, vec![output_grad_id], variable_table, false)[0];
+            let sum_to_id = graph.generate_function_from_function_contents(Box::new(SumTo::new(input_shape.clone())));
+            let grad_id = graph.try_forward(sum_to_id, vec![output_grad_id])?[0];
 
-            variable_table.update_grad(input_id, grad_id, function_table);
+            graph.try_update_grad(input_id, grad_id)?;
 
-            vec![input_id]
+            Ok(vec![input_id])
         }
     }
 }
@@ -71,59 +79,55 @@ impl FunctionContents for BroadcastTo {
 mod tests {
     use super::*;
     use ktensor::tensor::Tensor;
-    use crate::{variable::VariableTable, function::FunctionTable};
 
     #[test]
     fn forward_normal() {
-        let mut variable_table = VariableTable::new();
-        let mut function_table = FunctionTable::new();
+        let mut graph = Graph::new();
 
         let data = vec![0.0, 1.0];
-        let broadcast_to_id = function_table.generate_function_from_function_contents(Box::new(BroadcastTo::new(vec![2, 2])));
-        let x_id = variable_table.generate_variable_from_f64_tensor(
+        let broadcast_to_id = graph.generate_function_from_function_contents(Box::new(BroadcastTo::new(vec![2, 2])));
+        let x_id = graph.generate_variable_from_f64_tensor(
             Tensor::new_from_num_vec(data.clone(), vec![2,]), "x");
-        let y_id = function_table.forward(broadcast_to_id, vec![x_id], &mut variable_table, false);
+        let y_id = graph.forward(broadcast_to_id, vec![x_id]);
 
-        let y = variable_table.get_variable_contents_f64(y_id[0]).unwrap();
+        let y = graph.get_variable_contents_f64(y_id[0]).unwrap();
         assert_eq!(y, &Tensor::new_from_num_vec(vec![0.0, 1.0, 0.0, 1.0], vec![2, 2]));
     }
 
     #[test]
     fn backward_normal() {
-        let mut variable_table = VariableTable::new();
-        let mut function_table = FunctionTable::new();
+        let mut graph = Graph::new();
 
         let data = vec![0.0, 1.0];
-        let broadcast_to_id = function_table.generate_function_from_function_contents(Box::new(BroadcastTo::new(vec![2, 2])));
-        let x_id = variable_table.generate_variable_from_f64_tensor(
+        let broadcast_to_id = graph.generate_function_from_function_contents(Box::new(BroadcastTo::new(vec![2, 2])));
+        let x_id = graph.generate_variable_from_f64_tensor(
             Tensor::new_from_num_vec(data.clone(), vec![2,]), "x");
-        let y_id = function_table.forward(broadcast_to_id, vec![x_id], &mut variable_table, false);
+        let y_id = graph.forward(broadcast_to_id, vec![x_id]);
 
-        variable_table.backward(y_id, &mut function_table, false);
+        graph.backward(y_id, false, false);
 
-        let x_grad = variable_table.get_variable_grad_contents_f64(x_id).unwrap();
+        let x_grad = graph.get_variable_grad_contents_f64(x_id).unwrap();
         assert_eq!(x_grad, &Tensor::new_from_num_vec(vec![2.0, 2.0], vec![2,]));
     }
 
     #[test]
     #[should_panic]
     fn backward_backward_normal() {
-        let mut variable_table = VariableTable::new();
-        let mut function_table = FunctionTable::new();
+        let mut graph = Graph::new();
 
         let data = vec![0.0, 1.0];
-        let broadcast_to_id = function_table.generate_function_from_function_contents(Box::new(BroadcastTo::new(vec![2, 2])));
-        let x_id = variable_table.generate_variable_from_f64_tensor(
+        let broadcast_to_id = graph.generate_function_from_function_contents(Box::new(BroadcastTo::new(vec![2, 2])));
+        let x_id = graph.generate_variable_from_f64_tensor(
             Tensor::new_from_num_vec(data.clone(), vec![2,]), "x");
-        let y_id = function_table.forward(broadcast_to_id, vec![x_id], &mut variable_table, false);
+        let y_id = graph.forward(broadcast_to_id, vec![x_id]);
 
-        variable_table.backward(y_id, &mut function_table, false);
+        graph.backward(y_id, false, true);
 
-        let x_grad_id = variable_table.get_variable_grad_id(x_id).unwrap();
-        variable_table.clear_grad(x_id);
+        let x_grad_id = graph.get_variable_grad_id(x_id).unwrap();
+        graph.clear_grad(x_id);
 
-        variable_table.backward(vec![x_grad_id], &mut function_table, false);
+        graph.backward(vec![x_grad_id], false, false);
 
-        let _ = variable_table.get_variable_grad_contents_f64(x_id).unwrap();
+        let _ = graph.get_variable_grad_contents_f64(x_id).unwrap();
     }
 }
@@ -0,0 +1,169 @@
+use std::any::Any;
+use super::Mul;
+use super::super::FunctionContents;
+use crate::variable::VariableTable;
+use crate::error::KdezeroError;
+use crate::graph::Graph;
+
+/// Limit each element to the range from `min` to `max`
+/// 
+/// The gradient passes through the elements inside the range, bounds included,
+/// and is 0 for the clipped elements.
+#[derive(Debug, Clone)]
+pub struct Clip {
+    min: f64,
+    max: f64,
+}
+
+impl Clip {
+    /// Create a new Clip instance.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `min` - Lower bound
+    /// * `max` - Upper bound
+    /// 
+    /// # Panics
+    /// 
+    /// Panics if `min` is greater than `max` or either bound is NaN.
+    pub fn new(min: f64, max: f64) -> Self {
+        if min.is_nan() || max.is_nan() || min > max {
+            panic!("Clip function needs min <= max, but got min {} and max {}.", min, max);
+        }
+        Self { min, max }
+    }
+
+    pub fn get_min(&self) -> f64 {
+        self.min
+    }
+
+    pub fn get_max(&self) -> f64 {
+        self.max
+    }
+
+    fn input_check(inputs: &Vec<usize>) -> Result<(), KdezeroError> {
+        if inputs.len() != 1 {
+            return Err(KdezeroError::InvalidInputCount { function: "Clip".to_string(), expected: 1, actual: inputs.len() });
+        }
+        Ok(())
+    }
+
+    fn output_check(outputs: &Vec<usize>) -> Result<(), KdezeroError> {
+        if outputs.len() != 1 {
+            return Err(KdezeroError::InvalidOutputCount { function: "Clip".to_string(), expected: 1, actual: outputs.len() });
+        }
+        Ok(())
+    }
+}
+
+impl FunctionContents for Clip {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "Clip"
+    }
+
+    fn forward(&self, _info: &crate::function::FunctionInfo, inputs: &Vec<usize>, variable_table: &mut VariableTable) -> Vec<usize> {
+        Clip::input_check(inputs).unwrap_or_else(|e| panic!("{}", e));
+        let x = variable_table.get_variable_contents_f64(inputs[0]).expect("Invalid variable id");
+
+        let output = x.clip(self.min, self.max);
+
+        let output_id = variable_table.generate_variable_from_f64_tensor(output, "");
+        vec![output_id]
+    }
+
+    fn get_backward(&self) -> fn(usize, &mut Graph) -> Result<Vec<usize>, KdezeroError> {
+        |function_id, graph| {
+            let function = graph.get_function(function_id).ok_or(KdezeroError::InvalidFunctionId { id: function_id })?;
+            let function_contents = function.get_function_contents::<Clip>().ok_or(KdezeroError::InvalidFunctionId { id: function_id })?;
+            let min = function_contents.get_min();
+            let max = function_contents.get_max();
+
+            let inputs = function.try_get_inputs()?;
+            let outputs = function.try_get_outputs()?;
+            Clip::input_check(inputs)?;
+            Clip::output_check(outputs)?;
+            let input_id = inputs[0];
+            let output_id = outputs[0];
+            let output_grad_id = graph.get_variable_grad_id(output_id).ok_or_else(|| KdezeroError::GradNotFound { function: "Clip".to_string(), id: output_id })?;
+
+            // grad * (min <= x <= max)
+            let x = graph.get_variable_contents_f64(input_id).ok_or(KdezeroError::InvalidVariableId { id: input_id })?;
+            let mask = x.map(|x| (if min <= *x.data() && *x.data() <= max { 1.0 } else { 0.0 }).into());
+            let mask_id = graph.generate_variable_from_f64_tensor(mask, "");
+            let mul_id = graph.generate_function_from_function_contents(Box::new(Mul::new()));
+            let grad_id = graph.try_forward(mul_id, vec![output_grad_id, mask_id])?[0];
+
+            graph.try_update_grad(input_id, grad_id)?;
+
+            Ok(vec![input_id])
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ktensor::Tensor;
+
+    #[test]
+    fn forward_normal() {
+        let mut graph = Graph::new();
+
+        let clip_id = graph.generate_function_from_function_contents(Box::new(Clip::new(-1.0, 1.0)));
+        let x_id = graph.generate_variable_from_f64_tensor(
+            Tensor::new_from_num_vec(vec![-2.0, 0.5, 1.0, 3.0], vec![4]), "x");
+        let y_id = graph.forward(clip_id, vec![x_id]);
+
+        let y = graph.get_variable_contents_f64(y_id[0]).unwrap();
+        assert_eq!(y, &Tensor::new_from_num_vec(vec![-1.0, 0.5, 1.0, 1.0], vec![4]));
+    }
+
+    #[test]
+    fn backward_normal() {
+        let mut graph = Graph::new();
+
+        let clip_id = graph.generate_function_from_function_contents(Box::new(Clip::new(-1.0, 1.0)));
+        let x_id = graph.generate_variable_from_f64_tensor(
+            Tensor::new_from_num_vec(vec![-2.0, 0.5, 1.0, 3.0], vec![4]), "x");
+        let y_ids = graph.forward(clip_id, vec![x_id]);
+
+        graph.backward(y_ids, false, false);
+
+        let x_grad = graph.get_variable_grad_contents_f64(x_id).unwrap();
+        assert_eq!(x_grad, &Tensor::new_from_num_vec(vec![0.0, 1.0, 1.0, 0.0], vec![4]));
+    }
+
+    #[test]
+    #[should_panic]
+    fn new_error_range() {
+        Clip::new(1.0, -1.0);
+    }
+
+    /// The second order gradient of `clip(x)^2` is 2 inside the range and 0 outside
+    #[test]
+    fn backward_backward_normal() {
+        let mut graph = Graph::new();
+
+        let clip_id = graph.generate_function_from_function_contents(Box::new(Clip::new(-1.0, 1.0)));
+        let square_id = graph.generate_function_from_function_contents(Box::new(super::super::Square::new()));
+        let x_id = graph.generate_variable_from_f64_tensor(
+            Tensor::new_from_num_vec(vec![-2.0, 0.5, 3.0], vec![3]), "x");
+        let y_id = graph.forward(clip_id, vec![x_id])[0];
+        let z_ids = graph.forward(square_id, vec![y_id]);
+
+        graph.backward(z_ids, false, true);
+
+        let x_grad_id = graph.get_variable_grad_id(x_id).unwrap();
+        assert_eq!(graph.get_variable_contents_f64(x_grad_id).unwrap(), &Tensor::new_from_num_vec(vec![0.0, 1.0, 0.0], vec![3]));
+        graph.clear_grad(x_id);
+
+        graph.backward(vec![x_grad_id], false, false);
+
+        let x_grad = graph.get_variable_grad_contents_f64(x_id).unwrap();
+        assert_eq!(x_grad, &Tensor::new_from_num_vec(vec![0.0, 2.0, 0.0], vec![3]));
+    }
+}
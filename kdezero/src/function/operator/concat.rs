@@ -0,0 +1,164 @@
+use std::any::Any;
+use super::Split;
+use super::super::{FunctionContents, get_all_inputs_f64};
+use ktensor::{Tensor, TensorError};
+use crate::error::KdezeroError;
+use crate::variable::VariableTable;
+use crate::graph::Graph;
+
+/// Concatenation of the inputs along the axis
+/// 
+/// The function takes any number of inputs with the same shape except for the axis.
+/// The gradient of the output is split back into the parts of the inputs.
+#[derive(Debug, Clone)]
+pub struct Concat {
+    axis: usize,
+}
+
+impl Concat {
+    /// Create a new Concat instance.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `axis` - Axis to concatenate along
+    pub fn new(axis: usize) -> Self {
+        Self { axis }
+    }
+
+    pub fn get_axis(&self) -> usize {
+        self.axis
+    }
+
+    /// Convert the error of joining the inputs into the error of the input that caused it.
+    pub(crate) fn join_error(name: &str, xs: &[&Tensor<f64>], error: TensorError) -> KdezeroError {
+        let index = match &error {
+            TensorError::ShapeMismatch { right, .. } => xs.iter().position(|x| x.shape() == right).unwrap_or(0),
+            _ => 0,
+        };
+        KdezeroError::invalid_input(name, index, xs[index].shape(), error)
+    }
+
+    fn input_check(inputs: &Vec<usize>) -> Result<(), KdezeroError> {
+        if inputs.is_empty() {
+            return Err(KdezeroError::InvalidInputCount { function: "Concat".to_string(), expected: 1, actual: inputs.len() });
+        }
+        Ok(())
+    }
+
+    fn output_check(outputs: &Vec<usize>) -> Result<(), KdezeroError> {
+        if outputs.len() != 1 {
+            return Err(KdezeroError::InvalidOutputCount { function: "Concat".to_string(), expected: 1, actual: outputs.len() });
+        }
+        Ok(())
+    }
+}
+
+impl FunctionContents for Concat {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "Concat"
+    }
+
+    fn forward(&self, info: &crate::function::FunctionInfo, inputs: &Vec<usize>, variable_table: &mut VariableTable) -> Vec<usize> {
+        self.try_forward(info, inputs, variable_table).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    fn try_forward(&self, _info: &crate::function::FunctionInfo, inputs: &Vec<usize>, variable_table: &mut VariableTable) -> Result<Vec<usize>, KdezeroError> {
+        let xs = get_all_inputs_f64(self.name(), inputs, variable_table)?;
+
+        let output = Tensor::try_concat(&xs, self.axis)
+            .map_err(|e| Concat::join_error(self.name(), &xs, e))?;
+
+        let output_id = variable_table.generate_variable_from_f64_tensor(output, "");
+        Ok(vec![output_id])
+    }
+
+    fn get_backward(&self) -> fn(usize, &mut Graph) -> Result<Vec<usize>, KdezeroError> {
+        |function_id, graph| {
+            let function = graph.get_function(function_id).ok_or(KdezeroError::InvalidFunctionId { id: function_id })?;
+            let function_contents = function.get_function_contents::<Concat>().ok_or(KdezeroError::InvalidFunctionId { id: function_id })?;
+            let axis = function_contents.get_axis();
+            let inputs = function.try_get_inputs()?;
+            let outputs = function.try_get_outputs()?;
+            Concat::input_check(inputs)?;
+            Concat::output_check(outputs)?;
+            let input_ids = inputs.clone();
+            let output_id = outputs[0];
+            let output_grad_id = graph.get_variable_grad_id(output_id).ok_or_else(|| KdezeroError::GradNotFound { function: "Concat".to_string(), id: output_id })?;
+
+            let sizes = input_ids.iter()
+                .map(|&id| graph.get_variable(id).map(|x| x.shape()[axis]).ok_or(KdezeroError::InvalidVariableId { id }))
+                .collect::<Result<_, _>>()?;
+            let split_id = graph.generate_function_from_function_contents(Box::new(Split::new(axis, sizes)));
+            let grad_ids = graph.try_forward(split_id, vec![output_grad_id])?;
+
+            for (&input_id, &grad_id) in input_ids.iter().zip(grad_ids.iter()) {
+                graph.try_update_grad(input_id, grad_id)?;
+            }
+
+            Ok(input_ids)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn forward_normal() {
+        let mut graph = Graph::new();
+
+        let concat_id = graph.generate_function_from_function_contents(Box::new(Concat::new(1)));
+        let x0_id = graph.generate_variable_from_f64_tensor(Tensor::arrange([2, 1]), "x0");
+        let x1_id = graph.generate_variable_from_f64_tensor(Tensor::arrange([2, 2]), "x1");
+        let y_id = graph.forward(concat_id, vec![x0_id, x1_id]);
+
+        let y = graph.get_variable_contents_f64(y_id[0]).unwrap();
+        assert_eq!(y, &Tensor::new_from_num_vec(vec![0.0, 0.0, 1.0, 1.0, 2.0, 3.0], vec![2, 3]));
+    }
+
+    #[test]
+    fn backward_normal() {
+        let mut graph = Graph::new();
+
+        let concat_id = graph.generate_function_from_function_contents(Box::new(Concat::new(0)));
+        let x0_id = graph.generate_variable_from_f64_tensor(Tensor::arrange([1]), "x0");
+        let x1_id = graph.generate_variable_from_f64_tensor(Tensor::arrange([2]), "x1");
+        let w_id = graph.generate_variable_from_f64_tensor(Tensor::new_from_num_vec(vec![2.0, 3.0, 4.0, 5.0], vec![4]), "w");
+        let y_id = graph.forward(concat_id, vec![x0_id, x1_id, x0_id])[0];
+        let mul_id = graph.generate_function_from_function_contents(Box::new(super::super::Mul::new()));
+        let z_id = graph.forward(mul_id, vec![y_id, w_id])[0];
+
+        graph.backward(vec![z_id], false, false);
+
+        assert_eq!(graph.get_variable_grad_contents_f64(x0_id).unwrap(), &Tensor::new_from_num_vec(vec![7.0], vec![1]));
+        assert_eq!(graph.get_variable_grad_contents_f64(x1_id).unwrap(), &Tensor::new_from_num_vec(vec![3.0, 4.0], vec![2]));
+    }
+
+    #[test]
+    fn try_forward_error_shape() {
+        let mut graph = Graph::new();
+
+        let concat_id = graph.generate_function_from_function_contents(Box::new(Concat::new(0)));
+        let x0_id = graph.generate_variable_from_f64_tensor(Tensor::arrange([1, 2]), "x0");
+        let x1_id = graph.generate_variable_from_f64_tensor(Tensor::arrange([1, 3]), "x1");
+        let error = graph.try_forward(concat_id, vec![x0_id, x0_id, x1_id]).unwrap_err();
+
+        assert_eq!(error, KdezeroError::invalid_input("Concat", 2, &[1, 3],
+            TensorError::ShapeMismatch { left: vec![1, 2], right: vec![1, 3] }));
+    }
+
+    #[test]
+    fn try_forward_error_no_input() {
+        let mut graph = Graph::new();
+
+        let concat_id = graph.generate_function_from_function_contents(Box::new(Concat::new(0)));
+        let error = graph.try_forward(concat_id, vec![]).unwrap_err();
+
+        assert_eq!(error, KdezeroError::InvalidInputCount { function: "Concat".to_string(), expected: 1, actual: 0 });
+    }
+}
@@ -0,0 +1,273 @@
+use std::any::Any;
+use super::{Sub, Mul, Div, Square, Sqrt, Sum, Maximum, Reshape, Reduction};
+use super::reduction::get_loss_inputs;
+use super::super::FunctionContents;
+use crate::error::KdezeroError;
+use crate::variable::VariableTable;
+use crate::graph::Graph;
+use ktensor::{Tensor, TensorError};
+
+/// Lower bound of the norms, to keep the cosine finite for zero vectors
+const EPS: f64 = 1e-8;
+
+/// Cosine embedding loss of the pairs of the rows of x1 and x2
+/// 
+/// The inputs are x1 and x2 with the shape (N, D), the labels y with the shape (N)
+/// and the optional weights broadcast to the shape (N).
+/// The loss of a pair is `1 - cos` for a positive label and `max(0, cos - margin)` otherwise.
+/// The labels and the weights get no gradient.
+#[derive(Debug, Clone)]
+pub struct CosineEmbeddingLoss {
+    margin: f64,
+    reduction: Reduction,
+}
+
+impl CosineEmbeddingLoss {
+    /// Create a new CosineEmbeddingLoss instance, which takes the mean of the losses.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `margin` - Cosine under which a dissimilar pair has no loss
+    pub fn new(margin: f64) -> Self {
+        Self::with_reduction(margin, Reduction::Mean)
+    }
+
+    /// Create a new CosineEmbeddingLoss instance with the reduction.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `margin` - Cosine under which a dissimilar pair has no loss
+    /// * `reduction` - Reduction of the losses of the pairs
+    pub fn with_reduction(margin: f64, reduction: Reduction) -> Self {
+        Self { margin, reduction }
+    }
+
+    pub fn get_margin(&self) -> f64 {
+        self.margin
+    }
+
+    pub fn get_reduction(&self) -> Reduction {
+        self.reduction
+    }
+
+    /// Compute the cosine of each pair of the rows.
+    pub(crate) fn cosine(x1: &Tensor<f64>, x2: &Tensor<f64>) -> Tensor<f64> {
+        let norm = |x: &Tensor<f64>| x.powi(2).sum([1], false).sqrt().map(|n| n.data().max(EPS).into());
+        &(x1 * x2).sum([1], false) / &(&norm(x1) * &norm(x2))
+    }
+
+    /// Add the norm of each row, kept as a column, to the graph.
+    fn norm(graph: &mut Graph, x_id: usize) -> Result<usize, KdezeroError> {
+        let eps_id = graph.generate_variable_from_f64_tensor(Tensor::full(EPS, vec![]), "");
+        let square_id = graph.generate_function_from_function_contents(Box::new(Square::new()));
+        let temp_id = graph.try_forward(square_id, vec![x_id])?[0];
+        let sum_id = graph.generate_function_from_function_contents(Box::new(Sum::new(Some([1]), true)));
+        let temp_id = graph.try_forward(sum_id, vec![temp_id])?[0];
+        let sqrt_id = graph.generate_function_from_function_contents(Box::new(Sqrt::new()));
+        let temp_id = graph.try_forward(sqrt_id, vec![temp_id])?[0];
+        let maximum_id = graph.generate_function_from_function_contents(Box::new(Maximum::new()));
+        Ok(graph.try_forward(maximum_id, vec![temp_id, eps_id])?[0])
+    }
+
+    /// Add `grad * (other / (|x| * |other|) - cos * x / |x|^2)`, the gradient of x, to the graph.
+    fn cosine_grad(graph: &mut Graph, grad_id: usize, x_id: usize, other_id: usize, norm_id: usize, denominator_id: usize, cos_id: usize) -> Result<usize, KdezeroError> {
+        let div_id = graph.generate_function_from_function_contents(Box::new(Div::new()));
+        let temp0_id = graph.try_forward(div_id, vec![other_id, denominator_id])?[0];
+        let mul_id = graph.generate_function_from_function_contents(Box::new(Mul::new()));
+        let temp1_id = graph.try_forward(mul_id, vec![cos_id, x_id])?[0];
+        let square_id = graph.generate_function_from_function_contents(Box::new(Square::new()));
+        let temp2_id = graph.try_forward(square_id, vec![norm_id])?[0];
+        let div_id = graph.generate_function_from_function_contents(Box::new(Div::new()));
+        let temp1_id = graph.try_forward(div_id, vec![temp1_id, temp2_id])?[0];
+        let sub_id = graph.generate_function_from_function_contents(Box::new(Sub::new()));
+        let temp_id = graph.try_forward(sub_id, vec![temp0_id, temp1_id])?[0];
+        let mul_id = graph.generate_function_from_function_contents(Box::new(Mul::new()));
+        Ok(graph.try_forward(mul_id, vec![grad_id, temp_id])?[0])
+    }
+
+    fn input_check(inputs: &Vec<usize>) -> Result<(), KdezeroError> {
+        if inputs.len() != 3 && inputs.len() != 4 {
+            return Err(KdezeroError::InvalidInputCount { function: "CosineEmbeddingLoss".to_string(), expected: 3, actual: inputs.len() });
+        }
+        Ok(())
+    }
+
+    fn output_check(outputs: &Vec<usize>) -> Result<(), KdezeroError> {
+        if outputs.len() != 1 {
+            return Err(KdezeroError::InvalidOutputCount { function: "CosineEmbeddingLoss".to_string(), expected: 1, actual: outputs.len() });
+        }
+        Ok(())
+    }
+}
+
+impl FunctionContents for CosineEmbeddingLoss {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "CosineEmbeddingLoss"
+    }
+
+    fn forward(&self, info: &crate::function::FunctionInfo, inputs: &Vec<usize>, variable_table: &mut VariableTable) -> Vec<usize> {
+        self.try_forward(info, inputs, variable_table).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    fn try_forward(&self, _info: &crate::function::FunctionInfo, inputs: &Vec<usize>, variable_table: &mut VariableTable) -> Result<Vec<usize>, KdezeroError> {
+        let ([x1, x2, y], weights) = get_loss_inputs(self.name(), inputs, variable_table)?;
+        if x1.ndim() != 2 {
+            return Err(KdezeroError::invalid_input(self.name(), 0, x1.shape(),
+                TensorError::NdimMismatch { expected: 2, actual: x1.ndim() }));
+        }
+        if x2.shape() != x1.shape() {
+            return Err(KdezeroError::invalid_input(self.name(), 1, x2.shape(),
+                TensorError::ShapeMismatch { left: x1.shape().clone(), right: x2.shape().clone() }));
+        }
+        let n = x1.shape()[0];
+        if y.shape() != &vec![n] {
+            return Err(KdezeroError::invalid_input(self.name(), 2, y.shape(),
+                TensorError::ShapeMismatch { left: vec![n], right: y.shape().clone() }));
+        }
+        if let Some(weights) = weights {
+            weights.try_broadcast_to(&[n])
+                .map_err(|e| KdezeroError::invalid_input(self.name(), 3, weights.shape(), e))?;
+        }
+
+        let margin = self.margin;
+        let loss = CosineEmbeddingLoss::cosine(x1, x2).zip_map(y, |cos, y| {
+            let cos = *cos.data();
+            (if *y.data() > 0.0 { 1.0 - cos } else { (cos - margin).max(0.0) }).into()
+        });
+        let output = self.reduction.reduce(loss, weights);
+
+        let output_id = variable_table.generate_variable_from_f64_tensor(output, "");
+        Ok(vec![output_id])
+    }
+
+    fn get_backward(&self) -> fn(usize, &mut Graph) -> Result<Vec<usize>, KdezeroError> {
+        |function_id, graph| {
+            let function = graph.get_function(function_id).ok_or(KdezeroError::InvalidFunctionId { id: function_id })?;
+            let function_contents = function.get_function_contents::<CosineEmbeddingLoss>().ok_or(KdezeroError::InvalidFunctionId { id: function_id })?;
+            let (margin, reduction) = (function_contents.get_margin(), function_contents.get_reduction());
+            let inputs = function.try_get_inputs()?;
+            let outputs = function.try_get_outputs()?;
+            CosineEmbeddingLoss::input_check(inputs)?;
+            CosineEmbeddingLoss::output_check(outputs)?;
+            let input_ids = inputs.clone();
+            let output_id = outputs[0];
+            let output_grad_id = graph.get_variable_grad_id(output_id).ok_or_else(|| KdezeroError::GradNotFound { function: "CosineEmbeddingLoss".to_string(), id: output_id })?;
+
+            // Derivative of the loss by the cosine: -1 for a positive label, 1 over the margin and 0 otherwise
+            let x1 = graph.get_variable_contents_f64(input_ids[0]).ok_or(KdezeroError::InvalidVariableId { id: input_ids[0] })?;
+            let x2 = graph.get_variable_contents_f64(input_ids[1]).ok_or(KdezeroError::InvalidVariableId { id: input_ids[1] })?;
+            let y = graph.get_variable_contents_f64(input_ids[2]).ok_or(KdezeroError::InvalidVariableId { id: input_ids[2] })?;
+            let n = x1.shape()[0];
+            let coefficient = CosineEmbeddingLoss::cosine(x1, x2).zip_map(y, |cos, y| {
+                (if *y.data() > 0.0 { -1.0 } else if *cos.data() > margin { 1.0 } else { 0.0 }).into()
+            });
+            let coefficient_id = graph.generate_variable_from_f64_tensor(coefficient, "");
+            let grad_id = reduction.loss_grad(graph, output_grad_id, input_ids.get(3).copied(), &[n])?;
+            let mul_id = graph.generate_function_from_function_contents(Box::new(Mul::new()));
+            let grad_id = graph.try_forward(mul_id, vec![grad_id, coefficient_id])?[0];
+            let reshape_id = graph.generate_function_from_function_contents(Box::new(Reshape::new(vec![n, 1])));
+            let grad_id = graph.try_forward(reshape_id, vec![grad_id])?[0];
+
+            // cos = sum(x1 * x2) / (|x1| * |x2|) along the rows
+            let norm1_id = CosineEmbeddingLoss::norm(graph, input_ids[0])?;
+            let norm2_id = CosineEmbeddingLoss::norm(graph, input_ids[1])?;
+            let mul_id = graph.generate_function_from_function_contents(Box::new(Mul::new()));
+            let denominator_id = graph.try_forward(mul_id, vec![norm1_id, norm2_id])?[0];
+            let mul_id = graph.generate_function_from_function_contents(Box::new(Mul::new()));
+            let temp_id = graph.try_forward(mul_id, vec![input_ids[0], input_ids[1]])?[0];
+            let sum_id = graph.generate_function_from_function_contents(Box::new(Sum::new(Some([1]), true)));
+            let temp_id = graph.try_forward(sum_id, vec![temp_id])?[0];
+            let div_id = graph.generate_function_from_function_contents(Box::new(Div::new()));
+            let cos_id = graph.try_forward(div_id, vec![temp_id, denominator_id])?[0];
+
+            let gx1_id = CosineEmbeddingLoss::cosine_grad(graph, grad_id, input_ids[0], input_ids[1], norm1_id, denominator_id, cos_id)?;
+            let gx2_id = CosineEmbeddingLoss::cosine_grad(graph, grad_id, input_ids[1], input_ids[0], norm2_id, denominator_id, cos_id)?;
+            graph.try_update_grad(input_ids[0], gx1_id)?;
+            graph.try_update_grad(input_ids[1], gx2_id)?;
+
+            Ok(input_ids[..2].to_vec())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ktensor::utility::assert_approx_eq;
+
+    #[test]
+    fn forward_normal() {
+        let mut graph = Graph::new();
+
+        let loss_id = graph.generate_function_from_function_contents(
+            Box::new(CosineEmbeddingLoss::with_reduction(0.5, Reduction::None)));
+        let x1_id = graph.generate_variable_from_f64_tensor(
+            Tensor::new_from_num_vec(vec![1.0, 0.0, 1.0, 1.0, 1.0, 0.0], vec![3, 2]), "x1");
+        let x2_id = graph.generate_variable_from_f64_tensor(
+            Tensor::new_from_num_vec(vec![0.0, 2.0, 1.0, 0.0, 3.0, 0.0], vec![3, 2]), "x2");
+        let y_id = graph.generate_variable_from_f64_tensor(Tensor::new_from_num_vec(vec![1.0, -1.0, -1.0], vec![3]), "y");
+        let output_id = graph.forward(loss_id, vec![x1_id, x2_id, y_id]);
+
+        let output = graph.get_variable_contents_f64(output_id[0]).unwrap();
+        let cos = 0.5_f64.sqrt();
+        [1.0, cos - 0.5, 0.5].iter().zip(output.data().iter())
+            .for_each(|(x, y)| assert_approx_eq(*x, *y.data(), 1e-12));
+    }
+
+    #[test]
+    fn backward_normal() {
+        let mut graph = Graph::new();
+
+        let x1 = Tensor::new_from_num_vec(vec![1.0, 2.0, 3.0, -1.0], vec![2, 2]);
+        let x2 = Tensor::new_from_num_vec(vec![2.0, 1.0, 1.0, 1.0], vec![2, 2]);
+        let y = Tensor::new_from_num_vec(vec![1.0, -1.0], vec![2]);
+        let loss_id = graph.generate_function_from_function_contents(Box::new(CosineEmbeddingLoss::new(0.0)));
+        let x1_id = graph.generate_variable_from_f64_tensor(x1.clone(), "x1");
+        let x2_id = graph.generate_variable_from_f64_tensor(x2.clone(), "x2");
+        let y_id = graph.generate_variable_from_f64_tensor(y.clone(), "y");
+        let w_id = graph.generate_variable_from_f64_tensor(Tensor::new_from_num_vec(vec![1.0, 3.0], vec![2]), "w");
+        let output_ids = graph.forward(loss_id, vec![x1_id, x2_id, y_id, w_id]);
+
+        graph.backward(output_ids, false, false);
+
+        // Central differences of the weighted mean of the losses
+        let loss = |x1: &Tensor<f64>, x2: &Tensor<f64>| {
+            let cos = CosineEmbeddingLoss::cosine(x1, x2);
+            (1.0 - cos.data()[0].data() + 3.0 * cos.data()[1].data().max(0.0)) / 2.0
+        };
+        let x1_grad = graph.get_variable_grad_contents_f64(x1_id).unwrap();
+        let x2_grad = graph.get_variable_grad_contents_f64(x2_id).unwrap();
+        for i in 0..4 {
+            let (mut plus, mut minus) = (x1.clone(), x1.clone());
+            *plus.at_mut(&[i / 2, i % 2]) = (x1.data()[i].data() + 1e-6).into();
+            *minus.at_mut(&[i / 2, i % 2]) = (x1.data()[i].data() - 1e-6).into();
+            let expected = (loss(&plus, &x2) - loss(&minus, &x2)) / 2e-6;
+            assert_approx_eq(expected, *x1_grad.data()[i].data(), 1e-6);
+
+            let (mut plus, mut minus) = (x2.clone(), x2.clone());
+            *plus.at_mut(&[i / 2, i % 2]) = (x2.data()[i].data() + 1e-6).into();
+            *minus.at_mut(&[i / 2, i % 2]) = (x2.data()[i].data() - 1e-6).into();
+            let expected = (loss(&x1, &plus) - loss(&x1, &minus)) / 2e-6;
+            assert_approx_eq(expected, *x2_grad.data()[i].data(), 1e-6);
+        }
+        assert!(graph.get_variable_grad_id(y_id).is_none());
+    }
+
+    #[test]
+    fn try_forward_error_label_shape() {
+        let mut graph = Graph::new();
+
+        let loss_id = graph.generate_function_from_function_contents(Box::new(CosineEmbeddingLoss::new(0.0)));
+        let x1_id = graph.generate_variable_from_f64_tensor(Tensor::full(1.0, vec![2, 3]), "x1");
+        let x2_id = graph.generate_variable_from_f64_tensor(Tensor::full(1.0, vec![2, 3]), "x2");
+        let y_id = graph.generate_variable_from_f64_tensor(Tensor::full(1.0, vec![3]), "y");
+        let error = graph.try_forward(loss_id, vec![x1_id, x2_id, y_id]).unwrap_err();
+
+        assert_eq!(error, KdezeroError::invalid_input("CosineEmbeddingLoss", 2, &[3],
+            TensorError::ShapeMismatch { left: vec![2], right: vec![3] }));
+    }
+}
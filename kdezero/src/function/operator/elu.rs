@@ -0,0 +1,164 @@
+use std::any::Any;
+use super::{Add, Mul};
+use super::super::FunctionContents;
+use ktensor::Tensor;
+use crate::variable::VariableTable;
+use crate::error::KdezeroError;
+use crate::graph::Graph;
+
+/// Exponential linear unit
+/// 
+/// x for a positive x and `alpha * (exp(x) - 1)` otherwise.
+#[derive(Debug, Clone)]
+pub struct ELU {
+    alpha: f64,
+}
+
+impl ELU {
+    /// Create a new ELU instance.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `alpha` - Scale of the negative part
+    pub fn new(alpha: f64) -> Self {
+        Self { alpha }
+    }
+
+    pub fn get_alpha(&self) -> f64 {
+        self.alpha
+    }
+
+    /// Compute ELU of each element of the tensor.
+    pub(crate) fn elu(x: &Tensor<f64>, alpha: f64) -> Tensor<f64> {
+        x.map(|x| (if *x.data() > 0.0 { *x.data() } else { alpha * x.data().exp_m1() }).into())
+    }
+
+    fn input_check(inputs: &Vec<usize>) -> Result<(), KdezeroError> {
+        if inputs.len() != 1 {
+            return Err(KdezeroError::InvalidInputCount { function: "ELU".to_string(), expected: 1, actual: inputs.len() });
+        }
+        Ok(())
+    }
+
+    fn output_check(outputs: &Vec<usize>) -> Result<(), KdezeroError> {
+        if outputs.len() != 1 {
+            return Err(KdezeroError::InvalidOutputCount { function: "ELU".to_string(), expected: 1, actual: outputs.len() });
+        }
+        Ok(())
+    }
+}
+
+impl FunctionContents for ELU {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "ELU"
+    }
+
+    fn forward(&self, _info: &crate::function::FunctionInfo, inputs: &Vec<usize>, variable_table: &mut VariableTable) -> Vec<usize> {
+        ELU::input_check(inputs).unwrap_or_else(|e| panic!("{}", e));
+        let x = variable_table.get_variable_contents_f64(inputs[0]).expect("Invalid variable id");
+
+        let output = ELU::elu(x, self.alpha);
+
+        let output_id = variable_table.generate_variable_from_f64_tensor(output, "");
+        vec![output_id]
+    }
+
+    fn get_backward(&self) -> fn(usize, &mut Graph) -> Result<Vec<usize>, KdezeroError> {
+        |function_id, graph| {
+            let function = graph.get_function(function_id).ok_or(KdezeroError::InvalidFunctionId { id: function_id })?;
+            let function_contents = function.get_function_contents::<ELU>().ok_or(KdezeroError::InvalidFunctionId { id: function_id })?;
+            let alpha = function_contents.get_alpha();
+
+            let inputs = function.try_get_inputs()?;
+            let outputs = function.try_get_outputs()?;
+            ELU::input_check(inputs)?;
+            ELU::output_check(outputs)?;
+            let input_id = inputs[0];
+            let output_id = outputs[0];
+            let output_grad_id = graph.get_variable_grad_id(output_id).ok_or_else(|| KdezeroError::GradNotFound { function: "ELU".to_string(), id: output_id })?;
+
+            // grad * (1 if x > 0 else y + alpha)
+            let x = graph.get_variable_contents_f64(input_id).ok_or(KdezeroError::InvalidVariableId { id: input_id })?;
+            let positive = x.map(|x| (if *x.data() > 0.0 { 1.0 } else { 0.0 }).into());
+            let negative = positive.map(|x| (1.0 - x.data()).into());
+            let positive_id = graph.generate_variable_from_f64_tensor(positive, "");
+            let negative_id = graph.generate_variable_from_f64_tensor(negative, "");
+            let alpha_id = graph.generate_variable_from_f64_tensor(Tensor::full(alpha, vec![]), "");
+
+            let add_id = graph.generate_function_from_function_contents(Box::new(Add::new()));
+            let temp_id = graph.try_forward(add_id, vec![output_id, alpha_id])?[0];
+            let mul_id = graph.generate_function_from_function_contents(Box::new(Mul::new()));
+            let temp_id = graph.try_forward(mul_id, vec![negative_id, temp_id])?[0];
+            let add_id = graph.generate_function_from_function_contents(Box::new(Add::new()));
+            let temp_id = graph.try_forward(add_id, vec![positive_id, temp_id])?[0];
+            let mul_id = graph.generate_function_from_function_contents(Box::new(Mul::new()));
+            let grad_id = graph.try_forward(mul_id, vec![output_grad_id, temp_id])?[0];
+
+            graph.try_update_grad(input_id, grad_id)?;
+
+            Ok(vec![input_id])
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ktensor::utility::{assert_approx_eq, numerical_diff};
+
+    #[test]
+    fn forward_normal() {
+        let mut graph = Graph::new();
+
+        let elu_id = graph.generate_function_from_function_contents(Box::new(ELU::new(2.0)));
+        let x_id = graph.generate_variable_from_f64_tensor(
+            Tensor::new_from_num_vec(vec![-1.0, 0.0, 3.0], vec![3]), "x");
+        let y_id = graph.forward(elu_id, vec![x_id]);
+
+        let y = graph.get_variable_contents_f64(y_id[0]).unwrap();
+        assert_eq!(y, &Tensor::new_from_num_vec(vec![2.0 * (-1.0_f64).exp_m1(), 0.0, 3.0], vec![3]));
+    }
+
+    #[test]
+    fn backward_normal() {
+        let mut graph = Graph::new();
+
+        let x = Tensor::new_from_num_vec(vec![-1.0, -0.5, 3.0], vec![3]);
+        let elu_id = graph.generate_function_from_function_contents(Box::new(ELU::new(2.0)));
+        let x_id = graph.generate_variable_from_f64_tensor(x.clone(), "x");
+        let y_ids = graph.forward(elu_id, vec![x_id]);
+
+        graph.backward(y_ids, false, false);
+
+        let x_grad = graph.get_variable_grad_contents_f64(x_id).unwrap();
+        let expected = numerical_diff(&mut |x| ELU::elu(x, 2.0), &x, 1e-6);
+        expected.data().iter().zip(x_grad.data().iter())
+            .for_each(|(x, y)| assert_approx_eq(*x.data(), *y.data(), 1e-6));
+    }
+
+    #[test]
+    fn backward_backward_normal() {
+        let mut graph = Graph::new();
+
+        let elu_id = graph.generate_function_from_function_contents(Box::new(ELU::new(2.0)));
+        let x_id = graph.generate_variable_from_f64_tensor(
+            Tensor::new_from_num_vec(vec![-1.0, 3.0], vec![2]), "x");
+        let y_ids = graph.forward(elu_id, vec![x_id]);
+
+        graph.backward(y_ids, false, true);
+
+        let x_grad_id = graph.get_variable_grad_id(x_id).unwrap();
+        graph.clear_grad(x_id);
+
+        graph.backward(vec![x_grad_id], false, false);
+
+        // alpha * exp(x) for a negative x and 0 otherwise
+        let x_grad = graph.get_variable_grad_contents_f64(x_id).unwrap();
+        assert_approx_eq(*x_grad.data()[0].data(), 2.0 * (-1.0_f64).exp(), 1e-12);
+        assert_eq!(*x_grad.data()[1].data(), 0.0);
+    }
+}
@@ -0,0 +1,118 @@
+use std::any::Any;
+use super::{Mul, Neg, Square, Exp};
+use super::super::FunctionContents;
+use ktensor::Tensor;
+use crate::variable::VariableTable;
+use crate::error::KdezeroError;
+use crate::graph::Graph;
+
+/// Error function
+#[derive(Debug, Clone)]
+pub struct Erf {}
+
+impl Erf {
+    pub fn new() -> Self {
+        Self {}
+    }
+
+    fn input_check(inputs: &Vec<usize>) -> Result<(), KdezeroError> {
+        if inputs.len() != 1 {
+            return Err(KdezeroError::InvalidInputCount { function: "Erf".to_string(), expected: 1, actual: inputs.len() });
+        }
+        Ok(())
+    }
+
+    fn output_check(outputs: &Vec<usize>) -> Result<(), KdezeroError> {
+        if outputs.len() != 1 {
+            return Err(KdezeroError::InvalidOutputCount { function: "Erf".to_string(), expected: 1, actual: outputs.len() });
+        }
+        Ok(())
+    }
+}
+
+impl FunctionContents for Erf {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "Erf"
+    }
+
+    fn forward(&self, _info: &crate::function::FunctionInfo, inputs: &Vec<usize>, variable_table: &mut VariableTable) -> Vec<usize> {
+        Erf::input_check(inputs).unwrap_or_else(|e| panic!("{}", e));
+        let x = variable_table.get_variable_contents_f64(inputs[0]).expect("Invalid variable id");
+
+        let output = x.erf();
+
+        let output_id = variable_table.generate_variable_from_f64_tensor(output, "");
+        vec![output_id]
+    }
+
+    fn get_backward(&self) -> fn(usize, &mut Graph) -> Result<Vec<usize>, KdezeroError> {
+        |function_id, graph| {
+            let function = graph.get_function(function_id).ok_or(KdezeroError::InvalidFunctionId { id: function_id })?;
+            let inputs = function.try_get_inputs()?;
+            let outputs = function.try_get_outputs()?;
+            Erf::input_check(inputs)?;
+            Erf::output_check(outputs)?;
+            let input_id = inputs[0];
+            let output_id = outputs[0];
+            let output_grad_id = graph.get_variable_grad_id(output_id).ok_or_else(|| KdezeroError::GradNotFound { function: "Erf".to_string(), id: output_id })?;
+
+            // grad * 2 / sqrt(pi) * exp(-x^2)
+            let const_id = graph.generate_variable_from_f64_tensor(
+                Tensor::full(std::f64::consts::FRAC_2_SQRT_PI, vec![]), "");
+            let square_id = graph.generate_function_from_function_contents(Box::new(Square::new()));
+            let temp_id = graph.try_forward(square_id, vec![input_id])?[0];
+            let neg_id = graph.generate_function_from_function_contents(Box::new(Neg::new()));
+            let temp_id = graph.try_forward(neg_id, vec![temp_id])?[0];
+            let exp_id = graph.generate_function_from_function_contents(Box::new(Exp::new()));
+            let temp_id = graph.try_forward(exp_id, vec![temp_id])?[0];
+            let mul_id = graph.generate_function_from_function_contents(Box::new(Mul::new()));
+            let temp_id = graph.try_forward(mul_id, vec![const_id, temp_id])?[0];
+            let mul_id = graph.generate_function_from_function_contents(Box::new(Mul::new()));
+            let grad_id = graph.try_forward(mul_id, vec![output_grad_id, temp_id])?[0];
+
+            graph.try_update_grad(input_id, grad_id)?;
+
+            Ok(vec![input_id])
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ktensor::utility::{assert_approx_eq, numerical_diff};
+
+    #[test]
+    fn forward_normal() {
+        let mut graph = Graph::new();
+
+        let x = Tensor::new_from_num_vec(vec![-1.0, 0.0, 0.5], vec![3]);
+        let erf_id = graph.generate_function_from_function_contents(Box::new(Erf::new()));
+        let x_id = graph.generate_variable_from_f64_tensor(x.clone(), "x");
+        let y_id = graph.forward(erf_id, vec![x_id]);
+
+        let y = graph.get_variable_contents_f64(y_id[0]).unwrap();
+        assert_eq!(y, &x.erf());
+    }
+
+    #[test]
+    fn backward_normal() {
+        let mut graph = Graph::new();
+
+        let x = Tensor::new_from_num_vec(vec![-1.0, 0.0, 0.5], vec![3]);
+        let erf_id = graph.generate_function_from_function_contents(Box::new(Erf::new()));
+        let x_id = graph.generate_variable_from_f64_tensor(x.clone(), "x");
+        let y_ids = graph.forward(erf_id, vec![x_id]);
+
+        graph.backward(y_ids, false, false);
+
+        let x_grad = graph.get_variable_grad_contents_f64(x_id).unwrap();
+        let expected = numerical_diff(&mut |x: &Tensor<f64>| x.erf(), &x, 1e-6);
+        expected.data().iter().zip(x_grad.data().iter())
+            .for_each(|(x, y)| assert_approx_eq(*x.data(), *y.data(), 1e-6));
+    }
+}
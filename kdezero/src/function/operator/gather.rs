@@ -0,0 +1,146 @@
+use std::any::Any;
+use super::ScatterAdd;
+use super::super::{FunctionContents, get_inputs_f64};
+use crate::error::KdezeroError;
+use crate::variable::VariableTable;
+use crate::graph::Graph;
+use ktensor::Tensor;
+
+/// Values of the input gathered along the axis
+/// 
+/// The output at `i` is the input at `i` with the index of the axis replaced by `indexes[i]`.
+/// The gradient is scatter-added into zeros in the shape of the input.
+#[derive(Debug, Clone)]
+pub struct Gather {
+    axis: usize,
+    indexes: Tensor<usize>,
+}
+
+impl Gather {
+    /// Create a new Gather instance.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `axis` - Axis to gather along
+    /// * `indexes` - Indexes with the shape of the input except for the axis
+    pub fn new(axis: usize, indexes: Tensor<usize>) -> Self {
+        Self { axis, indexes }
+    }
+
+    pub fn get_axis(&self) -> usize {
+        self.axis
+    }
+
+    pub fn get_indexes(&self) -> &Tensor<usize> {
+        &self.indexes
+    }
+
+    fn input_check(inputs: &Vec<usize>) -> Result<(), KdezeroError> {
+        if inputs.len() != 1 {
+            return Err(KdezeroError::InvalidInputCount { function: "Gather".to_string(), expected: 1, actual: inputs.len() });
+        }
+        Ok(())
+    }
+
+    fn output_check(outputs: &Vec<usize>) -> Result<(), KdezeroError> {
+        if outputs.len() != 1 {
+            return Err(KdezeroError::InvalidOutputCount { function: "Gather".to_string(), expected: 1, actual: outputs.len() });
+        }
+        Ok(())
+    }
+}
+
+impl FunctionContents for Gather {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "Gather"
+    }
+
+    fn forward(&self, info: &crate::function::FunctionInfo, inputs: &Vec<usize>, variable_table: &mut VariableTable) -> Vec<usize> {
+        self.try_forward(info, inputs, variable_table).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    fn try_forward(&self, _info: &crate::function::FunctionInfo, inputs: &Vec<usize>, variable_table: &mut VariableTable) -> Result<Vec<usize>, KdezeroError> {
+        let [x] = get_inputs_f64(self.name(), inputs, variable_table)?;
+
+        let output = x.try_gather(self.axis, &self.indexes)
+            .map_err(|e| KdezeroError::invalid_input(self.name(), 0, x.shape(), e))?;
+
+        let output_id = variable_table.generate_variable_from_f64_tensor(output, "");
+        Ok(vec![output_id])
+    }
+
+    fn get_backward(&self) -> fn(usize, &mut Graph) -> Result<Vec<usize>, KdezeroError> {
+        |function_id, graph| {
+            let function = graph.get_function(function_id).ok_or(KdezeroError::InvalidFunctionId { id: function_id })?;
+            let function_contents = function.get_function_contents::<Gather>().ok_or(KdezeroError::InvalidFunctionId { id: function_id })?;
+            let (axis, indexes) = (function_contents.get_axis(), function_contents.get_indexes().clone());
+            let inputs = function.try_get_inputs()?;
+            let outputs = function.try_get_outputs()?;
+            Gather::input_check(inputs)?;
+            Gather::output_check(outputs)?;
+            let input_id = inputs[0];
+            let input_shape = graph.get_variable(input_id).ok_or(KdezeroError::InvalidVariableId { id: input_id })?.shape().clone();
+            let output_id = outputs[0];
+            let output_grad_id = graph.get_variable_grad_id(output_id).ok_or_else(|| KdezeroError::GradNotFound { function: "Gather".to_string(), id: output_id })?;
+
+            let zeros_id = graph.generate_variable_from_f64_tensor(Tensor::full(0.0, input_shape), "");
+            let scatter_add_id = graph.generate_function_from_function_contents(Box::new(ScatterAdd::new(axis, indexes)));
+            let grad_id = graph.try_forward(scatter_add_id, vec![zeros_id, output_grad_id])?[0];
+
+            graph.try_update_grad(input_id, grad_id)?;
+
+            Ok(vec![input_id])
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ktensor::TensorError;
+
+    #[test]
+    fn forward_normal() {
+        let mut graph = Graph::new();
+
+        let gather_id = graph.generate_function_from_function_contents(
+            Box::new(Gather::new(1, Tensor::new_from_num_vec(vec![2, 0, 1, 1], vec![2, 2]))));
+        let x_id = graph.generate_variable_from_f64_tensor(Tensor::arrange([2, 3]), "x");
+        let y_id = graph.forward(gather_id, vec![x_id]);
+
+        let y = graph.get_variable_contents_f64(y_id[0]).unwrap();
+        assert_eq!(y, &Tensor::new_from_num_vec(vec![2.0, 0.0, 4.0, 4.0], vec![2, 2]));
+    }
+
+    #[test]
+    fn backward_normal() {
+        let mut graph = Graph::new();
+
+        let gather_id = graph.generate_function_from_function_contents(
+            Box::new(Gather::new(1, Tensor::new_from_num_vec(vec![2, 0, 1, 1], vec![2, 2]))));
+        let x_id = graph.generate_variable_from_f64_tensor(Tensor::arrange([2, 3]), "x");
+        let y_ids = graph.forward(gather_id, vec![x_id]);
+
+        graph.backward(y_ids, false, false);
+
+        let x_grad = graph.get_variable_grad_contents_f64(x_id).unwrap();
+        assert_eq!(x_grad, &Tensor::new_from_num_vec(vec![1.0, 0.0, 1.0, 0.0, 2.0, 0.0], vec![2, 3]));
+    }
+
+    #[test]
+    fn try_forward_error_index() {
+        let mut graph = Graph::new();
+
+        let gather_id = graph.generate_function_from_function_contents(
+            Box::new(Gather::new(0, Tensor::new_from_num_vec(vec![2], vec![1]))));
+        let x_id = graph.generate_variable_from_f64_tensor(Tensor::arrange([2]), "x");
+        let error = graph.try_forward(gather_id, vec![x_id]).unwrap_err();
+
+        assert_eq!(error, KdezeroError::invalid_input("Gather", 0, &[2],
+            TensorError::IndexOutOfRange { index: vec![2], shape: vec![2] }));
+    }
+}
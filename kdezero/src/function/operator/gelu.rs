@@ -0,0 +1,174 @@
+use std::any::Any;
+use super::{Add, Mul, Square, Exp, Erf};
+use super::super::FunctionContents;
+use ktensor::{Tensor, Scaler};
+use crate::variable::VariableTable;
+use crate::error::KdezeroError;
+use crate::graph::Graph;
+
+/// Gaussian error linear unit, x * Φ(x)
+/// 
+/// Φ is the cumulative distribution function of the standard normal distribution,
+/// computed exactly with the error function.
+#[derive(Debug, Clone)]
+pub struct GELU {}
+
+impl GELU {
+    pub fn new() -> Self {
+        Self {}
+    }
+
+    /// Compute GELU of each element of the tensor.
+    pub(crate) fn gelu(x: &Tensor<f64>) -> Tensor<f64> {
+        x.map(|x| {
+            let cdf = 0.5 * (1.0 + Scaler::new(x.data() * std::f64::consts::FRAC_1_SQRT_2).erf().data());
+            (x.data() * cdf).into()
+        })
+    }
+
+    fn input_check(inputs: &Vec<usize>) -> Result<(), KdezeroError> {
+        if inputs.len() != 1 {
+            return Err(KdezeroError::InvalidInputCount { function: "GELU".to_string(), expected: 1, actual: inputs.len() });
+        }
+        Ok(())
+    }
+
+    fn output_check(outputs: &Vec<usize>) -> Result<(), KdezeroError> {
+        if outputs.len() != 1 {
+            return Err(KdezeroError::InvalidOutputCount { function: "GELU".to_string(), expected: 1, actual: outputs.len() });
+        }
+        Ok(())
+    }
+}
+
+impl FunctionContents for GELU {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "GELU"
+    }
+
+    fn forward(&self, _info: &crate::function::FunctionInfo, inputs: &Vec<usize>, variable_table: &mut VariableTable) -> Vec<usize> {
+        GELU::input_check(inputs).unwrap_or_else(|e| panic!("{}", e));
+        let x = variable_table.get_variable_contents_f64(inputs[0]).expect("Invalid variable id");
+
+        let output = GELU::gelu(x);
+
+        let output_id = variable_table.generate_variable_from_f64_tensor(output, "");
+        vec![output_id]
+    }
+
+    fn get_backward(&self) -> fn(usize, &mut Graph) -> Result<Vec<usize>, KdezeroError> {
+        |function_id, graph| {
+            let function = graph.get_function(function_id).ok_or(KdezeroError::InvalidFunctionId { id: function_id })?;
+            let inputs = function.try_get_inputs()?;
+            let outputs = function.try_get_outputs()?;
+            GELU::input_check(inputs)?;
+            GELU::output_check(outputs)?;
+            let input_id = inputs[0];
+            let output_id = outputs[0];
+            let output_grad_id = graph.get_variable_grad_id(output_id).ok_or_else(|| KdezeroError::GradNotFound { function: "GELU".to_string(), id: output_id })?;
+
+            // Φ(x) = 0.5 * (1 + erf(x / sqrt(2)))
+            let half_id = graph.generate_variable_from_f64_tensor(Tensor::full(0.5, vec![]), "");
+            let one_id = graph.generate_variable_from_f64_tensor(Tensor::full(1.0, vec![]), "");
+            let scale_id = graph.generate_variable_from_f64_tensor(
+                Tensor::full(std::f64::consts::FRAC_1_SQRT_2, vec![]), "");
+            let mul_id = graph.generate_function_from_function_contents(Box::new(Mul::new()));
+            let temp_id = graph.try_forward(mul_id, vec![input_id, scale_id])?[0];
+            let erf_id = graph.generate_function_from_function_contents(Box::new(Erf::new()));
+            let temp_id = graph.try_forward(erf_id, vec![temp_id])?[0];
+            let add_id = graph.generate_function_from_function_contents(Box::new(Add::new()));
+            let temp_id = graph.try_forward(add_id, vec![one_id, temp_id])?[0];
+            let mul_id = graph.generate_function_from_function_contents(Box::new(Mul::new()));
+            let cdf_id = graph.try_forward(mul_id, vec![half_id, temp_id])?[0];
+
+            // x * φ(x) = x * exp(-x^2 / 2) / sqrt(2 * pi)
+            let neg_half_id = graph.generate_variable_from_f64_tensor(Tensor::full(-0.5, vec![]), "");
+            let norm_id = graph.generate_variable_from_f64_tensor(
+                Tensor::full(0.5 * std::f64::consts::FRAC_2_SQRT_PI * std::f64::consts::FRAC_1_SQRT_2, vec![]), "");
+            let square_id = graph.generate_function_from_function_contents(Box::new(Square::new()));
+            let temp_id = graph.try_forward(square_id, vec![input_id])?[0];
+            let mul_id = graph.generate_function_from_function_contents(Box::new(Mul::new()));
+            let temp_id = graph.try_forward(mul_id, vec![neg_half_id, temp_id])?[0];
+            let exp_id = graph.generate_function_from_function_contents(Box::new(Exp::new()));
+            let temp_id = graph.try_forward(exp_id, vec![temp_id])?[0];
+            let mul_id = graph.generate_function_from_function_contents(Box::new(Mul::new()));
+            let temp_id = graph.try_forward(mul_id, vec![norm_id, temp_id])?[0];
+            let mul_id = graph.generate_function_from_function_contents(Box::new(Mul::new()));
+            let pdf_id = graph.try_forward(mul_id, vec![input_id, temp_id])?[0];
+
+            // grad * (Φ(x) + x * φ(x))
+            let add_id = graph.generate_function_from_function_contents(Box::new(Add::new()));
+            let temp_id = graph.try_forward(add_id, vec![cdf_id, pdf_id])?[0];
+            let mul_id = graph.generate_function_from_function_contents(Box::new(Mul::new()));
+            let grad_id = graph.try_forward(mul_id, vec![output_grad_id, temp_id])?[0];
+
+            graph.try_update_grad(input_id, grad_id)?;
+
+            Ok(vec![input_id])
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ktensor::utility::{assert_approx_eq, numerical_diff};
+
+    #[test]
+    fn forward_normal() {
+        let mut graph = Graph::new();
+
+        let gelu_id = graph.generate_function_from_function_contents(Box::new(GELU::new()));
+        let x_id = graph.generate_variable_from_f64_tensor(
+            Tensor::new_from_num_vec(vec![-1.0, 0.0, 1.0], vec![3]), "x");
+        let y_id = graph.forward(gelu_id, vec![x_id]);
+
+        let y = graph.get_variable_contents_f64(y_id[0]).unwrap();
+        [-0.15865525393145707, 0.0, 0.8413447460685429].iter().zip(y.data().iter())
+            .for_each(|(x, y)| assert_approx_eq(*x, *y.data(), 1e-12));
+    }
+
+    #[test]
+    fn backward_normal() {
+        let mut graph = Graph::new();
+
+        let x = Tensor::new_from_num_vec(vec![-2.0, 0.0, 1.5], vec![3]);
+        let gelu_id = graph.generate_function_from_function_contents(Box::new(GELU::new()));
+        let x_id = graph.generate_variable_from_f64_tensor(x.clone(), "x");
+        let y_ids = graph.forward(gelu_id, vec![x_id]);
+
+        graph.backward(y_ids, false, false);
+
+        let x_grad = graph.get_variable_grad_contents_f64(x_id).unwrap();
+        let expected = numerical_diff(&mut |x| GELU::gelu(x), &x, 1e-6);
+        expected.data().iter().zip(x_grad.data().iter())
+            .for_each(|(x, y)| assert_approx_eq(*x.data(), *y.data(), 1e-6));
+    }
+
+    #[test]
+    fn backward_backward_normal() {
+        let mut graph = Graph::new();
+
+        let gelu_id = graph.generate_function_from_function_contents(Box::new(GELU::new()));
+        let x_id = graph.generate_variable_from_f64_tensor(
+            Tensor::new_from_num_vec(vec![0.0, 1.0], vec![2]), "x");
+        let y_ids = graph.forward(gelu_id, vec![x_id]);
+
+        graph.backward(y_ids, false, true);
+
+        let x_grad_id = graph.get_variable_grad_id(x_id).unwrap();
+        graph.clear_grad(x_id);
+
+        graph.backward(vec![x_grad_id], false, false);
+
+        // φ(x) * (2 - x^2)
+        let pdf = |x: f64| (-x * x / 2.0).exp() / (2.0 * std::f64::consts::PI).sqrt();
+        let x_grad = graph.get_variable_grad_contents_f64(x_id).unwrap();
+        assert_approx_eq(*x_grad.data()[0].data(), 2.0 * pdf(0.0), 1e-12);
+        assert_approx_eq(*x_grad.data()[1].data(), pdf(1.0), 1e-12);
+    }
+}
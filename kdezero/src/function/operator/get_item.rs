@@ -0,0 +1,164 @@
+use std::any::Any;
+use super::GetItemGrad;
+use super::super::{FunctionContents, get_inputs_f64};
+use crate::error::KdezeroError;
+use crate::variable::VariableTable;
+use crate::graph::Graph;
+use ktensor::SliceIndex;
+
+/// Slice of the input with an index for each axis
+/// 
+/// The gradient is added to the sliced elements of zeros in the shape of the input,
+/// so the gradients of a repeated index are summed.
+#[derive(Debug, Clone)]
+pub struct GetItem {
+    indexes: Vec<SliceIndex>,
+}
+
+impl GetItem {
+    /// Create a new GetItem instance.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `indexes` - Index of each axis. The axes after them are kept whole
+    pub fn new(indexes: Vec<SliceIndex>) -> Self {
+        Self { indexes }
+    }
+
+    pub fn get_indexes(&self) -> &Vec<SliceIndex> {
+        &self.indexes
+    }
+
+    fn input_check(inputs: &Vec<usize>) -> Result<(), KdezeroError> {
+        if inputs.len() != 1 {
+            return Err(KdezeroError::InvalidInputCount { function: "GetItem".to_string(), expected: 1, actual: inputs.len() });
+        }
+        Ok(())
+    }
+
+    fn output_check(outputs: &Vec<usize>) -> Result<(), KdezeroError> {
+        if outputs.len() != 1 {
+            return Err(KdezeroError::InvalidOutputCount { function: "GetItem".to_string(), expected: 1, actual: outputs.len() });
+        }
+        Ok(())
+    }
+}
+
+impl FunctionContents for GetItem {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "GetItem"
+    }
+
+    fn forward(&self, info: &crate::function::FunctionInfo, inputs: &Vec<usize>, variable_table: &mut VariableTable) -> Vec<usize> {
+        self.try_forward(info, inputs, variable_table).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    fn try_forward(&self, _info: &crate::function::FunctionInfo, inputs: &Vec<usize>, variable_table: &mut VariableTable) -> Result<Vec<usize>, KdezeroError> {
+        let [x] = get_inputs_f64(self.name(), inputs, variable_table)?;
+
+        let output = x.try_slice(&self.indexes)
+            .map_err(|e| KdezeroError::invalid_input(self.name(), 0, x.shape(), e))?;
+
+        let output_id = variable_table.generate_variable_from_f64_tensor(output, "");
+        Ok(vec![output_id])
+    }
+
+    fn get_backward(&self) -> fn(usize, &mut Graph) -> Result<Vec<usize>, KdezeroError> {
+        |function_id, graph| {
+            let function = graph.get_function(function_id).ok_or(KdezeroError::InvalidFunctionId { id: function_id })?;
+            let indexes = function.get_function_contents::<GetItem>().ok_or(KdezeroError::InvalidFunctionId { id: function_id })?.get_indexes().clone();
+            let inputs = function.try_get_inputs()?;
+            let outputs = function.try_get_outputs()?;
+            GetItem::input_check(inputs)?;
+            GetItem::output_check(outputs)?;
+            let input_id = inputs[0];
+            let input_shape = graph.get_variable(input_id).ok_or(KdezeroError::InvalidVariableId { id: input_id })?.shape().clone();
+            let output_id = outputs[0];
+            let output_grad_id = graph.get_variable_grad_id(output_id).ok_or_else(|| KdezeroError::GradNotFound { function: "GetItem".to_string(), id: output_id })?;
+
+            let get_item_grad_id = graph.generate_function_from_function_contents(Box::new(GetItemGrad::new(indexes, input_shape)));
+            let grad_id = graph.try_forward(get_item_grad_id, vec![output_grad_id])?[0];
+
+            graph.try_update_grad(input_id, grad_id)?;
+
+            Ok(vec![input_id])
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ktensor::{Tensor, TensorError};
+
+    #[test]
+    fn forward_normal() {
+        let mut graph = Graph::new();
+
+        let get_item_id = graph.generate_function_from_function_contents(
+            Box::new(GetItem::new(vec![(1..3).into(), SliceIndex::step(0, 4, 2)])));
+        let x_id = graph.generate_variable_from_f64_tensor(Tensor::arrange([3, 4]), "x");
+        let y_id = graph.forward(get_item_id, vec![x_id]);
+
+        let y = graph.get_variable_contents_f64(y_id[0]).unwrap();
+        assert_eq!(y, &Tensor::new_from_num_vec(vec![4.0, 6.0, 8.0, 10.0], vec![2, 2]));
+    }
+
+    #[test]
+    fn backward_normal() {
+        let mut graph = Graph::new();
+
+        let get_item_id = graph.generate_function_from_function_contents(
+            Box::new(GetItem::new(vec![vec![2, 0, 2].into(), 1.into()])));
+        let x_id = graph.generate_variable_from_f64_tensor(Tensor::arrange([3, 2]), "x");
+        let y_ids = graph.forward(get_item_id, vec![x_id]);
+
+        let y = graph.get_variable_contents_f64(y_ids[0]).unwrap();
+        assert_eq!(y, &Tensor::new_from_num_vec(vec![5.0, 1.0, 5.0], vec![3]));
+
+        graph.backward(y_ids, false, false);
+
+        let x_grad = graph.get_variable_grad_contents_f64(x_id).unwrap();
+        assert_eq!(x_grad, &Tensor::new_from_num_vec(vec![0.0, 1.0, 0.0, 0.0, 0.0, 2.0], vec![3, 2]));
+    }
+
+    #[test]
+    fn backward_backward_normal() {
+        let mut graph = Graph::new();
+
+        let get_item_id = graph.generate_function_from_function_contents(Box::new(GetItem::new(vec![vec![1, 1].into()])));
+        let x_id = graph.generate_variable_from_f64_tensor(Tensor::new_from_num_vec(vec![1.0, 2.0], vec![2]), "x");
+        let y_id = graph.forward(get_item_id, vec![x_id])[0];
+        let pow_id = graph.generate_function_from_function_contents(Box::new(super::super::Pow::new(3.0)));
+        let z_id = graph.forward(pow_id, vec![y_id])[0];
+
+        graph.backward(vec![z_id], false, true);
+
+        let x_grad_id = graph.get_variable_grad_id(x_id).unwrap();
+        assert_eq!(graph.get_variable_contents_f64(x_grad_id).unwrap(),
+            &Tensor::new_from_num_vec(vec![0.0, 24.0], vec![2]));
+        graph.clear_grad(x_id);
+
+        graph.backward(vec![x_grad_id], false, false);
+
+        // sum of 6 * x for each selection
+        let x_grad = graph.get_variable_grad_contents_f64(x_id).unwrap();
+        assert_eq!(x_grad, &Tensor::new_from_num_vec(vec![0.0, 24.0], vec![2]));
+    }
+
+    #[test]
+    fn try_forward_error_index() {
+        let mut graph = Graph::new();
+
+        let get_item_id = graph.generate_function_from_function_contents(Box::new(GetItem::new(vec![3.into()])));
+        let x_id = graph.generate_variable_from_f64_tensor(Tensor::arrange([3]), "x");
+        let error = graph.try_forward(get_item_id, vec![x_id]).unwrap_err();
+
+        assert_eq!(error, KdezeroError::invalid_input("GetItem", 0, &[3],
+            TensorError::IndexOutOfRange { index: vec![3], shape: vec![3] }));
+    }
+}
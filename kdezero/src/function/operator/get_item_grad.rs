@@ -0,0 +1,124 @@
+use std::any::Any;
+use super::GetItem;
+use super::super::{FunctionContents, get_inputs_f64};
+use crate::error::KdezeroError;
+use crate::variable::VariableTable;
+use crate::graph::Graph;
+use ktensor::{Tensor, SliceIndex};
+
+/// Scatter-add of the input into the slice of zeros, the gradient of GetItem
+/// 
+/// The gradient is the slice of the gradient of the output.
+#[derive(Debug, Clone)]
+pub struct GetItemGrad {
+    indexes: Vec<SliceIndex>,
+    shape: Vec<usize>,
+}
+
+impl GetItemGrad {
+    /// Create a new GetItemGrad instance.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `indexes` - Index of each axis of the slice
+    /// * `shape` - Shape of the output
+    pub fn new(indexes: Vec<SliceIndex>, shape: Vec<usize>) -> Self {
+        Self { indexes, shape }
+    }
+
+    pub fn get_indexes(&self) -> &Vec<SliceIndex> {
+        &self.indexes
+    }
+
+    fn input_check(inputs: &Vec<usize>) -> Result<(), KdezeroError> {
+        if inputs.len() != 1 {
+            return Err(KdezeroError::InvalidInputCount { function: "GetItemGrad".to_string(), expected: 1, actual: inputs.len() });
+        }
+        Ok(())
+    }
+
+    fn output_check(outputs: &Vec<usize>) -> Result<(), KdezeroError> {
+        if outputs.len() != 1 {
+            return Err(KdezeroError::InvalidOutputCount { function: "GetItemGrad".to_string(), expected: 1, actual: outputs.len() });
+        }
+        Ok(())
+    }
+}
+
+impl FunctionContents for GetItemGrad {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "GetItemGrad"
+    }
+
+    fn forward(&self, info: &crate::function::FunctionInfo, inputs: &Vec<usize>, variable_table: &mut VariableTable) -> Vec<usize> {
+        self.try_forward(info, inputs, variable_table).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    fn try_forward(&self, _info: &crate::function::FunctionInfo, inputs: &Vec<usize>, variable_table: &mut VariableTable) -> Result<Vec<usize>, KdezeroError> {
+        let [x] = get_inputs_f64(self.name(), inputs, variable_table)?;
+
+        let output = Tensor::full(0.0, self.shape.clone()).try_slice_add(&self.indexes, x)
+            .map_err(|e| KdezeroError::invalid_input(self.name(), 0, x.shape(), e))?;
+
+        let output_id = variable_table.generate_variable_from_f64_tensor(output, "");
+        Ok(vec![output_id])
+    }
+
+    fn get_backward(&self) -> fn(usize, &mut Graph) -> Result<Vec<usize>, KdezeroError> {
+        |function_id, graph| {
+            let function = graph.get_function(function_id).ok_or(KdezeroError::InvalidFunctionId { id: function_id })?;
+            let indexes = function.get_function_contents::<GetItemGrad>().ok_or(KdezeroError::InvalidFunctionId { id: function_id })?.get_indexes().clone();
+            let inputs = function.try_get_inputs()?;
+            let outputs = function.try_get_outputs()?;
+            GetItemGrad::input_check(inputs)?;
+            GetItemGrad::output_check(outputs)?;
+            let input_id = inputs[0];
+            let output_id = outputs[0];
+            let output_grad_id = graph.get_variable_grad_id(output_id).ok_or_else(|| KdezeroError::GradNotFound { function: "GetItemGrad".to_string(), id: output_id })?;
+
+            let get_item_id = graph.generate_function_from_function_contents(Box::new(GetItem::new(indexes)));
+            let grad_id = graph.try_forward(get_item_id, vec![output_grad_id])?[0];
+
+            graph.try_update_grad(input_id, grad_id)?;
+
+            Ok(vec![input_id])
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn forward_normal() {
+        let mut graph = Graph::new();
+
+        let get_item_grad_id = graph.generate_function_from_function_contents(
+            Box::new(GetItemGrad::new(vec![vec![0, 2, 0].into()], vec![3, 2])));
+        let x_id = graph.generate_variable_from_f64_tensor(Tensor::arrange([3, 2]), "x");
+        let y_id = graph.forward(get_item_grad_id, vec![x_id]);
+
+        let y = graph.get_variable_contents_f64(y_id[0]).unwrap();
+        assert_eq!(y, &Tensor::new_from_num_vec(vec![4.0, 6.0, 0.0, 0.0, 2.0, 3.0], vec![3, 2]));
+    }
+
+    #[test]
+    fn backward_normal() {
+        let mut graph = Graph::new();
+
+        let get_item_grad_id = graph.generate_function_from_function_contents(
+            Box::new(GetItemGrad::new(vec![vec![0, 2, 0].into()], vec![3, 2])));
+        let x_id = graph.generate_variable_from_f64_tensor(Tensor::arrange([3, 2]), "x");
+        let y_ids = graph.forward(get_item_grad_id, vec![x_id]);
+
+        graph.backward(y_ids, false, false);
+
+        let x_grad = graph.get_variable_grad_contents_f64(x_id).unwrap();
+        assert_eq!(x_grad, &Tensor::full(1.0, vec![3, 2]));
+    }
+}
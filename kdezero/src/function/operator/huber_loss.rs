@@ -0,0 +1,179 @@
+use std::any::Any;
+use super::{Sub, Clip, Reduction};
+use super::reduction::{get_loss_inputs, loss_shape, difference_backward};
+use super::super::FunctionContents;
+use crate::error::KdezeroError;
+use crate::variable::VariableTable;
+use crate::graph::Graph;
+use ktensor::Tensor;
+
+/// Huber loss of the inputs
+/// 
+/// With `d = x - t`, the loss is `d^2 / 2` for `|d| <= delta` and `delta * (|d| - delta / 2)` otherwise.
+/// The inputs are x, t and the optional weights, broadcast to a common shape.
+/// The weights multiply each elementwise loss and get no gradient.
+#[derive(Debug, Clone)]
+pub struct HuberLoss {
+    delta: f64,
+    reduction: Reduction,
+}
+
+impl HuberLoss {
+    /// Create a new HuberLoss instance, which takes the mean of the losses.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `delta` - Threshold between the quadratic and the linear part
+    /// 
+    /// # Panics
+    /// 
+    /// Panics if `delta` is not positive.
+    pub fn new(delta: f64) -> Self {
+        Self::with_reduction(delta, Reduction::Mean)
+    }
+
+    /// Create a new HuberLoss instance with the reduction.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `delta` - Threshold between the quadratic and the linear part
+    /// * `reduction` - Reduction of the elementwise losses
+    /// 
+    /// # Panics
+    /// 
+    /// Panics if `delta` is not positive.
+    pub fn with_reduction(delta: f64, reduction: Reduction) -> Self {
+        if delta.is_nan() || delta <= 0.0 {
+            panic!("HuberLoss function needs a positive delta, but got {}.", delta);
+        }
+        Self { delta, reduction }
+    }
+
+    pub fn get_delta(&self) -> f64 {
+        self.delta
+    }
+
+    pub fn get_reduction(&self) -> Reduction {
+        self.reduction
+    }
+
+    /// Compute the Huber loss of each element of the difference.
+    pub(crate) fn huber(d: &Tensor<f64>, delta: f64) -> Tensor<f64> {
+        d.map(|d| {
+            let a = d.data().abs();
+            (if a <= delta { 0.5 * a * a } else { delta * (a - 0.5 * delta) }).into()
+        })
+    }
+
+    fn input_check(inputs: &Vec<usize>) -> Result<(), KdezeroError> {
+        if inputs.len() != 2 && inputs.len() != 3 {
+            return Err(KdezeroError::InvalidInputCount { function: "HuberLoss".to_string(), expected: 2, actual: inputs.len() });
+        }
+        Ok(())
+    }
+
+    fn output_check(outputs: &Vec<usize>) -> Result<(), KdezeroError> {
+        if outputs.len() != 1 {
+            return Err(KdezeroError::InvalidOutputCount { function: "HuberLoss".to_string(), expected: 1, actual: outputs.len() });
+        }
+        Ok(())
+    }
+}
+
+impl FunctionContents for HuberLoss {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "HuberLoss"
+    }
+
+    fn forward(&self, info: &crate::function::FunctionInfo, inputs: &Vec<usize>, variable_table: &mut VariableTable) -> Vec<usize> {
+        self.try_forward(info, inputs, variable_table).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    fn try_forward(&self, _info: &crate::function::FunctionInfo, inputs: &Vec<usize>, variable_table: &mut VariableTable) -> Result<Vec<usize>, KdezeroError> {
+        let ([x, t], weights) = get_loss_inputs(self.name(), inputs, variable_table)?;
+        match weights {
+            Some(weights) => loss_shape(self.name(), &[x, t, weights])?,
+            None => loss_shape(self.name(), &[x, t])?,
+        };
+
+        let output = self.reduction.reduce(HuberLoss::huber(&(x - t), self.delta), weights);
+
+        let output_id = variable_table.generate_variable_from_f64_tensor(output, "");
+        Ok(vec![output_id])
+    }
+
+    fn get_backward(&self) -> fn(usize, &mut Graph) -> Result<Vec<usize>, KdezeroError> {
+        |function_id, graph| {
+            let function = graph.get_function(function_id).ok_or(KdezeroError::InvalidFunctionId { id: function_id })?;
+            let function_contents = function.get_function_contents::<HuberLoss>().ok_or(KdezeroError::InvalidFunctionId { id: function_id })?;
+            let (delta, reduction) = (function_contents.get_delta(), function_contents.get_reduction());
+            let inputs = function.try_get_inputs()?;
+            let outputs = function.try_get_outputs()?;
+            HuberLoss::input_check(inputs)?;
+            HuberLoss::output_check(outputs)?;
+            let input_ids = inputs.clone();
+            let output_id = outputs[0];
+            let output_grad_id = graph.get_variable_grad_id(output_id).ok_or_else(|| KdezeroError::GradNotFound { function: "HuberLoss".to_string(), id: output_id })?;
+
+            // grad * clip(x - t, -delta, delta)
+            let sub_id = graph.generate_function_from_function_contents(Box::new(Sub::new()));
+            let diff_id = graph.try_forward(sub_id, vec![input_ids[0], input_ids[1]])?[0];
+            let clip_id = graph.generate_function_from_function_contents(Box::new(Clip::new(-delta, delta)));
+            let local_grad_id = graph.try_forward(clip_id, vec![diff_id])?[0];
+            let shape = graph.get_variable(diff_id).ok_or(KdezeroError::InvalidVariableId { id: diff_id })?.shape().clone();
+            let grad_id = reduction.loss_grad(graph, output_grad_id, input_ids.get(2).copied(), &shape)?;
+
+            difference_backward(graph, grad_id, local_grad_id, &input_ids)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ktensor::utility::{assert_approx_eq, numerical_diff};
+
+    #[test]
+    fn forward_normal() {
+        let mut graph = Graph::new();
+
+        let huber_id = graph.generate_function_from_function_contents(Box::new(HuberLoss::with_reduction(1.0, Reduction::None)));
+        let x_id = graph.generate_variable_from_f64_tensor(Tensor::new_from_num_vec(vec![-3.0, 0.5, 1.0, 2.0], vec![4]), "x");
+        let t_id = graph.generate_variable_from_f64_tensor(Tensor::full(0.0, vec![]), "t");
+        let y_id = graph.forward(huber_id, vec![x_id, t_id]);
+
+        let y = graph.get_variable_contents_f64(y_id[0]).unwrap();
+        assert_eq!(y, &Tensor::new_from_num_vec(vec![2.5, 0.125, 0.5, 1.5], vec![4]));
+    }
+
+    #[test]
+    fn backward_normal() {
+        let mut graph = Graph::new();
+
+        let x = Tensor::new_from_num_vec(vec![-3.0, 0.5, 1.5, 2.0], vec![2, 2]);
+        let huber_id = graph.generate_function_from_function_contents(Box::new(HuberLoss::new(1.0)));
+        let x_id = graph.generate_variable_from_f64_tensor(x.clone(), "x");
+        let t_id = graph.generate_variable_from_f64_tensor(Tensor::new_from_num_vec(vec![0.0, 1.0], vec![2]), "t");
+        let y_ids = graph.forward(huber_id, vec![x_id, t_id]);
+
+        graph.backward(y_ids, false, false);
+
+        let t = Tensor::new_from_num_vec(vec![0.0, 1.0], vec![2]);
+        let expected = numerical_diff(&mut |x| HuberLoss::huber(&(x - &t), 1.0), &x, 1e-6).scalar_div(4.0.into());
+        let x_grad = graph.get_variable_grad_contents_f64(x_id).unwrap();
+        expected.data().iter().zip(x_grad.data().iter())
+            .for_each(|(x, y)| assert_approx_eq(*x.data(), *y.data(), 1e-6));
+        assert_eq!(graph.get_variable_grad_contents_f64(t_id).unwrap(),
+            &Tensor::new_from_num_vec(vec![0.0, -0.125], vec![2]));
+    }
+
+    #[test]
+    #[should_panic]
+    fn new_error_delta() {
+        HuberLoss::new(0.0);
+    }
+}
@@ -0,0 +1,149 @@
+use std::any::Any;
+use super::GetItemGrad;
+use super::super::{FunctionContents, get_inputs_f64};
+use crate::error::KdezeroError;
+use crate::variable::VariableTable;
+use crate::graph::Graph;
+use ktensor::SliceIndex;
+
+/// Selection of the indexes of the input along the axis
+/// 
+/// The gradient is added to the selected indexes of zeros in the shape of the input,
+/// so the gradients of a repeated index are summed, as for the rows of an embedding.
+#[derive(Debug, Clone)]
+pub struct IndexSelect {
+    axis: usize,
+    indexes: Vec<usize>,
+}
+
+impl IndexSelect {
+    /// Create a new IndexSelect instance.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `axis` - Axis to select along
+    /// * `indexes` - Indexes to select, which may repeat
+    pub fn new(axis: usize, indexes: Vec<usize>) -> Self {
+        Self { axis, indexes }
+    }
+
+    pub fn get_axis(&self) -> usize {
+        self.axis
+    }
+
+    pub fn get_indexes(&self) -> &Vec<usize> {
+        &self.indexes
+    }
+
+    /// Make the slice that selects the indexes along the axis.
+    pub(crate) fn slice(axis: usize, indexes: &[usize]) -> Vec<SliceIndex> {
+        let mut slice = vec![SliceIndex::full(); axis];
+        slice.push(SliceIndex::Array(indexes.to_vec()));
+        slice
+    }
+
+    fn input_check(inputs: &Vec<usize>) -> Result<(), KdezeroError> {
+        if inputs.len() != 1 {
+            return Err(KdezeroError::InvalidInputCount { function: "IndexSelect".to_string(), expected: 1, actual: inputs.len() });
+        }
+        Ok(())
+    }
+
+    fn output_check(outputs: &Vec<usize>) -> Result<(), KdezeroError> {
+        if outputs.len() != 1 {
+            return Err(KdezeroError::InvalidOutputCount { function: "IndexSelect".to_string(), expected: 1, actual: outputs.len() });
+        }
+        Ok(())
+    }
+}
+
+impl FunctionContents for IndexSelect {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "IndexSelect"
+    }
+
+    fn forward(&self, info: &crate::function::FunctionInfo, inputs: &Vec<usize>, variable_table: &mut VariableTable) -> Vec<usize> {
+        self.try_forward(info, inputs, variable_table).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    fn try_forward(&self, _info: &crate::function::FunctionInfo, inputs: &Vec<usize>, variable_table: &mut VariableTable) -> Result<Vec<usize>, KdezeroError> {
+        let [x] = get_inputs_f64(self.name(), inputs, variable_table)?;
+
+        let output = x.try_index_select(self.axis, &self.indexes)
+            .map_err(|e| KdezeroError::invalid_input(self.name(), 0, x.shape(), e))?;
+
+        let output_id = variable_table.generate_variable_from_f64_tensor(output, "");
+        Ok(vec![output_id])
+    }
+
+    fn get_backward(&self) -> fn(usize, &mut Graph) -> Result<Vec<usize>, KdezeroError> {
+        |function_id, graph| {
+            let function = graph.get_function(function_id).ok_or(KdezeroError::InvalidFunctionId { id: function_id })?;
+            let function_contents = function.get_function_contents::<IndexSelect>().ok_or(KdezeroError::InvalidFunctionId { id: function_id })?;
+            let slice = IndexSelect::slice(function_contents.get_axis(), function_contents.get_indexes());
+            let inputs = function.try_get_inputs()?;
+            let outputs = function.try_get_outputs()?;
+            IndexSelect::input_check(inputs)?;
+            IndexSelect::output_check(outputs)?;
+            let input_id = inputs[0];
+            let input_shape = graph.get_variable(input_id).ok_or(KdezeroError::InvalidVariableId { id: input_id })?.shape().clone();
+            let output_id = outputs[0];
+            let output_grad_id = graph.get_variable_grad_id(output_id).ok_or_else(|| KdezeroError::GradNotFound { function: "IndexSelect".to_string(), id: output_id })?;
+
+            let get_item_grad_id = graph.generate_function_from_function_contents(Box::new(GetItemGrad::new(slice, input_shape)));
+            let grad_id = graph.try_forward(get_item_grad_id, vec![output_grad_id])?[0];
+
+            graph.try_update_grad(input_id, grad_id)?;
+
+            Ok(vec![input_id])
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ktensor::{Tensor, TensorError};
+
+    #[test]
+    fn forward_normal() {
+        let mut graph = Graph::new();
+
+        let index_select_id = graph.generate_function_from_function_contents(Box::new(IndexSelect::new(1, vec![2, 0])));
+        let x_id = graph.generate_variable_from_f64_tensor(Tensor::arrange([2, 3]), "x");
+        let y_id = graph.forward(index_select_id, vec![x_id]);
+
+        let y = graph.get_variable_contents_f64(y_id[0]).unwrap();
+        assert_eq!(y, &Tensor::new_from_num_vec(vec![2.0, 0.0, 5.0, 3.0], vec![2, 2]));
+    }
+
+    #[test]
+    fn backward_normal() {
+        let mut graph = Graph::new();
+
+        let index_select_id = graph.generate_function_from_function_contents(Box::new(IndexSelect::new(0, vec![1, 3, 1])));
+        let x_id = graph.generate_variable_from_f64_tensor(Tensor::arrange([4, 2]), "x");
+        let y_ids = graph.forward(index_select_id, vec![x_id]);
+
+        graph.backward(y_ids, false, false);
+
+        let x_grad = graph.get_variable_grad_contents_f64(x_id).unwrap();
+        assert_eq!(x_grad, &Tensor::new_from_num_vec(vec![0.0, 0.0, 2.0, 2.0, 0.0, 0.0, 1.0, 1.0], vec![4, 2]));
+    }
+
+    #[test]
+    fn try_forward_error_axis() {
+        let mut graph = Graph::new();
+
+        let index_select_id = graph.generate_function_from_function_contents(Box::new(IndexSelect::new(2, vec![0])));
+        let x_id = graph.generate_variable_from_f64_tensor(Tensor::arrange([2, 3]), "x");
+        let error = graph.try_forward(index_select_id, vec![x_id]).unwrap_err();
+
+        assert_eq!(error, KdezeroError::invalid_input("IndexSelect", 0, &[2, 3],
+            TensorError::InvalidAxis { axis: 2, ndim: 2 }));
+    }
+}
@@ -0,0 +1,176 @@
+use std::any::Any;
+use super::{Add, Sub, Mul, Neg, Log, SumTo, Reduction};
+use super::reduction::{get_loss_inputs, loss_shape};
+use super::super::FunctionContents;
+use crate::error::KdezeroError;
+use crate::variable::VariableTable;
+use crate::graph::Graph;
+use ktensor::Tensor;
+
+/// Kullback-Leibler divergence `t * (log(t) - x)` of the target probabilities t from the log probabilities x
+/// 
+/// The loss is 0 where t is 0.
+/// The inputs are x, t and the optional weights, broadcast to a common shape.
+/// The weights multiply each elementwise loss and get no gradient.
+#[derive(Debug, Clone)]
+pub struct KLDivergence {
+    reduction: Reduction,
+}
+
+impl KLDivergence {
+    /// Create a new KLDivergence instance, which takes the mean of the losses.
+    pub fn new() -> Self {
+        Self { reduction: Reduction::Mean }
+    }
+
+    /// Create a new KLDivergence instance with the reduction.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `reduction` - Reduction of the elementwise losses
+    pub fn with_reduction(reduction: Reduction) -> Self {
+        Self { reduction }
+    }
+
+    pub fn get_reduction(&self) -> Reduction {
+        self.reduction
+    }
+
+    fn input_check(inputs: &Vec<usize>) -> Result<(), KdezeroError> {
+        if inputs.len() != 2 && inputs.len() != 3 {
+            return Err(KdezeroError::InvalidInputCount { function: "KLDivergence".to_string(), expected: 2, actual: inputs.len() });
+        }
+        Ok(())
+    }
+
+    fn output_check(outputs: &Vec<usize>) -> Result<(), KdezeroError> {
+        if outputs.len() != 1 {
+            return Err(KdezeroError::InvalidOutputCount { function: "KLDivergence".to_string(), expected: 1, actual: outputs.len() });
+        }
+        Ok(())
+    }
+}
+
+impl FunctionContents for KLDivergence {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "KLDivergence"
+    }
+
+    fn forward(&self, info: &crate::function::FunctionInfo, inputs: &Vec<usize>, variable_table: &mut VariableTable) -> Vec<usize> {
+        self.try_forward(info, inputs, variable_table).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    fn try_forward(&self, _info: &crate::function::FunctionInfo, inputs: &Vec<usize>, variable_table: &mut VariableTable) -> Result<Vec<usize>, KdezeroError> {
+        let ([x, t], weights) = get_loss_inputs(self.name(), inputs, variable_table)?;
+        match weights {
+            Some(weights) => loss_shape(self.name(), &[x, t, weights])?,
+            None => loss_shape(self.name(), &[x, t])?,
+        };
+
+        let loss = x.zip_map(t, |x, t| {
+            let (x, t) = (*x.data(), *t.data());
+            (if t > 0.0 { t * (t.ln() - x) } else { 0.0 }).into()
+        });
+        let output = self.reduction.reduce(loss, weights);
+
+        let output_id = variable_table.generate_variable_from_f64_tensor(output, "");
+        Ok(vec![output_id])
+    }
+
+    fn get_backward(&self) -> fn(usize, &mut Graph) -> Result<Vec<usize>, KdezeroError> {
+        |function_id, graph| {
+            let function = graph.get_function(function_id).ok_or(KdezeroError::InvalidFunctionId { id: function_id })?;
+            let reduction = function.get_function_contents::<KLDivergence>().ok_or(KdezeroError::InvalidFunctionId { id: function_id })?.get_reduction();
+            let inputs = function.try_get_inputs()?;
+            let outputs = function.try_get_outputs()?;
+            KLDivergence::input_check(inputs)?;
+            KLDivergence::output_check(outputs)?;
+            let input_ids = inputs.clone();
+            let output_id = outputs[0];
+            let output_grad_id = graph.get_variable_grad_id(output_id).ok_or_else(|| KdezeroError::GradNotFound { function: "KLDivergence".to_string(), id: output_id })?;
+
+            let x = graph.get_variable_contents_f64(input_ids[0]).ok_or(KdezeroError::InvalidVariableId { id: input_ids[0] })?;
+            let t = graph.get_variable_contents_f64(input_ids[1]).ok_or(KdezeroError::InvalidVariableId { id: input_ids[1] })?;
+            let shape = ktensor::tensor::broadcast_shapes(x.shape(), t.shape());
+            let mask = t.map(|t| (if *t.data() > 0.0 { 1.0 } else { 0.0 }).into());
+            let unmask = mask.map(|m| (1.0 - m.data()).into());
+            let grad_id = reduction.loss_grad(graph, output_grad_id, input_ids.get(2).copied(), &shape)?;
+
+            // -grad * t
+            let neg_id = graph.generate_function_from_function_contents(Box::new(Neg::new()));
+            let temp_id = graph.try_forward(neg_id, vec![input_ids[1]])?[0];
+            let mul_id = graph.generate_function_from_function_contents(Box::new(Mul::new()));
+            let gx_id = graph.try_forward(mul_id, vec![grad_id, temp_id])?[0];
+
+            // grad * (log(t) + 1 - x) where t > 0, with t replaced by 1 elsewhere to keep the log finite
+            let mask_id = graph.generate_variable_from_f64_tensor(mask, "");
+            let unmask_id = graph.generate_variable_from_f64_tensor(unmask, "");
+            let one_id = graph.generate_variable_from_f64_tensor(Tensor::full(1.0, vec![]), "");
+            let mul_id = graph.generate_function_from_function_contents(Box::new(Mul::new()));
+            let temp_id = graph.try_forward(mul_id, vec![input_ids[1], mask_id])?[0];
+            let add_id = graph.generate_function_from_function_contents(Box::new(Add::new()));
+            let temp_id = graph.try_forward(add_id, vec![temp_id, unmask_id])?[0];
+            let log_id = graph.generate_function_from_function_contents(Box::new(Log::new()));
+            let temp_id = graph.try_forward(log_id, vec![temp_id])?[0];
+            let add_id = graph.generate_function_from_function_contents(Box::new(Add::new()));
+            let temp_id = graph.try_forward(add_id, vec![temp_id, one_id])?[0];
+            let sub_id = graph.generate_function_from_function_contents(Box::new(Sub::new()));
+            let temp_id = graph.try_forward(sub_id, vec![temp_id, input_ids[0]])?[0];
+            let mul_id = graph.generate_function_from_function_contents(Box::new(Mul::new()));
+            let temp_id = graph.try_forward(mul_id, vec![temp_id, mask_id])?[0];
+            let mul_id = graph.generate_function_from_function_contents(Box::new(Mul::new()));
+            let gt_id = graph.try_forward(mul_id, vec![grad_id, temp_id])?[0];
+
+            let gx_id = SumTo::reduce_grad(graph, gx_id, input_ids[0])?;
+            let gt_id = SumTo::reduce_grad(graph, gt_id, input_ids[1])?;
+            graph.try_update_grad(input_ids[0], gx_id)?;
+            graph.try_update_grad(input_ids[1], gt_id)?;
+
+            Ok(input_ids[..2].to_vec())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ktensor::utility::assert_approx_eq;
+
+    #[test]
+    fn forward_normal() {
+        let mut graph = Graph::new();
+
+        let x = [0.2_f64.ln(), 0.3_f64.ln(), 0.5_f64.ln()];
+        let t = [0.5, 0.5, 0.0];
+        let kl_id = graph.generate_function_from_function_contents(Box::new(KLDivergence::with_reduction(Reduction::Sum)));
+        let x_id = graph.generate_variable_from_f64_tensor(Tensor::new_from_num_vec(x, vec![3]), "x");
+        let t_id = graph.generate_variable_from_f64_tensor(Tensor::new_from_num_vec(t, vec![3]), "t");
+        let y_id = graph.forward(kl_id, vec![x_id, t_id]);
+
+        let y = graph.get_variable_contents_f64(y_id[0]).unwrap();
+        assert_approx_eq(*y.at(&[]).data(), 0.5 * (0.5_f64 / 0.2).ln() + 0.5 * (0.5_f64 / 0.3).ln(), 1e-12);
+    }
+
+    #[test]
+    fn backward_normal() {
+        let mut graph = Graph::new();
+
+        let x = Tensor::new_from_num_vec(vec![-1.0, -2.0, -0.5, -3.0], vec![2, 2]);
+        let kl_id = graph.generate_function_from_function_contents(Box::new(KLDivergence::new()));
+        let x_id = graph.generate_variable_from_f64_tensor(x, "x");
+        let t_id = graph.generate_variable_from_f64_tensor(Tensor::new_from_num_vec(vec![1.0, 0.0], vec![2]), "t");
+        let y_ids = graph.forward(kl_id, vec![x_id, t_id]);
+
+        graph.backward(y_ids, false, false);
+
+        // -t / 4 and the sum of (log(t) + 1 - x) / 4 over the rows where t > 0
+        assert_eq!(graph.get_variable_grad_contents_f64(x_id).unwrap(),
+            &Tensor::new_from_num_vec(vec![-0.25, 0.0, -0.25, 0.0], vec![2, 2]));
+        assert_eq!(graph.get_variable_grad_contents_f64(t_id).unwrap(),
+            &Tensor::new_from_num_vec(vec![(2.0 + 1.5) / 4.0, 0.0], vec![2]));
+    }
+}
@@ -0,0 +1,124 @@
+use std::any::Any;
+use super::Mul;
+use super::super::FunctionContents;
+use crate::variable::VariableTable;
+use crate::error::KdezeroError;
+use crate::graph::Graph;
+
+/// Leaky rectified linear unit
+/// 
+/// x for a positive x and `slope * x` otherwise.
+#[derive(Debug, Clone)]
+pub struct LeakyReLU {
+    slope: f64,
+}
+
+impl LeakyReLU {
+    /// Create a new LeakyReLU instance.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `slope` - Slope for the non-positive inputs
+    pub fn new(slope: f64) -> Self {
+        Self { slope }
+    }
+
+    pub fn get_slope(&self) -> f64 {
+        self.slope
+    }
+
+    fn input_check(inputs: &Vec<usize>) -> Result<(), KdezeroError> {
+        if inputs.len() != 1 {
+            return Err(KdezeroError::InvalidInputCount { function: "LeakyReLU".to_string(), expected: 1, actual: inputs.len() });
+        }
+        Ok(())
+    }
+
+    fn output_check(outputs: &Vec<usize>) -> Result<(), KdezeroError> {
+        if outputs.len() != 1 {
+            return Err(KdezeroError::InvalidOutputCount { function: "LeakyReLU".to_string(), expected: 1, actual: outputs.len() });
+        }
+        Ok(())
+    }
+}
+
+impl FunctionContents for LeakyReLU {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "LeakyReLU"
+    }
+
+    fn forward(&self, _info: &crate::function::FunctionInfo, inputs: &Vec<usize>, variable_table: &mut VariableTable) -> Vec<usize> {
+        LeakyReLU::input_check(inputs).unwrap_or_else(|e| panic!("{}", e));
+        let x = variable_table.get_variable_contents_f64(inputs[0]).expect("Invalid variable id");
+
+        let output = x.map(|x| (if *x.data() > 0.0 { *x.data() } else { self.slope * x.data() }).into());
+
+        let output_id = variable_table.generate_variable_from_f64_tensor(output, "");
+        vec![output_id]
+    }
+
+    fn get_backward(&self) -> fn(usize, &mut Graph) -> Result<Vec<usize>, KdezeroError> {
+        |function_id, graph| {
+            let function = graph.get_function(function_id).ok_or(KdezeroError::InvalidFunctionId { id: function_id })?;
+            let function_contents = function.get_function_contents::<LeakyReLU>().ok_or(KdezeroError::InvalidFunctionId { id: function_id })?;
+            let slope = function_contents.get_slope();
+
+            let inputs = function.try_get_inputs()?;
+            let outputs = function.try_get_outputs()?;
+            LeakyReLU::input_check(inputs)?;
+            LeakyReLU::output_check(outputs)?;
+            let input_id = inputs[0];
+            let output_id = outputs[0];
+            let output_grad_id = graph.get_variable_grad_id(output_id).ok_or_else(|| KdezeroError::GradNotFound { function: "LeakyReLU".to_string(), id: output_id })?;
+
+            // grad * (1 if x > 0 else slope)
+            let x = graph.get_variable_contents_f64(input_id).ok_or(KdezeroError::InvalidVariableId { id: input_id })?;
+            let mask = x.map(|x| (if *x.data() > 0.0 { 1.0 } else { slope }).into());
+            let mask_id = graph.generate_variable_from_f64_tensor(mask, "");
+            let mul_id = graph.generate_function_from_function_contents(Box::new(Mul::new()));
+            let grad_id = graph.try_forward(mul_id, vec![output_grad_id, mask_id])?[0];
+
+            graph.try_update_grad(input_id, grad_id)?;
+
+            Ok(vec![input_id])
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ktensor::Tensor;
+
+    #[test]
+    fn forward_normal() {
+        let mut graph = Graph::new();
+
+        let leaky_relu_id = graph.generate_function_from_function_contents(Box::new(LeakyReLU::new(0.25)));
+        let x_id = graph.generate_variable_from_f64_tensor(
+            Tensor::new_from_num_vec(vec![-2.0, 0.0, 3.0], vec![3]), "x");
+        let y_id = graph.forward(leaky_relu_id, vec![x_id]);
+
+        let y = graph.get_variable_contents_f64(y_id[0]).unwrap();
+        assert_eq!(y, &Tensor::new_from_num_vec(vec![-0.5, 0.0, 3.0], vec![3]));
+    }
+
+    #[test]
+    fn backward_normal() {
+        let mut graph = Graph::new();
+
+        let leaky_relu_id = graph.generate_function_from_function_contents(Box::new(LeakyReLU::new(0.25)));
+        let x_id = graph.generate_variable_from_f64_tensor(
+            Tensor::new_from_num_vec(vec![-2.0, 0.0, 3.0], vec![3]), "x");
+        let y_ids = graph.forward(leaky_relu_id, vec![x_id]);
+
+        graph.backward(y_ids, false, false);
+
+        let x_grad = graph.get_variable_grad_contents_f64(x_id).unwrap();
+        assert_eq!(x_grad, &Tensor::new_from_num_vec(vec![0.25, 0.25, 1.0], vec![3]));
+    }
+}
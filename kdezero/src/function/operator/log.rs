@@ -0,0 +1,128 @@
+use std::any::Any;
+use super::Div;
+use super::super::FunctionContents;
+use crate::variable::VariableTable;
+use crate::error::KdezeroError;
+use crate::graph::Graph;
+
+/// Natural logarithm
+#[derive(Debug, Clone)]
+pub struct Log {}
+
+impl Log {
+    pub fn new() -> Self {
+        Self {}
+    }
+
+    fn input_check(inputs: &Vec<usize>) -> Result<(), KdezeroError> {
+        if inputs.len() != 1 {
+            return Err(KdezeroError::InvalidInputCount { function: "Log".to_string(), expected: 1, actual: inputs.len() });
+        }
+        Ok(())
+    }
+
+    fn output_check(outputs: &Vec<usize>) -> Result<(), KdezeroError> {
+        if outputs.len() != 1 {
+            return Err(KdezeroError::InvalidOutputCount { function: "Log".to_string(), expected: 1, actual: outputs.len() });
+        }
+        Ok(())
+    }
+}
+
+impl FunctionContents for Log {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "Log"
+    }
+
+    fn forward(&self, _info: &crate::function::FunctionInfo, inputs: &Vec<usize>, variable_table: &mut VariableTable) -> Vec<usize> {
+        Log::input_check(inputs).unwrap_or_else(|e| panic!("{}", e));
+        let x = variable_table.get_variable_contents_f64(inputs[0]).expect("Invalid variable id");
+
+        let output = x.ln();
+
+        let output_id = variable_table.generate_variable_from_f64_tensor(output, "");
+        vec![output_id]
+    }
+
+    fn get_backward(&self) -> fn(usize, &mut Graph) -> Result<Vec<usize>, KdezeroError> {
+        |function_id, graph| {
+            let function = graph.get_function(function_id).ok_or(KdezeroError::InvalidFunctionId { id: function_id })?;
+            let inputs = function.try_get_inputs()?;
+            let outputs = function.try_get_outputs()?;
+            Log::input_check(inputs)?;
+            Log::output_check(outputs)?;
+            let input_id = inputs[0];
+            let output_id = outputs[0];
+            let output_grad_id = graph.get_variable_grad_id(output_id).ok_or_else(|| KdezeroError::GradNotFound { function: "Log".to_string(), id: output_id })?;
+
+            // grad / x
+            let div_id = graph.generate_function_from_function_contents(Box::new(Div::new()));
+            let grad_id = graph.try_forward(div_id, vec![output_grad_id, input_id])?[0];
+
+            graph.try_update_grad(input_id, grad_id)?;
+
+            Ok(vec![input_id])
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ktensor::Tensor;
+
+    #[test]
+    fn forward_normal() {
+        let mut graph = Graph::new();
+
+        let data = vec![1.0, 2.0, 3.0];
+        let log_id = graph.generate_function_from_function_contents(Box::new(Log::new()));
+        let x_id = graph.generate_variable_from_f64_tensor(
+            Tensor::new_from_num_vec(data.clone(), vec![3]), "x");
+        let y_id = graph.forward(log_id, vec![x_id]);
+
+        let y = graph.get_variable_contents_f64(y_id[0]).unwrap();
+        assert_eq!(y, &Tensor::new_from_num_vec(data.iter().map(|x| x.ln()), vec![3]));
+    }
+
+    #[test]
+    fn backward_normal() {
+        let mut graph = Graph::new();
+
+        let data = vec![1.0, 2.0, 4.0];
+        let log_id = graph.generate_function_from_function_contents(Box::new(Log::new()));
+        let x_id = graph.generate_variable_from_f64_tensor(
+            Tensor::new_from_num_vec(data.clone(), vec![3]), "x");
+        let y_ids = graph.forward(log_id, vec![x_id]);
+
+        graph.backward(y_ids, false, false);
+
+        let x_grad = graph.get_variable_grad_contents_f64(x_id).unwrap();
+        assert_eq!(x_grad, &Tensor::new_from_num_vec(vec![1.0, 0.5, 0.25], vec![3]));
+    }
+
+    #[test]
+    fn backward_backward_normal() {
+        let mut graph = Graph::new();
+
+        let data = vec![1.0, 2.0, 4.0];
+        let log_id = graph.generate_function_from_function_contents(Box::new(Log::new()));
+        let x_id = graph.generate_variable_from_f64_tensor(
+            Tensor::new_from_num_vec(data.clone(), vec![3]), "x");
+        let y_ids = graph.forward(log_id, vec![x_id]);
+
+        graph.backward(y_ids, false, true);
+
+        let x_grad_id = graph.get_variable_grad_id(x_id).unwrap();
+        graph.clear_grad(x_id);
+
+        graph.backward(vec![x_grad_id], false, false);
+
+        let x_grad = graph.get_variable_grad_contents_f64(x_id).unwrap();
+        assert_eq!(x_grad, &Tensor::new_from_num_vec(vec![-1.0, -0.25, -0.0625], vec![3]));
+    }
+}
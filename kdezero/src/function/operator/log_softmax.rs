@@ -0,0 +1,161 @@
+use std::any::Any;
+use super::{Sub, Mul, Exp, Sum, Softmax};
+use super::super::{FunctionContents, get_inputs_f64};
+use ktensor::Tensor;
+use crate::error::KdezeroError;
+use crate::variable::VariableTable;
+use crate::graph::Graph;
+
+/// Logarithm of the softmax along the axis
+/// 
+/// The result is computed as `x - max - log(sum(exp(x - max)))`,
+/// so that it stays finite where the softmax underflows to 0.
+#[derive(Debug, Clone)]
+pub struct LogSoftmax {
+    axis: usize,
+}
+
+impl LogSoftmax {
+    /// Create a new LogSoftmax instance.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `axis` - Axis to normalize
+    pub fn new(axis: usize) -> Self {
+        Self { axis }
+    }
+
+    pub fn get_axis(&self) -> usize {
+        self.axis
+    }
+
+    /// Compute log softmax of the tensor along the axis.
+    pub(crate) fn log_softmax(x: &Tensor<f64>, axis: usize) -> Tensor<f64> {
+        let shifted = x - &x.max([axis], true);
+        let log_sum = shifted.exp().sum([axis], true).ln();
+        &shifted - &log_sum
+    }
+
+    fn input_check(inputs: &Vec<usize>) -> Result<(), KdezeroError> {
+        if inputs.len() != 1 {
+            return Err(KdezeroError::InvalidInputCount { function: "LogSoftmax".to_string(), expected: 1, actual: inputs.len() });
+        }
+        Ok(())
+    }
+
+    fn output_check(outputs: &Vec<usize>) -> Result<(), KdezeroError> {
+        if outputs.len() != 1 {
+            return Err(KdezeroError::InvalidOutputCount { function: "LogSoftmax".to_string(), expected: 1, actual: outputs.len() });
+        }
+        Ok(())
+    }
+}
+
+impl FunctionContents for LogSoftmax {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "LogSoftmax"
+    }
+
+    fn forward(&self, info: &crate::function::FunctionInfo, inputs: &Vec<usize>, variable_table: &mut VariableTable) -> Vec<usize> {
+        self.try_forward(info, inputs, variable_table).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    fn try_forward(&self, _info: &crate::function::FunctionInfo, inputs: &Vec<usize>, variable_table: &mut VariableTable) -> Result<Vec<usize>, KdezeroError> {
+        let [x] = get_inputs_f64(self.name(), inputs, variable_table)?;
+        Softmax::axis_check(self.name(), x, self.axis)?;
+
+        let output = LogSoftmax::log_softmax(x, self.axis);
+
+        let output_id = variable_table.generate_variable_from_f64_tensor(output, "");
+        Ok(vec![output_id])
+    }
+
+    fn get_backward(&self) -> fn(usize, &mut Graph) -> Result<Vec<usize>, KdezeroError> {
+        |function_id, graph| {
+            let function = graph.get_function(function_id).ok_or(KdezeroError::InvalidFunctionId { id: function_id })?;
+            let function_contents = function.get_function_contents::<LogSoftmax>().ok_or(KdezeroError::InvalidFunctionId { id: function_id })?;
+            let axis = function_contents.get_axis();
+
+            let inputs = function.try_get_inputs()?;
+            let outputs = function.try_get_outputs()?;
+            LogSoftmax::input_check(inputs)?;
+            LogSoftmax::output_check(outputs)?;
+            let input_id = inputs[0];
+            let output_id = outputs[0];
+            let output_grad_id = graph.get_variable_grad_id(output_id).ok_or_else(|| KdezeroError::GradNotFound { function: "LogSoftmax".to_string(), id: output_id })?;
+
+            // grad - exp(y) * sum(grad, axis)
+            let sum_id = graph.generate_function_from_function_contents(Box::new(Sum::new(Some([axis]), true)));
+            let temp_id = graph.try_forward(sum_id, vec![output_grad_id])?[0];
+            let exp_id = graph.generate_function_from_function_contents(Box::new(Exp::new()));
+            let softmax_id = graph.try_forward(exp_id, vec![output_id])?[0];
+            let mul_id = graph.generate_function_from_function_contents(Box::new(Mul::new()));
+            let temp_id = graph.try_forward(mul_id, vec![softmax_id, temp_id])?[0];
+            let sub_id = graph.generate_function_from_function_contents(Box::new(Sub::new()));
+            let grad_id = graph.try_forward(sub_id, vec![output_grad_id, temp_id])?[0];
+
+            graph.try_update_grad(input_id, grad_id)?;
+
+            Ok(vec![input_id])
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ktensor::utility::assert_approx_eq;
+
+    #[test]
+    fn forward_normal() {
+        let mut graph = Graph::new();
+
+        let x = Tensor::new_from_num_vec(vec![1.0, 2.0, 3.0, 4.0], vec![2, 2]);
+        let log_softmax_id = graph.generate_function_from_function_contents(Box::new(LogSoftmax::new(0)));
+        let x_id = graph.generate_variable_from_f64_tensor(x.clone(), "x");
+        let y_id = graph.forward(log_softmax_id, vec![x_id]);
+
+        let y = graph.get_variable_contents_f64(y_id[0]).unwrap();
+        Softmax::softmax(&x, 0).ln().data().iter().zip(y.data().iter())
+            .for_each(|(x, y)| assert_approx_eq(*x.data(), *y.data(), 1e-12));
+    }
+
+    #[test]
+    fn forward_large() {
+        let mut graph = Graph::new();
+
+        let log_softmax_id = graph.generate_function_from_function_contents(Box::new(LogSoftmax::new(0)));
+        let x_id = graph.generate_variable_from_f64_tensor(
+            Tensor::new_from_num_vec(vec![0.0, 1000.0], vec![2]), "x");
+        let y_id = graph.forward(log_softmax_id, vec![x_id]);
+
+        let y = graph.get_variable_contents_f64(y_id[0]).unwrap();
+        assert_eq!(y, &Tensor::new_from_num_vec(vec![-1000.0, 0.0], vec![2]));
+    }
+
+    #[test]
+    fn backward_normal() {
+        let mut graph = Graph::new();
+
+        let x = Tensor::new_from_num_vec(vec![1.0, 2.0, 3.0, -1.0, 0.5, 0.0], vec![2, 3]);
+        let w = Tensor::new_from_num_vec(vec![1.0, -2.0, 0.5, 3.0, 2.0, 1.0], vec![2, 3]);
+        let log_softmax_id = graph.generate_function_from_function_contents(Box::new(LogSoftmax::new(1)));
+        let x_id = graph.generate_variable_from_f64_tensor(x.clone(), "x");
+        let w_id = graph.generate_variable_from_f64_tensor(w.clone(), "w");
+        let y_id = graph.forward(log_softmax_id, vec![x_id])[0];
+        let mul_id = graph.generate_function_from_function_contents(Box::new(Mul::new()));
+        let z_id = graph.forward(mul_id, vec![y_id, w_id])[0];
+
+        graph.backward(vec![z_id], false, false);
+
+        // w - softmax(x) * sum(w)
+        let expected = &w - &(&Softmax::softmax(&x, 1) * &w.sum([1], true));
+        let x_grad = graph.get_variable_grad_contents_f64(x_id).unwrap();
+        expected.data().iter().zip(x_grad.data().iter())
+            .for_each(|(x, y)| assert_approx_eq(*x.data(), *y.data(), 1e-12));
+    }
+}
@@ -1,8 +1,16 @@
 use std::any::Any;
-use super::Transpose;
-use super::super::{FunctionContents, FunctionTable};
+use ktensor::{TensorError, tensor::broadcast_shapes};
+use super::{Permute, Reshape, SumTo};
+use super::super::{FunctionContents, get_inputs_f64};
+use crate::error::KdezeroError;
 use crate::variable::VariableTable;
+use crate::graph::Graph;
 
+/// Matrix product with the semantics of numpy's matmul
+/// 
+/// 1-D inputs are promoted to matrices and the batch axes are broadcast.
+/// The gradients are summed over the broadcast batch axes
+/// and reshaped back for 1-D inputs.
 #[derive(Debug, Clone)]
 pub struct MatMul {}
 
@@ -11,16 +19,18 @@ impl MatMul {
         Self {}
     }
 
-    fn input_check(inputs: &Vec<usize>) {
+    fn input_check(inputs: &Vec<usize>) -> Result<(), KdezeroError> {
         if inputs.len() != 2 {
-            panic!("MatMul function must have only 2 input, but got {} inputs.", inputs.len());
+            return Err(KdezeroError::InvalidInputCount { function: "MatMul".to_string(), expected: 2, actual: inputs.len() });
         }
+        Ok(())
     }
 
-    fn output_check(outputs: &Vec<usize>) {
+    fn output_check(outputs: &Vec<usize>) -> Result<(), KdezeroError> {
         if outputs.len() != 1 {
-            panic!("MatMul function must have only one output, but got {} outputs.", outputs.len());
+            return Err(KdezeroError::InvalidOutputCount { function: "MatMul".to_string(), expected: 1, actual: outputs.len() });
         }
+        Ok(())
     }
 }
 
@@ -33,42 +43,94 @@ impl FunctionContents for MatMul {
         "MatMul"
     }
 
-    fn forward(&self, _info: &crate::function::FunctionInfo, inputs: &Vec<usize>, variable_table: &mut VariableTable) -> Vec<usize> {
-        MatMul::input_check(inputs);
-        let input0 = variable_table.get_variable_contents_f64(inputs[0]).expect("Invalid variable id");
-        let input1 = variable_table.get_variable_contents_f64(inputs[1]).expect("Invalid variable id");
+    fn forward(&self, info: &crate::function::FunctionInfo, inputs: &Vec<usize>, variable_table: &mut VariableTable) -> Vec<usize> {
+        self.try_forward(info, inputs, variable_table).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    fn try_forward(&self, _info: &crate::function::FunctionInfo, inputs: &Vec<usize>, variable_table: &mut VariableTable) -> Result<Vec<usize>, KdezeroError> {
+        let [input0, input1] = get_inputs_f64(self.name(), inputs, variable_table)?;
 
-        let output = input0.matmul(input1);
+        let output = input0.try_matmul(input1).map_err(|e| match e {
+            TensorError::InvalidShape { ref shape, .. } if shape == input0.shape() =>
+                KdezeroError::invalid_input(self.name(), 0, input0.shape(), e),
+            _ => KdezeroError::invalid_input(self.name(), 1, input1.shape(), e),
+        })?;
 
         let output_id = variable_table.generate_variable_from_f64_tensor(output, "");
-        vec![output_id]
+        Ok(vec![output_id])
     }
 
-    fn get_backward(&self) -> fn(usize, &mut FunctionTable, &mut VariableTable) -> Vec<usize> {
-        |function_id, function_table, variable_table| {
-            let function = function_table.get(function_id).expect("Invalid function id");
-            let inputs = function.get_inputs().expect("Invalid inputs");
-            let outputs = function.get_outputs().expect("Invalid outputs");
-            MatMul::input_check(inputs);
-            MatMul::output_check(outputs);
+    fn get_backward(&self) -> fn(usize, &mut Graph) -> Result<Vec<usize>, KdezeroError> {
+        |function_id, graph| {
+            let function = graph.get_function(function_id).ok_or(KdezeroError::InvalidFunctionId { id: function_id })?;
+            let inputs = function.try_get_inputs()?;
+            let outputs = function.try_get_outputs()?;
+            MatMul::input_check(inputs)?;
+            MatMul::output_check(outputs)?;
             let input_ids = inputs.clone();
             let output_id = outputs[0];
-            let output_grad_id = variable_table.get_variable_grad_id(output_id).expect("Output grad id not found");
-
-            let transpose_id0 = function_table.generate_function_from_function_contents(Box::new(Transpose::new()));
-            let input1_t_id = function_table.forward(transpose_id0, vec![input_ids[1]], variable_table, false)[0];
-            let matmul_id0 = function_table.generate_function_from_function_contents(Box::new(MatMul::new()));
-            let grad_id0 = function_table.forward(matmul_id0, vec![output_grad_id, input1_t_id], variable_table, false)[0];
-
-            let transpose_id1 = function_table.generate_function_from_function_contents(Box::new(Transpose::new()));
-            let input0_t_id = function_table.forward(transpose_id1, vec![input_ids[0]], variable_table, false)[0];
-            let matmul_id1 = function_table.generate_function_from_function_contents(Box::new(MatMul::new()));
-            let grad_id1 = function_table.forward(matmul_id1, vec![input0_t_id, output_grad_id], variable_table, false)[0];
-
-            variable_table.update_grad(input_ids[0], grad_id0, function_table);
-            variable_table.update_grad(input_ids[1], grad_id1, function_table);
-
-            input_ids
+            let output_grad_id = graph.get_variable_grad_id(output_id).ok_or_else(|| KdezeroError::GradNotFound { function: "MatMul".to_string(), id: output_id })?;
+            let shape0 = graph.get_variable(input_ids[0]).ok_or(KdezeroError::InvalidVariableId { id: input_ids[0] })?.shape().clone();
+            let shape1 = graph.get_variable(input_ids[1]).ok_or(KdezeroError::InvalidVariableId { id: input_ids[1] })?.shape().clone();
+
+            // Promote 1-D inputs to matrices, as the forward does
+            let (input0_id, input1_id) = (input_ids[0], input_ids[1]);
+            let input0_id = if shape0.len() == 1 {
+                let reshape_id = graph.generate_function_from_function_contents(Box::new(Reshape::new(vec![1, shape0[0]])));
+                graph.try_forward(reshape_id, vec![input0_id])?[0]
+            } else {
+                input0_id
+            };
+            let input1_id = if shape1.len() == 1 {
+                let reshape_id = graph.generate_function_from_function_contents(Box::new(Reshape::new(vec![shape1[0], 1])));
+                graph.try_forward(reshape_id, vec![input1_id])?[0]
+            } else {
+                input1_id
+            };
+            let matrix_shape0 = graph.get_variable(input0_id).ok_or(KdezeroError::InvalidVariableId { id: input0_id })?.shape().clone();
+            let matrix_shape1 = graph.get_variable(input1_id).ok_or(KdezeroError::InvalidVariableId { id: input1_id })?.shape().clone();
+            let (ndim0, ndim1) = (matrix_shape0.len(), matrix_shape1.len());
+            let output_grad_id = if shape0.len() == 1 || shape1.len() == 1 {
+                let mut output_shape = broadcast_shapes(&matrix_shape0[..ndim0 - 2], &matrix_shape1[..ndim1 - 2]);
+                output_shape.push(matrix_shape0[ndim0 - 2]);
+                output_shape.push(matrix_shape1[ndim1 - 1]);
+                let reshape_id = graph.generate_function_from_function_contents(Box::new(Reshape::new(output_shape)));
+                graph.try_forward(reshape_id, vec![output_grad_id])?[0]
+            } else {
+                output_grad_id
+            };
+
+            // grad0 = grad @ x1^T, grad1 = x0^T @ grad, summed over the broadcast batch axes
+            let permute_id = graph.generate_function_from_function_contents(Box::new(Permute::swapaxes(ndim1, ndim1 - 2, ndim1 - 1)));
+            let input1_t_id = graph.try_forward(permute_id, vec![input1_id])?[0];
+            let matmul_id = graph.generate_function_from_function_contents(Box::new(MatMul::new()));
+            let grad_id0 = graph.try_forward(matmul_id, vec![output_grad_id, input1_t_id])?[0];
+            let grad_id0 = SumTo::reduce_grad(graph, grad_id0, input0_id)?;
+
+            let permute_id = graph.generate_function_from_function_contents(Box::new(Permute::swapaxes(ndim0, ndim0 - 2, ndim0 - 1)));
+            let input0_t_id = graph.try_forward(permute_id, vec![input0_id])?[0];
+            let matmul_id = graph.generate_function_from_function_contents(Box::new(MatMul::new()));
+            let grad_id1 = graph.try_forward(matmul_id, vec![input0_t_id, output_grad_id])?[0];
+            let grad_id1 = SumTo::reduce_grad(graph, grad_id1, input1_id)?;
+
+            // Reshape the gradients of 1-D inputs back
+            let grad_id0 = if shape0.len() == 1 {
+                let reshape_id = graph.generate_function_from_function_contents(Box::new(Reshape::new(shape0)));
+                graph.try_forward(reshape_id, vec![grad_id0])?[0]
+            } else {
+                grad_id0
+            };
+            let grad_id1 = if shape1.len() == 1 {
+                let reshape_id = graph.generate_function_from_function_contents(Box::new(Reshape::new(shape1)));
+                graph.try_forward(reshape_id, vec![grad_id1])?[0]
+            } else {
+                grad_id1
+            };
+
+            graph.try_update_grad(input_ids[0], grad_id0)?;
+            graph.try_update_grad(input_ids[1], grad_id1)?;
+
+            Ok(input_ids)
         }
     }
 }
@@ -77,99 +139,204 @@ impl FunctionContents for MatMul {
 mod tests {
     use super::*;
     use ktensor::Tensor;
-    use crate::{variable::VariableTable, function::FunctionTable};
 
     #[test]
     fn forward_normal() {
-        let mut variable_table = VariableTable::new();
-        let mut function_table = FunctionTable::new();
+        let mut graph = Graph::new();
 
         let data0 = vec![0.0, 1.0, 2.0, 3.0, 4.0, 5.0];
         let data1 = vec![0.0, 1.0, 2.0, 3.0];
-        let matmul_id = function_table.generate_function_from_function_contents(Box::new(MatMul::new()));
-        let id0 = variable_table.generate_variable_from_f64_tensor(
+        let matmul_id = graph.generate_function_from_function_contents(Box::new(MatMul::new()));
+        let id0 = graph.generate_variable_from_f64_tensor(
             Tensor::new_from_num_vec(data0.clone(), vec![3, 2]), "x");
-        let id1 = variable_table.generate_variable_from_f64_tensor(
+        let id1 = graph.generate_variable_from_f64_tensor(
             Tensor::new_from_num_vec(data1.clone(), vec![2, 2]), "y");
         
-        let output_ids = function_table.forward(matmul_id, vec![id0, id1], &mut variable_table, false);
+        let output_ids = graph.forward(matmul_id, vec![id0, id1]);
 
-        let output = variable_table.get_variable_contents_f64(output_ids[0]).unwrap();
+        let output = graph.get_variable_contents_f64(output_ids[0]).unwrap();
         assert_eq!(output.data(), Tensor::new_from_num_vec(vec![2.0, 3.0, 6.0, 11.0, 10.0, 19.0], vec![3, 2]).data());
     }
 
     #[test]
     fn backward_normal() {
-        let mut variable_table = VariableTable::new();
-        let mut function_table = FunctionTable::new();
+        let mut graph = Graph::new();
 
         let data0 = vec![0.0, 1.0, 2.0, 3.0, 4.0, 5.0];
         let data1 = vec![0.0, 1.0, 2.0, 3.0];
-        let matmul_id = function_table.generate_function_from_function_contents(Box::new(MatMul::new()));
-        let id0 = variable_table.generate_variable_from_f64_tensor(
+        let matmul_id = graph.generate_function_from_function_contents(Box::new(MatMul::new()));
+        let id0 = graph.generate_variable_from_f64_tensor(
             Tensor::new_from_num_vec(data0.clone(), vec![3, 2]), "x");
-        let id1 = variable_table.generate_variable_from_f64_tensor(
+        let id1 = graph.generate_variable_from_f64_tensor(
             Tensor::new_from_num_vec(data1.clone(), vec![2, 2]), "y");
 
-        let output_ids = function_table.forward(matmul_id, vec![id0, id1], &mut variable_table, false);
+        let output_ids = graph.forward(matmul_id, vec![id0, id1]);
 
-        variable_table.backward(output_ids, &mut function_table, false);
+        graph.backward(output_ids, false, false);
 
-        let grad0 = variable_table.get_variable_grad_contents_f64(id0).unwrap();
-        let grad1 = variable_table.get_variable_grad_contents_f64(id1).unwrap();
+        let grad0 = graph.get_variable_grad_contents_f64(id0).unwrap();
+        let grad1 = graph.get_variable_grad_contents_f64(id1).unwrap();
         assert_eq!(grad0, &Tensor::new_from_num_vec(vec![1.0, 5.0, 1.0, 5.0, 1.0, 5.0], vec![3, 2]));
         assert_eq!(grad1, &Tensor::new_from_num_vec(vec![6.0, 6.0, 9.0, 9.0], vec![2, 2]));
     }
 
     #[test]
     fn backward_backward_x_2() {
-        let mut variable_table = VariableTable::new();
-        let mut function_table = FunctionTable::new();
+        let mut graph = Graph::new();
 
         let data = vec![0.0, 1.0, 2.0, 3.0];
-        let matmul_id = function_table.generate_function_from_function_contents(Box::new(MatMul::new()));
-        let id = variable_table.generate_variable_from_f64_tensor(
+        let matmul_id = graph.generate_function_from_function_contents(Box::new(MatMul::new()));
+        let id = graph.generate_variable_from_f64_tensor(
             Tensor::new_from_num_vec(data.clone(), vec![2, 2]), "x");
 
-        let output_ids = function_table.forward(matmul_id, vec![id, id], &mut variable_table, false);
+        let output_ids = graph.forward(matmul_id, vec![id, id]);
 
-        variable_table.backward(output_ids, &mut function_table, false);
+        graph.backward(output_ids, false, true);
 
-        let grad0_id = variable_table.get_variable_grad_id(id).unwrap();
-        variable_table.clear_grad(id);
+        let grad0_id = graph.get_variable_grad_id(id).unwrap();
+        graph.clear_grad(id);
 
-        variable_table.backward(vec![grad0_id], &mut function_table, false);
+        graph.backward(vec![grad0_id], false, false);
 
-        let grad = variable_table.get_variable_grad_contents_f64(id).unwrap();
+        let grad = graph.get_variable_grad_contents_f64(id).unwrap();
         assert_eq!(grad, &Tensor::new_from_num_vec(vec![4.0, 4.0, 4.0, 4.0], vec![2, 2]));
     }
 
     /// Test for backward of backward
-    /// This test is expected to panic
-    /// because it doesn't get through to x (probably)
+    /// The gradient of x does not depend on x,
+    /// so only y gets the second order gradient
     #[test]
-    #[should_panic]
     fn backward_backward_x_y() {
-        let mut variable_table = VariableTable::new();
-        let mut function_table = FunctionTable::new();
+        let mut graph = Graph::new();
 
         let data0 = vec![1.0, 2.0, 3.0];
         let data1 = vec![4.0, 5.0, 6.0];
-        let matmul_id = function_table.generate_function_from_function_contents(Box::new(MatMul::new()));
-        let id0 = variable_table.generate_variable_from_f64_tensor(
-            Tensor::new_from_num_vec(data0.clone(), vec![3]), "x");
-        let id1 = variable_table.generate_variable_from_f64_tensor(
-            Tensor::new_from_num_vec(data1.clone(), vec![3]), "y");
+        let matmul_id = graph.generate_function_from_function_contents(Box::new(MatMul::new()));
+        let id0 = graph.generate_variable_from_f64_tensor(
+            Tensor::new_from_num_vec(data0.clone(), vec![1, 3]), "x");
+        let id1 = graph.generate_variable_from_f64_tensor(
+            Tensor::new_from_num_vec(data1.clone(), vec![3, 1]), "y");
+
+        let output_ids = graph.forward(matmul_id, vec![id0, id1]);
+
+        graph.backward(output_ids, false, true);
+
+        let grad0_id = graph.get_variable_grad_id(id0).unwrap();
+        graph.clear_grad(id0);
+        graph.clear_grad(id1);
+
+        graph.backward(vec![grad0_id], false, false);
+
+        assert!(graph.get_variable_grad_id(id0).is_none());
+        let grad = graph.get_variable_grad_contents_f64(id1).unwrap();
+        assert_eq!(grad, &Tensor::new_from_num_vec(vec![1.0, 1.0, 1.0], vec![3, 1]));
+    }
+
+    #[test]
+    fn backward_vector() {
+        let mut graph = Graph::new();
+
+        let matmul_id = graph.generate_function_from_function_contents(Box::new(MatMul::new()));
+        let id0 = graph.generate_variable_from_f64_tensor(
+            Tensor::new_from_num_vec(vec![1.0, 2.0], vec![2]), "x");
+        let id1 = graph.generate_variable_from_f64_tensor(Tensor::arrange([2, 3]), "y");
+
+        let output_ids = graph.forward(matmul_id, vec![id0, id1]);
+        assert_eq!(graph.get_variable(output_ids[0]).unwrap().shape(), &vec![3]);
+
+        graph.backward(output_ids, false, false);
+
+        let grad0 = graph.get_variable_grad_contents_f64(id0).unwrap();
+        let grad1 = graph.get_variable_grad_contents_f64(id1).unwrap();
+        assert_eq!(grad0, &Tensor::new_from_num_vec(vec![3.0, 12.0], vec![2]));
+        assert_eq!(grad1, &Tensor::new_from_num_vec(vec![1.0, 1.0, 1.0, 2.0, 2.0, 2.0], vec![2, 3]));
+    }
+
+    #[test]
+    fn backward_vector_vector() {
+        let mut graph = Graph::new();
+
+        let matmul_id = graph.generate_function_from_function_contents(Box::new(MatMul::new()));
+        let id0 = graph.generate_variable_from_f64_tensor(
+            Tensor::new_from_num_vec(vec![1.0, 2.0, 3.0], vec![3]), "x");
+        let id1 = graph.generate_variable_from_f64_tensor(
+            Tensor::new_from_num_vec(vec![4.0, 5.0, 6.0], vec![3]), "y");
+
+        let output_ids = graph.forward(matmul_id, vec![id0, id1]);
+        assert_eq!(graph.get_variable_contents_f64(output_ids[0]).unwrap(), &Tensor::new_from_num_vec(vec![32.0], vec![]));
+
+        graph.backward(output_ids, false, false);
+
+        assert_eq!(graph.get_variable_grad_contents_f64(id0).unwrap(), &Tensor::new_from_num_vec(vec![4.0, 5.0, 6.0], vec![3]));
+        assert_eq!(graph.get_variable_grad_contents_f64(id1).unwrap(), &Tensor::new_from_num_vec(vec![1.0, 2.0, 3.0], vec![3]));
+    }
+
+    #[test]
+    fn backward_broadcast_batch() {
+        let mut graph = Graph::new();
+
+        let x = Tensor::<f64>::arrange([2, 2, 3]);
+        let y = Tensor::<f64>::arrange([3, 2]);
+        let matmul_id = graph.generate_function_from_function_contents(Box::new(MatMul::new()));
+        let id0 = graph.generate_variable_from_f64_tensor(x.clone(), "x");
+        let id1 = graph.generate_variable_from_f64_tensor(y.clone(), "y");
+
+        let output_ids = graph.forward(matmul_id, vec![id0, id1]);
+        assert_eq!(graph.get_variable(output_ids[0]).unwrap().shape(), &vec![2, 2, 2]);
+
+        graph.backward(output_ids, false, false);
+
+        // Each batch contributes ones @ y^T to x and x_b^T @ ones to y
+        let ones = Tensor::full(1.0, vec![2, 2]);
+        let grad0 = graph.get_variable_grad_contents_f64(id0).unwrap();
+        assert_eq!(grad0, &ones.matmul(&y.transpose()).broadcast_to(&[2, 2, 3]));
+        let grad1 = graph.get_variable_grad_contents_f64(id1).unwrap();
+        let expected = x.swapaxes(1, 2).matmul(&ones).sum([0], false);
+        assert_eq!(grad1, &expected);
+    }
+
+    #[test]
+    fn backward_batch_vector() {
+        let mut graph = Graph::new();
+
+        let matmul_id = graph.generate_function_from_function_contents(Box::new(MatMul::new()));
+        let id0 = graph.generate_variable_from_f64_tensor(Tensor::arrange([2, 2, 3]), "x");
+        let id1 = graph.generate_variable_from_f64_tensor(
+            Tensor::new_from_num_vec(vec![1.0, 2.0, 3.0], vec![3]), "y");
+
+        let output_ids = graph.forward(matmul_id, vec![id0, id1]);
+        assert_eq!(graph.get_variable(output_ids[0]).unwrap().shape(), &vec![2, 2]);
+
+        graph.backward(output_ids, false, false);
+
+        let grad0 = graph.get_variable_grad_contents_f64(id0).unwrap();
+        assert_eq!(grad0, &Tensor::new_from_num_vec(vec![1.0, 2.0, 3.0], vec![3]).broadcast_to(&[2, 2, 3]));
+        let grad1 = graph.get_variable_grad_contents_f64(id1).unwrap();
+        assert_eq!(grad1, &Tensor::new_from_num_vec(vec![18.0, 22.0, 26.0], vec![3]));
+    }
 
-        let output_ids = function_table.forward(matmul_id, vec![id0, id1], &mut variable_table, false);
+    #[test]
+    fn try_forward_error_shape() {
+        let mut graph = Graph::new();
 
-        variable_table.backward(output_ids, &mut function_table, false);
+        let matmul_id = graph.generate_function_from_function_contents(Box::new(MatMul::new()));
+        let x_id = graph.generate_variable_from_f64_tensor(Tensor::arrange([2, 3]), "x");
+        let w_id = graph.generate_variable_from_f64_tensor(Tensor::arrange([2, 3]), "w");
+        let error = graph.try_forward(matmul_id, vec![x_id, w_id]).unwrap_err();
 
-        let grad0_id = variable_table.get_variable_grad_id(id0).unwrap();
-        variable_table.clear_grad(id0);
+        assert_eq!(error, KdezeroError::invalid_input("MatMul", 1, &[2, 3],
+            TensorError::ShapeMismatch { left: vec![2, 3], right: vec![2, 3] }));
+        assert!(graph.get_function(matmul_id).unwrap().get_outputs().is_none());
+    }
+
+    #[test]
+    fn try_forward_error_input_count() {
+        let mut graph = Graph::new();
 
-        variable_table.backward(vec![grad0_id], &mut function_table, false);
+        let matmul_id = graph.generate_function_from_function_contents(Box::new(MatMul::new()));
+        let x_id = graph.generate_variable_from_f64_tensor(Tensor::arrange([2, 3]), "x");
+        let error = graph.try_forward(matmul_id, vec![x_id]).unwrap_err();
 
-        variable_table.get_variable_grad_contents_f64(id0).unwrap();
+        assert_eq!(error, KdezeroError::InvalidInputCount { function: "MatMul".to_string(), expected: 2, actual: 1 });
     }
 }
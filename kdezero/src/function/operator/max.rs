@@ -0,0 +1,221 @@
+use std::any::Any;
+use ktensor::Tensor;
+use super::{Sum, Mul};
+use super::super::{FunctionContents, get_inputs_f64};
+use crate::error::KdezeroError;
+use crate::variable::VariableTable;
+use crate::graph::Graph;
+
+/// Maximum along the given axes
+/// 
+/// The gradient is routed to the selected element of each output,
+/// which is the first one when the maximum appears more than once.
+#[derive(Debug, Clone)]
+pub struct Max {
+    axis: Option<Vec<usize>>,
+    keepdims: bool,
+}
+
+impl Max {
+    /// Create a new Max instance.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `axis` - Axes to reduce. If None, all the axes are reduced
+    /// * `keepdims` - Keep the reduced axes with the size 1
+    pub fn new<T: AsRef<[usize]>>(axis: Option<T>, keepdims: bool) -> Self {
+        Self { axis: axis.map(|x| x.as_ref().to_vec()), keepdims }
+    }
+
+    pub fn get_axis(&self) -> Option<&Vec<usize>> {
+        self.axis.as_ref()
+    }
+
+    pub fn get_keepdims(&self) -> bool {
+        self.keepdims
+    }
+
+    /// Make the mask that is 1 at the first element of each group equal to the selected value.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `x` - Input of the reduction
+    /// * `y` - Output of the reduction with `keepdims`
+    pub(crate) fn select_mask(x: &Tensor<f64>, y: &Tensor<f64>) -> Tensor<f64> {
+        let groups = Tensor::<f64>::arrange(y.shape()).broadcast_to(x.shape());
+        let mut selected = vec![false; y.size()];
+        let mask = x.iter().zip(y.broadcast_to(x.shape()).iter()).zip(groups.iter())
+            .map(|((x, y), group)| {
+                let group = *group.data() as usize;
+                if x == y && !selected[group] {
+                    selected[group] = true;
+                    1.0
+                } else {
+                    0.0
+                }
+            })
+            .collect::<Vec<f64>>();
+        Tensor::new_from_num_vec(mask, x.shape())
+    }
+
+    /// Route the gradient of a max or min reduction to the selected elements.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `graph` - Computation graph
+    /// * `grad_id` - Variable ID of the gradient of the output
+    /// * `input_id` - Variable ID of the input
+    /// * `output_id` - Variable ID of the output
+    /// * `axis` - Reduced axes. If None or empty, all the axes were reduced
+    /// * `keepdims` - Whether the reduction kept the reduced axes
+    /// 
+    /// # Errors
+    /// 
+    /// * `InvalidVariableId` - If the input or the output is not in the graph
+    /// * `InvalidInput` - If the gradient cannot be broadcast to the shape of the input
+    pub(crate) fn route_grad(graph: &mut Graph, grad_id: usize, input_id: usize, output_id: usize, axis: Option<&Vec<usize>>, keepdims: bool) -> Result<usize, KdezeroError> {
+        let x = graph.get_variable_contents_f64(input_id).ok_or(KdezeroError::InvalidVariableId { id: input_id })?;
+        let y = graph.get_variable_contents_f64(output_id).ok_or(KdezeroError::InvalidVariableId { id: output_id })?;
+        let y_shape = x.shape().iter().enumerate()
+            .map(|(i, &size)| match axis {
+                Some(axis) if !axis.is_empty() && !axis.contains(&i) => size,
+                _ => 1,
+            })
+            .collect::<Vec<usize>>();
+        let mask = Max::select_mask(x, &y.reshape(y_shape));
+
+        let grad_id = Sum::expand_grad(graph, grad_id, input_id, axis, keepdims)?;
+        let mask_id = graph.generate_variable_from_f64_tensor(mask, "");
+        let mul_id = graph.generate_function_from_function_contents(Box::new(Mul::new()));
+        Ok(graph.try_forward(mul_id, vec![grad_id, mask_id])?[0])
+    }
+
+    fn input_check(inputs: &Vec<usize>) -> Result<(), KdezeroError> {
+        if inputs.len() != 1 {
+            return Err(KdezeroError::InvalidInputCount { function: "Max".to_string(), expected: 1, actual: inputs.len() });
+        }
+        Ok(())
+    }
+
+    fn output_check(outputs: &Vec<usize>) -> Result<(), KdezeroError> {
+        if outputs.len() != 1 {
+            return Err(KdezeroError::InvalidOutputCount { function: "Max".to_string(), expected: 1, actual: outputs.len() });
+        }
+        Ok(())
+    }
+}
+
+impl FunctionContents for Max {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "Max"
+    }
+
+    fn forward(&self, info: &crate::function::FunctionInfo, inputs: &Vec<usize>, variable_table: &mut VariableTable) -> Vec<usize> {
+        self.try_forward(info, inputs, variable_table).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    fn try_forward(&self, _info: &crate::function::FunctionInfo, inputs: &Vec<usize>, variable_table: &mut VariableTable) -> Result<Vec<usize>, KdezeroError> {
+        let [input] = get_inputs_f64(self.name(), inputs, variable_table)?;
+
+        let output = input.try_max(self.axis.as_deref().unwrap_or(&[]), self.keepdims)
+            .map_err(|e| KdezeroError::invalid_input(self.name(), 0, input.shape(), e))?;
+
+        let output_id = variable_table.generate_variable_from_f64_tensor(output, "");
+        Ok(vec![output_id])
+    }
+
+    fn get_backward(&self) -> fn(usize, &mut Graph) -> Result<Vec<usize>, KdezeroError> {
+        |function_id, graph| {
+            let function = graph.get_function(function_id).ok_or(KdezeroError::InvalidFunctionId { id: function_id })?;
+            let function_contents = function.get_function_contents::<Max>().ok_or(KdezeroError::InvalidFunctionId { id: function_id })?;
+            let axis = function_contents.get_axis().cloned();
+            let keepdims = function_contents.get_keepdims();
+
+            let inputs = function.try_get_inputs()?;
+            let outputs = function.try_get_outputs()?;
+            Max::input_check(inputs)?;
+            Max::output_check(outputs)?;
+            let input_id = inputs[0];
+            let output_id = outputs[0];
+            let output_grad_id = graph.get_variable_grad_id(output_id).ok_or_else(|| KdezeroError::GradNotFound { function: "Max".to_string(), id: output_id })?;
+
+            let grad_id = Max::route_grad(graph, output_grad_id, input_id, output_id, axis.as_ref(), keepdims)?;
+
+            graph.try_update_grad(input_id, grad_id)?;
+
+            Ok(vec![input_id])
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ktensor::TensorError;
+
+    #[test]
+    fn forward_normal() {
+        let mut graph = Graph::new();
+
+        let max_id = graph.generate_function_from_function_contents(Box::new(Max::new(Some([1]), false)));
+        let x_id = graph.generate_variable_from_f64_tensor(
+            Tensor::new_from_num_vec(vec![1.0, 5.0, 3.0, 4.0, 2.0, 6.0], vec![2, 3]), "x");
+        let y_id = graph.forward(max_id, vec![x_id]);
+
+        let y = graph.get_variable_contents_f64(y_id[0]).unwrap();
+        assert_eq!(y, &Tensor::new_from_num_vec(vec![5.0, 6.0], vec![2]));
+    }
+
+    #[test]
+    fn backward_normal() {
+        let mut graph = Graph::new();
+
+        let max_id = graph.generate_function_from_function_contents(Box::new(Max::new(Some([0]), false)));
+        let x_id = graph.generate_variable_from_f64_tensor(
+            Tensor::new_from_num_vec(vec![1.0, 5.0, 3.0, 4.0, 2.0, 6.0], vec![2, 3]), "x");
+        let y_id = graph.forward(max_id, vec![x_id]);
+
+        graph.backward(y_id, false, false);
+
+        let x_grad = graph.get_variable_grad_contents_f64(x_id).unwrap();
+        assert_eq!(x_grad, &Tensor::new_from_num_vec(vec![0.0, 1.0, 0.0, 1.0, 0.0, 1.0], vec![2, 3]));
+    }
+
+    #[test]
+    fn backward_tie() {
+        let mut graph = Graph::new();
+
+        let max_id = graph.generate_function_from_function_contents(Box::new(Max::new::<&[usize]>(None, true)));
+        let x_id = graph.generate_variable_from_f64_tensor(
+            Tensor::new_from_num_vec(vec![1.0, 6.0, 3.0, 6.0], vec![2, 2]), "x");
+        let y_id = graph.forward(max_id, vec![x_id]);
+
+        graph.backward(y_id, false, false);
+
+        let x_grad = graph.get_variable_grad_contents_f64(x_id).unwrap();
+        assert_eq!(x_grad, &Tensor::new_from_num_vec(vec![0.0, 1.0, 0.0, 0.0], vec![2, 2]));
+    }
+
+    #[test]
+    fn select_mask_normal() {
+        let x = Tensor::new_from_num_vec(vec![2.0, 2.0, 1.0, 3.0, 0.0, 3.0], vec![2, 3]);
+        let mask = Max::select_mask(&x, &x.max([1], true));
+        assert_eq!(mask, Tensor::new_from_num_vec(vec![1.0, 0.0, 0.0, 1.0, 0.0, 0.0], vec![2, 3]));
+    }
+
+    #[test]
+    fn try_forward_error_axis() {
+        let mut graph = Graph::new();
+
+        let max_id = graph.generate_function_from_function_contents(Box::new(Max::new(Some([7]), false)));
+        let x_id = graph.generate_variable_from_f64_tensor(Tensor::arrange([2, 3]), "x");
+        let error = graph.try_forward(max_id, vec![x_id]).unwrap_err();
+
+        assert_eq!(error, KdezeroError::invalid_input("Max", 0, &[2, 3],
+            TensorError::InvalidAxis { axis: 7, ndim: 2 }));
+    }
+}
@@ -0,0 +1,181 @@
+use std::any::Any;
+use ktensor::Tensor;
+use super::{Sum, Mul};
+use super::super::{FunctionContents, get_inputs_f64};
+use crate::error::KdezeroError;
+use crate::variable::VariableTable;
+use crate::graph::Graph;
+
+/// Mean along the given axes
+/// 
+/// The gradient is spread evenly over the reduced elements.
+#[derive(Debug, Clone)]
+pub struct Mean {
+    axis: Option<Vec<usize>>,
+    keepdims: bool,
+}
+
+impl Mean {
+    /// Create a new Mean instance.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `axis` - Axes to reduce. If None, all the axes are reduced
+    /// * `keepdims` - Keep the reduced axes with the size 1
+    pub fn new<T: AsRef<[usize]>>(axis: Option<T>, keepdims: bool) -> Self {
+        Self { axis: axis.map(|x| x.as_ref().to_vec()), keepdims }
+    }
+
+    pub fn get_axis(&self) -> Option<&Vec<usize>> {
+        self.axis.as_ref()
+    }
+
+    pub fn get_keepdims(&self) -> bool {
+        self.keepdims
+    }
+
+    /// Get the number of the elements reduced into each output element.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `shape` - Shape of the input
+    /// * `axis` - Reduced axes. If None or empty, all the axes are reduced
+    pub(crate) fn reduce_count(shape: &[usize], axis: Option<&Vec<usize>>) -> usize {
+        match axis {
+            Some(axis) if !axis.is_empty() =>
+                shape.iter().enumerate().filter(|(i, _)| axis.contains(i)).map(|(_, size)| size).product(),
+            _ => shape.iter().product(),
+        }
+    }
+
+    fn input_check(inputs: &Vec<usize>) -> Result<(), KdezeroError> {
+        if inputs.len() != 1 {
+            return Err(KdezeroError::InvalidInputCount { function: "Mean".to_string(), expected: 1, actual: inputs.len() });
+        }
+        Ok(())
+    }
+
+    fn output_check(outputs: &Vec<usize>) -> Result<(), KdezeroError> {
+        if outputs.len() != 1 {
+            return Err(KdezeroError::InvalidOutputCount { function: "Mean".to_string(), expected: 1, actual: outputs.len() });
+        }
+        Ok(())
+    }
+}
+
+impl FunctionContents for Mean {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "Mean"
+    }
+
+    fn forward(&self, info: &crate::function::FunctionInfo, inputs: &Vec<usize>, variable_table: &mut VariableTable) -> Vec<usize> {
+        self.try_forward(info, inputs, variable_table).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    fn try_forward(&self, _info: &crate::function::FunctionInfo, inputs: &Vec<usize>, variable_table: &mut VariableTable) -> Result<Vec<usize>, KdezeroError> {
+        let [input] = get_inputs_f64(self.name(), inputs, variable_table)?;
+
+        let output = input.try_mean(self.axis.as_deref().unwrap_or(&[]), self.keepdims)
+            .map_err(|e| KdezeroError::invalid_input(self.name(), 0, input.shape(), e))?;
+
+        let output_id = variable_table.generate_variable_from_f64_tensor(output, "");
+        Ok(vec![output_id])
+    }
+
+    fn get_backward(&self) -> fn(usize, &mut Graph) -> Result<Vec<usize>, KdezeroError> {
+        |function_id, graph| {
+            let function = graph.get_function(function_id).ok_or(KdezeroError::InvalidFunctionId { id: function_id })?;
+            let function_contents = function.get_function_contents::<Mean>().ok_or(KdezeroError::InvalidFunctionId { id: function_id })?;
+            let axis = function_contents.get_axis().cloned();
+            let keepdims = function_contents.get_keepdims();
+
+            let inputs = function.try_get_inputs()?;
+            let outputs = function.try_get_outputs()?;
+            Mean::input_check(inputs)?;
+            Mean::output_check(outputs)?;
+            let input_id = inputs[0];
+            let output_id = outputs[0];
+            let output_grad_id = graph.get_variable_grad_id(output_id).ok_or_else(|| KdezeroError::GradNotFound { function: "Mean".to_string(), id: output_id })?;
+            let input_shape = graph.get_variable(input_id).ok_or(KdezeroError::InvalidVariableId { id: input_id })?.shape().clone();
+            let count = Mean::reduce_count(&input_shape, axis.as_ref());
+
+            let grad_id = Sum::expand_grad(graph, output_grad_id, input_id, axis.as_ref(), keepdims)?;
+            let scale_id = graph.generate_variable_from_f64_tensor(Tensor::full(1.0 / count as f64, vec![]), "");
+            let mul_id = graph.generate_function_from_function_contents(Box::new(Mul::new()));
+            let grad_id = graph.try_forward(mul_id, vec![grad_id, scale_id])?[0];
+
+            graph.try_update_grad(input_id, grad_id)?;
+
+            Ok(vec![input_id])
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ktensor::TensorError;
+
+    #[test]
+    fn forward_normal() {
+        let mut graph = Graph::new();
+
+        let mean_id = graph.generate_function_from_function_contents(Box::new(Mean::new(Some([1]), false)));
+        let x_id = graph.generate_variable_from_f64_tensor(Tensor::arrange([2, 3]), "x");
+        let y_id = graph.forward(mean_id, vec![x_id]);
+
+        let y = graph.get_variable_contents_f64(y_id[0]).unwrap();
+        assert_eq!(y, &Tensor::new_from_num_vec(vec![1.0, 4.0], vec![2]));
+    }
+
+    #[test]
+    fn backward_normal() {
+        let mut graph = Graph::new();
+
+        let mean_id = graph.generate_function_from_function_contents(Box::new(Mean::new(Some([0]), true)));
+        let x_id = graph.generate_variable_from_f64_tensor(Tensor::arrange([4, 2]), "x");
+        let y_id = graph.forward(mean_id, vec![x_id]);
+
+        graph.backward(y_id, false, false);
+
+        let x_grad = graph.get_variable_grad_contents_f64(x_id).unwrap();
+        assert_eq!(x_grad, &Tensor::full(0.25, vec![4, 2]));
+    }
+
+    #[test]
+    fn backward_all() {
+        let mut graph = Graph::new();
+
+        let mean_id = graph.generate_function_from_function_contents(Box::new(Mean::new::<&[usize]>(None, false)));
+        let x_id = graph.generate_variable_from_f64_tensor(Tensor::arrange([2, 5]), "x");
+        let y_id = graph.forward(mean_id, vec![x_id]);
+
+        graph.backward(y_id, false, false);
+
+        let x_grad = graph.get_variable_grad_contents_f64(x_id).unwrap();
+        assert_eq!(x_grad, &Tensor::full(0.1, vec![2, 5]));
+    }
+
+    #[test]
+    fn reduce_count_normal() {
+        assert_eq!(Mean::reduce_count(&[2, 3, 4], Some(&vec![0, 2])), 8);
+        assert_eq!(Mean::reduce_count(&[2, 3, 4], Some(&vec![])), 24);
+        assert_eq!(Mean::reduce_count(&[2, 3, 4], None), 24);
+    }
+
+    #[test]
+    fn try_forward_error_axis() {
+        let mut graph = Graph::new();
+
+        let mean_id = graph.generate_function_from_function_contents(Box::new(Mean::new(Some([5]), false)));
+        let x_id = graph.generate_variable_from_f64_tensor(Tensor::arrange([2, 3]), "x");
+        let error = graph.try_forward(mean_id, vec![x_id]).unwrap_err();
+
+        assert_eq!(error, KdezeroError::invalid_input("Mean", 0, &[2, 3],
+            TensorError::InvalidAxis { axis: 5, ndim: 2 }));
+    }
+}
@@ -0,0 +1,144 @@
+use std::any::Any;
+use super::Reduction;
+use super::reduction::{get_loss_inputs, loss_shape, difference_backward};
+use super::super::FunctionContents;
+use crate::error::KdezeroError;
+use crate::variable::VariableTable;
+use crate::graph::Graph;
+
+/// Absolute error of the inputs `|x - t|`, also known as the L1 loss
+/// 
+/// The inputs are x, t and the optional weights, broadcast to a common shape.
+/// The weights multiply each elementwise loss and get no gradient.
+/// The gradient is 0 where x equals t.
+#[derive(Debug, Clone)]
+pub struct MeanAbsoluteError {
+    reduction: Reduction,
+}
+
+impl MeanAbsoluteError {
+    /// Create a new MeanAbsoluteError instance, which takes the mean of the losses.
+    pub fn new() -> Self {
+        Self { reduction: Reduction::Mean }
+    }
+
+    /// Create a new MeanAbsoluteError instance with the reduction.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `reduction` - Reduction of the elementwise losses
+    pub fn with_reduction(reduction: Reduction) -> Self {
+        Self { reduction }
+    }
+
+    pub fn get_reduction(&self) -> Reduction {
+        self.reduction
+    }
+
+    fn input_check(inputs: &Vec<usize>) -> Result<(), KdezeroError> {
+        if inputs.len() != 2 && inputs.len() != 3 {
+            return Err(KdezeroError::InvalidInputCount { function: "MeanAbsoluteError".to_string(), expected: 2, actual: inputs.len() });
+        }
+        Ok(())
+    }
+
+    fn output_check(outputs: &Vec<usize>) -> Result<(), KdezeroError> {
+        if outputs.len() != 1 {
+            return Err(KdezeroError::InvalidOutputCount { function: "MeanAbsoluteError".to_string(), expected: 1, actual: outputs.len() });
+        }
+        Ok(())
+    }
+}
+
+impl FunctionContents for MeanAbsoluteError {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "MeanAbsoluteError"
+    }
+
+    fn forward(&self, info: &crate::function::FunctionInfo, inputs: &Vec<usize>, variable_table: &mut VariableTable) -> Vec<usize> {
+        self.try_forward(info, inputs, variable_table).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    fn try_forward(&self, _info: &crate::function::FunctionInfo, inputs: &Vec<usize>, variable_table: &mut VariableTable) -> Result<Vec<usize>, KdezeroError> {
+        let ([x, t], weights) = get_loss_inputs(self.name(), inputs, variable_table)?;
+        match weights {
+            Some(weights) => loss_shape(self.name(), &[x, t, weights])?,
+            None => loss_shape(self.name(), &[x, t])?,
+        };
+
+        let output = self.reduction.reduce((x - t).abs(), weights);
+
+        let output_id = variable_table.generate_variable_from_f64_tensor(output, "");
+        Ok(vec![output_id])
+    }
+
+    fn get_backward(&self) -> fn(usize, &mut Graph) -> Result<Vec<usize>, KdezeroError> {
+        |function_id, graph| {
+            let function = graph.get_function(function_id).ok_or(KdezeroError::InvalidFunctionId { id: function_id })?;
+            let reduction = function.get_function_contents::<MeanAbsoluteError>().ok_or(KdezeroError::InvalidFunctionId { id: function_id })?.get_reduction();
+            let inputs = function.try_get_inputs()?;
+            let outputs = function.try_get_outputs()?;
+            MeanAbsoluteError::input_check(inputs)?;
+            MeanAbsoluteError::output_check(outputs)?;
+            let input_ids = inputs.clone();
+            let output_id = outputs[0];
+            let output_grad_id = graph.get_variable_grad_id(output_id).ok_or_else(|| KdezeroError::GradNotFound { function: "MeanAbsoluteError".to_string(), id: output_id })?;
+
+            // grad * sign(x - t)
+            let x = graph.get_variable_contents_f64(input_ids[0]).ok_or(KdezeroError::InvalidVariableId { id: input_ids[0] })?;
+            let t = graph.get_variable_contents_f64(input_ids[1]).ok_or(KdezeroError::InvalidVariableId { id: input_ids[1] })?;
+            let sign = (x - t).sign();
+            let shape = sign.shape().clone();
+            let local_grad_id = graph.generate_variable_from_f64_tensor(sign, "");
+            let grad_id = reduction.loss_grad(graph, output_grad_id, input_ids.get(2).copied(), &shape)?;
+
+            difference_backward(graph, grad_id, local_grad_id, &input_ids)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ktensor::Tensor;
+
+    #[test]
+    fn forward_normal() {
+        let mut graph = Graph::new();
+
+        let mae_id = graph.generate_function_from_function_contents(Box::new(MeanAbsoluteError::with_reduction(Reduction::None)));
+        let x_id = graph.generate_variable_from_f64_tensor(
+            Tensor::new_from_num_vec(vec![1.0, 2.0, 3.0, 4.0], vec![2, 2]), "x");
+        let t_id = graph.generate_variable_from_f64_tensor(Tensor::new_from_num_vec(vec![2.0, 2.0], vec![2]), "t");
+        let y_id = graph.forward(mae_id, vec![x_id, t_id]);
+
+        let y = graph.get_variable_contents_f64(y_id[0]).unwrap();
+        assert_eq!(y, &Tensor::new_from_num_vec(vec![1.0, 0.0, 1.0, 2.0], vec![2, 2]));
+    }
+
+    #[test]
+    fn backward_normal() {
+        let mut graph = Graph::new();
+
+        let mae_id = graph.generate_function_from_function_contents(Box::new(MeanAbsoluteError::with_reduction(Reduction::Sum)));
+        let x_id = graph.generate_variable_from_f64_tensor(
+            Tensor::new_from_num_vec(vec![1.0, 2.0, 3.0, 4.0], vec![2, 2]), "x");
+        let t_id = graph.generate_variable_from_f64_tensor(Tensor::new_from_num_vec(vec![2.0, 2.0], vec![2]), "t");
+        let w_id = graph.generate_variable_from_f64_tensor(Tensor::new_from_num_vec(vec![1.0, 3.0], vec![2]), "w");
+        let y_ids = graph.forward(mae_id, vec![x_id, t_id, w_id]);
+
+        let y = graph.get_variable_contents_f64(y_ids[0]).unwrap();
+        assert_eq!(y, &Tensor::full(8.0, vec![]));
+
+        graph.backward(y_ids, false, false);
+
+        assert_eq!(graph.get_variable_grad_contents_f64(x_id).unwrap(),
+            &Tensor::new_from_num_vec(vec![-1.0, 0.0, 1.0, 3.0], vec![2, 2]));
+        assert_eq!(graph.get_variable_grad_contents_f64(t_id).unwrap(),
+            &Tensor::new_from_num_vec(vec![0.0, -3.0], vec![2]));
+    }
+}
@@ -1,27 +1,52 @@
 use std::any::Any;
-use super::{Sub, BroadcastTo, Mul, Neg};
-use super::super::{FunctionContents, FunctionTable};
+use super::{Sub, Mul, Reduction};
+use super::reduction::{get_loss_inputs, loss_shape, difference_backward};
+use super::super::FunctionContents;
+use crate::error::KdezeroError;
 use crate::variable::VariableTable;
+use crate::graph::Graph;
 use ktensor::Tensor;
 
+/// Squared error of the inputs `(x - t)^2`
+/// 
+/// The inputs are x, t and the optional weights, broadcast to a common shape.
+/// The weights multiply each elementwise loss and get no gradient.
 #[derive(Debug, Clone)]
-pub struct MeanSquaredError {}
+pub struct MeanSquaredError {
+    reduction: Reduction,
+}
 
 impl MeanSquaredError {
+    /// Create a new MeanSquaredError instance, which takes the mean of the losses.
     pub fn new() -> Self {
-        Self {}
+        Self { reduction: Reduction::Mean }
+    }
+
+    /// Create a new MeanSquaredError instance with the reduction.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `reduction` - Reduction of the elementwise losses
+    pub fn with_reduction(reduction: Reduction) -> Self {
+        Self { reduction }
     }
 
-    fn input_check(inputs: &Vec<usize>) {
-        if inputs.len() != 2 {
-            panic!("MeanSquaredError function must have only 2 input, but got {} inputs.", inputs.len());
+    pub fn get_reduction(&self) -> Reduction {
+        self.reduction
+    }
+
+    fn input_check(inputs: &Vec<usize>) -> Result<(), KdezeroError> {
+        if inputs.len() != 2 && inputs.len() != 3 {
+            return Err(KdezeroError::InvalidInputCount { function: "MeanSquaredError".to_string(), expected: 2, actual: inputs.len() });
         }
+        Ok(())
     }
 
-    fn output_check(outputs: &Vec<usize>) {
+    fn output_check(outputs: &Vec<usize>) -> Result<(), KdezeroError> {
         if outputs.len() != 1 {
-            panic!("MeanSquaredError function must have only one output, but got {} outputs.", outputs.len());
+            return Err(KdezeroError::InvalidOutputCount { function: "MeanSquaredError".to_string(), expected: 1, actual: outputs.len() });
         }
+        Ok(())
     }
 }
 
@@ -34,53 +59,45 @@ impl FunctionContents for MeanSquaredError {
         "MeanSquaredError"
     }
 
-    fn forward(&self, _info: &crate::function::FunctionInfo, inputs: &Vec<usize>, variable_table: &mut VariableTable) -> Vec<usize> {
-        MeanSquaredError::input_check(inputs);
-        let input0 = variable_table.get_variable_contents_f64(inputs[0]).expect("Invalid variable id");
-        let input1 = variable_table.get_variable_contents_f64(inputs[1]).expect("Invalid variable id");
+    fn forward(&self, info: &crate::function::FunctionInfo, inputs: &Vec<usize>, variable_table: &mut VariableTable) -> Vec<usize> {
+        self.try_forward(info, inputs, variable_table).unwrap_or_else(|e| panic!("{}", e))
+    }
 
-        let output = (input0 - input1)
-            .powi(2)
-            .sum([], false)
-            .scalar_div((input0.size() as f64).into());
+    fn try_forward(&self, _info: &crate::function::FunctionInfo, inputs: &Vec<usize>, variable_table: &mut VariableTable) -> Result<Vec<usize>, KdezeroError> {
+        let ([x, t], weights) = get_loss_inputs(self.name(), inputs, variable_table)?;
+        match weights {
+            Some(weights) => loss_shape(self.name(), &[x, t, weights])?,
+            None => loss_shape(self.name(), &[x, t])?,
+        };
+
+        let output = self.reduction.reduce((x - t).powi(2), weights);
 
         let output_id = variable_table.generate_variable_from_f64_tensor(output, "");
-        vec![output_id]
+        Ok(vec![output_id])
     }
 
-    fn get_backward(&self) -> fn(usize, &mut FunctionTable, &mut VariableTable) -> Vec<usize> {
-        |function_id, function_table, variable_table| {
-            let function = function_table.get(function_id).expect("Invalid function id");
-            let inputs = function.get_inputs().expect("Invalid inputs");
-            let outputs = function.get_outputs().expect("Invalid outputs");
-            MeanSquaredError::input_check(inputs);
-            MeanSquaredError::output_check(outputs);
+    fn get_backward(&self) -> fn(usize, &mut Graph) -> Result<Vec<usize>, KdezeroError> {
+        |function_id, graph| {
+            let function = graph.get_function(function_id).ok_or(KdezeroError::InvalidFunctionId { id: function_id })?;
+            let reduction = function.get_function_contents::<MeanSquaredError>().ok_or(KdezeroError::InvalidFunctionId { id: function_id })?.get_reduction();
+            let inputs = function.try_get_inputs()?;
+            let outputs = function.try_get_outputs()?;
+            MeanSquaredError::input_check(inputs)?;
+            MeanSquaredError::output_check(outputs)?;
             let input_ids = inputs.clone();
-            let input0 = variable_table
-                .get(input_ids[0]).expect("Invalid variable id");
-            let input_shape = input0.shape().clone();
-            let input_size = input0.size();
             let output_id = outputs[0];
-            let output_grad_id = variable_table.get_variable_grad_id(output_id).expect("Output grad id not found");
-
-            let sub_id = function_table.generate_function_from_function_contents(Box::new(Sub::new()));
-            let diff_id = function_table.forward(sub_id, input_ids.clone(), variable_table, false)[0];
-            let broadcast_to_id = function_table.generate_function_from_function_contents(Box::new(BroadcastTo::new(input_shape)));
-            let broadcast_gy_id = function_table.forward(broadcast_to_id, vec![output_grad_id], variable_table, false)[0];
-            let mul_id0 = function_table.generate_function_from_function_contents(Box::new(Mul::new()));
-            let gx0_id = function_table.forward(mul_id0, vec![broadcast_gy_id, diff_id], variable_table, false)[0];
-            let gx0 = variable_table.get_variable_contents_f64(gx0_id).expect("Invalid variable id");
-            let constant_tensor = Tensor::full_like(gx0, 2.0 / input_size as f64);
-            let constant_id = variable_table.generate_variable_from_f64_tensor(constant_tensor, "");
-            let mul_id1 = function_table.generate_function_from_function_contents(Box::new(Mul::new()));
-            let gx0_id = function_table.forward(mul_id1, vec![gx0_id, constant_id], variable_table, false)[0];
-            let neg_id = function_table.generate_function_from_function_contents(Box::new(Neg::new()));
-            let gx1_id = function_table.forward(neg_id, vec![gx0_id], variable_table, false)[0];
-
-            variable_table.update_grad(input_ids[0], gx0_id, function_table);
-            variable_table.update_grad(input_ids[1], gx1_id, function_table);
-
-            input_ids
+            let output_grad_id = graph.get_variable_grad_id(output_id).ok_or_else(|| KdezeroError::GradNotFound { function: "MeanSquaredError".to_string(), id: output_id })?;
+
+            // grad * 2 * (x - t)
+            let sub_id = graph.generate_function_from_function_contents(Box::new(Sub::new()));
+            let diff_id = graph.try_forward(sub_id, vec![input_ids[0], input_ids[1]])?[0];
+            let two_id = graph.generate_variable_from_f64_tensor(Tensor::full(2.0, vec![]), "");
+            let mul_id = graph.generate_function_from_function_contents(Box::new(Mul::new()));
+            let local_grad_id = graph.try_forward(mul_id, vec![diff_id, two_id])?[0];
+            let shape = graph.get_variable(diff_id).ok_or(KdezeroError::InvalidVariableId { id: diff_id })?.shape().clone();
+            let grad_id = reduction.loss_grad(graph, output_grad_id, input_ids.get(2).copied(), &shape)?;
+
+            difference_backward(graph, grad_id, local_grad_id, &input_ids)
         }
     }
 }
@@ -88,96 +105,162 @@ impl FunctionContents for MeanSquaredError {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use ktensor::Tensor;
-    use crate::{variable::VariableTable, function::FunctionTable};
 
     #[test]
     fn forward_normal() {
-        let mut variable_table = VariableTable::new();
-        let mut function_table = FunctionTable::new();
+        let mut graph = Graph::new();
 
         let data0 = vec![1.0, 2.0, 3.0];
         let data1 = vec![3.0, 2.0, 2.0];
-        let mse_id = function_table.generate_function_from_function_contents(Box::new(MeanSquaredError::new()));
-        let id0 = variable_table.generate_variable_from_f64_tensor(
+        let mse_id = graph.generate_function_from_function_contents(Box::new(MeanSquaredError::new()));
+        let id0 = graph.generate_variable_from_f64_tensor(
             Tensor::new_from_num_vec(data0.clone(), vec![3]), "x");
-        let id1 = variable_table.generate_variable_from_f64_tensor(
+        let id1 = graph.generate_variable_from_f64_tensor(
             Tensor::new_from_num_vec(data1.clone(), vec![3]), "y");
 
-        let output_ids = function_table.forward(mse_id, vec![id0, id1], &mut variable_table, false);
+        let output_ids = graph.forward(mse_id, vec![id0, id1]);
 
-        let output = variable_table.get_variable_contents_f64(output_ids[0]).unwrap();
+        let output = graph.get_variable_contents_f64(output_ids[0]).unwrap();
         assert_eq!(output, &Tensor::new_from_num_vec(vec![5.0 / 3.0], vec![]));
     }
 
     #[test]
     fn backward_normal() {
-        let mut variable_table = VariableTable::new();
-        let mut function_table = FunctionTable::new();
+        let mut graph = Graph::new();
 
         let data0 = vec![1.0, 2.0, 3.0];
         let data1 = vec![3.0, 2.0, 2.0];
-        let mse_id = function_table.generate_function_from_function_contents(Box::new(MeanSquaredError::new()));
-        let id0 = variable_table.generate_variable_from_f64_tensor(
+        let mse_id = graph.generate_function_from_function_contents(Box::new(MeanSquaredError::new()));
+        let id0 = graph.generate_variable_from_f64_tensor(
             Tensor::new_from_num_vec(data0.clone(), vec![3]), "x");
-        let id1 = variable_table.generate_variable_from_f64_tensor(
+        let id1 = graph.generate_variable_from_f64_tensor(
             Tensor::new_from_num_vec(data1.clone(), vec![3]), "y");
 
-        let output_ids = function_table.forward(mse_id, vec![id0, id1], &mut variable_table, false);
+        let output_ids = graph.forward(mse_id, vec![id0, id1]);
 
-        variable_table.backward(output_ids, &mut function_table, false);
+        graph.backward(output_ids, false, false);
 
-        let grad0 = variable_table.get_variable_grad_contents_f64(id0).unwrap();
-        let grad1 = variable_table.get_variable_grad_contents_f64(id1).unwrap();
+        let grad0 = graph.get_variable_grad_contents_f64(id0).unwrap();
+        let grad1 = graph.get_variable_grad_contents_f64(id1).unwrap();
         assert_eq!(grad0, &Tensor::new_from_num_vec(vec![-4.0 / 3.0, 0.0, 2.0 / 3.0], vec![3]));
         assert_eq!(grad1, &Tensor::new_from_num_vec(vec![4.0 / 3.0, 0.0, -2.0 / 3.0], vec![3]));
     }
 
     #[test]
     fn backward_backward_x_2() {
-        let mut variable_table = VariableTable::new();
-        let mut function_table = FunctionTable::new();
+        let mut graph = Graph::new();
 
         let data = vec![1.0, 2.0, 3.0];
-        let mse_id = function_table.generate_function_from_function_contents(Box::new(MeanSquaredError::new()));
-        let id = variable_table.generate_variable_from_f64_tensor(
+        let mse_id = graph.generate_function_from_function_contents(Box::new(MeanSquaredError::new()));
+        let id = graph.generate_variable_from_f64_tensor(
             Tensor::new_from_num_vec(data.clone(), vec![3]), "x");
 
-        let output_ids = function_table.forward(mse_id, vec![id, id], &mut variable_table, false);
+        let output_ids = graph.forward(mse_id, vec![id, id]);
 
-        variable_table.backward(output_ids, &mut function_table, false);
+        graph.backward(output_ids, false, true);
 
-        let grad0_id = variable_table.get_variable_grad_id(id).unwrap();
-        variable_table.clear_grad(id);
+        let grad0_id = graph.get_variable_grad_id(id).unwrap();
+        graph.clear_grad(id);
 
-        variable_table.backward(vec![grad0_id], &mut function_table, false);
+        graph.backward(vec![grad0_id], false, false);
 
-        let grad = variable_table.get_variable_grad_contents_f64(id).unwrap();
+        let grad = graph.get_variable_grad_contents_f64(id).unwrap();
         assert_eq!(grad, &Tensor::new_from_num_vec(vec![0.0, 0.0, 0.0], vec![3]));
     }
 
     #[test]
     fn backward_backward_x_y() {
-        let mut variable_table = VariableTable::new();
-        let mut function_table = FunctionTable::new();
+        let mut graph = Graph::new();
 
         let data0 = vec![1.0, 2.0, 3.0];
         let data1 = vec![3.0, 2.0, 2.0];
-        let mse_id = function_table.generate_function_from_function_contents(Box::new(MeanSquaredError::new()));
-        let id0 = variable_table.generate_variable_from_f64_tensor(
+        let mse_id = graph.generate_function_from_function_contents(Box::new(MeanSquaredError::new()));
+        let id0 = graph.generate_variable_from_f64_tensor(
             Tensor::new_from_num_vec(data0.clone(), vec![3]), "x");
-        let id1 = variable_table.generate_variable_from_f64_tensor(
+        let id1 = graph.generate_variable_from_f64_tensor(
             Tensor::new_from_num_vec(data1.clone(), vec![3]), "y");
 
-        let output_ids = function_table.forward(mse_id, vec![id0, id1], &mut variable_table, false);
+        let output_ids = graph.forward(mse_id, vec![id0, id1]);
+
+        graph.backward(output_ids, false, true);
+
+        let grad0_id = graph.get_variable_grad_id(id0).unwrap();
+        graph.clear_grad(id0);
 
-        variable_table.backward(output_ids, &mut function_table, false);
+        graph.backward(vec![grad0_id], false, false);
+
+        graph.get_variable_grad_contents_f64(id0).unwrap();
+    }
 
-        let grad0_id = variable_table.get_variable_grad_id(id0).unwrap();
-        variable_table.clear_grad(id0);
+    #[test]
+    fn forward_reduction() {
+        let mut graph = Graph::new();
+
+        let x_id = graph.generate_variable_from_f64_tensor(
+            Tensor::new_from_num_vec(vec![1.0, 2.0, 3.0, 4.0], vec![2, 2]), "x");
+        let t_id = graph.generate_variable_from_f64_tensor(Tensor::new_from_num_vec(vec![2.0, 2.0], vec![2]), "t");
+        let w_id = graph.generate_variable_from_f64_tensor(Tensor::new_from_num_vec(vec![1.0, 0.5], vec![2, 1]), "w");
+
+        let none_id = graph.generate_function_from_function_contents(Box::new(MeanSquaredError::with_reduction(Reduction::None)));
+        let none = graph.forward(none_id, vec![x_id, t_id])[0];
+        let sum_id = graph.generate_function_from_function_contents(Box::new(MeanSquaredError::with_reduction(Reduction::Sum)));
+        let sum = graph.forward(sum_id, vec![x_id, t_id, w_id])[0];
+
+        assert_eq!(graph.get_variable_contents_f64(none).unwrap(),
+            &Tensor::new_from_num_vec(vec![1.0, 0.0, 1.0, 4.0], vec![2, 2]));
+        assert_eq!(graph.get_variable_contents_f64(sum).unwrap(), &Tensor::full(3.5, vec![]));
+    }
+
+    #[test]
+    fn backward_broadcast_weights() {
+        let mut graph = Graph::new();
+
+        let x_id = graph.generate_variable_from_f64_tensor(
+            Tensor::new_from_num_vec(vec![1.0, 2.0, 3.0, 4.0], vec![2, 2]), "x");
+        let t_id = graph.generate_variable_from_f64_tensor(Tensor::new_from_num_vec(vec![2.0, 2.0], vec![2]), "t");
+        let w_id = graph.generate_variable_from_f64_tensor(Tensor::new_from_num_vec(vec![1.0, 0.5], vec![2, 1]), "w");
+        let mse_id = graph.generate_function_from_function_contents(Box::new(MeanSquaredError::new()));
+        let output_ids = graph.forward(mse_id, vec![x_id, t_id, w_id]);
+
+        graph.backward(output_ids, false, false);
+
+        // w * 2 * (x - t) / 4
+        assert_eq!(graph.get_variable_grad_contents_f64(x_id).unwrap(),
+            &Tensor::new_from_num_vec(vec![-0.5, 0.0, 0.25, 0.5], vec![2, 2]));
+        assert_eq!(graph.get_variable_grad_contents_f64(t_id).unwrap(),
+            &Tensor::new_from_num_vec(vec![0.25, -0.5], vec![2]));
+        assert!(graph.get_variable_grad_id(w_id).is_none());
+    }
+
+    #[test]
+    fn backward_weights_broadcast_past_inputs() {
+        let mut graph = Graph::new();
+
+        let x_id = graph.generate_variable_from_f64_tensor(Tensor::new_from_num_vec(vec![1.0, 3.0], vec![2]), "x");
+        let t_id = graph.generate_variable_from_f64_tensor(Tensor::full(0.0, vec![2]), "t");
+        let w_id = graph.generate_variable_from_f64_tensor(Tensor::full(1.0, vec![3, 2]), "w");
+        let mse_id = graph.generate_function_from_function_contents(Box::new(MeanSquaredError::new()));
+        let output_ids = graph.forward(mse_id, vec![x_id, t_id, w_id]);
+
+        graph.backward(output_ids, false, false);
+
+        // 3 rows of 2 * (x - t) / 6
+        assert_eq!(graph.get_variable_grad_contents_f64(x_id).unwrap(),
+            &Tensor::new_from_num_vec(vec![1.0, 3.0], vec![2]));
+        assert_eq!(graph.get_variable_grad_contents_f64(t_id).unwrap(),
+            &Tensor::new_from_num_vec(vec![-1.0, -3.0], vec![2]));
+    }
+
+    #[test]
+    fn try_forward_error_weights() {
+        let mut graph = Graph::new();
 
-        variable_table.backward(vec![grad0_id], &mut function_table, false);
+        let x_id = graph.generate_variable_from_f64_tensor(Tensor::full(0.0, vec![2, 2]), "x");
+        let t_id = graph.generate_variable_from_f64_tensor(Tensor::full(0.0, vec![2]), "t");
+        let w_id = graph.generate_variable_from_f64_tensor(Tensor::full(0.0, vec![3]), "w");
+        let mse_id = graph.generate_function_from_function_contents(Box::new(MeanSquaredError::new()));
+        let error = graph.try_forward(mse_id, vec![x_id, t_id, w_id]).unwrap_err();
 
-        variable_table.get_variable_grad_contents_f64(id0).unwrap();
+        assert!(matches!(error, KdezeroError::InvalidInput { index: 2, .. }));
     }
 }
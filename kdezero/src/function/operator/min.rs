@@ -0,0 +1,143 @@
+use std::any::Any;
+use super::Max;
+use super::super::{FunctionContents, get_inputs_f64};
+use crate::error::KdezeroError;
+use crate::variable::VariableTable;
+use crate::graph::Graph;
+
+/// Minimum along the given axes
+/// 
+/// The gradient is routed to the selected element of each output,
+/// which is the first one when the minimum appears more than once.
+#[derive(Debug, Clone)]
+pub struct Min {
+    axis: Option<Vec<usize>>,
+    keepdims: bool,
+}
+
+impl Min {
+    /// Create a new Min instance.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `axis` - Axes to reduce. If None, all the axes are reduced
+    /// * `keepdims` - Keep the reduced axes with the size 1
+    pub fn new<T: AsRef<[usize]>>(axis: Option<T>, keepdims: bool) -> Self {
+        Self { axis: axis.map(|x| x.as_ref().to_vec()), keepdims }
+    }
+
+    pub fn get_axis(&self) -> Option<&Vec<usize>> {
+        self.axis.as_ref()
+    }
+
+    pub fn get_keepdims(&self) -> bool {
+        self.keepdims
+    }
+
+    fn input_check(inputs: &Vec<usize>) -> Result<(), KdezeroError> {
+        if inputs.len() != 1 {
+            return Err(KdezeroError::InvalidInputCount { function: "Min".to_string(), expected: 1, actual: inputs.len() });
+        }
+        Ok(())
+    }
+
+    fn output_check(outputs: &Vec<usize>) -> Result<(), KdezeroError> {
+        if outputs.len() != 1 {
+            return Err(KdezeroError::InvalidOutputCount { function: "Min".to_string(), expected: 1, actual: outputs.len() });
+        }
+        Ok(())
+    }
+}
+
+impl FunctionContents for Min {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "Min"
+    }
+
+    fn forward(&self, info: &crate::function::FunctionInfo, inputs: &Vec<usize>, variable_table: &mut VariableTable) -> Vec<usize> {
+        self.try_forward(info, inputs, variable_table).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    fn try_forward(&self, _info: &crate::function::FunctionInfo, inputs: &Vec<usize>, variable_table: &mut VariableTable) -> Result<Vec<usize>, KdezeroError> {
+        let [input] = get_inputs_f64(self.name(), inputs, variable_table)?;
+
+        let output = input.try_min(self.axis.as_deref().unwrap_or(&[]), self.keepdims)
+            .map_err(|e| KdezeroError::invalid_input(self.name(), 0, input.shape(), e))?;
+
+        let output_id = variable_table.generate_variable_from_f64_tensor(output, "");
+        Ok(vec![output_id])
+    }
+
+    fn get_backward(&self) -> fn(usize, &mut Graph) -> Result<Vec<usize>, KdezeroError> {
+        |function_id, graph| {
+            let function = graph.get_function(function_id).ok_or(KdezeroError::InvalidFunctionId { id: function_id })?;
+            let function_contents = function.get_function_contents::<Min>().ok_or(KdezeroError::InvalidFunctionId { id: function_id })?;
+            let axis = function_contents.get_axis().cloned();
+            let keepdims = function_contents.get_keepdims();
+
+            let inputs = function.try_get_inputs()?;
+            let outputs = function.try_get_outputs()?;
+            Min::input_check(inputs)?;
+            Min::output_check(outputs)?;
+            let input_id = inputs[0];
+            let output_id = outputs[0];
+            let output_grad_id = graph.get_variable_grad_id(output_id).ok_or_else(|| KdezeroError::GradNotFound { function: "Min".to_string(), id: output_id })?;
+
+            let grad_id = Max::route_grad(graph, output_grad_id, input_id, output_id, axis.as_ref(), keepdims)?;
+
+            graph.try_update_grad(input_id, grad_id)?;
+
+            Ok(vec![input_id])
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ktensor::{Tensor, TensorError};
+
+    #[test]
+    fn forward_normal() {
+        let mut graph = Graph::new();
+
+        let min_id = graph.generate_function_from_function_contents(Box::new(Min::new(Some([0]), true)));
+        let x_id = graph.generate_variable_from_f64_tensor(
+            Tensor::new_from_num_vec(vec![1.0, 5.0, 3.0, 4.0, 2.0, 6.0], vec![2, 3]), "x");
+        let y_id = graph.forward(min_id, vec![x_id]);
+
+        let y = graph.get_variable_contents_f64(y_id[0]).unwrap();
+        assert_eq!(y, &Tensor::new_from_num_vec(vec![1.0, 2.0, 3.0], vec![1, 3]));
+    }
+
+    #[test]
+    fn backward_normal() {
+        let mut graph = Graph::new();
+
+        let min_id = graph.generate_function_from_function_contents(Box::new(Min::new(Some([1]), false)));
+        let x_id = graph.generate_variable_from_f64_tensor(
+            Tensor::new_from_num_vec(vec![1.0, 5.0, 3.0, 4.0, 2.0, 6.0], vec![2, 3]), "x");
+        let y_id = graph.forward(min_id, vec![x_id]);
+
+        graph.backward(y_id, false, false);
+
+        let x_grad = graph.get_variable_grad_contents_f64(x_id).unwrap();
+        assert_eq!(x_grad, &Tensor::new_from_num_vec(vec![1.0, 0.0, 0.0, 0.0, 1.0, 0.0], vec![2, 3]));
+    }
+
+    #[test]
+    fn try_forward_error_axis() {
+        let mut graph = Graph::new();
+
+        let min_id = graph.generate_function_from_function_contents(Box::new(Min::new(Some([2]), true)));
+        let x_id = graph.generate_variable_from_f64_tensor(Tensor::arrange([2, 3]), "x");
+        let error = graph.try_forward(min_id, vec![x_id]).unwrap_err();
+
+        assert_eq!(error, KdezeroError::invalid_input("Min", 0, &[2, 3],
+            TensorError::InvalidAxis { axis: 2, ndim: 2 }));
+    }
+}
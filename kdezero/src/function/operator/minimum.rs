@@ -0,0 +1,152 @@
+use std::any::Any;
+use super::Maximum;
+use super::super::{FunctionContents, get_inputs_f64};
+use ktensor::tensor::try_broadcast_shapes;
+use crate::error::KdezeroError;
+use crate::variable::VariableTable;
+use crate::graph::Graph;
+
+/// Elementwise minimum of two variables
+/// 
+/// The inputs are broadcast to the same shape.
+/// The gradient goes to the selected input, which is the first one on a tie.
+#[derive(Debug, Clone)]
+pub struct Minimum {}
+
+impl Minimum {
+    pub fn new() -> Self {
+        Self {}
+    }
+
+    fn input_check(inputs: &Vec<usize>) -> Result<(), KdezeroError> {
+        if inputs.len() != 2 {
+            return Err(KdezeroError::InvalidInputCount { function: "Minimum".to_string(), expected: 2, actual: inputs.len() });
+        }
+        Ok(())
+    }
+
+    fn output_check(outputs: &Vec<usize>) -> Result<(), KdezeroError> {
+        if outputs.len() != 1 {
+            return Err(KdezeroError::InvalidOutputCount { function: "Minimum".to_string(), expected: 1, actual: outputs.len() });
+        }
+        Ok(())
+    }
+}
+
+impl FunctionContents for Minimum {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "Minimum"
+    }
+
+    fn forward(&self, info: &crate::function::FunctionInfo, inputs: &Vec<usize>, variable_table: &mut VariableTable) -> Vec<usize> {
+        self.try_forward(info, inputs, variable_table).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    fn try_forward(&self, _info: &crate::function::FunctionInfo, inputs: &Vec<usize>, variable_table: &mut VariableTable) -> Result<Vec<usize>, KdezeroError> {
+        let [input0, input1] = get_inputs_f64(self.name(), inputs, variable_table)?;
+        try_broadcast_shapes(input0.shape(), input1.shape())
+            .map_err(|e| KdezeroError::invalid_input(self.name(), 1, input1.shape(), e))?;
+
+        let output = input0.minimum(input1);
+
+        let output_id = variable_table.generate_variable_from_f64_tensor(output, "");
+        Ok(vec![output_id])
+    }
+
+    fn get_backward(&self) -> fn(usize, &mut Graph) -> Result<Vec<usize>, KdezeroError> {
+        |function_id, graph| {
+            let function = graph.get_function(function_id).ok_or(KdezeroError::InvalidFunctionId { id: function_id })?;
+            let inputs = function.try_get_inputs()?;
+            let outputs = function.try_get_outputs()?;
+            Minimum::input_check(inputs)?;
+            Minimum::output_check(outputs)?;
+            let input_ids = inputs.clone();
+            let output_id = outputs[0];
+            let output_grad_id = graph.get_variable_grad_id(output_id).ok_or_else(|| KdezeroError::GradNotFound { function: "Minimum".to_string(), id: output_id })?;
+
+            let x0 = graph.get_variable_contents_f64(input_ids[0]).ok_or(KdezeroError::InvalidVariableId { id: input_ids[0] })?;
+            let x1 = graph.get_variable_contents_f64(input_ids[1]).ok_or(KdezeroError::InvalidVariableId { id: input_ids[1] })?;
+            let mask = x0.zip_map(x1, |x0, x1| (if x0 <= x1 { 1.0 } else { 0.0 }).into());
+
+            let (grad_id0, grad_id1) = Maximum::route_grad(graph, output_grad_id, &input_ids, mask)?;
+
+            graph.try_update_grad(input_ids[0], grad_id0)?;
+            graph.try_update_grad(input_ids[1], grad_id1)?;
+
+            Ok(input_ids)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ktensor::Tensor;
+
+    #[test]
+    fn forward_normal() {
+        let mut graph = Graph::new();
+
+        let minimum_id = graph.generate_function_from_function_contents(Box::new(Minimum::new()));
+        let id0 = graph.generate_variable_from_f64_tensor(
+            Tensor::new_from_num_vec(vec![1.0, 4.0, 2.0, 5.0], vec![2, 2]), "x");
+        let id1 = graph.generate_variable_from_f64_tensor(
+            Tensor::new_from_num_vec(vec![3.0, 3.0], vec![2]), "y");
+        let output_ids = graph.forward(minimum_id, vec![id0, id1]);
+
+        let output = graph.get_variable_contents_f64(output_ids[0]).unwrap();
+        assert_eq!(output, &Tensor::new_from_num_vec(vec![1.0, 3.0, 2.0, 3.0], vec![2, 2]));
+    }
+
+    #[test]
+    fn backward_normal() {
+        let mut graph = Graph::new();
+
+        let minimum_id = graph.generate_function_from_function_contents(Box::new(Minimum::new()));
+        let id0 = graph.generate_variable_from_f64_tensor(
+            Tensor::new_from_num_vec(vec![1.0, 4.0, 3.0, 5.0], vec![2, 2]), "x");
+        let id1 = graph.generate_variable_from_f64_tensor(
+            Tensor::new_from_num_vec(vec![3.0, 3.0], vec![2]), "y");
+        let output_ids = graph.forward(minimum_id, vec![id0, id1]);
+
+        graph.backward(output_ids, false, false);
+
+        let grad0 = graph.get_variable_grad_contents_f64(id0).unwrap();
+        let grad1 = graph.get_variable_grad_contents_f64(id1).unwrap();
+        assert_eq!(grad0, &Tensor::new_from_num_vec(vec![1.0, 0.0, 1.0, 0.0], vec![2, 2]));
+        assert_eq!(grad1, &Tensor::new_from_num_vec(vec![0.0, 2.0], vec![2]));
+    }
+
+    /// The second order gradient of `minimum(x, y)^2` flows back only to the selected input
+    #[test]
+    fn backward_backward_normal() {
+        let mut graph = Graph::new();
+
+        let minimum_id = graph.generate_function_from_function_contents(Box::new(Minimum::new()));
+        let square_id = graph.generate_function_from_function_contents(Box::new(super::super::Square::new()));
+        let id0 = graph.generate_variable_from_f64_tensor(
+            Tensor::new_from_num_vec(vec![1.0, 4.0], vec![2]), "x");
+        let id1 = graph.generate_variable_from_f64_tensor(
+            Tensor::new_from_num_vec(vec![3.0, 3.0], vec![2]), "y");
+        let y_id = graph.forward(minimum_id, vec![id0, id1])[0];
+        let z_ids = graph.forward(square_id, vec![y_id]);
+
+        graph.backward(z_ids, false, true);
+
+        let grad0_id = graph.get_variable_grad_id(id0).unwrap();
+        assert_eq!(graph.get_variable_contents_f64(grad0_id).unwrap(), &Tensor::new_from_num_vec(vec![2.0, 0.0], vec![2]));
+        graph.clear_grad(id0);
+        graph.clear_grad(id1);
+
+        graph.backward(vec![grad0_id], false, false);
+
+        let grad0 = graph.get_variable_grad_contents_f64(id0).unwrap();
+        let grad1 = graph.get_variable_grad_contents_f64(id1).unwrap();
+        assert_eq!(grad0, &Tensor::new_from_num_vec(vec![2.0, 0.0], vec![2]));
+        assert_eq!(grad1, &Tensor::new_from_num_vec(vec![0.0, 0.0], vec![2]));
+    }
+}
@@ -1,6 +1,10 @@
 use std::any::Any;
-use super::super::{FunctionContents, FunctionTable};
+use super::SumTo;
+use super::super::{FunctionContents, get_inputs_f64};
+use ktensor::tensor::try_broadcast_shapes;
+use crate::error::KdezeroError;
 use crate::variable::VariableTable;
+use crate::graph::Graph;
 
 #[derive(Debug, Clone)]
 pub struct Mul {}
@@ -10,16 +14,18 @@ impl Mul {
         Self {}
     }
 
-    fn input_check(inputs: &Vec<usize>) {
+    fn input_check(inputs: &Vec<usize>) -> Result<(), KdezeroError> {
         if inputs.len() != 2 {
-            panic!("Mul function must have only 2 input, but got {} inputs.", inputs.len());
+            return Err(KdezeroError::InvalidInputCount { function: "Mul".to_string(), expected: 2, actual: inputs.len() });
         }
+        Ok(())
     }
 
-    fn output_check(outputs: &Vec<usize>) {
+    fn output_check(outputs: &Vec<usize>) -> Result<(), KdezeroError> {
         if outputs.len() != 1 {
-            panic!("Mul function must have only one output, but got {} outputs.", outputs.len());
+            return Err(KdezeroError::InvalidOutputCount { function: "Mul".to_string(), expected: 1, actual: outputs.len() });
         }
+        Ok(())
     }
 }
 
@@ -32,37 +38,44 @@ impl FunctionContents for Mul {
         "Mul"
     }
 
-    fn forward(&self, _info: &crate::function::FunctionInfo, inputs: &Vec<usize>, variable_table: &mut VariableTable) -> Vec<usize> {
-        Mul::input_check(inputs);
-        let input0 = variable_table.get_variable_contents_f64(inputs[0]).expect("Invalid variable id");
-        let input1 = variable_table.get_variable_contents_f64(inputs[1]).expect("Invalid variable id");
+    fn forward(&self, info: &crate::function::FunctionInfo, inputs: &Vec<usize>, variable_table: &mut VariableTable) -> Vec<usize> {
+        self.try_forward(info, inputs, variable_table).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    fn try_forward(&self, _info: &crate::function::FunctionInfo, inputs: &Vec<usize>, variable_table: &mut VariableTable) -> Result<Vec<usize>, KdezeroError> {
+        let [input0, input1] = get_inputs_f64(self.name(), inputs, variable_table)?;
+        try_broadcast_shapes(input0.shape(), input1.shape())
+            .map_err(|e| KdezeroError::invalid_input(self.name(), 1, input1.shape(), e))?;
 
         let output = input0 * input1;
 
         let output_id = variable_table.generate_variable_from_f64_tensor(output, "");
-        vec![output_id]
+        Ok(vec![output_id])
     }
 
-    fn get_backward(&self) -> fn(usize, &mut FunctionTable, &mut VariableTable) -> Vec<usize> {
-        |function_id, function_table, variable_table| {
-            let function = function_table.get(function_id).expect("Invalid function id");
-            let inputs = function.get_inputs().expect("Invalid inputs");
-            let outputs = function.get_outputs().expect("Invalid outputs");
-            Mul::input_check(inputs);
-            Mul::output_check(outputs);
+    fn get_backward(&self) -> fn(usize, &mut Graph) -> Result<Vec<usize>, KdezeroError> {
+        |function_id, graph| {
+            let function = graph.get_function(function_id).ok_or(KdezeroError::InvalidFunctionId { id: function_id })?;
+            let inputs = function.try_get_inputs()?;
+            let outputs = function.try_get_outputs()?;
+            Mul::input_check(inputs)?;
+            Mul::output_check(outputs)?;
             let input_ids = inputs.clone();
             let output_id = outputs[0];
-            let output_grad_id = variable_table.get_variable_grad_id(output_id).expect("Output grad id not found");
+            let output_grad_id = graph.get_variable_grad_id(output_id).ok_or_else(|| KdezeroError::GradNotFound { function: "Mul".to_string(), id: output_id })?;
+
+            let mul_id0 = graph.generate_function_from_function_contents(Box::new(Mul::new()));
+            let grad_id0 = graph.try_forward(mul_id0, vec![output_grad_id, input_ids[1]])?[0];
+            let mul_id1 = graph.generate_function_from_function_contents(Box::new(Mul::new()));
+            let grad_id1 = graph.try_forward(mul_id1, vec![output_grad_id, input_ids[0]])?[0];
 
-            let mul_id0 = function_table.generate_function_from_function_contents(Box::new(Mul::new()));
-            let grad_id0 = function_table.forward(mul_id0, vec![output_grad_id, input_ids[1]], variable_table, false)[0];
-            let mul_id1 = function_table.generate_function_from_function_contents(Box::new(Mul::new()));
-            let grad_id1 = function_table.forward(mul_id1, vec![output_grad_id, input_ids[0]], variable_table, false)[0];
+            let grad_id0 = SumTo::reduce_grad(graph, grad_id0, input_ids[0])?;
+            let grad_id1 = SumTo::reduce_grad(graph, grad_id1, input_ids[1])?;
 
-            variable_table.update_grad(input_ids[0], grad_id0, function_table);
-            variable_table.update_grad(input_ids[1], grad_id1, function_table);
+            graph.try_update_grad(input_ids[0], grad_id0)?;
+            graph.try_update_grad(input_ids[1], grad_id1)?;
 
-            input_ids
+            Ok(input_ids)
         }
     }
 }
@@ -71,99 +84,116 @@ impl FunctionContents for Mul {
 mod tests {
     use super::*;
     use ktensor::Tensor;
-    use crate::{variable::VariableTable, function::FunctionTable};
 
     #[test]
     fn forward_normal() {
-        let mut variable_table = VariableTable::new();
-        let mut function_table = FunctionTable::new();
+        let mut graph = Graph::new();
 
         let data0 = vec![1.0, 2.0, 3.0];
         let data1 = vec![4.0, 5.0, 6.0];
-        let mul_id = function_table.generate_function_from_function_contents(Box::new(Mul::new()));
-        let id0 = variable_table.generate_variable_from_f64_tensor(
+        let mul_id = graph.generate_function_from_function_contents(Box::new(Mul::new()));
+        let id0 = graph.generate_variable_from_f64_tensor(
             Tensor::new_from_num_vec(data0.clone(), vec![3]), "x");
-        let id1 = variable_table.generate_variable_from_f64_tensor(
+        let id1 = graph.generate_variable_from_f64_tensor(
             Tensor::new_from_num_vec(data1.clone(), vec![3]), "y");
         
-        let output_ids = function_table.forward(mul_id, vec![id0, id1], &mut variable_table, false);
+        let output_ids = graph.forward(mul_id, vec![id0, id1]);
 
-        let output = variable_table.get_variable_contents_f64(output_ids[0]).unwrap();
+        let output = graph.get_variable_contents_f64(output_ids[0]).unwrap();
         assert_eq!(output.data(), Tensor::new_from_num_vec(vec![4.0, 10.0, 18.0], vec![3]).data());
     }
 
     #[test]
     fn backward_normal() {
-        let mut variable_table = VariableTable::new();
-        let mut function_table = FunctionTable::new();
+        let mut graph = Graph::new();
 
         let data0 = vec![1.0, 2.0, 3.0];
         let data1 = vec![4.0, 5.0, 6.0];
-        let mul_id = function_table.generate_function_from_function_contents(Box::new(Mul::new()));
-        let id0 = variable_table.generate_variable_from_f64_tensor(
+        let mul_id = graph.generate_function_from_function_contents(Box::new(Mul::new()));
+        let id0 = graph.generate_variable_from_f64_tensor(
             Tensor::new_from_num_vec(data0.clone(), vec![3]), "x");
-        let id1 = variable_table.generate_variable_from_f64_tensor(
+        let id1 = graph.generate_variable_from_f64_tensor(
             Tensor::new_from_num_vec(data1.clone(), vec![3]), "y");
 
-        let output_ids = function_table.forward(mul_id, vec![id0, id1], &mut variable_table, false);
+        let output_ids = graph.forward(mul_id, vec![id0, id1]);
 
-        variable_table.backward(output_ids, &mut function_table, false);
+        graph.backward(output_ids, false, false);
 
-        let grad0 = variable_table.get_variable_grad_contents_f64(id0).unwrap();
-        let grad1 = variable_table.get_variable_grad_contents_f64(id1).unwrap();
+        let grad0 = graph.get_variable_grad_contents_f64(id0).unwrap();
+        let grad1 = graph.get_variable_grad_contents_f64(id1).unwrap();
         assert_eq!(grad0, &Tensor::new_from_num_vec(vec![4.0, 5.0, 6.0], vec![3]));
         assert_eq!(grad1, &Tensor::new_from_num_vec(vec![1.0, 2.0, 3.0], vec![3]));
     }
 
     #[test]
     fn backward_backward_x_2() {
-        let mut variable_table = VariableTable::new();
-        let mut function_table = FunctionTable::new();
+        let mut graph = Graph::new();
 
         let data = vec![1.0, 2.0, 3.0];
-        let mul_id = function_table.generate_function_from_function_contents(Box::new(Mul::new()));
-        let id = variable_table.generate_variable_from_f64_tensor(
+        let mul_id = graph.generate_function_from_function_contents(Box::new(Mul::new()));
+        let id = graph.generate_variable_from_f64_tensor(
             Tensor::new_from_num_vec(data.clone(), vec![3]), "x");
 
-        let output_ids = function_table.forward(mul_id, vec![id, id], &mut variable_table, false);
+        let output_ids = graph.forward(mul_id, vec![id, id]);
 
-        variable_table.backward(output_ids, &mut function_table, false);
+        graph.backward(output_ids, false, true);
 
-        let grad0_id = variable_table.get_variable_grad_id(id).unwrap();
-        variable_table.clear_grad(id);
+        let grad0_id = graph.get_variable_grad_id(id).unwrap();
+        graph.clear_grad(id);
 
-        variable_table.backward(vec![grad0_id], &mut function_table, false);
+        graph.backward(vec![grad0_id], false, false);
 
-        let grad = variable_table.get_variable_grad_contents_f64(id).unwrap();
+        let grad = graph.get_variable_grad_contents_f64(id).unwrap();
         assert_eq!(grad, &Tensor::new_from_num_vec(vec![2.0, 2.0, 2.0], vec![3]));
     }
 
     /// Test for backward of backward
-    /// This test is expected to panic
-    /// because it doesn't get through to x (probably)
+    /// The gradient of x does not depend on x,
+    /// so only y gets the second order gradient
     #[test]
-    #[should_panic]
     fn backward_backward_x_y() {
-        let mut variable_table = VariableTable::new();
-        let mut function_table = FunctionTable::new();
+        let mut graph = Graph::new();
 
         let data0 = vec![1.0, 2.0, 3.0];
         let data1 = vec![4.0, 5.0, 6.0];
-        let mul_id = function_table.generate_function_from_function_contents(Box::new(Mul::new()));
-        let id0 = variable_table.generate_variable_from_f64_tensor(
+        let mul_id = graph.generate_function_from_function_contents(Box::new(Mul::new()));
+        let id0 = graph.generate_variable_from_f64_tensor(
             Tensor::new_from_num_vec(data0.clone(), vec![3]), "x");
-        let id1 = variable_table.generate_variable_from_f64_tensor(
+        let id1 = graph.generate_variable_from_f64_tensor(
             Tensor::new_from_num_vec(data1.clone(), vec![3]), "y");
 
-        let output_ids = function_table.forward(mul_id, vec![id0, id1], &mut variable_table, false);
+        let output_ids = graph.forward(mul_id, vec![id0, id1]);
+
+        graph.backward(output_ids, false, true);
+
+        let grad0_id = graph.get_variable_grad_id(id0).unwrap();
+        graph.clear_grad(id0);
+        graph.clear_grad(id1);
+
+        graph.backward(vec![grad0_id], false, false);
+
+        assert!(graph.get_variable_grad_id(id0).is_none());
+        let grad = graph.get_variable_grad_contents_f64(id1).unwrap();
+        assert_eq!(grad, &Tensor::new_from_num_vec(vec![1.0, 1.0, 1.0], vec![3]));
+    }
+
+    #[test]
+    fn backward_broadcast() {
+        let mut graph = Graph::new();
 
-        variable_table.backward(output_ids, &mut function_table, false);
+        let mul_id = graph.generate_function_from_function_contents(Box::new(Mul::new()));
+        let id0 = graph.generate_variable_from_f64_tensor(Tensor::arrange([2, 3]), "x");
+        let id1 = graph.generate_variable_from_f64_tensor(
+            Tensor::new_from_num_vec(vec![1.0, 2.0, 4.0], vec![3]), "y");
 
-        let grad0_id = variable_table.get_variable_grad_id(id0).unwrap();
-        variable_table.clear_grad(id0);
+        let output_ids = graph.forward(mul_id, vec![id0, id1]);
+        assert_eq!(graph.get_variable(output_ids[0]).unwrap().shape(), &vec![2, 3]);
 
-        variable_table.backward(vec![grad0_id], &mut function_table, false);
+        graph.backward(output_ids, false, false);
 
-        variable_table.get_variable_grad_contents_f64(id0).unwrap();
+        let grad0 = graph.get_variable_grad_contents_f64(id0).unwrap();
+        let grad1 = graph.get_variable_grad_contents_f64(id1).unwrap();
+        assert_eq!(grad0, &Tensor::new_from_num_vec(vec![1.0, 2.0, 4.0, 1.0, 2.0, 4.0], vec![2, 3]));
+        assert_eq!(grad1, &Tensor::new_from_num_vec(vec![3.0, 5.0, 7.0], vec![3]));
     }
 }
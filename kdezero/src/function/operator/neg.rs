@@ -1,6 +1,8 @@
 use std::any::Any;
-use super::super::{FunctionContents, FunctionTable};
+use super::super::FunctionContents;
 use crate::variable::VariableTable;
+use crate::error::KdezeroError;
+use crate::graph::Graph;
 
 #[derive(Debug, Clone)]
 pub struct Neg {}
@@ -10,16 +12,18 @@ impl Neg {
         Self {}
     }
 
-    fn input_check(inputs: &Vec<usize>) {
+    fn input_check(inputs: &Vec<usize>) -> Result<(), KdezeroError> {
         if inputs.len() != 1 {
-            panic!("Neg function must have only one input, but got {} inputs.", inputs.len());
+            return Err(KdezeroError::InvalidInputCount { function: "Neg".to_string(), expected: 1, actual: inputs.len() });
         }
+        Ok(())
     }
 
-    fn output_check(outputs: &Vec<usize>) {
+    fn output_check(outputs: &Vec<usize>) -> Result<(), KdezeroError> {
         if outputs.len() != 1 {
-            panic!("Neg function must have only one output, but got {} outputs.", outputs.len());
+            return Err(KdezeroError::InvalidOutputCount { function: "Neg".to_string(), expected: 1, actual: outputs.len() });
         }
+        Ok(())
     }
 }
 
@@ -33,7 +37,7 @@ impl FunctionContents for Neg {
     }
 
     fn forward(&self, _info: &crate::function::FunctionInfo, inputs: &Vec<usize>, variable_table: &mut VariableTable) -> Vec<usize> {
-        Neg::input_check(inputs);
+        Neg::input_check(inputs).unwrap_or_else(|e| panic!("{}", e));
         let x = variable_table.get_variable_contents_f64(inputs[0]).expect("Invalid variable id");
 
         let output = -x;
@@ -42,23 +46,23 @@ impl FunctionContents for Neg {
         vec![output_id]
     }
 
-    fn get_backward(&self) -> fn(usize, &mut FunctionTable, &mut VariableTable) -> Vec<usize> {
-        |function_id, function_table, variable_table| {
-            let function = function_table.get(function_id).expect("Invalid function id");
-            let inputs = function.get_inputs().expect("Invalid inputs");
-            let outputs = function.get_outputs().expect("Invalid outputs");
-            Neg::input_check(inputs);
-            Neg::output_check(outputs);
+    fn get_backward(&self) -> fn(usize, &mut Graph) -> Result<Vec<usize>, KdezeroError> {
+        |function_id, graph| {
+            let function = graph.get_function(function_id).ok_or(KdezeroError::InvalidFunctionId { id: function_id })?;
+            let inputs = function.try_get_inputs()?;
+            let outputs = function.try_get_outputs()?;
+            Neg::input_check(inputs)?;
+            Neg::output_check(outputs)?;
             let input_id = inputs[0];
             let output_id = outputs[0];
-            let output_grad_id = variable_table.get_variable_grad_id(output_id).expect("Output grad id not found");
+            let output_grad_id = graph.get_variable_grad_id(output_id).ok_or_else(|| KdezeroError::GradNotFound { function: "Neg".to_string(), id: output_id })?;
 
-            let neg_id = function_table.generate_function_from_function_contents(Box::new(Neg::new()));
-            let grad_id = function_table.forward(neg_id, vec![output_grad_id], variable_table, false)[0];
+            let neg_id = graph.generate_function_from_function_contents(Box::new(Neg::new()));
+            let grad_id = graph.try_forward(neg_id, vec![output_grad_id])?[0];
 
-            variable_table.update_grad(input_id, grad_id, function_table);
+            graph.try_update_grad(input_id, grad_id)?;
 
-            vec![input_id]
+            Ok(vec![input_id])
         }
     }
 }
@@ -67,37 +71,34 @@ impl FunctionContents for Neg {
 mod tests {
     use super::*;
     use ktensor::Tensor;
-    use crate::{variable::VariableTable, function::FunctionTable};
 
     #[test]
     fn forward_normal() {
-        let mut variable_table = VariableTable::new();
-        let mut function_table = FunctionTable::new();
+        let mut graph = Graph::new();
 
         let data = vec![1.0, 2.0, 3.0];
-        let neg_id = function_table.generate_function_from_function_contents(Box::new(Neg::new()));
-        let x_id = variable_table.generate_variable_from_f64_tensor(
+        let neg_id = graph.generate_function_from_function_contents(Box::new(Neg::new()));
+        let x_id = graph.generate_variable_from_f64_tensor(
             Tensor::new_from_num_vec(data.clone(), vec![3]), "x");
-        let y_id = function_table.forward(neg_id, vec![x_id], &mut variable_table, false);
+        let y_id = graph.forward(neg_id, vec![x_id]);
 
-        let y = variable_table.get_variable_contents_f64(y_id[0]).unwrap();
+        let y = graph.get_variable_contents_f64(y_id[0]).unwrap();
         assert_eq!(y.data(), Tensor::new_from_num_vec(vec![-1.0, -2.0, -3.0], vec![3]).data());
     }
 
     #[test]
     fn backward_normal() {
-        let mut variable_table = VariableTable::new();
-        let mut function_table = FunctionTable::new();
+        let mut graph = Graph::new();
 
         let data = vec![1.0, 2.0, 3.0];
-        let neg_id = function_table.generate_function_from_function_contents(Box::new(Neg::new()));
-        let x_id = variable_table.generate_variable_from_f64_tensor(
+        let neg_id = graph.generate_function_from_function_contents(Box::new(Neg::new()));
+        let x_id = graph.generate_variable_from_f64_tensor(
             Tensor::new_from_num_vec(data.clone(), vec![3]), "x");
-        let y_ids = function_table.forward(neg_id, vec![x_id], &mut variable_table, false);
+        let y_ids = graph.forward(neg_id, vec![x_id]);
 
-        variable_table.backward(y_ids, &mut function_table, false);
+        graph.backward(y_ids, false, false);
 
-        let x_grad = variable_table.get_variable_grad_contents_f64(x_id).unwrap();
+        let x_grad = graph.get_variable_grad_contents_f64(x_id).unwrap();
         assert_eq!(x_grad, &Tensor::new_from_num_vec(vec![-1.0, -1.0, -1.0], vec![3]));
     }
 
@@ -107,22 +108,21 @@ mod tests {
     #[test]
     #[should_panic]
     fn backward_backward_normal() {
-        let mut variable_table = VariableTable::new();
-        let mut function_table = FunctionTable::new();
+        let mut graph = Graph::new();
 
         let data = vec![1.0, 2.0, 3.0];
-        let neg_id = function_table.generate_function_from_function_contents(Box::new(Neg::new()));
-        let x_id = variable_table.generate_variable_from_f64_tensor(
+        let neg_id = graph.generate_function_from_function_contents(Box::new(Neg::new()));
+        let x_id = graph.generate_variable_from_f64_tensor(
             Tensor::new_from_num_vec(data.clone(), vec![3]), "x");
-        let y_ids = function_table.forward(neg_id, vec![x_id], &mut variable_table, false);
+        let y_ids = graph.forward(neg_id, vec![x_id]);
 
-        variable_table.backward(y_ids, &mut function_table, false);
+        graph.backward(y_ids, false, true);
 
-        let x_grad_id = variable_table.get_variable_grad_id(x_id).unwrap();
-        variable_table.clear_grad(x_id);
+        let x_grad_id = graph.get_variable_grad_id(x_id).unwrap();
+        graph.clear_grad(x_id);
 
-        variable_table.backward(vec![x_grad_id], &mut function_table, false);
+        graph.backward(vec![x_grad_id], false, false);
 
-        variable_table.get_variable_grad_contents_f64(x_id).unwrap();
+        graph.get_variable_grad_contents_f64(x_id).unwrap();
     }
 }
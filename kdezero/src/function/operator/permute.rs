@@ -0,0 +1,172 @@
+use std::any::Any;
+use super::super::{FunctionContents, get_inputs_f64};
+use crate::error::KdezeroError;
+use crate::variable::VariableTable;
+use crate::graph::Graph;
+
+#[derive(Debug, Clone)]
+pub struct Permute {
+    axes: Vec<usize>,
+}
+
+impl Permute {
+    /// Create a new Permute instance.
+    /// 
+    /// The axis `i` of the output is the axis `axes[i]` of the input.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `axes` - New order of the axes
+    pub fn new(axes: Vec<usize>) -> Self {
+        Self { axes }
+    }
+
+    /// Create a Permute instance that swaps two axes of an `ndim` dimensional input.
+    /// 
+    /// # Panics
+    /// 
+    /// * `Invalid axis` - If an axis is not less than `ndim`
+    pub fn swapaxes(ndim: usize, axis1: usize, axis2: usize) -> Self {
+        assert!(axis1 < ndim && axis2 < ndim, "Invalid axis");
+        let mut axes = (0..ndim).collect::<Vec<usize>>();
+        axes.swap(axis1, axis2);
+        Self::new(axes)
+    }
+
+    pub fn get_axes(&self) -> &Vec<usize> {
+        &self.axes
+    }
+
+    /// Get the axes of the permutation that undoes this one.
+    pub fn inverse_axes(&self) -> Vec<usize> {
+        let mut inverse = vec![0; self.axes.len()];
+        for (i, &axis) in self.axes.iter().enumerate() {
+            inverse[axis] = i;
+        }
+        inverse
+    }
+
+    fn input_check(inputs: &Vec<usize>) -> Result<(), KdezeroError> {
+        if inputs.len() != 1 {
+            return Err(KdezeroError::InvalidInputCount { function: "Permute".to_string(), expected: 1, actual: inputs.len() });
+        }
+        Ok(())
+    }
+
+    fn output_check(outputs: &Vec<usize>) -> Result<(), KdezeroError> {
+        if outputs.len() != 1 {
+            return Err(KdezeroError::InvalidOutputCount { function: "Permute".to_string(), expected: 1, actual: outputs.len() });
+        }
+        Ok(())
+    }
+}
+
+impl FunctionContents for Permute {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "Permute"
+    }
+
+    fn forward(&self, info: &crate::function::FunctionInfo, inputs: &Vec<usize>, variable_table: &mut VariableTable) -> Vec<usize> {
+        self.try_forward(info, inputs, variable_table).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    fn try_forward(&self, _info: &crate::function::FunctionInfo, inputs: &Vec<usize>, variable_table: &mut VariableTable) -> Result<Vec<usize>, KdezeroError> {
+        let [x] = get_inputs_f64(self.name(), inputs, variable_table)?;
+
+        let output = x.try_permute(&self.axes)
+            .map_err(|e| KdezeroError::invalid_input(self.name(), 0, x.shape(), e))?;
+
+        let output_id = variable_table.generate_variable_from_f64_tensor(output, "");
+        Ok(vec![output_id])
+    }
+
+    fn get_backward(&self) -> fn(usize, &mut Graph) -> Result<Vec<usize>, KdezeroError> {
+        |function_id, graph| {
+            let function = graph.get_function(function_id).ok_or(KdezeroError::InvalidFunctionId { id: function_id })?;
+            let function_contents = function.get_function_contents::<Permute>().ok_or(KdezeroError::InvalidFunctionId { id: function_id })?;
+            let inverse_axes = function_contents.inverse_axes();
+
+            let inputs = function.try_get_inputs()?;
+            let outputs = function.try_get_outputs()?;
+            Permute::input_check(inputs)?;
+            Permute::output_check(outputs)?;
+            let input_id = inputs[0];
+            let output_id = outputs[0];
+            let output_grad_id = graph.get_variable_grad_id(output_id).ok_or_else(|| KdezeroError::GradNotFound { function: "Permute".to_string(), id: output_id })?;
+
+            let permute_id = graph.generate_function_from_function_contents(Box::new(Permute::new(inverse_axes)));
+            let grad_id = graph.try_forward(permute_id, vec![output_grad_id])?[0];
+
+            graph.try_update_grad(input_id, grad_id)?;
+
+            Ok(vec![input_id])
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ktensor::Tensor;
+    use crate::function::operator::Mul;
+
+    #[test]
+    fn forward_normal() {
+        let mut graph = Graph::new();
+
+        let permute_id = graph.generate_function_from_function_contents(Box::new(Permute::new(vec![2, 0, 1])));
+        let x_id = graph.generate_variable_from_f64_tensor(Tensor::arrange([2, 3, 4]), "x");
+        let y_id = graph.forward(permute_id, vec![x_id]);
+
+        let x = graph.get_variable_contents_f64(x_id).unwrap().clone();
+        let y = graph.get_variable_contents_f64(y_id[0]).unwrap();
+        assert_eq!(y.shape(), &vec![4, 2, 3]);
+        assert_eq!(y.at(&[3, 1, 2]), x.at(&[1, 2, 3]));
+    }
+
+    #[test]
+    fn forward_swapaxes() {
+        let mut graph = Graph::new();
+
+        let permute_id = graph.generate_function_from_function_contents(Box::new(Permute::swapaxes(3, 0, 2)));
+        let x_id = graph.generate_variable_from_f64_tensor(Tensor::arrange([2, 3, 4]), "x");
+        let y_id = graph.forward(permute_id, vec![x_id]);
+
+        let y = graph.get_variable_contents_f64(y_id[0]).unwrap();
+        assert_eq!(y, &Tensor::<f64>::arrange([2, 3, 4]).swapaxes(0, 2));
+    }
+
+    #[test]
+    fn backward_normal() {
+        let mut graph = Graph::new();
+
+        let permute_id = graph.generate_function_from_function_contents(Box::new(Permute::new(vec![2, 0, 1])));
+        let x_id = graph.generate_variable_from_f64_tensor(Tensor::arrange([2, 3, 4]), "x");
+        let y_id = graph.forward(permute_id, vec![x_id])[0];
+        let w = Tensor::<f64>::arrange([4, 2, 3]);
+        let w_id = graph.generate_variable_from_f64_tensor(w.clone(), "w");
+        let mul_id = graph.generate_function_from_function_contents(Box::new(Mul::new()));
+        let z_id = graph.forward(mul_id, vec![y_id, w_id])[0];
+
+        graph.backward(vec![z_id], false, false);
+
+        let x_grad = graph.get_variable_grad_contents_f64(x_id).unwrap();
+        assert_eq!(x_grad, &w.permute(&[1, 2, 0]));
+    }
+
+    #[test]
+    fn inverse_axes_normal() {
+        let permute = Permute::new(vec![2, 0, 3, 1]);
+        assert_eq!(permute.inverse_axes(), vec![1, 3, 0, 2]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid axis")]
+    fn swapaxes_error_invalid_axis() {
+        Permute::swapaxes(2, 0, 2);
+    }
+}
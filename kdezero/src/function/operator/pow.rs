@@ -1,8 +1,10 @@
 use std::any::Any;
 use super::Mul;
-use super::super::{FunctionContents, FunctionTable};
+use super::super::{FunctionContents, get_inputs_f64};
 use ktensor::Tensor;
+use crate::error::KdezeroError;
 use crate::variable::VariableTable;
+use crate::graph::Graph;
 
 #[derive(Debug, Clone)]
 pub struct Pow<T> {
@@ -18,16 +20,18 @@ impl<T> Pow<T> {
         &self.c
     }
 
-    fn input_check(inputs: &Vec<usize>) {
+    fn input_check(inputs: &Vec<usize>) -> Result<(), KdezeroError> {
         if inputs.len() != 1 {
-            panic!("Pow function must have only one input, but got {} inputs.", inputs.len());
+            return Err(KdezeroError::InvalidInputCount { function: "Pow".to_string(), expected: 1, actual: inputs.len() });
         }
+        Ok(())
     }
 
-    fn output_check(outputs: &Vec<usize>) {
+    fn output_check(outputs: &Vec<usize>) -> Result<(), KdezeroError> {
         if outputs.len() != 1 {
-            panic!("Pow function must have only one output, but got {} outputs.", outputs.len());
+            return Err(KdezeroError::InvalidOutputCount { function: "Pow".to_string(), expected: 1, actual: outputs.len() });
         }
+        Ok(())
     }
 }
 
@@ -40,45 +44,48 @@ impl FunctionContents for Pow<f64> {
         "Pow"
     }
 
-    fn forward(&self, _info: &crate::function::FunctionInfo, inputs: &Vec<usize>, variable_table: &mut VariableTable) -> Vec<usize> {
-        Pow::<f64>::input_check(inputs);
-        let x = variable_table.get_variable_contents_f64(inputs[0]).expect("Invalid variable id");
+    fn forward(&self, info: &crate::function::FunctionInfo, inputs: &Vec<usize>, variable_table: &mut VariableTable) -> Vec<usize> {
+        self.try_forward(info, inputs, variable_table).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    fn try_forward(&self, _info: &crate::function::FunctionInfo, inputs: &Vec<usize>, variable_table: &mut VariableTable) -> Result<Vec<usize>, KdezeroError> {
+        let [x] = get_inputs_f64(self.name(), inputs, variable_table)?;
 
         let output = x.powf(self.c);
 
         let output_id = variable_table.generate_variable_from_f64_tensor(output, "");
-        vec![output_id]
+        Ok(vec![output_id])
     }
 
-    fn get_backward(&self) -> fn(usize, &mut FunctionTable, &mut VariableTable) -> Vec<usize> {
-        |function_id, function_table, variable_table| {
-            let function = function_table.get(function_id).expect("Invalid function id");
-            let function_contents = function.get_function_contents::<Pow<f64>>().expect("Invalid function contents");
-            let c = function_contents.get_c();
+    fn get_backward(&self) -> fn(usize, &mut Graph) -> Result<Vec<usize>, KdezeroError> {
+        |function_id, graph| {
+            let function = graph.get_function(function_id).ok_or(KdezeroError::InvalidFunctionId { id: function_id })?;
+            let function_contents = function.get_function_contents::<Pow<f64>>().ok_or(KdezeroError::InvalidFunctionId { id: function_id })?;
+            let c = *function_contents.get_c();
 
-            let inputs = function.get_inputs().expect("Invalid inputs");
-            let outputs = function.get_outputs().expect("Invalid outputs");
-            Pow::<f64>::input_check(inputs);
-            Pow::<f64>::output_check(outputs);
+            let inputs = function.try_get_inputs()?;
+            let outputs = function.try_get_outputs()?;
+            Pow::<f64>::input_check(inputs)?;
+            Pow::<f64>::output_check(outputs)?;
             let input_id = inputs[0];
             let output_id = outputs[0];
-            let output_grad_id = variable_table.get_variable_grad_id(output_id).expect("Output grad id not found");
+            let output_grad_id = graph.get_variable_grad_id(output_id).ok_or_else(|| KdezeroError::GradNotFound { function: "Pow".to_string(), id: output_id })?;
 
-            let variable = variable_table.get_variable_contents_f64(output_id).expect("Invalid variable id");
-            let const_id = variable_table.generate_variable_from_f64_tensor(
-                Tensor::full_like(variable, *c), ""
+            let variable = graph.get_variable_contents_f64(output_id).ok_or(KdezeroError::InvalidVariableId { id: output_id })?;
+            let const_id = graph.generate_variable_from_f64_tensor(
+                Tensor::full_like(variable, c), ""
             );
 
-            let pow_id = function_table.generate_function_from_function_contents(Box::new(Pow::<f64>::new(*c - 1.0)));
-            let temp_id = function_table.forward(pow_id, vec![input_id], variable_table, false)[0];
-            let mul_id = function_table.generate_function_from_function_contents(Box::new(Mul::new()));
-            let temp_id = function_table.forward(mul_id, vec![const_id, temp_id], variable_table, false)[0];
-            let mul_id = function_table.generate_function_from_function_contents(Box::new(Mul::new()));
-            let grad_id = function_table.forward(mul_id, vec![temp_id, output_grad_id], variable_table, false)[0];
+            let pow_id = graph.generate_function_from_function_contents(Box::new(Pow::<f64>::new(c - 1.0)));
+            let temp_id = graph.try_forward(pow_id, vec![input_id])?[0];
+            let mul_id = graph.generate_function_from_function_contents(Box::new(Mul::new()));
+            let temp_id = graph.try_forward(mul_id, vec![const_id, temp_id])?[0];
+            let mul_id = graph.generate_function_from_function_contents(Box::new(Mul::new()));
+            let grad_id = graph.try_forward(mul_id, vec![temp_id, output_grad_id])?[0];
 
-            variable_table.update_grad(input_id, grad_id, function_table);
+            graph.try_update_grad(input_id, grad_id)?;
 
-            vec![input_id]
+            Ok(vec![input_id])
         }
     }
 }
@@ -87,59 +94,66 @@ impl FunctionContents for Pow<f64> {
 mod tests {
     use super::*;
     use ktensor::Tensor;
-    use crate::{variable::VariableTable, function::FunctionTable};
 
     #[test]
     fn forward_normal() {
-        let mut variable_table = VariableTable::new();
-        let mut function_table = FunctionTable::new();
+        let mut graph = Graph::new();
 
         let data = vec![1.0, 2.0, 3.0];
-        let pow_id = function_table.generate_function_from_function_contents(Box::new(Pow::new(3.0)));
-        let x_id = variable_table.generate_variable_from_f64_tensor(
+        let pow_id = graph.generate_function_from_function_contents(Box::new(Pow::new(3.0)));
+        let x_id = graph.generate_variable_from_f64_tensor(
             Tensor::new_from_num_vec(data.clone(), vec![3]), "x");
-        let y_id = function_table.forward(pow_id, vec![x_id], &mut variable_table, false);
+        let y_id = graph.forward(pow_id, vec![x_id]);
 
-        let y = variable_table.get_variable_contents_f64(y_id[0]).unwrap();
+        let y = graph.get_variable_contents_f64(y_id[0]).unwrap();
         assert_eq!(y.data(), Tensor::new_from_num_vec(data.iter().map(|x| x.powf(3.0)), vec![3]).data());
     }
 
     #[test]
     fn backward_normal() {
-        let mut variable_table = VariableTable::new();
-        let mut function_table = FunctionTable::new();
+        let mut graph = Graph::new();
 
         let data = vec![1.0, 2.0, 3.0];
-        let pow_id = function_table.generate_function_from_function_contents(Box::new(Pow::new(2.0)));
-        let x_id = variable_table.generate_variable_from_f64_tensor(
+        let pow_id = graph.generate_function_from_function_contents(Box::new(Pow::new(2.0)));
+        let x_id = graph.generate_variable_from_f64_tensor(
             Tensor::new_from_num_vec(data.clone(), vec![3]), "x");
-        let y_ids = function_table.forward(pow_id, vec![x_id], &mut variable_table, false);
+        let y_ids = graph.forward(pow_id, vec![x_id]);
 
-        variable_table.backward(y_ids, &mut function_table, false);
+        graph.backward(y_ids, false, false);
 
-        let x_grad = variable_table.get_variable_grad_contents_f64(x_id).unwrap();
+        let x_grad = graph.get_variable_grad_contents_f64(x_id).unwrap();
         assert_eq!(x_grad, &Tensor::new_from_num_vec(data.iter().map(|x| 2.0 * x), vec![3]));
     }
 
     #[test]
     fn backward_backward_normal() {
-        let mut variable_table = VariableTable::new();
-        let mut function_table = FunctionTable::new();
+        let mut graph = Graph::new();
 
         let data = vec![1.0, 2.0, 3.0];
-        let pos_id = function_table.generate_function_from_function_contents(Box::new(Pow::new(3.0)));
-        let x_id = variable_table.generate_variable_from_f64_tensor(
+        let pos_id = graph.generate_function_from_function_contents(Box::new(Pow::new(3.0)));
+        let x_id = graph.generate_variable_from_f64_tensor(
             Tensor::new_from_num_vec(data.clone(), vec![3]), "x");
-        let y_ids = function_table.forward(pos_id, vec![x_id], &mut variable_table, false);
+        let y_ids = graph.forward(pos_id, vec![x_id]);
 
-        variable_table.backward(y_ids, &mut function_table, false);
+        graph.backward(y_ids, false, true);
 
-        let x_grad_id = variable_table.get_variable_grad_id(x_id).unwrap();
-        variable_table.clear_grad(x_id);
+        let x_grad_id = graph.get_variable_grad_id(x_id).unwrap();
+        graph.clear_grad(x_id);
 
-        variable_table.backward(vec![x_grad_id], &mut function_table, false);
+        graph.backward(vec![x_grad_id], false, false);
 
-        let x_grad = variable_table.get_variable_grad_contents_f64(x_id).unwrap();
+        let x_grad = graph.get_variable_grad_contents_f64(x_id).unwrap();
         assert_eq!(x_grad, &Tensor::new_from_num_vec(data.iter().map(|x| 6.0 * x), vec![3]));
     }
+
+    #[test]
+    fn try_forward_error_input_count() {
+        let mut graph = Graph::new();
+
+        let pow_id = graph.generate_function_from_function_contents(Box::new(Pow::new(2.0)));
+        let x_id = graph.generate_variable_from_f64_tensor(Tensor::full(1.0, vec![2]), "x");
+        let error = graph.try_forward(pow_id, vec![x_id, x_id]).unwrap_err();
+
+        assert_eq!(error, KdezeroError::InvalidInputCount { function: "Pow".to_string(), expected: 1, actual: 2 });
+    }
 }
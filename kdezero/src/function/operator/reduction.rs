@@ -0,0 +1,210 @@
+use super::{Mul, Neg, BroadcastTo, SumTo};
+use crate::error::KdezeroError;
+use crate::variable::VariableTable;
+use crate::graph::Graph;
+use ktensor::{Tensor, tensor::{broadcast_shapes, try_broadcast_shapes}};
+
+/// Reduction of the elementwise losses
+/// 
+/// # Variants
+/// 
+/// * `None` - Keep the loss of each element
+/// * `Mean` - Mean of the losses
+/// * `Sum` - Sum of the losses
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Reduction {
+    None,
+    Mean,
+    Sum,
+}
+
+impl Reduction {
+    /// Weight and reduce the elementwise losses.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `loss` - Elementwise losses
+    /// * `weights` - Weights broadcast to the shape of the losses
+    pub(crate) fn reduce(&self, loss: Tensor<f64>, weights: Option<&Tensor<f64>>) -> Tensor<f64> {
+        let loss = match weights {
+            Some(weights) => &loss * weights,
+            None => loss,
+        };
+        match self {
+            Reduction::None => loss,
+            Reduction::Mean => {
+                let size = loss.size();
+                loss.sum([], false).scalar_div((size as f64).into())
+            },
+            Reduction::Sum => loss.sum([], false),
+        }
+    }
+
+    /// Spread the gradient of the reduced loss to the elements of the loss.
+    /// 
+    /// The result is the gradient of each elementwise loss, including the weights.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `graph` - Graph
+    /// * `grad_id` - Variable ID of the gradient of the output
+    /// * `weights_id` - Variable ID of the weights
+    /// * `shape` - Shape of the elementwise losses, which is broadcast with the shape of the weights
+    /// 
+    /// # Errors
+    /// 
+    /// * `InvalidVariableId` - If the weights are not in the graph
+    /// * `InvalidInput` - If the gradient cannot be broadcast to the shape of the losses
+    pub(crate) fn loss_grad(&self, graph: &mut Graph, grad_id: usize, weights_id: Option<usize>, shape: &[usize]) -> Result<usize, KdezeroError> {
+        let shape = match weights_id {
+            Some(weights_id) => {
+                let weights = graph.get_variable_contents_f64(weights_id).ok_or(KdezeroError::InvalidVariableId { id: weights_id })?;
+                broadcast_shapes(shape, weights.shape())
+            },
+            None => shape.to_vec(),
+        };
+        let grad_id = match self {
+            Reduction::None => grad_id,
+            Reduction::Mean | Reduction::Sum => {
+                let broadcast_to_id = graph.generate_function_from_function_contents(Box::new(BroadcastTo::new(shape.to_vec())));
+                graph.try_forward(broadcast_to_id, vec![grad_id])?[0]
+            },
+        };
+        let grad_id = match self {
+            Reduction::Mean => {
+                let size = shape.iter().product::<usize>();
+                let scale_id = graph.generate_variable_from_f64_tensor(Tensor::full(1.0 / size as f64, vec![]), "");
+                let mul_id = graph.generate_function_from_function_contents(Box::new(Mul::new()));
+                graph.try_forward(mul_id, vec![grad_id, scale_id])?[0]
+            },
+            Reduction::None | Reduction::Sum => grad_id,
+        };
+        match weights_id {
+            Some(weights_id) => {
+                let mul_id = graph.generate_function_from_function_contents(Box::new(Mul::new()));
+                Ok(graph.try_forward(mul_id, vec![grad_id, weights_id])?[0])
+            },
+            None => Ok(grad_id),
+        }
+    }
+}
+
+/// Inputs of a loss function and its optional weights
+type LossInputs<'a, const N: usize> = ([&'a Tensor<f64>; N], Option<&'a Tensor<f64>>);
+
+/// Get the f64 contents of the inputs of a loss function.
+/// 
+/// The weights are the optional input after the `N` inputs.
+/// 
+/// # Errors
+/// 
+/// * `InvalidInputCount` - If the number of inputs is neither `N` nor `N + 1`
+/// * `InvalidInputId` - If an input variable is not in the variable table
+/// * `InvalidInput` - If an input variable does not have f64 contents
+pub(crate) fn get_loss_inputs<'a, const N: usize>(name: &str, inputs: &[usize], variable_table: &'a VariableTable) -> Result<LossInputs<'a, N>, KdezeroError> {
+    if inputs.len() != N + 1 {
+        return Ok((super::super::get_inputs_f64(name, inputs, variable_table)?, None));
+    }
+    let tensors = super::super::get_inputs_f64(name, &inputs[..N], variable_table)?;
+    let weights = super::super::get_input_f64(name, N, inputs[N], variable_table)?;
+    Ok((tensors, Some(weights)))
+}
+
+/// Get the shape of the elementwise losses, to which all the inputs are broadcast.
+/// 
+/// # Errors
+/// 
+/// * `InvalidInput` - If an input cannot be broadcast to the others
+pub(crate) fn loss_shape(name: &str, inputs: &[&Tensor<f64>]) -> Result<Vec<usize>, KdezeroError> {
+    let mut shape = inputs[0].shape().clone();
+    for (index, x) in inputs.iter().enumerate().skip(1) {
+        shape = try_broadcast_shapes(&shape, x.shape())
+            .map_err(|e| KdezeroError::invalid_input(name, index, x.shape(), e))?;
+    }
+    Ok(shape)
+}
+
+/// Propagate the gradient of a loss of the difference `x - t`.
+/// 
+/// The gradients are summed to the shapes of `x` and `t`.
+/// 
+/// # Arguments
+/// 
+/// * `graph` - Graph
+/// * `grad_id` - Variable ID of the gradient of each elementwise loss
+/// * `local_grad_id` - Variable ID of the derivative of the loss by the difference
+/// * `input_ids` - Variable IDs of `x` and `t`
+/// 
+/// # Errors
+/// 
+/// * `InvalidVariableId` - If an input is not in the graph
+/// * `InvalidInput` - If the gradient cannot be summed to the shape of an input
+pub(crate) fn difference_backward(graph: &mut Graph, grad_id: usize, local_grad_id: usize, input_ids: &[usize]) -> Result<Vec<usize>, KdezeroError> {
+    let mul_id = graph.generate_function_from_function_contents(Box::new(Mul::new()));
+    let gx_id = graph.try_forward(mul_id, vec![grad_id, local_grad_id])?[0];
+    let neg_id = graph.generate_function_from_function_contents(Box::new(Neg::new()));
+    let gt_id = graph.try_forward(neg_id, vec![gx_id])?[0];
+    let gx_id = SumTo::reduce_grad(graph, gx_id, input_ids[0])?;
+    let gt_id = SumTo::reduce_grad(graph, gt_id, input_ids[1])?;
+
+    graph.try_update_grad(input_ids[0], gx_id)?;
+    graph.try_update_grad(input_ids[1], gt_id)?;
+
+    Ok(input_ids[..2].to_vec())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reduce_normal() {
+        let loss = Tensor::new_from_num_vec(vec![1.0, 2.0, 3.0, 6.0], vec![2, 2]);
+        let weights = Tensor::new_from_num_vec(vec![2.0, 0.5], vec![2]);
+
+        assert_eq!(Reduction::None.reduce(loss.clone(), None), loss);
+        assert_eq!(Reduction::Sum.reduce(loss.clone(), None), Tensor::full(12.0, vec![]));
+        assert_eq!(Reduction::Mean.reduce(loss.clone(), None), Tensor::full(3.0, vec![]));
+        assert_eq!(Reduction::None.reduce(loss.clone(), Some(&weights)),
+            Tensor::new_from_num_vec(vec![2.0, 1.0, 6.0, 3.0], vec![2, 2]));
+        assert_eq!(Reduction::Mean.reduce(loss, Some(&weights)), Tensor::full(3.0, vec![]));
+    }
+
+    #[test]
+    fn loss_grad_normal() {
+        let mut graph = Graph::new();
+
+        let grad_id = graph.generate_variable_from_f64_tensor(Tensor::full(2.0, vec![]), "gy");
+        let weights_id = graph.generate_variable_from_f64_tensor(Tensor::new_from_num_vec(vec![1.0, 3.0], vec![2]), "w");
+
+        let mean_id = Reduction::Mean.loss_grad(&mut graph, grad_id, Some(weights_id), &[2, 2]).unwrap();
+        let sum_id = Reduction::Sum.loss_grad(&mut graph, grad_id, None, &[2, 2]).unwrap();
+        assert_eq!(graph.get_variable_contents_f64(mean_id).unwrap(),
+            &Tensor::new_from_num_vec(vec![0.5, 1.5, 0.5, 1.5], vec![2, 2]));
+        assert_eq!(graph.get_variable_contents_f64(sum_id).unwrap(), &Tensor::full(2.0, vec![2, 2]));
+        assert_eq!(Reduction::None.loss_grad(&mut graph, grad_id, None, &[]).unwrap(), grad_id);
+    }
+
+    #[test]
+    fn loss_grad_broadcast_weights() {
+        let mut graph = Graph::new();
+
+        let grad_id = graph.generate_variable_from_f64_tensor(Tensor::full(6.0, vec![]), "gy");
+        let weights_id = graph.generate_variable_from_f64_tensor(Tensor::new_from_num_vec(vec![1.0, 2.0], vec![2, 1]), "w");
+
+        let mean_id = Reduction::Mean.loss_grad(&mut graph, grad_id, Some(weights_id), &[3]).unwrap();
+        assert_eq!(graph.get_variable_contents_f64(mean_id).unwrap(),
+            &Tensor::new_from_num_vec(vec![1.0, 1.0, 1.0, 2.0, 2.0, 2.0], vec![2, 3]));
+    }
+
+    #[test]
+    fn loss_shape_error() {
+        let x = Tensor::full(0.0, vec![2, 3]);
+        let t = Tensor::full(0.0, vec![3]);
+        let w = Tensor::full(0.0, vec![2]);
+
+        assert_eq!(loss_shape("Loss", &[&x, &t]), Ok(vec![2, 3]));
+        assert!(matches!(loss_shape("Loss", &[&x, &t, &w]),
+            Err(KdezeroError::InvalidInput { index: 2, .. })));
+    }
+}
@@ -0,0 +1,156 @@
+use std::any::Any;
+use super::Gather;
+use super::super::{FunctionContents, get_inputs_f64};
+use crate::error::KdezeroError;
+use crate::variable::VariableTable;
+use crate::graph::Graph;
+use ktensor::Tensor;
+
+/// Values of the second input added to the first input along the axis
+/// 
+/// The second input at `i` is added to the first input at `i` with the index of the axis replaced by `indexes[i]`.
+/// The gradient of the first input is the gradient of the output,
+/// and the gradient of the second input is the gradient of the output gathered along the axis.
+#[derive(Debug, Clone)]
+pub struct ScatterAdd {
+    axis: usize,
+    indexes: Tensor<usize>,
+}
+
+impl ScatterAdd {
+    /// Create a new ScatterAdd instance.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `axis` - Axis to add along
+    /// * `indexes` - Indexes with the shape of the second input
+    pub fn new(axis: usize, indexes: Tensor<usize>) -> Self {
+        Self { axis, indexes }
+    }
+
+    pub fn get_axis(&self) -> usize {
+        self.axis
+    }
+
+    pub fn get_indexes(&self) -> &Tensor<usize> {
+        &self.indexes
+    }
+
+    fn input_check(inputs: &Vec<usize>) -> Result<(), KdezeroError> {
+        if inputs.len() != 2 {
+            return Err(KdezeroError::InvalidInputCount { function: "ScatterAdd".to_string(), expected: 2, actual: inputs.len() });
+        }
+        Ok(())
+    }
+
+    fn output_check(outputs: &Vec<usize>) -> Result<(), KdezeroError> {
+        if outputs.len() != 1 {
+            return Err(KdezeroError::InvalidOutputCount { function: "ScatterAdd".to_string(), expected: 1, actual: outputs.len() });
+        }
+        Ok(())
+    }
+}
+
+impl FunctionContents for ScatterAdd {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "ScatterAdd"
+    }
+
+    fn forward(&self, info: &crate::function::FunctionInfo, inputs: &Vec<usize>, variable_table: &mut VariableTable) -> Vec<usize> {
+        self.try_forward(info, inputs, variable_table).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    fn try_forward(&self, _info: &crate::function::FunctionInfo, inputs: &Vec<usize>, variable_table: &mut VariableTable) -> Result<Vec<usize>, KdezeroError> {
+        let [x, src] = get_inputs_f64(self.name(), inputs, variable_table)?;
+        if src.shape() != self.indexes.shape() {
+            return Err(KdezeroError::invalid_input(self.name(), 1, src.shape(),
+                ktensor::TensorError::ShapeMismatch { left: self.indexes.shape().clone(), right: src.shape().clone() }));
+        }
+
+        let output = x.try_scatter_add(self.axis, &self.indexes, src)
+            .map_err(|e| KdezeroError::invalid_input(self.name(), 0, x.shape(), e))?;
+
+        let output_id = variable_table.generate_variable_from_f64_tensor(output, "");
+        Ok(vec![output_id])
+    }
+
+    fn get_backward(&self) -> fn(usize, &mut Graph) -> Result<Vec<usize>, KdezeroError> {
+        |function_id, graph| {
+            let function = graph.get_function(function_id).ok_or(KdezeroError::InvalidFunctionId { id: function_id })?;
+            let function_contents = function.get_function_contents::<ScatterAdd>().ok_or(KdezeroError::InvalidFunctionId { id: function_id })?;
+            let (axis, indexes) = (function_contents.get_axis(), function_contents.get_indexes().clone());
+            let inputs = function.try_get_inputs()?;
+            let outputs = function.try_get_outputs()?;
+            ScatterAdd::input_check(inputs)?;
+            ScatterAdd::output_check(outputs)?;
+            let input_ids = inputs.clone();
+            let output_id = outputs[0];
+            let output_grad_id = graph.get_variable_grad_id(output_id).ok_or_else(|| KdezeroError::GradNotFound { function: "ScatterAdd".to_string(), id: output_id })?;
+
+            let gather_id = graph.generate_function_from_function_contents(Box::new(Gather::new(axis, indexes)));
+            let src_grad_id = graph.try_forward(gather_id, vec![output_grad_id])?[0];
+
+            graph.try_update_grad(input_ids[0], output_grad_id)?;
+            graph.try_update_grad(input_ids[1], src_grad_id)?;
+
+            Ok(input_ids)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ktensor::TensorError;
+
+    #[test]
+    fn forward_normal() {
+        let mut graph = Graph::new();
+
+        let scatter_add_id = graph.generate_function_from_function_contents(
+            Box::new(ScatterAdd::new(0, Tensor::new_from_num_vec(vec![1, 1, 0], vec![3]))));
+        let x_id = graph.generate_variable_from_f64_tensor(Tensor::full(1.0, vec![2]), "x");
+        let src_id = graph.generate_variable_from_f64_tensor(Tensor::new_from_num_vec(vec![1.0, 2.0, 3.0], vec![3]), "src");
+        let y_id = graph.forward(scatter_add_id, vec![x_id, src_id]);
+
+        let y = graph.get_variable_contents_f64(y_id[0]).unwrap();
+        assert_eq!(y, &Tensor::new_from_num_vec(vec![4.0, 4.0], vec![2]));
+    }
+
+    #[test]
+    fn backward_normal() {
+        let mut graph = Graph::new();
+
+        let scatter_add_id = graph.generate_function_from_function_contents(
+            Box::new(ScatterAdd::new(0, Tensor::new_from_num_vec(vec![1, 1, 0], vec![3]))));
+        let x_id = graph.generate_variable_from_f64_tensor(Tensor::full(1.0, vec![2]), "x");
+        let src_id = graph.generate_variable_from_f64_tensor(Tensor::new_from_num_vec(vec![1.0, 2.0, 3.0], vec![3]), "src");
+        let w_id = graph.generate_variable_from_f64_tensor(Tensor::new_from_num_vec(vec![2.0, 5.0], vec![2]), "w");
+        let y_id = graph.forward(scatter_add_id, vec![x_id, src_id])[0];
+        let mul_id = graph.generate_function_from_function_contents(Box::new(super::super::Mul::new()));
+        let z_id = graph.forward(mul_id, vec![y_id, w_id])[0];
+
+        graph.backward(vec![z_id], false, false);
+
+        assert_eq!(graph.get_variable_grad_contents_f64(x_id).unwrap(), &Tensor::new_from_num_vec(vec![2.0, 5.0], vec![2]));
+        assert_eq!(graph.get_variable_grad_contents_f64(src_id).unwrap(), &Tensor::new_from_num_vec(vec![5.0, 5.0, 2.0], vec![3]));
+    }
+
+    #[test]
+    fn try_forward_error_shape() {
+        let mut graph = Graph::new();
+
+        let scatter_add_id = graph.generate_function_from_function_contents(
+            Box::new(ScatterAdd::new(0, Tensor::new_from_num_vec(vec![1, 1, 0], vec![3]))));
+        let x_id = graph.generate_variable_from_f64_tensor(Tensor::full(1.0, vec![2]), "x");
+        let src_id = graph.generate_variable_from_f64_tensor(Tensor::full(1.0, vec![2]), "src");
+        let error = graph.try_forward(scatter_add_id, vec![x_id, src_id]).unwrap_err();
+
+        assert_eq!(error, KdezeroError::invalid_input("ScatterAdd", 1, &[2],
+            TensorError::ShapeMismatch { left: vec![3], right: vec![2] }));
+    }
+}
@@ -0,0 +1,144 @@
+use std::any::Any;
+use super::{Mul, Sub};
+use super::super::FunctionContents;
+use ktensor::Tensor;
+use crate::variable::VariableTable;
+use crate::error::KdezeroError;
+use crate::graph::Graph;
+
+/// Logistic sigmoid
+/// 
+/// The forward is computed in a single node without overflow for large negative inputs.
+#[derive(Debug, Clone)]
+pub struct Sigmoid {}
+
+impl Sigmoid {
+    pub fn new() -> Self {
+        Self {}
+    }
+
+    fn input_check(inputs: &Vec<usize>) -> Result<(), KdezeroError> {
+        if inputs.len() != 1 {
+            return Err(KdezeroError::InvalidInputCount { function: "Sigmoid".to_string(), expected: 1, actual: inputs.len() });
+        }
+        Ok(())
+    }
+
+    fn output_check(outputs: &Vec<usize>) -> Result<(), KdezeroError> {
+        if outputs.len() != 1 {
+            return Err(KdezeroError::InvalidOutputCount { function: "Sigmoid".to_string(), expected: 1, actual: outputs.len() });
+        }
+        Ok(())
+    }
+}
+
+impl FunctionContents for Sigmoid {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "Sigmoid"
+    }
+
+    fn forward(&self, _info: &crate::function::FunctionInfo, inputs: &Vec<usize>, variable_table: &mut VariableTable) -> Vec<usize> {
+        Sigmoid::input_check(inputs).unwrap_or_else(|e| panic!("{}", e));
+        let x = variable_table.get_variable_contents_f64(inputs[0]).expect("Invalid variable id");
+
+        let output = x.sigmoid();
+
+        let output_id = variable_table.generate_variable_from_f64_tensor(output, "");
+        vec![output_id]
+    }
+
+    fn get_backward(&self) -> fn(usize, &mut Graph) -> Result<Vec<usize>, KdezeroError> {
+        |function_id, graph| {
+            let function = graph.get_function(function_id).ok_or(KdezeroError::InvalidFunctionId { id: function_id })?;
+            let inputs = function.try_get_inputs()?;
+            let outputs = function.try_get_outputs()?;
+            Sigmoid::input_check(inputs)?;
+            Sigmoid::output_check(outputs)?;
+            let input_id = inputs[0];
+            let output_id = outputs[0];
+            let output_grad_id = graph.get_variable_grad_id(output_id).ok_or_else(|| KdezeroError::GradNotFound { function: "Sigmoid".to_string(), id: output_id })?;
+
+            // grad * y * (1 - y)
+            let one_id = graph.generate_variable_from_f64_tensor(Tensor::full(1.0, vec![]), "");
+            let sub_id = graph.generate_function_from_function_contents(Box::new(Sub::new()));
+            let temp_id = graph.try_forward(sub_id, vec![one_id, output_id])?[0];
+            let mul_id = graph.generate_function_from_function_contents(Box::new(Mul::new()));
+            let temp_id = graph.try_forward(mul_id, vec![output_id, temp_id])?[0];
+            let mul_id = graph.generate_function_from_function_contents(Box::new(Mul::new()));
+            let grad_id = graph.try_forward(mul_id, vec![output_grad_id, temp_id])?[0];
+
+            graph.try_update_grad(input_id, grad_id)?;
+
+            Ok(vec![input_id])
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ktensor::utility::assert_approx_eq;
+
+    fn sigmoid_f64(x: f64) -> f64 {
+        1.0 / (1.0 + (-x).exp())
+    }
+
+    #[test]
+    fn forward_normal() {
+        let mut graph = Graph::new();
+
+        let data = vec![-1000.0, -1.0, 0.0, 2.0];
+        let sigmoid_id = graph.generate_function_from_function_contents(Box::new(Sigmoid::new()));
+        let x_id = graph.generate_variable_from_f64_tensor(
+            Tensor::new_from_num_vec(data.clone(), vec![4]), "x");
+        let y_id = graph.forward(sigmoid_id, vec![x_id]);
+
+        let y = graph.get_variable_contents_f64(y_id[0]).unwrap();
+        data.iter().zip(y.data().iter())
+            .for_each(|(x, y)| assert_approx_eq(sigmoid_f64(*x), *y.data(), 1e-12));
+    }
+
+    #[test]
+    fn backward_normal() {
+        let mut graph = Graph::new();
+
+        let data = vec![-1000.0, -1.0, 0.0, 2.0];
+        let sigmoid_id = graph.generate_function_from_function_contents(Box::new(Sigmoid::new()));
+        let x_id = graph.generate_variable_from_f64_tensor(
+            Tensor::new_from_num_vec(data.clone(), vec![4]), "x");
+        let y_ids = graph.forward(sigmoid_id, vec![x_id]);
+
+        graph.backward(y_ids, false, false);
+
+        let x_grad = graph.get_variable_grad_contents_f64(x_id).unwrap();
+        data.iter().zip(x_grad.data().iter())
+            .for_each(|(x, y)| assert_approx_eq(sigmoid_f64(*x) * (1.0 - sigmoid_f64(*x)), *y.data(), 1e-12));
+    }
+
+    #[test]
+    fn backward_backward_normal() {
+        let mut graph = Graph::new();
+
+        let data = vec![-1.0, 0.0, 2.0];
+        let sigmoid_id = graph.generate_function_from_function_contents(Box::new(Sigmoid::new()));
+        let x_id = graph.generate_variable_from_f64_tensor(
+            Tensor::new_from_num_vec(data.clone(), vec![3]), "x");
+        let y_ids = graph.forward(sigmoid_id, vec![x_id]);
+
+        graph.backward(y_ids, false, true);
+
+        let x_grad_id = graph.get_variable_grad_id(x_id).unwrap();
+        graph.clear_grad(x_id);
+
+        graph.backward(vec![x_grad_id], false, false);
+
+        // s * (1 - s) * (1 - 2s)
+        let x_grad = graph.get_variable_grad_contents_f64(x_id).unwrap();
+        data.iter().map(|&x| sigmoid_f64(x)).zip(x_grad.data().iter())
+            .for_each(|(s, y)| assert_approx_eq(s * (1.0 - s) * (1.0 - 2.0 * s), *y.data(), 1e-12));
+    }
+}
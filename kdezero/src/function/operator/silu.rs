@@ -0,0 +1,151 @@
+use std::any::Any;
+use super::{Add, Sub, Mul, Sigmoid};
+use super::super::FunctionContents;
+use ktensor::Tensor;
+use crate::variable::VariableTable;
+use crate::error::KdezeroError;
+use crate::graph::Graph;
+
+/// Sigmoid linear unit, x * sigmoid(x)
+#[derive(Debug, Clone)]
+pub struct SiLU {}
+
+impl SiLU {
+    pub fn new() -> Self {
+        Self {}
+    }
+
+    /// Compute SiLU of each element of the tensor.
+    pub(crate) fn silu(x: &Tensor<f64>) -> Tensor<f64> {
+        x.map(|x| x * &x.sigmoid())
+    }
+
+    fn input_check(inputs: &Vec<usize>) -> Result<(), KdezeroError> {
+        if inputs.len() != 1 {
+            return Err(KdezeroError::InvalidInputCount { function: "SiLU".to_string(), expected: 1, actual: inputs.len() });
+        }
+        Ok(())
+    }
+
+    fn output_check(outputs: &Vec<usize>) -> Result<(), KdezeroError> {
+        if outputs.len() != 1 {
+            return Err(KdezeroError::InvalidOutputCount { function: "SiLU".to_string(), expected: 1, actual: outputs.len() });
+        }
+        Ok(())
+    }
+}
+
+impl FunctionContents for SiLU {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "SiLU"
+    }
+
+    fn forward(&self, _info: &crate::function::FunctionInfo, inputs: &Vec<usize>, variable_table: &mut VariableTable) -> Vec<usize> {
+        SiLU::input_check(inputs).unwrap_or_else(|e| panic!("{}", e));
+        let x = variable_table.get_variable_contents_f64(inputs[0]).expect("Invalid variable id");
+
+        let output = SiLU::silu(x);
+
+        let output_id = variable_table.generate_variable_from_f64_tensor(output, "");
+        vec![output_id]
+    }
+
+    fn get_backward(&self) -> fn(usize, &mut Graph) -> Result<Vec<usize>, KdezeroError> {
+        |function_id, graph| {
+            let function = graph.get_function(function_id).ok_or(KdezeroError::InvalidFunctionId { id: function_id })?;
+            let inputs = function.try_get_inputs()?;
+            let outputs = function.try_get_outputs()?;
+            SiLU::input_check(inputs)?;
+            SiLU::output_check(outputs)?;
+            let input_id = inputs[0];
+            let output_id = outputs[0];
+            let output_grad_id = graph.get_variable_grad_id(output_id).ok_or_else(|| KdezeroError::GradNotFound { function: "SiLU".to_string(), id: output_id })?;
+
+            // grad * s * (1 + x * (1 - s)) where s = sigmoid(x)
+            let one_id = graph.generate_variable_from_f64_tensor(Tensor::full(1.0, vec![]), "");
+            let sigmoid_id = graph.generate_function_from_function_contents(Box::new(Sigmoid::new()));
+            let s_id = graph.try_forward(sigmoid_id, vec![input_id])?[0];
+            let sub_id = graph.generate_function_from_function_contents(Box::new(Sub::new()));
+            let temp_id = graph.try_forward(sub_id, vec![one_id, s_id])?[0];
+            let mul_id = graph.generate_function_from_function_contents(Box::new(Mul::new()));
+            let temp_id = graph.try_forward(mul_id, vec![input_id, temp_id])?[0];
+            let add_id = graph.generate_function_from_function_contents(Box::new(Add::new()));
+            let temp_id = graph.try_forward(add_id, vec![one_id, temp_id])?[0];
+            let mul_id = graph.generate_function_from_function_contents(Box::new(Mul::new()));
+            let temp_id = graph.try_forward(mul_id, vec![s_id, temp_id])?[0];
+            let mul_id = graph.generate_function_from_function_contents(Box::new(Mul::new()));
+            let grad_id = graph.try_forward(mul_id, vec![output_grad_id, temp_id])?[0];
+
+            graph.try_update_grad(input_id, grad_id)?;
+
+            Ok(vec![input_id])
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ktensor::utility::{assert_approx_eq, numerical_diff};
+
+    #[test]
+    fn forward_normal() {
+        let mut graph = Graph::new();
+
+        let data = vec![-2.0, 0.0, 3.0];
+        let silu_id = graph.generate_function_from_function_contents(Box::new(SiLU::new()));
+        let x_id = graph.generate_variable_from_f64_tensor(
+            Tensor::new_from_num_vec(data.clone(), vec![3]), "x");
+        let y_id = graph.forward(silu_id, vec![x_id]);
+
+        let y = graph.get_variable_contents_f64(y_id[0]).unwrap();
+        data.iter().zip(y.data().iter())
+            .for_each(|(x, y)| assert_approx_eq(x / (1.0 + (-x).exp()), *y.data(), 1e-12));
+    }
+
+    #[test]
+    fn backward_normal() {
+        let mut graph = Graph::new();
+
+        let x = Tensor::new_from_num_vec(vec![-2.0, 0.0, 3.0], vec![3]);
+        let silu_id = graph.generate_function_from_function_contents(Box::new(SiLU::new()));
+        let x_id = graph.generate_variable_from_f64_tensor(x.clone(), "x");
+        let y_ids = graph.forward(silu_id, vec![x_id]);
+
+        graph.backward(y_ids, false, false);
+
+        let x_grad = graph.get_variable_grad_contents_f64(x_id).unwrap();
+        let expected = numerical_diff(&mut |x| SiLU::silu(x), &x, 1e-6);
+        expected.data().iter().zip(x_grad.data().iter())
+            .for_each(|(x, y)| assert_approx_eq(*x.data(), *y.data(), 1e-6));
+    }
+
+    #[test]
+    fn backward_backward_normal() {
+        let mut graph = Graph::new();
+
+        let x = Tensor::new_from_num_vec(vec![-2.0, 0.0, 3.0], vec![3]);
+        let silu_id = graph.generate_function_from_function_contents(Box::new(SiLU::new()));
+        let x_id = graph.generate_variable_from_f64_tensor(x.clone(), "x");
+        let y_ids = graph.forward(silu_id, vec![x_id]);
+
+        graph.backward(y_ids, false, true);
+
+        let x_grad_id = graph.get_variable_grad_id(x_id).unwrap();
+        graph.clear_grad(x_id);
+
+        graph.backward(vec![x_grad_id], false, false);
+
+        // s * (1 - s) * (2 + x * (1 - 2s))
+        let x_grad = graph.get_variable_grad_contents_f64(x_id).unwrap();
+        x.data().iter().zip(x_grad.data().iter())
+            .for_each(|(x, y)| {
+                let (x, s) = (*x.data(), *x.sigmoid().data());
+                assert_approx_eq(s * (1.0 - s) * (2.0 + x * (1.0 - 2.0 * s)), *y.data(), 1e-12)
+            });
+    }
+}
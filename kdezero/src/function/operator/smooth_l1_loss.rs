@@ -0,0 +1,167 @@
+use std::any::Any;
+use super::{Sub, Mul, Clip, HuberLoss, Reduction};
+use super::reduction::{get_loss_inputs, loss_shape, difference_backward};
+use super::super::FunctionContents;
+use crate::error::KdezeroError;
+use crate::variable::VariableTable;
+use crate::graph::Graph;
+use ktensor::Tensor;
+
+/// Smooth L1 loss of the inputs
+/// 
+/// With `d = x - t`, the loss is `d^2 / (2 * beta)` for `|d| < beta` and `|d| - beta / 2` otherwise,
+/// which is the Huber loss with the delta `beta` divided by `beta`.
+/// The inputs are x, t and the optional weights, broadcast to a common shape.
+/// The weights multiply each elementwise loss and get no gradient.
+#[derive(Debug, Clone)]
+pub struct SmoothL1Loss {
+    beta: f64,
+    reduction: Reduction,
+}
+
+impl SmoothL1Loss {
+    /// Create a new SmoothL1Loss instance, which takes the mean of the losses.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `beta` - Threshold between the quadratic and the linear part
+    /// 
+    /// # Panics
+    /// 
+    /// Panics if `beta` is not positive.
+    pub fn new(beta: f64) -> Self {
+        Self::with_reduction(beta, Reduction::Mean)
+    }
+
+    /// Create a new SmoothL1Loss instance with the reduction.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `beta` - Threshold between the quadratic and the linear part
+    /// * `reduction` - Reduction of the elementwise losses
+    /// 
+    /// # Panics
+    /// 
+    /// Panics if `beta` is not positive.
+    pub fn with_reduction(beta: f64, reduction: Reduction) -> Self {
+        if beta.is_nan() || beta <= 0.0 {
+            panic!("SmoothL1Loss function needs a positive beta, but got {}.", beta);
+        }
+        Self { beta, reduction }
+    }
+
+    pub fn get_beta(&self) -> f64 {
+        self.beta
+    }
+
+    pub fn get_reduction(&self) -> Reduction {
+        self.reduction
+    }
+
+    fn input_check(inputs: &Vec<usize>) -> Result<(), KdezeroError> {
+        if inputs.len() != 2 && inputs.len() != 3 {
+            return Err(KdezeroError::InvalidInputCount { function: "SmoothL1Loss".to_string(), expected: 2, actual: inputs.len() });
+        }
+        Ok(())
+    }
+
+    fn output_check(outputs: &Vec<usize>) -> Result<(), KdezeroError> {
+        if outputs.len() != 1 {
+            return Err(KdezeroError::InvalidOutputCount { function: "SmoothL1Loss".to_string(), expected: 1, actual: outputs.len() });
+        }
+        Ok(())
+    }
+}
+
+impl FunctionContents for SmoothL1Loss {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "SmoothL1Loss"
+    }
+
+    fn forward(&self, info: &crate::function::FunctionInfo, inputs: &Vec<usize>, variable_table: &mut VariableTable) -> Vec<usize> {
+        self.try_forward(info, inputs, variable_table).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    fn try_forward(&self, _info: &crate::function::FunctionInfo, inputs: &Vec<usize>, variable_table: &mut VariableTable) -> Result<Vec<usize>, KdezeroError> {
+        let ([x, t], weights) = get_loss_inputs(self.name(), inputs, variable_table)?;
+        match weights {
+            Some(weights) => loss_shape(self.name(), &[x, t, weights])?,
+            None => loss_shape(self.name(), &[x, t])?,
+        };
+
+        let loss = HuberLoss::huber(&(x - t), self.beta).scalar_div(self.beta.into());
+        let output = self.reduction.reduce(loss, weights);
+
+        let output_id = variable_table.generate_variable_from_f64_tensor(output, "");
+        Ok(vec![output_id])
+    }
+
+    fn get_backward(&self) -> fn(usize, &mut Graph) -> Result<Vec<usize>, KdezeroError> {
+        |function_id, graph| {
+            let function = graph.get_function(function_id).ok_or(KdezeroError::InvalidFunctionId { id: function_id })?;
+            let function_contents = function.get_function_contents::<SmoothL1Loss>().ok_or(KdezeroError::InvalidFunctionId { id: function_id })?;
+            let (beta, reduction) = (function_contents.get_beta(), function_contents.get_reduction());
+            let inputs = function.try_get_inputs()?;
+            let outputs = function.try_get_outputs()?;
+            SmoothL1Loss::input_check(inputs)?;
+            SmoothL1Loss::output_check(outputs)?;
+            let input_ids = inputs.clone();
+            let output_id = outputs[0];
+            let output_grad_id = graph.get_variable_grad_id(output_id).ok_or_else(|| KdezeroError::GradNotFound { function: "SmoothL1Loss".to_string(), id: output_id })?;
+
+            // grad * clip(x - t, -beta, beta) / beta
+            let sub_id = graph.generate_function_from_function_contents(Box::new(Sub::new()));
+            let diff_id = graph.try_forward(sub_id, vec![input_ids[0], input_ids[1]])?[0];
+            let clip_id = graph.generate_function_from_function_contents(Box::new(Clip::new(-beta, beta)));
+            let temp_id = graph.try_forward(clip_id, vec![diff_id])?[0];
+            let scale_id = graph.generate_variable_from_f64_tensor(Tensor::full(1.0 / beta, vec![]), "");
+            let mul_id = graph.generate_function_from_function_contents(Box::new(Mul::new()));
+            let local_grad_id = graph.try_forward(mul_id, vec![temp_id, scale_id])?[0];
+            let shape = graph.get_variable(diff_id).ok_or(KdezeroError::InvalidVariableId { id: diff_id })?.shape().clone();
+            let grad_id = reduction.loss_grad(graph, output_grad_id, input_ids.get(2).copied(), &shape)?;
+
+            difference_backward(graph, grad_id, local_grad_id, &input_ids)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn forward_normal() {
+        let mut graph = Graph::new();
+
+        let smooth_l1_id = graph.generate_function_from_function_contents(Box::new(SmoothL1Loss::with_reduction(0.5, Reduction::None)));
+        let x_id = graph.generate_variable_from_f64_tensor(Tensor::new_from_num_vec(vec![-3.0, 0.25, 1.0], vec![3]), "x");
+        let t_id = graph.generate_variable_from_f64_tensor(Tensor::full(0.0, vec![1]), "t");
+        let y_id = graph.forward(smooth_l1_id, vec![x_id, t_id]);
+
+        let y = graph.get_variable_contents_f64(y_id[0]).unwrap();
+        assert_eq!(y, &Tensor::new_from_num_vec(vec![2.75, 0.0625, 0.75], vec![3]));
+    }
+
+    #[test]
+    fn backward_normal() {
+        let mut graph = Graph::new();
+
+        let smooth_l1_id = graph.generate_function_from_function_contents(Box::new(SmoothL1Loss::with_reduction(0.5, Reduction::Sum)));
+        let x_id = graph.generate_variable_from_f64_tensor(Tensor::new_from_num_vec(vec![-3.0, 0.25, 1.0], vec![3]), "x");
+        let t_id = graph.generate_variable_from_f64_tensor(Tensor::full(0.0, vec![1]), "t");
+        let w_id = graph.generate_variable_from_f64_tensor(Tensor::new_from_num_vec(vec![1.0, 2.0, 4.0], vec![3]), "w");
+        let y_ids = graph.forward(smooth_l1_id, vec![x_id, t_id, w_id]);
+
+        graph.backward(y_ids, false, false);
+
+        // w * clip(d, -beta, beta) / beta
+        assert_eq!(graph.get_variable_grad_contents_f64(x_id).unwrap(),
+            &Tensor::new_from_num_vec(vec![-1.0, 1.0, 4.0], vec![3]));
+        assert_eq!(graph.get_variable_grad_contents_f64(t_id).unwrap(),
+            &Tensor::new_from_num_vec(vec![-4.0], vec![1]));
+    }
+}
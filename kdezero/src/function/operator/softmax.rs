@@ -0,0 +1,172 @@
+use std::any::Any;
+use super::{Sub, Mul, Sum};
+use super::super::{FunctionContents, get_inputs_f64};
+use ktensor::{Tensor, TensorError};
+use crate::error::KdezeroError;
+use crate::variable::VariableTable;
+use crate::graph::Graph;
+
+/// Softmax along the axis
+/// 
+/// The maximum along the axis is subtracted before the exponential so that it does not overflow.
+#[derive(Debug, Clone)]
+pub struct Softmax {
+    axis: usize,
+}
+
+impl Softmax {
+    /// Create a new Softmax instance.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `axis` - Axis to normalize
+    pub fn new(axis: usize) -> Self {
+        Self { axis }
+    }
+
+    pub fn get_axis(&self) -> usize {
+        self.axis
+    }
+
+    /// Compute softmax of the tensor along the axis.
+    pub(crate) fn softmax(x: &Tensor<f64>, axis: usize) -> Tensor<f64> {
+        let y = (x - &x.max([axis], true)).exp();
+        &y / &y.sum([axis], true)
+    }
+
+    /// Check that the axis is in the range of the dimensions of the input.
+    pub(crate) fn axis_check(name: &str, x: &Tensor<f64>, axis: usize) -> Result<(), KdezeroError> {
+        if axis >= x.ndim() {
+            return Err(KdezeroError::invalid_input(name, 0, x.shape(),
+                TensorError::InvalidAxis { axis, ndim: x.ndim() }));
+        }
+        Ok(())
+    }
+
+    fn input_check(inputs: &Vec<usize>) -> Result<(), KdezeroError> {
+        if inputs.len() != 1 {
+            return Err(KdezeroError::InvalidInputCount { function: "Softmax".to_string(), expected: 1, actual: inputs.len() });
+        }
+        Ok(())
+    }
+
+    fn output_check(outputs: &Vec<usize>) -> Result<(), KdezeroError> {
+        if outputs.len() != 1 {
+            return Err(KdezeroError::InvalidOutputCount { function: "Softmax".to_string(), expected: 1, actual: outputs.len() });
+        }
+        Ok(())
+    }
+}
+
+impl FunctionContents for Softmax {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "Softmax"
+    }
+
+    fn forward(&self, info: &crate::function::FunctionInfo, inputs: &Vec<usize>, variable_table: &mut VariableTable) -> Vec<usize> {
+        self.try_forward(info, inputs, variable_table).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    fn try_forward(&self, _info: &crate::function::FunctionInfo, inputs: &Vec<usize>, variable_table: &mut VariableTable) -> Result<Vec<usize>, KdezeroError> {
+        let [x] = get_inputs_f64(self.name(), inputs, variable_table)?;
+        Softmax::axis_check(self.name(), x, self.axis)?;
+
+        let output = Softmax::softmax(x, self.axis);
+
+        let output_id = variable_table.generate_variable_from_f64_tensor(output, "");
+        Ok(vec![output_id])
+    }
+
+    fn get_backward(&self) -> fn(usize, &mut Graph) -> Result<Vec<usize>, KdezeroError> {
+        |function_id, graph| {
+            let function = graph.get_function(function_id).ok_or(KdezeroError::InvalidFunctionId { id: function_id })?;
+            let function_contents = function.get_function_contents::<Softmax>().ok_or(KdezeroError::InvalidFunctionId { id: function_id })?;
+            let axis = function_contents.get_axis();
+
+            let inputs = function.try_get_inputs()?;
+            let outputs = function.try_get_outputs()?;
+            Softmax::input_check(inputs)?;
+            Softmax::output_check(outputs)?;
+            let input_id = inputs[0];
+            let output_id = outputs[0];
+            let output_grad_id = graph.get_variable_grad_id(output_id).ok_or_else(|| KdezeroError::GradNotFound { function: "Softmax".to_string(), id: output_id })?;
+
+            // y * (grad - sum(y * grad, axis))
+            let mul_id = graph.generate_function_from_function_contents(Box::new(Mul::new()));
+            let temp_id = graph.try_forward(mul_id, vec![output_id, output_grad_id])?[0];
+            let sum_id = graph.generate_function_from_function_contents(Box::new(Sum::new(Some([axis]), true)));
+            let temp_id = graph.try_forward(sum_id, vec![temp_id])?[0];
+            let sub_id = graph.generate_function_from_function_contents(Box::new(Sub::new()));
+            let temp_id = graph.try_forward(sub_id, vec![output_grad_id, temp_id])?[0];
+            let mul_id = graph.generate_function_from_function_contents(Box::new(Mul::new()));
+            let grad_id = graph.try_forward(mul_id, vec![output_id, temp_id])?[0];
+
+            graph.try_update_grad(input_id, grad_id)?;
+
+            Ok(vec![input_id])
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ktensor::utility::assert_approx_eq;
+
+    #[test]
+    fn forward_normal() {
+        let mut graph = Graph::new();
+
+        let softmax_id = graph.generate_function_from_function_contents(Box::new(Softmax::new(1)));
+        let x_id = graph.generate_variable_from_f64_tensor(
+            Tensor::new_from_num_vec(vec![1.0, 2.0, 3.0, 1000.0, 1000.0, 1000.0], vec![2, 3]), "x");
+        let y_id = graph.forward(softmax_id, vec![x_id]);
+
+        let y = graph.get_variable_contents_f64(y_id[0]).unwrap();
+        let e = [1.0_f64.exp(), 2.0_f64.exp(), 3.0_f64.exp()];
+        let total = e.iter().sum::<f64>();
+        [e[0] / total, e[1] / total, e[2] / total, 1.0 / 3.0, 1.0 / 3.0, 1.0 / 3.0].iter().zip(y.data().iter())
+            .for_each(|(x, y)| assert_approx_eq(*x, *y.data(), 1e-12));
+    }
+
+    #[test]
+    fn backward_normal() {
+        let mut graph = Graph::new();
+
+        let x = Tensor::new_from_num_vec(vec![1.0, 2.0, 3.0, -1.0, 0.5, 0.0], vec![2, 3]);
+        let w = Tensor::new_from_num_vec(vec![1.0, -2.0, 0.5, 3.0, 2.0, 1.0], vec![2, 3]);
+        let softmax_id = graph.generate_function_from_function_contents(Box::new(Softmax::new(1)));
+        let x_id = graph.generate_variable_from_f64_tensor(x.clone(), "x");
+        let w_id = graph.generate_variable_from_f64_tensor(w.clone(), "w");
+        let y_id = graph.forward(softmax_id, vec![x_id])[0];
+        let mul_id = graph.generate_function_from_function_contents(Box::new(Mul::new()));
+        let z_id = graph.forward(mul_id, vec![y_id, w_id])[0];
+
+        graph.backward(vec![z_id], false, false);
+
+        // y * (w - sum(y * w))
+        let y = Softmax::softmax(&x, 1);
+        let expected = &y * &(&w - &(&y * &w).sum([1], true));
+        let x_grad = graph.get_variable_grad_contents_f64(x_id).unwrap();
+        expected.data().iter().zip(x_grad.data().iter())
+            .for_each(|(x, y)| assert_approx_eq(*x.data(), *y.data(), 1e-12));
+        let row_sums = x_grad.sum([1], false);
+        row_sums.data().iter().for_each(|x| assert_approx_eq(*x.data(), 0.0, 1e-12));
+    }
+
+    #[test]
+    fn try_forward_error_axis() {
+        let mut graph = Graph::new();
+
+        let softmax_id = graph.generate_function_from_function_contents(Box::new(Softmax::new(2)));
+        let x_id = graph.generate_variable_from_f64_tensor(Tensor::full(1.0, vec![2, 3]), "x");
+        let error = graph.try_forward(softmax_id, vec![x_id]).unwrap_err();
+
+        assert_eq!(error, KdezeroError::invalid_input("Softmax", 0, &[2, 3],
+            TensorError::InvalidAxis { axis: 2, ndim: 2 }));
+    }
+}
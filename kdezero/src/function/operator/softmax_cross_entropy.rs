@@ -0,0 +1,215 @@
+use std::any::Any;
+use super::{Sub, Mul, Softmax, LogSoftmax};
+use super::super::{FunctionContents, get_inputs_f64};
+use ktensor::{Tensor, TensorError};
+use crate::error::KdezeroError;
+use crate::variable::VariableTable;
+use crate::graph::Graph;
+
+/// Mean cross entropy of the softmax of the scores and the class labels
+/// 
+/// The inputs are the scores with the shape (N, C) and the labels with the shape (N).
+/// Each label is the index of the class stored as f64, and gets no gradient.
+/// The softmax and the logarithm are fused, so that the loss stays finite for large scores
+/// and the gradient of the scores is `(softmax(x) - onehot(t)) / N`.
+#[derive(Debug, Clone)]
+pub struct SoftmaxCrossEntropy {}
+
+impl SoftmaxCrossEntropy {
+    pub fn new() -> Self {
+        Self {}
+    }
+
+    /// Convert the labels to class indexes.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `name` - Function name for the error
+    /// * `t` - Labels
+    /// * `classes` - Number of the classes
+    /// 
+    /// # Errors
+    /// 
+    /// * `InvalidLabel` - If a label is not an integer in the range from 0 to `classes`
+    pub(crate) fn labels(name: &str, t: &Tensor<f64>, classes: usize) -> Result<Vec<usize>, KdezeroError> {
+        t.iter().enumerate().map(|(index, label)| {
+            let label = *label.data();
+            if label.fract() == 0.0 && label >= 0.0 && label < classes as f64 {
+                Ok(label as usize)
+            } else {
+                Err(KdezeroError::InvalidLabel { function: name.to_string(), index, label, classes })
+            }
+        }).collect()
+    }
+
+    fn input_check(inputs: &Vec<usize>) -> Result<(), KdezeroError> {
+        if inputs.len() != 2 {
+            return Err(KdezeroError::InvalidInputCount { function: "SoftmaxCrossEntropy".to_string(), expected: 2, actual: inputs.len() });
+        }
+        Ok(())
+    }
+
+    fn output_check(outputs: &Vec<usize>) -> Result<(), KdezeroError> {
+        if outputs.len() != 1 {
+            return Err(KdezeroError::InvalidOutputCount { function: "SoftmaxCrossEntropy".to_string(), expected: 1, actual: outputs.len() });
+        }
+        Ok(())
+    }
+}
+
+impl FunctionContents for SoftmaxCrossEntropy {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "SoftmaxCrossEntropy"
+    }
+
+    fn forward(&self, info: &crate::function::FunctionInfo, inputs: &Vec<usize>, variable_table: &mut VariableTable) -> Vec<usize> {
+        self.try_forward(info, inputs, variable_table).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    fn try_forward(&self, _info: &crate::function::FunctionInfo, inputs: &Vec<usize>, variable_table: &mut VariableTable) -> Result<Vec<usize>, KdezeroError> {
+        let [x, t] = get_inputs_f64(self.name(), inputs, variable_table)?;
+        if x.ndim() != 2 {
+            return Err(KdezeroError::invalid_input(self.name(), 0, x.shape(),
+                TensorError::NdimMismatch { expected: 2, actual: x.ndim() }));
+        }
+        if t.shape() != &vec![x.shape()[0]] {
+            return Err(KdezeroError::invalid_input(self.name(), 1, t.shape(),
+                TensorError::ShapeMismatch { left: x.shape().clone(), right: t.shape().clone() }));
+        }
+        let (n, classes) = (x.shape()[0], x.shape()[1]);
+        let labels = SoftmaxCrossEntropy::labels(self.name(), t, classes)?;
+
+        let log_p = LogSoftmax::log_softmax(x, 1);
+        let total = labels.iter().enumerate()
+            .map(|(i, &label)| *log_p.at(&[i, label]).data())
+            .sum::<f64>();
+        let output = Tensor::full(-total / n.max(1) as f64, vec![]);
+
+        let output_id = variable_table.generate_variable_from_f64_tensor(output, "");
+        Ok(vec![output_id])
+    }
+
+    fn get_backward(&self) -> fn(usize, &mut Graph) -> Result<Vec<usize>, KdezeroError> {
+        |function_id, graph| {
+            let function = graph.get_function(function_id).ok_or(KdezeroError::InvalidFunctionId { id: function_id })?;
+            let inputs = function.try_get_inputs()?;
+            let outputs = function.try_get_outputs()?;
+            SoftmaxCrossEntropy::input_check(inputs)?;
+            SoftmaxCrossEntropy::output_check(outputs)?;
+            let input_ids = inputs.clone();
+            let output_id = outputs[0];
+            let output_grad_id = graph.get_variable_grad_id(output_id).ok_or_else(|| KdezeroError::GradNotFound { function: "SoftmaxCrossEntropy".to_string(), id: output_id })?;
+
+            let x = graph.get_variable_contents_f64(input_ids[0]).ok_or(KdezeroError::InvalidVariableId { id: input_ids[0] })?;
+            let t = graph.get_variable_contents_f64(input_ids[1]).ok_or(KdezeroError::InvalidVariableId { id: input_ids[1] })?;
+            let (n, classes) = (x.shape()[0], x.shape()[1]);
+            let labels = SoftmaxCrossEntropy::labels("SoftmaxCrossEntropy", t, classes)?;
+            let mut onehot = Tensor::full(0.0, vec![n, classes]);
+            for (i, &label) in labels.iter().enumerate() {
+                *onehot.at_mut(&[i, label]) = 1.0.into();
+            }
+
+            // (softmax(x) - onehot(t)) * grad / N
+            let onehot_id = graph.generate_variable_from_f64_tensor(onehot, "");
+            let scale_id = graph.generate_variable_from_f64_tensor(Tensor::full(1.0 / n.max(1) as f64, vec![]), "");
+            let softmax_id = graph.generate_function_from_function_contents(Box::new(Softmax::new(1)));
+            let temp_id = graph.try_forward(softmax_id, vec![input_ids[0]])?[0];
+            let sub_id = graph.generate_function_from_function_contents(Box::new(Sub::new()));
+            let temp_id = graph.try_forward(sub_id, vec![temp_id, onehot_id])?[0];
+            let mul_id = graph.generate_function_from_function_contents(Box::new(Mul::new()));
+            let temp_id = graph.try_forward(mul_id, vec![temp_id, scale_id])?[0];
+            let mul_id = graph.generate_function_from_function_contents(Box::new(Mul::new()));
+            let grad_id = graph.try_forward(mul_id, vec![temp_id, output_grad_id])?[0];
+
+            graph.try_update_grad(input_ids[0], grad_id)?;
+
+            Ok(vec![input_ids[0]])
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ktensor::utility::assert_approx_eq;
+
+    #[test]
+    fn forward_normal() {
+        let mut graph = Graph::new();
+
+        let x = Tensor::new_from_num_vec(vec![1.0, 2.0, 3.0, 0.5, 0.0, -1.0], vec![2, 3]);
+        let sce_id = graph.generate_function_from_function_contents(Box::new(SoftmaxCrossEntropy::new()));
+        let x_id = graph.generate_variable_from_f64_tensor(x.clone(), "x");
+        let t_id = graph.generate_variable_from_f64_tensor(Tensor::new_from_num_vec(vec![2.0, 0.0], vec![2]), "t");
+        let y_id = graph.forward(sce_id, vec![x_id, t_id]);
+
+        let p = Softmax::softmax(&x, 1);
+        let expected = -(p.at(&[0, 2]).data().ln() + p.at(&[1, 0]).data().ln()) / 2.0;
+        let y = graph.get_variable_contents_f64(y_id[0]).unwrap();
+        assert_eq!(y.shape(), &Vec::<usize>::new());
+        assert_approx_eq(*y.at(&[]).data(), expected, 1e-12);
+    }
+
+    #[test]
+    fn forward_large() {
+        let mut graph = Graph::new();
+
+        let sce_id = graph.generate_function_from_function_contents(Box::new(SoftmaxCrossEntropy::new()));
+        let x_id = graph.generate_variable_from_f64_tensor(Tensor::new_from_num_vec(vec![1000.0, 0.0], vec![1, 2]), "x");
+        let t_id = graph.generate_variable_from_f64_tensor(Tensor::new_from_num_vec(vec![1.0], vec![1]), "t");
+        let y_id = graph.forward(sce_id, vec![x_id, t_id]);
+
+        let y = graph.get_variable_contents_f64(y_id[0]).unwrap();
+        assert_eq!(y, &Tensor::full(1000.0, vec![]));
+    }
+
+    #[test]
+    fn backward_normal() {
+        let mut graph = Graph::new();
+
+        let x = Tensor::new_from_num_vec(vec![1.0, 2.0, 3.0, 0.5, 0.0, -1.0], vec![2, 3]);
+        let sce_id = graph.generate_function_from_function_contents(Box::new(SoftmaxCrossEntropy::new()));
+        let x_id = graph.generate_variable_from_f64_tensor(x.clone(), "x");
+        let t_id = graph.generate_variable_from_f64_tensor(Tensor::new_from_num_vec(vec![2.0, 0.0], vec![2]), "t");
+        let y_ids = graph.forward(sce_id, vec![x_id, t_id]);
+
+        graph.backward(y_ids, false, false);
+
+        let onehot = Tensor::new_from_num_vec(vec![0.0, 0.0, 1.0, 1.0, 0.0, 0.0], vec![2, 3]);
+        let expected = (&Softmax::softmax(&x, 1) - &onehot).scalar_div(2.0.into());
+        let x_grad = graph.get_variable_grad_contents_f64(x_id).unwrap();
+        expected.data().iter().zip(x_grad.data().iter())
+            .for_each(|(x, y)| assert_approx_eq(*x.data(), *y.data(), 1e-12));
+        assert!(graph.get_variable_grad_id(t_id).is_none());
+    }
+
+    #[test]
+    fn try_forward_error_label() {
+        let mut graph = Graph::new();
+
+        let sce_id = graph.generate_function_from_function_contents(Box::new(SoftmaxCrossEntropy::new()));
+        let x_id = graph.generate_variable_from_f64_tensor(Tensor::full(0.0, vec![2, 3]), "x");
+        let t_id = graph.generate_variable_from_f64_tensor(Tensor::new_from_num_vec(vec![0.0, 3.0], vec![2]), "t");
+        let error = graph.try_forward(sce_id, vec![x_id, t_id]).unwrap_err();
+
+        assert_eq!(error, KdezeroError::InvalidLabel {
+            function: "SoftmaxCrossEntropy".to_string(), index: 1, label: 3.0, classes: 3 });
+    }
+
+    #[test]
+    fn try_forward_error_shape() {
+        let mut graph = Graph::new();
+
+        let sce_id = graph.generate_function_from_function_contents(Box::new(SoftmaxCrossEntropy::new()));
+        let x_id = graph.generate_variable_from_f64_tensor(Tensor::full(0.0, vec![2, 3]), "x");
+        let t_id = graph.generate_variable_from_f64_tensor(Tensor::full(0.0, vec![3]), "t");
+        let error = graph.try_forward(sce_id, vec![x_id, t_id]).unwrap_err();
+
+        assert_eq!(error, KdezeroError::invalid_input("SoftmaxCrossEntropy", 1, &[3],
+            TensorError::ShapeMismatch { left: vec![2, 3], right: vec![3] }));
+    }
+}
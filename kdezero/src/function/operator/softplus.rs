@@ -0,0 +1,118 @@
+use std::any::Any;
+use super::{Mul, Sigmoid};
+use super::super::FunctionContents;
+use ktensor::Tensor;
+use crate::variable::VariableTable;
+use crate::error::KdezeroError;
+use crate::graph::Graph;
+
+/// Softplus, log(1 + exp(x))
+/// 
+/// The forward is computed as `max(x, 0) + log(1 + exp(-|x|))` so that it does not overflow.
+#[derive(Debug, Clone)]
+pub struct Softplus {}
+
+impl Softplus {
+    pub fn new() -> Self {
+        Self {}
+    }
+
+    /// Compute softplus of each element of the tensor.
+    pub(crate) fn softplus(x: &Tensor<f64>) -> Tensor<f64> {
+        x.map(|x| (x.data().max(0.0) + (-x.data().abs()).exp().ln_1p()).into())
+    }
+
+    fn input_check(inputs: &Vec<usize>) -> Result<(), KdezeroError> {
+        if inputs.len() != 1 {
+            return Err(KdezeroError::InvalidInputCount { function: "Softplus".to_string(), expected: 1, actual: inputs.len() });
+        }
+        Ok(())
+    }
+
+    fn output_check(outputs: &Vec<usize>) -> Result<(), KdezeroError> {
+        if outputs.len() != 1 {
+            return Err(KdezeroError::InvalidOutputCount { function: "Softplus".to_string(), expected: 1, actual: outputs.len() });
+        }
+        Ok(())
+    }
+}
+
+impl FunctionContents for Softplus {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "Softplus"
+    }
+
+    fn forward(&self, _info: &crate::function::FunctionInfo, inputs: &Vec<usize>, variable_table: &mut VariableTable) -> Vec<usize> {
+        Softplus::input_check(inputs).unwrap_or_else(|e| panic!("{}", e));
+        let x = variable_table.get_variable_contents_f64(inputs[0]).expect("Invalid variable id");
+
+        let output = Softplus::softplus(x);
+
+        let output_id = variable_table.generate_variable_from_f64_tensor(output, "");
+        vec![output_id]
+    }
+
+    fn get_backward(&self) -> fn(usize, &mut Graph) -> Result<Vec<usize>, KdezeroError> {
+        |function_id, graph| {
+            let function = graph.get_function(function_id).ok_or(KdezeroError::InvalidFunctionId { id: function_id })?;
+            let inputs = function.try_get_inputs()?;
+            let outputs = function.try_get_outputs()?;
+            Softplus::input_check(inputs)?;
+            Softplus::output_check(outputs)?;
+            let input_id = inputs[0];
+            let output_id = outputs[0];
+            let output_grad_id = graph.get_variable_grad_id(output_id).ok_or_else(|| KdezeroError::GradNotFound { function: "Softplus".to_string(), id: output_id })?;
+
+            // grad * sigmoid(x)
+            let sigmoid_id = graph.generate_function_from_function_contents(Box::new(Sigmoid::new()));
+            let temp_id = graph.try_forward(sigmoid_id, vec![input_id])?[0];
+            let mul_id = graph.generate_function_from_function_contents(Box::new(Mul::new()));
+            let grad_id = graph.try_forward(mul_id, vec![output_grad_id, temp_id])?[0];
+
+            graph.try_update_grad(input_id, grad_id)?;
+
+            Ok(vec![input_id])
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ktensor::utility::{assert_approx_eq, numerical_diff};
+
+    #[test]
+    fn forward_normal() {
+        let mut graph = Graph::new();
+
+        let softplus_id = graph.generate_function_from_function_contents(Box::new(Softplus::new()));
+        let x_id = graph.generate_variable_from_f64_tensor(
+            Tensor::new_from_num_vec(vec![-1000.0, 0.0, 1.0, 1000.0], vec![4]), "x");
+        let y_id = graph.forward(softplus_id, vec![x_id]);
+
+        let y = graph.get_variable_contents_f64(y_id[0]).unwrap();
+        [0.0, 2.0_f64.ln(), (1.0 + 1.0_f64.exp()).ln(), 1000.0].iter().zip(y.data().iter())
+            .for_each(|(x, y)| assert_approx_eq(*x, *y.data(), 1e-12));
+    }
+
+    #[test]
+    fn backward_normal() {
+        let mut graph = Graph::new();
+
+        let x = Tensor::new_from_num_vec(vec![-2.0, 0.0, 3.0], vec![3]);
+        let softplus_id = graph.generate_function_from_function_contents(Box::new(Softplus::new()));
+        let x_id = graph.generate_variable_from_f64_tensor(x.clone(), "x");
+        let y_ids = graph.forward(softplus_id, vec![x_id]);
+
+        graph.backward(y_ids, false, false);
+
+        let x_grad = graph.get_variable_grad_contents_f64(x_id).unwrap();
+        let expected = numerical_diff(&mut |x| Softplus::softplus(x), &x, 1e-6);
+        expected.data().iter().zip(x_grad.data().iter())
+            .for_each(|(x, y)| assert_approx_eq(*x.data(), *y.data(), 1e-6));
+    }
+}
@@ -0,0 +1,153 @@
+use std::any::Any;
+use super::Concat;
+use super::super::{FunctionContents, get_inputs_f64};
+use crate::error::KdezeroError;
+use crate::variable::VariableTable;
+use crate::graph::Graph;
+
+/// Split of the input along the axis into parts of the sizes
+/// 
+/// The function has an output for each part.
+/// The gradients of the outputs are concatenated into the gradient of the input,
+/// and an output without a gradient counts as zeros.
+#[derive(Debug, Clone)]
+pub struct Split {
+    axis: usize,
+    sizes: Vec<usize>,
+}
+
+impl Split {
+    /// Create a new Split instance.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `axis` - Axis to split along
+    /// * `sizes` - Size of the axis of each output, which add up to the size of the axis of the input
+    pub fn new(axis: usize, sizes: Vec<usize>) -> Self {
+        Self { axis, sizes }
+    }
+
+    pub fn get_axis(&self) -> usize {
+        self.axis
+    }
+
+    pub fn get_sizes(&self) -> &Vec<usize> {
+        &self.sizes
+    }
+
+    fn input_check(inputs: &Vec<usize>) -> Result<(), KdezeroError> {
+        if inputs.len() != 1 {
+            return Err(KdezeroError::InvalidInputCount { function: "Split".to_string(), expected: 1, actual: inputs.len() });
+        }
+        Ok(())
+    }
+}
+
+impl FunctionContents for Split {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "Split"
+    }
+
+    fn forward(&self, info: &crate::function::FunctionInfo, inputs: &Vec<usize>, variable_table: &mut VariableTable) -> Vec<usize> {
+        self.try_forward(info, inputs, variable_table).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    fn try_forward(&self, _info: &crate::function::FunctionInfo, inputs: &Vec<usize>, variable_table: &mut VariableTable) -> Result<Vec<usize>, KdezeroError> {
+        let [x] = get_inputs_f64(self.name(), inputs, variable_table)?;
+
+        let outputs = x.try_split(self.axis, &self.sizes)
+            .map_err(|e| KdezeroError::invalid_input(self.name(), 0, x.shape(), e))?;
+
+        Ok(outputs.into_iter()
+            .map(|output| variable_table.generate_variable_from_f64_tensor(output, ""))
+            .collect())
+    }
+
+    fn get_backward(&self) -> fn(usize, &mut Graph) -> Result<Vec<usize>, KdezeroError> {
+        |function_id, graph| {
+            let function = graph.get_function(function_id).ok_or(KdezeroError::InvalidFunctionId { id: function_id })?;
+            let function_contents = function.get_function_contents::<Split>().ok_or(KdezeroError::InvalidFunctionId { id: function_id })?;
+            let axis = function_contents.get_axis();
+            let inputs = function.try_get_inputs()?;
+            let outputs = function.try_get_outputs()?;
+            Split::input_check(inputs)?;
+            let input_id = inputs[0];
+            let output_grad_ids = outputs.iter()
+                .map(|&id| graph.get_variable_grad_id(id).ok_or_else(|| KdezeroError::GradNotFound { function: "Split".to_string(), id }))
+                .collect::<Result<_, _>>()?;
+
+            let concat_id = graph.generate_function_from_function_contents(Box::new(Concat::new(axis)));
+            let grad_id = graph.try_forward(concat_id, output_grad_ids)?[0];
+
+            graph.try_update_grad(input_id, grad_id)?;
+
+            Ok(vec![input_id])
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ktensor::{Tensor, TensorError};
+
+    #[test]
+    fn forward_normal() {
+        let mut graph = Graph::new();
+
+        let split_id = graph.generate_function_from_function_contents(Box::new(Split::new(1, vec![1, 2])));
+        let x_id = graph.generate_variable_from_f64_tensor(Tensor::arrange([2, 3]), "x");
+        let y_ids = graph.forward(split_id, vec![x_id]);
+
+        assert_eq!(y_ids.len(), 2);
+        assert_eq!(graph.get_variable_contents_f64(y_ids[0]).unwrap(), &Tensor::new_from_num_vec(vec![0.0, 3.0], vec![2, 1]));
+        assert_eq!(graph.get_variable_contents_f64(y_ids[1]).unwrap(), &Tensor::new_from_num_vec(vec![1.0, 2.0, 4.0, 5.0], vec![2, 2]));
+    }
+
+    #[test]
+    fn backward_normal() {
+        let mut graph = Graph::new();
+
+        let split_id = graph.generate_function_from_function_contents(Box::new(Split::new(0, vec![1, 2])));
+        let x_id = graph.generate_variable_from_f64_tensor(Tensor::arrange([3]), "x");
+        let y_ids = graph.forward(split_id, vec![x_id]);
+
+        graph.backward(y_ids, false, false);
+
+        let x_grad = graph.get_variable_grad_contents_f64(x_id).unwrap();
+        assert_eq!(x_grad, &Tensor::full(1.0, vec![3]));
+    }
+
+    #[test]
+    fn backward_one_output() {
+        let mut graph = Graph::new();
+
+        let split_id = graph.generate_function_from_function_contents(Box::new(Split::new(0, vec![1, 2])));
+        let x_id = graph.generate_variable_from_f64_tensor(Tensor::arrange([3]), "x");
+        let y_ids = graph.forward(split_id, vec![x_id]);
+        let square_id = graph.generate_function_from_function_contents(Box::new(super::super::Square::new()));
+        let z_id = graph.forward(square_id, vec![y_ids[1]])[0];
+
+        graph.backward(vec![z_id], false, false);
+
+        let x_grad = graph.get_variable_grad_contents_f64(x_id).unwrap();
+        assert_eq!(x_grad, &Tensor::new_from_num_vec(vec![0.0, 2.0, 4.0], vec![3]));
+        assert!(graph.get_variable_grad_id(y_ids[0]).is_none());
+    }
+
+    #[test]
+    fn try_forward_error_sizes() {
+        let mut graph = Graph::new();
+
+        let split_id = graph.generate_function_from_function_contents(Box::new(Split::new(0, vec![1, 1])));
+        let x_id = graph.generate_variable_from_f64_tensor(Tensor::arrange([3]), "x");
+        let error = graph.try_forward(split_id, vec![x_id]).unwrap_err();
+
+        assert_eq!(error, KdezeroError::invalid_input("Split", 0, &[3], TensorError::InvalidShape {
+            shape: vec![3], reason: "the sizes of the parts must add up to the size of the axis" }));
+    }
+}
@@ -0,0 +1,144 @@
+use std::any::Any;
+use super::{Concat, GetItem};
+use super::super::{FunctionContents, get_all_inputs_f64};
+use ktensor::{Tensor, SliceIndex};
+use crate::error::KdezeroError;
+use crate::variable::VariableTable;
+use crate::graph::Graph;
+
+/// Stack of the inputs along a new axis
+/// 
+/// The function takes any number of inputs with the same shape.
+/// The gradient of each input is the index of the gradient of the output along the new axis.
+#[derive(Debug, Clone)]
+pub struct Stack {
+    axis: usize,
+}
+
+impl Stack {
+    /// Create a new Stack instance.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `axis` - Position of the new axis in the output
+    pub fn new(axis: usize) -> Self {
+        Self { axis }
+    }
+
+    pub fn get_axis(&self) -> usize {
+        self.axis
+    }
+
+    fn input_check(inputs: &Vec<usize>) -> Result<(), KdezeroError> {
+        if inputs.is_empty() {
+            return Err(KdezeroError::InvalidInputCount { function: "Stack".to_string(), expected: 1, actual: inputs.len() });
+        }
+        Ok(())
+    }
+
+    fn output_check(outputs: &Vec<usize>) -> Result<(), KdezeroError> {
+        if outputs.len() != 1 {
+            return Err(KdezeroError::InvalidOutputCount { function: "Stack".to_string(), expected: 1, actual: outputs.len() });
+        }
+        Ok(())
+    }
+}
+
+impl FunctionContents for Stack {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "Stack"
+    }
+
+    fn forward(&self, info: &crate::function::FunctionInfo, inputs: &Vec<usize>, variable_table: &mut VariableTable) -> Vec<usize> {
+        self.try_forward(info, inputs, variable_table).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    fn try_forward(&self, _info: &crate::function::FunctionInfo, inputs: &Vec<usize>, variable_table: &mut VariableTable) -> Result<Vec<usize>, KdezeroError> {
+        let xs = get_all_inputs_f64(self.name(), inputs, variable_table)?;
+
+        let output = Tensor::try_stack(&xs, self.axis)
+            .map_err(|e| Concat::join_error(self.name(), &xs, e))?;
+
+        let output_id = variable_table.generate_variable_from_f64_tensor(output, "");
+        Ok(vec![output_id])
+    }
+
+    fn get_backward(&self) -> fn(usize, &mut Graph) -> Result<Vec<usize>, KdezeroError> {
+        |function_id, graph| {
+            let function = graph.get_function(function_id).ok_or(KdezeroError::InvalidFunctionId { id: function_id })?;
+            let function_contents = function.get_function_contents::<Stack>().ok_or(KdezeroError::InvalidFunctionId { id: function_id })?;
+            let axis = function_contents.get_axis();
+            let inputs = function.try_get_inputs()?;
+            let outputs = function.try_get_outputs()?;
+            Stack::input_check(inputs)?;
+            Stack::output_check(outputs)?;
+            let input_ids = inputs.clone();
+            let output_id = outputs[0];
+            let output_grad_id = graph.get_variable_grad_id(output_id).ok_or_else(|| KdezeroError::GradNotFound { function: "Stack".to_string(), id: output_id })?;
+
+            for (i, &input_id) in input_ids.iter().enumerate() {
+                let mut indexes = vec![SliceIndex::full(); axis];
+                indexes.push(SliceIndex::Index(i));
+                let get_item_id = graph.generate_function_from_function_contents(Box::new(GetItem::new(indexes)));
+                let grad_id = graph.try_forward(get_item_id, vec![output_grad_id])?[0];
+                graph.try_update_grad(input_id, grad_id)?;
+            }
+
+            Ok(input_ids)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ktensor::TensorError;
+
+    #[test]
+    fn forward_normal() {
+        let mut graph = Graph::new();
+
+        let stack_id = graph.generate_function_from_function_contents(Box::new(Stack::new(1)));
+        let x0_id = graph.generate_variable_from_f64_tensor(Tensor::arrange([2]), "x0");
+        let x1_id = graph.generate_variable_from_f64_tensor(Tensor::full(5.0, vec![2]), "x1");
+        let y_id = graph.forward(stack_id, vec![x0_id, x1_id]);
+
+        let y = graph.get_variable_contents_f64(y_id[0]).unwrap();
+        assert_eq!(y, &Tensor::new_from_num_vec(vec![0.0, 5.0, 1.0, 5.0], vec![2, 2]));
+    }
+
+    #[test]
+    fn backward_normal() {
+        let mut graph = Graph::new();
+
+        let stack_id = graph.generate_function_from_function_contents(Box::new(Stack::new(1)));
+        let x0_id = graph.generate_variable_from_f64_tensor(Tensor::arrange([2]), "x0");
+        let x1_id = graph.generate_variable_from_f64_tensor(Tensor::full(5.0, vec![2]), "x1");
+        let w_id = graph.generate_variable_from_f64_tensor(Tensor::arrange([2, 2]), "w");
+        let y_id = graph.forward(stack_id, vec![x0_id, x1_id])[0];
+        let mul_id = graph.generate_function_from_function_contents(Box::new(super::super::Mul::new()));
+        let z_id = graph.forward(mul_id, vec![y_id, w_id])[0];
+
+        graph.backward(vec![z_id], false, false);
+
+        assert_eq!(graph.get_variable_grad_contents_f64(x0_id).unwrap(), &Tensor::new_from_num_vec(vec![0.0, 2.0], vec![2]));
+        assert_eq!(graph.get_variable_grad_contents_f64(x1_id).unwrap(), &Tensor::new_from_num_vec(vec![1.0, 3.0], vec![2]));
+    }
+
+    #[test]
+    fn try_forward_error_shape() {
+        let mut graph = Graph::new();
+
+        let stack_id = graph.generate_function_from_function_contents(Box::new(Stack::new(0)));
+        let x0_id = graph.generate_variable_from_f64_tensor(Tensor::arrange([2]), "x0");
+        let x1_id = graph.generate_variable_from_f64_tensor(Tensor::arrange([3]), "x1");
+        let error = graph.try_forward(stack_id, vec![x0_id, x1_id]).unwrap_err();
+
+        assert_eq!(error, KdezeroError::invalid_input("Stack", 1, &[3],
+            TensorError::ShapeMismatch { left: vec![2], right: vec![3] }));
+    }
+}
@@ -1,7 +1,10 @@
 use std::any::Any;
-use super::Neg;
-use super::super::{FunctionContents, FunctionTable};
+use super::{Neg, SumTo};
+use super::super::{FunctionContents, get_inputs_f64};
+use ktensor::tensor::try_broadcast_shapes;
+use crate::error::KdezeroError;
 use crate::variable::VariableTable;
+use crate::graph::Graph;
 
 #[derive(Debug, Clone)]
 pub struct Sub {}
@@ -11,16 +14,18 @@ impl Sub {
         Self {}
     }
 
-    fn input_check(inputs: &Vec<usize>) {
+    fn input_check(inputs: &Vec<usize>) -> Result<(), KdezeroError> {
         if inputs.len() != 2 {
-            panic!("Sub function must have only 2 input, but got {} inputs.", inputs.len());
+            return Err(KdezeroError::InvalidInputCount { function: "Sub".to_string(), expected: 2, actual: inputs.len() });
         }
+        Ok(())
     }
 
-    fn output_check(outputs: &Vec<usize>) {
+    fn output_check(outputs: &Vec<usize>) -> Result<(), KdezeroError> {
         if outputs.len() != 1 {
-            panic!("Sub function must have only one output, but got {} outputs.", outputs.len());
+            return Err(KdezeroError::InvalidOutputCount { function: "Sub".to_string(), expected: 1, actual: outputs.len() });
         }
+        Ok(())
     }
 }
 
@@ -33,35 +38,42 @@ impl FunctionContents for Sub {
         "Sub"
     }
 
-    fn forward(&self, _info: &crate::function::FunctionInfo, inputs: &Vec<usize>, variable_table: &mut VariableTable) -> Vec<usize> {
-        Sub::input_check(inputs);
-        let input0 = variable_table.get_variable_contents_f64(inputs[0]).expect("Invalid variable id");
-        let input1 = variable_table.get_variable_contents_f64(inputs[1]).expect("Invalid variable id");
+    fn forward(&self, info: &crate::function::FunctionInfo, inputs: &Vec<usize>, variable_table: &mut VariableTable) -> Vec<usize> {
+        self.try_forward(info, inputs, variable_table).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    fn try_forward(&self, _info: &crate::function::FunctionInfo, inputs: &Vec<usize>, variable_table: &mut VariableTable) -> Result<Vec<usize>, KdezeroError> {
+        let [input0, input1] = get_inputs_f64(self.name(), inputs, variable_table)?;
+        try_broadcast_shapes(input0.shape(), input1.shape())
+            .map_err(|e| KdezeroError::invalid_input(self.name(), 1, input1.shape(), e))?;
 
         let output = input0 - input1;
 
         let output_id = variable_table.generate_variable_from_f64_tensor(output, "");
-        vec![output_id]
+        Ok(vec![output_id])
     }
 
-    fn get_backward(&self) -> fn(usize, &mut FunctionTable, &mut VariableTable) -> Vec<usize> {
-        |function_id, function_table, variable_table| {
-            let function = function_table.get(function_id).expect("Invalid function id");
-            let inputs = function.get_inputs().expect("Invalid inputs");
-            let outputs = function.get_outputs().expect("Invalid outputs");
-            Sub::input_check(inputs);
-            Sub::output_check(outputs);
+    fn get_backward(&self) -> fn(usize, &mut Graph) -> Result<Vec<usize>, KdezeroError> {
+        |function_id, graph| {
+            let function = graph.get_function(function_id).ok_or(KdezeroError::InvalidFunctionId { id: function_id })?;
+            let inputs = function.try_get_inputs()?;
+            let outputs = function.try_get_outputs()?;
+            Sub::input_check(inputs)?;
+            Sub::output_check(outputs)?;
             let input_ids = inputs.clone();
             let output_id = outputs[0];
-            let output_grad_id = variable_table.get_variable_grad_id(output_id).expect("Output grad id not found");
+            let output_grad_id = graph.get_variable_grad_id(output_id).ok_or_else(|| KdezeroError::GradNotFound { function: "Sub".to_string(), id: output_id })?;
+
+            let neg_id = graph.generate_function_from_function_contents(Box::new(Neg::new()));
+            let grad_id1 = graph.try_forward(neg_id, vec![output_grad_id])?[0];
 
-            let neg_id = function_table.generate_function_from_function_contents(Box::new(Neg::new()));
-            let grad_id1 = function_table.forward(neg_id, vec![output_grad_id], variable_table, false)[0];
+            let grad_id0 = SumTo::reduce_grad(graph, output_grad_id, input_ids[0])?;
+            let grad_id1 = SumTo::reduce_grad(graph, grad_id1, input_ids[1])?;
 
-            variable_table.update_grad(input_ids[0], output_grad_id, function_table);
-            variable_table.update_grad(input_ids[1], grad_id1, function_table);
+            graph.try_update_grad(input_ids[0], grad_id0)?;
+            graph.try_update_grad(input_ids[1], grad_id1)?;
 
-            input_ids
+            Ok(input_ids)
         }
     }
 }
@@ -70,46 +82,43 @@ impl FunctionContents for Sub {
 mod tests {
     use super::*;
     use ktensor::Tensor;
-    use crate::{variable::VariableTable, function::FunctionTable};
 
     #[test]
     fn forward_normal() {
-        let mut variable_table = VariableTable::new();
-        let mut function_table = FunctionTable::new();
+        let mut graph = Graph::new();
 
         let data0 = vec![1.0, 2.0, 3.0];
         let data1 = vec![4.0, 5.0, 6.0];
-        let sub_id = function_table.generate_function_from_function_contents(Box::new(Sub::new()));
-        let id0 = variable_table.generate_variable_from_f64_tensor(
+        let sub_id = graph.generate_function_from_function_contents(Box::new(Sub::new()));
+        let id0 = graph.generate_variable_from_f64_tensor(
             Tensor::new_from_num_vec(data0.clone(), vec![3]), "x");
-        let id1 = variable_table.generate_variable_from_f64_tensor(
+        let id1 = graph.generate_variable_from_f64_tensor(
             Tensor::new_from_num_vec(data1.clone(), vec![3]), "y");
 
-        let output_ids = function_table.forward(sub_id, vec![id0, id1], &mut variable_table, false);
+        let output_ids = graph.forward(sub_id, vec![id0, id1]);
 
-        let output = variable_table.get_variable_contents_f64(output_ids[0]).unwrap();
+        let output = graph.get_variable_contents_f64(output_ids[0]).unwrap();
         assert_eq!(output, &Tensor::new_from_num_vec(vec![-3.0, -3.0, -3.0], vec![3]));
     }
 
     #[test]
     fn backward_normal() {
-        let mut variable_table = VariableTable::new();
-        let mut function_table = FunctionTable::new();
+        let mut graph = Graph::new();
 
         let data0 = vec![1.0, 2.0, 3.0];
         let data1 = vec![4.0, 5.0, 6.0];
-        let sub_id = function_table.generate_function_from_function_contents(Box::new(Sub::new()));
-        let id0 = variable_table.generate_variable_from_f64_tensor(
+        let sub_id = graph.generate_function_from_function_contents(Box::new(Sub::new()));
+        let id0 = graph.generate_variable_from_f64_tensor(
             Tensor::new_from_num_vec(data0.clone(), vec![3]), "x");
-        let id1 = variable_table.generate_variable_from_f64_tensor(
+        let id1 = graph.generate_variable_from_f64_tensor(
             Tensor::new_from_num_vec(data1.clone(), vec![3]), "y");
 
-        let output_ids = function_table.forward(sub_id, vec![id0, id1], &mut variable_table, false);
+        let output_ids = graph.forward(sub_id, vec![id0, id1]);
 
-        variable_table.backward(output_ids, &mut function_table, false);
+        graph.backward(output_ids, false, false);
 
-        let grad0 = variable_table.get_variable_grad_contents_f64(id0).unwrap();
-        let grad1 = variable_table.get_variable_grad_contents_f64(id1).unwrap();
+        let grad0 = graph.get_variable_grad_contents_f64(id0).unwrap();
+        let grad1 = graph.get_variable_grad_contents_f64(id1).unwrap();
         assert_eq!(grad0, &Tensor::new_from_num_vec(vec![1.0, 1.0, 1.0], vec![3]));
         assert_eq!(grad1, &Tensor::new_from_num_vec(vec![-1.0, -1.0, -1.0], vec![3]));
     }
@@ -120,23 +129,42 @@ mod tests {
     #[test]
     #[should_panic]
     fn backward_backward_normal() {
-        let mut variable_table = VariableTable::new();
-        let mut function_table = FunctionTable::new();
+        let mut graph = Graph::new();
 
         let data = vec![1.0, 2.0, 3.0];
-        let sub_id = function_table.generate_function_from_function_contents(Box::new(Sub::new()));
-        let id = variable_table.generate_variable_from_f64_tensor(
+        let sub_id = graph.generate_function_from_function_contents(Box::new(Sub::new()));
+        let id = graph.generate_variable_from_f64_tensor(
             Tensor::new_from_num_vec(data.clone(), vec![3]), "x");
 
-        let output_ids = function_table.forward(sub_id, vec![id, id], &mut variable_table, false);
+        let output_ids = graph.forward(sub_id, vec![id, id]);
+
+        graph.backward(output_ids, false, true);
+
+        let grad0_id = graph.get_variable_grad_id(id).unwrap();
+        graph.clear_grad(id);
+
+        graph.backward(vec![grad0_id], false, false);
+
+        graph.get_variable_grad_contents_f64(id).unwrap();
+    }
+
+    #[test]
+    fn backward_broadcast() {
+        let mut graph = Graph::new();
 
-        variable_table.backward(output_ids, &mut function_table, false);
+        let sub_id = graph.generate_function_from_function_contents(Box::new(Sub::new()));
+        let id0 = graph.generate_variable_from_f64_tensor(Tensor::arrange([2, 3]), "x");
+        let id1 = graph.generate_variable_from_f64_tensor(
+            Tensor::new_from_num_vec(vec![1.0, 2.0, 4.0], vec![3]), "y");
 
-        let grad0_id = variable_table.get_variable_grad_id(id).unwrap();
-        variable_table.clear_grad(id);
+        let output_ids = graph.forward(sub_id, vec![id0, id1]);
+        assert_eq!(graph.get_variable(output_ids[0]).unwrap().shape(), &vec![2, 3]);
 
-        variable_table.backward(vec![grad0_id], &mut function_table, false);
+        graph.backward(output_ids, false, false);
 
-        variable_table.get_variable_grad_contents_f64(id).unwrap();
+        let grad0 = graph.get_variable_grad_contents_f64(id0).unwrap();
+        let grad1 = graph.get_variable_grad_contents_f64(id1).unwrap();
+        assert_eq!(grad0, &Tensor::full(1.0, vec![2, 3]));
+        assert_eq!(grad1, &Tensor::new_from_num_vec(vec![-2.0, -2.0, -2.0], vec![3]));
     }
 }
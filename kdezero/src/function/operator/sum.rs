@@ -1,7 +1,9 @@
 use std::any::Any;
 use super::{BroadcastTo, Reshape};
-use super::super::{FunctionContents, FunctionTable};
+use super::super::{FunctionContents, get_inputs_f64};
+use crate::error::KdezeroError;
 use crate::variable::VariableTable;
+use crate::graph::Graph;
 
 #[derive(Debug, Clone)]
 pub struct Sum {
@@ -22,16 +24,57 @@ impl Sum {
         self.keepdims
     }
 
-    fn input_check(inputs: &Vec<usize>) {
+    /// Reshape and broadcast the gradient of a reduction back to the shape of its input.
+    /// 
+    /// The axes removed by a reduction without `keepdims` are restored with the size 1
+    /// before broadcasting, so that the gradient lines up with the input.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `graph` - Computation graph
+    /// * `grad_id` - Variable ID of the gradient of the output
+    /// * `input_id` - Variable ID of the input of the reduction
+    /// * `axis` - Reduced axes. If None or empty, all the axes were reduced
+    /// * `keepdims` - Whether the reduction kept the reduced axes
+    /// 
+    /// # Errors
+    /// 
+    /// * `InvalidVariableId` - If the input is not in the graph
+    /// * `InvalidInput` - If the gradient cannot be broadcast to the shape of the input
+    pub(crate) fn expand_grad(graph: &mut Graph, grad_id: usize, input_id: usize, axis: Option<&Vec<usize>>, keepdims: bool) -> Result<usize, KdezeroError> {
+        let input_shape = graph.get_variable(input_id).ok_or(KdezeroError::InvalidVariableId { id: input_id })?.shape().clone();
+        let mut grad_id = grad_id;
+
+        if !keepdims && input_shape.len() != 0 {
+            let mut axis = match axis {
+                Some(axis) if !axis.is_empty() => axis.clone(),
+                _ => (0..input_shape.len()).collect::<Vec<usize>>(),
+            };
+            axis.sort();
+            let mut output_shape = graph.get_variable(grad_id).ok_or(KdezeroError::InvalidVariableId { id: grad_id })?.shape().clone();
+            for axis in axis.iter() {
+                output_shape.insert(*axis, 1);
+            }
+            let reshape_id = graph.generate_function_from_function_contents(Box::new(Reshape::new(output_shape)));
+            grad_id = graph.try_forward(reshape_id, vec![grad_id])?[0];
+        }
+
+        let broadcast_to_id = graph.generate_function_from_function_contents(Box::new(BroadcastTo::new(input_shape)));
+        Ok(graph.try_forward(broadcast_to_id, vec![grad_id])?[0])
+    }
+
+    fn input_check(inputs: &Vec<usize>) -> Result<(), KdezeroError> {
         if inputs.len() != 1 {
-            panic!("Sum function must have only 1 input, but got {} inputs.", inputs.len());
+            return Err(KdezeroError::InvalidInputCount { function: "Sum".to_string(), expected: 1, actual: inputs.len() });
         }
+        Ok(())
     }
 
-    fn output_check(outputs: &Vec<usize>) {
+    fn output_check(outputs: &Vec<usize>) -> Result<(), KdezeroError> {
         if outputs.len() != 1 {
-            panic!("Sum function must have only one output, but got {} outputs.", outputs.len());
+            return Err(KdezeroError::InvalidOutputCount { function: "Sum".to_string(), expected: 1, actual: outputs.len() });
         }
+        Ok(())
     }
 }
 
@@ -44,58 +87,46 @@ impl FunctionContents for Sum {
         "Sum"
     }
 
-    fn forward(&self, _info: &crate::function::FunctionInfo, inputs: &Vec<usize>, variable_table: &mut VariableTable) -> Vec<usize> {
-        Sum::input_check(inputs);
-        let input = variable_table.get_variable_contents_f64(inputs[0]).expect("Invalid variable id");
+    fn forward(&self, info: &crate::function::FunctionInfo, inputs: &Vec<usize>, variable_table: &mut VariableTable) -> Vec<usize> {
+        self.try_forward(info, inputs, variable_table).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    fn try_forward(&self, _info: &crate::function::FunctionInfo, inputs: &Vec<usize>, variable_table: &mut VariableTable) -> Result<Vec<usize>, KdezeroError> {
+        let [input] = get_inputs_f64(self.name(), inputs, variable_table)?;
 
-        let output = input.sum(
+        let output = input.try_sum(
             match &self.axis {
                 Some(axis) => axis.as_slice(),
                 None => &[],
             },
             self.keepdims,
-        );
+        ).map_err(|e| KdezeroError::invalid_input(self.name(), 0, input.shape(), e))?;
 
         let output_id = variable_table.generate_variable_from_f64_tensor(output, "");
-        vec![output_id]
+        Ok(vec![output_id])
     }
 
-    fn get_backward(&self) -> fn(usize, &mut FunctionTable, &mut VariableTable) -> Vec<usize> {
-        |function_id, function_table, variable_table| {
-            let function = function_table.get(function_id).expect("Invalid function id");
-            let function_contents = function.get_function_contents::<Sum>().expect("Invalid function contents");
+    fn get_backward(&self) -> fn(usize, &mut Graph) -> Result<Vec<usize>, KdezeroError> {
+        |function_id, graph| {
+            let function = graph.get_function(function_id).ok_or(KdezeroError::InvalidFunctionId { id: function_id })?;
+            let function_contents = function.get_function_contents::<Sum>().ok_or(KdezeroError::InvalidFunctionId { id: function_id })?;
             let axis = function_contents.get_axis();
             let keepdims = function_contents.get_keepdims();
 
-            let inputs = function.get_inputs().expect("Invalid inputs");
-            let outputs = function.get_outputs().expect("Invalid outputs");
-            Sum::input_check(inputs);
-            Sum::output_check(outputs);
+            let inputs = function.try_get_inputs()?;
+            let outputs = function.try_get_outputs()?;
+            Sum::input_check(inputs)?;
+            Sum::output_check(outputs)?;
             let input_id = inputs[0];
-            let input_shape = variable_table.get(input_id).expect("Invalid variable id").shape().clone();
             let output_id = outputs[0];
-            let mut output_grad_id = variable_table.get_variable_grad_id(output_id).expect("Output grad id not found");
-
-            if !keepdims && input_shape.len() != 0 {
-                let mut axis = match axis {
-                    Some(axis) => axis.clone(),
-                    None => (0..input_shape.len()).collect::<Vec<usize>>(),
-                };
-                axis.sort();
-                let mut output_shape = variable_table.get(output_id).expect("Invalid variable id").shape().clone();
-                for axis in axis.iter() {
-                    output_shape.insert(*axis, 1);
-                }
-                let reshape_id = function_table.generate_function_from_function_contents(Box::new(Reshape::new(output_shape)));
-                output_grad_id = function_table.forward(reshape_id, vec![output_grad_id], variable_table, false)[0];
-            }
+            let output_grad_id = graph.get_variable_grad_id(output_id).ok_or_else(|| KdezeroError::GradNotFound { function: "Sum".to_string(), id: output_id })?;
+            let axis = axis.cloned();
 
-            let broadcast_to_id = function_table.generate_function_from_function_contents(Box::new(BroadcastTo::new(input_shape)));
-            let grad_id = function_table.forward(broadcast_to_id, vec![output_grad_id], variable_table, false)[0];
+            let grad_id = Sum::expand_grad(graph, output_grad_id, input_id, axis.as_ref(), keepdims)?;
 
-            variable_table.update_grad(input_id, grad_id, function_table);
+            graph.try_update_grad(input_id, grad_id)?;
 
-            vec![input_id]
+            Ok(vec![input_id])
         }
     }
 }
@@ -103,77 +134,84 @@ impl FunctionContents for Sum {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use ktensor::Tensor;
-    use crate::{variable::VariableTable, function::FunctionTable};
+    use ktensor::{Tensor, TensorError};
 
     #[test]
     fn forward_normal() {
-        let mut variable_table = VariableTable::new();
-        let mut function_table = FunctionTable::new();
+        let mut graph = Graph::new();
 
         let data = vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0];
-        let sum_id = function_table.generate_function_from_function_contents(Box::new(Sum::new(Some([0]), false)));
-        let x_id = variable_table.generate_variable_from_f64_tensor(
+        let sum_id = graph.generate_function_from_function_contents(Box::new(Sum::new(Some([0]), false)));
+        let x_id = graph.generate_variable_from_f64_tensor(
             Tensor::new_from_num_vec(data.clone(), vec![2, 3]), "x");
-        let y_id = function_table.forward(sum_id, vec![x_id], &mut variable_table, false);
+        let y_id = graph.forward(sum_id, vec![x_id]);
 
-        let y = variable_table.get_variable_contents_f64(y_id[0]).unwrap();
+        let y = graph.get_variable_contents_f64(y_id[0]).unwrap();
         assert_eq!(y, &Tensor::new_from_num_vec(vec![5.0, 7.0, 9.0], vec![3]));
     }
 
     #[test]
     fn backward_normal() {
-        let mut variable_table = VariableTable::new();
-        let mut function_table = FunctionTable::new();
+        let mut graph = Graph::new();
 
         let data = vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0];
-        let sum_id = function_table.generate_function_from_function_contents(Box::new(Sum::new(Some([0]), false)));
-        let x_id = variable_table.generate_variable_from_f64_tensor(
+        let sum_id = graph.generate_function_from_function_contents(Box::new(Sum::new(Some([0]), false)));
+        let x_id = graph.generate_variable_from_f64_tensor(
             Tensor::new_from_num_vec(data.clone(), vec![2, 3]), "x");
-        let y_id = function_table.forward(sum_id, vec![x_id], &mut variable_table, false);
+        let y_id = graph.forward(sum_id, vec![x_id]);
 
-        variable_table.backward(y_id, &mut function_table, false);
+        graph.backward(y_id, false, false);
 
-        let x_grad = variable_table.get_variable_grad_contents_f64(x_id).unwrap();
+        let x_grad = graph.get_variable_grad_contents_f64(x_id).unwrap();
         assert_eq!(x_grad, &Tensor::new_from_num_vec(vec![1.0, 1.0, 1.0, 1.0, 1.0, 1.0], vec![2, 3]));
     }
 
     #[test]
     fn backward_reshape() {
-        let mut variable_table = VariableTable::new();
-        let mut function_table = FunctionTable::new();
+        let mut graph = Graph::new();
 
         let data = vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0];
-        let sum_id = function_table.generate_function_from_function_contents(Box::new(Sum::new::<&[usize]>(None, true)));
-        let x_id = variable_table.generate_variable_from_f64_tensor(
+        let sum_id = graph.generate_function_from_function_contents(Box::new(Sum::new::<&[usize]>(None, true)));
+        let x_id = graph.generate_variable_from_f64_tensor(
             Tensor::new_from_num_vec(data.clone(), vec![2, 3]), "x");
-        let y_id = function_table.forward(sum_id, vec![x_id], &mut variable_table, false);
+        let y_id = graph.forward(sum_id, vec![x_id]);
 
-        variable_table.backward(y_id, &mut function_table, false);
+        graph.backward(y_id, false, false);
 
-        let x_grad = variable_table.get_variable_grad_contents_f64(x_id).unwrap();
+        let x_grad = graph.get_variable_grad_contents_f64(x_id).unwrap();
         assert_eq!(x_grad, &Tensor::new_from_num_vec(vec![1.0, 1.0, 1.0, 1.0, 1.0, 1.0], vec![2, 3]));
     }
 
     #[test]
     #[should_panic]
     fn backward_backward_normal() {
-        let mut variable_table = VariableTable::new();
-        let mut function_table = FunctionTable::new();
+        let mut graph = Graph::new();
 
         let data = vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0];
-        let sum_id = function_table.generate_function_from_function_contents(Box::new(Sum::new(Some([0]), false)));
-        let x_id = variable_table.generate_variable_from_f64_tensor(
+        let sum_id = graph.generate_function_from_function_contents(Box::new(Sum::new(Some([0]), false)));
+        let x_id = graph.generate_variable_from_f64_tensor(
             Tensor::new_from_num_vec(data.clone(), vec![2, 3]), "x");
-        let y_id = function_table.forward(sum_id, vec![x_id], &mut variable_table, false);
+        let y_id = graph.forward(sum_id, vec![x_id]);
+
+        graph.backward(y_id, false, true);
+
+        let x_grad_id = graph.get_variable_grad_id(x_id).unwrap();
+        graph.clear_grad(x_id);
 
-        variable_table.backward(y_id, &mut function_table, false);
+        graph.backward(vec![x_grad_id], false, false);
 
-        let x_grad_id = variable_table.get_variable_grad_id(x_id).unwrap();
-        variable_table.clear_grad(x_id);
+        let _ = graph.get_variable_grad_contents_f64(x_id).unwrap();
+    }
+
+    #[test]
+    fn try_forward_error_axis() {
+        let mut graph = Graph::new();
 
-        variable_table.backward(vec![x_grad_id], &mut function_table, false);
+        let sum_id = graph.generate_function_from_function_contents(Box::new(Sum::new(Some([2]), false)));
+        let x_id = graph.generate_variable_from_f64_tensor(Tensor::full(1.0, vec![2, 3]), "x");
+        let error = graph.try_forward(sum_id, vec![x_id]).unwrap_err();
 
-        let _ = variable_table.get_variable_grad_contents_f64(x_id).unwrap();
+        assert_eq!(error, KdezeroError::invalid_input("Sum", 0, &[2, 3],
+            TensorError::InvalidAxis { axis: 2, ndim: 2 }));
     }
 }
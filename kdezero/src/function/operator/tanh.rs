@@ -1,8 +1,10 @@
 use std::any::Any;
 use super::{Mul, Sub};
-use super::super::{FunctionContents, FunctionTable};
+use super::super::FunctionContents;
 use ktensor::Tensor;
 use crate::variable::VariableTable;
+use crate::error::KdezeroError;
+use crate::graph::Graph;
 
 #[derive(Debug, Clone)]
 pub struct Tanh {}
@@ -12,16 +14,18 @@ impl Tanh {
         Self {}
     }
 
-    fn input_check(inputs: &Vec<usize>) {
+    fn input_check(inputs: &Vec<usize>) -> Result<(), KdezeroError> {
         if inputs.len() != 1 {
-            panic!("Tanh function must have only one input, but got {} inputs.", inputs.len());
+            return Err(KdezeroError::InvalidInputCount { function: "Tanh".to_string(), expected: 1, actual: inputs.len() });
         }
+        Ok(())
     }
 
-    fn output_check(outputs: &Vec<usize>) {
+    fn output_check(outputs: &Vec<usize>) -> Result<(), KdezeroError> {
         if outputs.len() != 1 {
-            panic!("Tanh function must have only one output, but got {} outputs.", outputs.len());
+            return Err(KdezeroError::InvalidOutputCount { function: "Tanh".to_string(), expected: 1, actual: outputs.len() });
         }
+        Ok(())
     }
 }
 
@@ -35,7 +39,7 @@ impl FunctionContents for Tanh {
     }
 
     fn forward(&self, _info: &crate::function::FunctionInfo, inputs: &Vec<usize>, variable_table: &mut VariableTable) -> Vec<usize> {
-        Tanh::input_check(inputs);
+        Tanh::input_check(inputs).unwrap_or_else(|e| panic!("{}", e));
         let x = variable_table.get_variable_contents_f64(inputs[0]).expect("Invalid variable id");
 
         let output = x.tanh();
@@ -44,32 +48,32 @@ impl FunctionContents for Tanh {
         vec![output_id]
     }
 
-    fn get_backward(&self) -> fn(usize, &mut FunctionTable, &mut VariableTable) -> Vec<usize> {
-        |function_id, function_table, variable_table| {
-            let function = function_table.get(function_id).expect("Invalid function id");
-            let inputs = function.get_inputs().expect("Invalid inputs");
-            let outputs = function.get_outputs().expect("Invalid outputs");
-            Tanh::input_check(inputs);
-            Tanh::output_check(outputs);
+    fn get_backward(&self) -> fn(usize, &mut Graph) -> Result<Vec<usize>, KdezeroError> {
+        |function_id, graph| {
+            let function = graph.get_function(function_id).ok_or(KdezeroError::InvalidFunctionId { id: function_id })?;
+            let inputs = function.try_get_inputs()?;
+            let outputs = function.try_get_outputs()?;
+            Tanh::input_check(inputs)?;
+            Tanh::output_check(outputs)?;
             let input_id = inputs[0];
             let output_id = outputs[0];
-            let output_grad_id = variable_table.get_variable_grad_id(output_id).expect("Output grad id not found");
+            let output_grad_id = graph.get_variable_grad_id(output_id).ok_or_else(|| KdezeroError::GradNotFound { function: "Tanh".to_string(), id: output_id })?;
 
-            let variable = variable_table.get_variable_contents_f64(output_id).expect("Invalid variable id");
-            let const_id = variable_table.generate_variable_from_f64_tensor(
+            let variable = graph.get_variable_contents_f64(output_id).ok_or(KdezeroError::InvalidVariableId { id: output_id })?;
+            let const_id = graph.generate_variable_from_f64_tensor(
                 Tensor::full_like(variable, 1.0), ""
             );
 
-            let mul_id = function_table.generate_function_from_function_contents(Box::new(Mul::new()));
-            let temp_id = function_table.forward(mul_id, vec![output_id, output_id], variable_table, false)[0];
-            let sub_id = function_table.generate_function_from_function_contents(Box::new(Sub::new()));
-            let temp_id = function_table.forward(sub_id, vec![const_id, temp_id], variable_table, false)[0];
-            let mul_id = function_table.generate_function_from_function_contents(Box::new(Mul::new()));
-            let grad_id = function_table.forward(mul_id, vec![output_grad_id, temp_id], variable_table, false)[0];
+            let mul_id = graph.generate_function_from_function_contents(Box::new(Mul::new()));
+            let temp_id = graph.try_forward(mul_id, vec![output_id, output_id])?[0];
+            let sub_id = graph.generate_function_from_function_contents(Box::new(Sub::new()));
+            let temp_id = graph.try_forward(sub_id, vec![const_id, temp_id])?[0];
+            let mul_id = graph.generate_function_from_function_contents(Box::new(Mul::new()));
+            let grad_id = graph.try_forward(mul_id, vec![output_grad_id, temp_id])?[0];
 
-            variable_table.update_grad(input_id, grad_id, function_table);
+            graph.try_update_grad(input_id, grad_id)?;
 
-            vec![input_id]
+            Ok(vec![input_id])
         }
     }
 }
@@ -78,37 +82,34 @@ impl FunctionContents for Tanh {
 mod tests {
     use super::*;
     use ktensor::Tensor;
-    use crate::{variable::VariableTable, function::FunctionTable};
 
     #[test]
     fn forward_normal() {
-        let mut variable_table = VariableTable::new();
-        let mut function_table = FunctionTable::new();
+        let mut graph = Graph::new();
 
         let data = vec![1.0, 2.0, 3.0];
-        let tanh_id = function_table.generate_function_from_function_contents(Box::new(Tanh::new()));
-        let x_id = variable_table.generate_variable_from_f64_tensor(
+        let tanh_id = graph.generate_function_from_function_contents(Box::new(Tanh::new()));
+        let x_id = graph.generate_variable_from_f64_tensor(
             Tensor::new_from_num_vec(data.clone(), vec![3]), "x");
-        let y_id = function_table.forward(tanh_id, vec![x_id], &mut variable_table, false);
+        let y_id = graph.forward(tanh_id, vec![x_id]);
 
-        let y = variable_table.get_variable_contents_f64(y_id[0]).unwrap();
+        let y = graph.get_variable_contents_f64(y_id[0]).unwrap();
         assert_eq!(y, &Tensor::new_from_num_vec(data.iter().map(|x| x.tanh()), vec![3]));
     }
 
     #[test]
     fn backward_normal() {
-        let mut variable_table = VariableTable::new();
-        let mut function_table = FunctionTable::new();
+        let mut graph = Graph::new();
 
         let data = vec![1.0, 2.0, 3.0];
-        let tanh_id = function_table.generate_function_from_function_contents(Box::new(Tanh::new()));
-        let x_id = variable_table.generate_variable_from_f64_tensor(
+        let tanh_id = graph.generate_function_from_function_contents(Box::new(Tanh::new()));
+        let x_id = graph.generate_variable_from_f64_tensor(
             Tensor::new_from_num_vec(data.clone(), vec![3]), "x");
-        let y_ids = function_table.forward(tanh_id, vec![x_id], &mut variable_table, false);
+        let y_ids = graph.forward(tanh_id, vec![x_id]);
 
-        variable_table.backward(y_ids, &mut function_table, false);
+        graph.backward(y_ids, false, false);
 
-        let x_grad = variable_table.get_variable_grad_contents_f64(x_id).unwrap();
+        let x_grad = graph.get_variable_grad_contents_f64(x_id).unwrap();
         assert_eq!(x_grad, &Tensor::new_from_num_vec(data.iter().map(|x| 1.0 - x.tanh() * x.tanh()), vec![3]));
     }
 }
@@ -1,6 +1,8 @@
 use std::any::Any;
-use super::super::{FunctionContents, FunctionTable};
+use super::super::{FunctionContents, get_inputs_f64};
+use crate::error::KdezeroError;
 use crate::variable::VariableTable;
+use crate::graph::Graph;
 
 #[derive(Debug, Clone)]
 pub struct Transpose {}
@@ -10,16 +12,18 @@ impl Transpose {
         Self { }
     }
 
-    fn input_check(inputs: &Vec<usize>) {
+    fn input_check(inputs: &Vec<usize>) -> Result<(), KdezeroError> {
         if inputs.len() != 1 {
-            panic!("Transpose function must have only one input, but got {} inputs.", inputs.len());
+            return Err(KdezeroError::InvalidInputCount { function: "Transpose".to_string(), expected: 1, actual: inputs.len() });
         }
+        Ok(())
     }
 
-    fn output_check(outputs: &Vec<usize>) {
+    fn output_check(outputs: &Vec<usize>) -> Result<(), KdezeroError> {
         if outputs.len() != 1 {
-            panic!("Transpose function must have only one output, but got {} outputs.", outputs.len());
+            return Err(KdezeroError::InvalidOutputCount { function: "Transpose".to_string(), expected: 1, actual: outputs.len() });
         }
+        Ok(())
     }
 }
 
@@ -32,33 +36,36 @@ impl FunctionContents for Transpose {
         "Transpose"
     }
 
-    fn forward(&self, _info: &crate::function::FunctionInfo, inputs: &Vec<usize>, variable_table: &mut VariableTable) -> Vec<usize> {
-        Transpose::input_check(inputs);
-        let x = variable_table.get_variable_contents_f64(inputs[0]).expect("Invalid variable id");
+    fn forward(&self, info: &crate::function::FunctionInfo, inputs: &Vec<usize>, variable_table: &mut VariableTable) -> Vec<usize> {
+        self.try_forward(info, inputs, variable_table).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    fn try_forward(&self, _info: &crate::function::FunctionInfo, inputs: &Vec<usize>, variable_table: &mut VariableTable) -> Result<Vec<usize>, KdezeroError> {
+        let [x] = get_inputs_f64(self.name(), inputs, variable_table)?;
 
         let output = x.transpose();
 
         let output_id = variable_table.generate_variable_from_f64_tensor(output, "");
-        vec![output_id]
+        Ok(vec![output_id])
     }
 
-    fn get_backward(&self) -> fn(usize, &mut FunctionTable, &mut VariableTable) -> Vec<usize> {
-        |function_id, function_table, variable_table| {
-            let function = function_table.get(function_id).expect("Invalid function id");
-            let inputs = function.get_inputs().expect("Invalid inputs");
-            let outputs = function.get_outputs().expect("Invalid outputs");
-            Transpose::input_check(inputs);
-            Transpose::output_check(outputs);
+    fn get_backward(&self) -> fn(usize, &mut Graph) -> Result<Vec<usize>, KdezeroError> {
+        |function_id, graph| {
+            let function = graph.get_function(function_id).ok_or(KdezeroError::InvalidFunctionId { id: function_id })?;
+            let inputs = function.try_get_inputs()?;
+            let outputs = function.try_get_outputs()?;
+            Transpose::input_check(inputs)?;
+            Transpose::output_check(outputs)?;
             let input_id = inputs[0];
             let output_id = outputs[0];
-            let output_grad_id = variable_table.get_variable_grad_id(output_id).expect("Output grad id not found");
+            let output_grad_id = graph.get_variable_grad_id(output_id).ok_or_else(|| KdezeroError::GradNotFound { function: "Transpose".to_string(), id: output_id })?;
 
-            let transpose_id = function_table.generate_function_from_function_contents(Box::new(Transpose::new()));
-            let grad_id = function_table.forward(transpose_id, vec![output_grad_id], variable_table, false)[0];
+            let transpose_id = graph.generate_function_from_function_contents(Box::new(Transpose::new()));
+            let grad_id = graph.try_forward(transpose_id, vec![output_grad_id])?[0];
 
-            variable_table.update_grad(input_id, grad_id, function_table);
+            graph.try_update_grad(input_id, grad_id)?;
 
-            vec![input_id]
+            Ok(vec![input_id])
         }
     }
 }
@@ -67,37 +74,34 @@ impl FunctionContents for Transpose {
 mod tests {
     use super::*;
     use ktensor::Tensor;
-    use crate::{variable::VariableTable, function::FunctionTable};
 
     #[test]
     fn forward_normal() {
-        let mut variable_table = VariableTable::new();
-        let mut function_table = FunctionTable::new();
+        let mut graph = Graph::new();
 
         let data = vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0];
-        let transpose_id = function_table.generate_function_from_function_contents(Box::new(Transpose::new()));
-        let x_id = variable_table.generate_variable_from_f64_tensor(
+        let transpose_id = graph.generate_function_from_function_contents(Box::new(Transpose::new()));
+        let x_id = graph.generate_variable_from_f64_tensor(
             Tensor::new_from_num_vec(data.clone(), vec![2, 3]), "x");
-        let y_id = function_table.forward(transpose_id, vec![x_id], &mut variable_table, false);
+        let y_id = graph.forward(transpose_id, vec![x_id]);
 
-        let y = variable_table.get_variable_contents_f64(y_id[0]).unwrap();
+        let y = graph.get_variable_contents_f64(y_id[0]).unwrap();
         assert_eq!(y, &Tensor::new_from_num_vec(vec![1.0, 4.0, 2.0, 5.0, 3.0, 6.0], vec![3, 2]));
     }
 
     #[test]
     fn backward_normal() {
-        let mut variable_table = VariableTable::new();
-        let mut function_table = FunctionTable::new();
+        let mut graph = Graph::new();
 
         let data = vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0];
-        let transpose_id = function_table.generate_function_from_function_contents(Box::new(Transpose::new()));
-        let x_id = variable_table.generate_variable_from_f64_tensor(
+        let transpose_id = graph.generate_function_from_function_contents(Box::new(Transpose::new()));
+        let x_id = graph.generate_variable_from_f64_tensor(
             Tensor::new_from_num_vec(data.clone(), vec![2, 3]), "x");
-        let y_ids = function_table.forward(transpose_id, vec![x_id], &mut variable_table, false);
+        let y_ids = graph.forward(transpose_id, vec![x_id]);
 
-        variable_table.backward(y_ids, &mut function_table, false);
+        graph.backward(y_ids, false, false);
 
-        let x_grad = variable_table.get_variable_grad_contents_f64(x_id).unwrap();
+        let x_grad = graph.get_variable_grad_contents_f64(x_id).unwrap();
         assert_eq!(x_grad, &Tensor::new_from_num_vec(vec![1.0, 1.0, 1.0, 1.0, 1.0, 1.0], vec![2, 3]));
     }
 
@@ -107,22 +111,32 @@ mod tests {
     #[test]
     #[should_panic]
     fn backward_backward_normal() {
-        let mut variable_table = VariableTable::new();
-        let mut function_table = FunctionTable::new();
+        let mut graph = Graph::new();
 
         let data = vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0];
-        let transpose_id = function_table.generate_function_from_function_contents(Box::new(Transpose::new()));
-        let x_id = variable_table.generate_variable_from_f64_tensor(
+        let transpose_id = graph.generate_function_from_function_contents(Box::new(Transpose::new()));
+        let x_id = graph.generate_variable_from_f64_tensor(
             Tensor::new_from_num_vec(data.clone(), vec![2, 3]), "x");
-        let y_ids = function_table.forward(transpose_id, vec![x_id], &mut variable_table, false);
+        let y_ids = graph.forward(transpose_id, vec![x_id]);
+
+        graph.backward(y_ids, false, true);
+
+        let x_grad_id = graph.get_variable_grad_id(x_id).unwrap();
+        graph.clear_grad(x_id);
 
-        variable_table.backward(y_ids, &mut function_table, false);
+        graph.backward(vec![x_grad_id], false, false);
 
-        let x_grad_id = variable_table.get_variable_grad_id(x_id).unwrap();
-        variable_table.clear_grad(x_id);
+        graph.get_variable_grad_contents_f64(x_id).unwrap();
+    }
+
+    #[test]
+    fn try_forward_error_input_count() {
+        let mut graph = Graph::new();
 
-        variable_table.backward(vec![x_grad_id], &mut function_table, false);
+        let transpose_id = graph.generate_function_from_function_contents(Box::new(Transpose::new()));
+        let x_id = graph.generate_variable_from_f64_tensor(Tensor::full(1.0, vec![2]), "x");
+        let error = graph.try_forward(transpose_id, vec![x_id, x_id]).unwrap_err();
 
-        variable_table.get_variable_grad_contents_f64(x_id).unwrap();
+        assert_eq!(error, KdezeroError::InvalidInputCount { function: "Transpose".to_string(), expected: 1, actual: 2 });
     }
 }
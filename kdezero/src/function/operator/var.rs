@@ -0,0 +1,189 @@
+use std::any::Any;
+use ktensor::Tensor;
+use super::{Sum, Mean, Sub, Mul};
+use super::super::{FunctionContents, get_inputs_f64};
+use crate::error::KdezeroError;
+use crate::variable::VariableTable;
+use crate::graph::Graph;
+
+/// Variance along the given axes
+/// 
+/// The sum of the squared deviations is divided by `N - ddof`,
+/// where `N` is the number of the reduced elements.
+#[derive(Debug, Clone)]
+pub struct Var {
+    axis: Option<Vec<usize>>,
+    keepdims: bool,
+    ddof: usize,
+}
+
+impl Var {
+    /// Create a new Var instance.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `axis` - Axes to reduce. If None, all the axes are reduced
+    /// * `keepdims` - Keep the reduced axes with the size 1
+    /// * `ddof` - Delta degrees of freedom
+    pub fn new<T: AsRef<[usize]>>(axis: Option<T>, keepdims: bool, ddof: usize) -> Self {
+        Self { axis: axis.map(|x| x.as_ref().to_vec()), keepdims, ddof }
+    }
+
+    pub fn get_axis(&self) -> Option<&Vec<usize>> {
+        self.axis.as_ref()
+    }
+
+    pub fn get_keepdims(&self) -> bool {
+        self.keepdims
+    }
+
+    pub fn get_ddof(&self) -> usize {
+        self.ddof
+    }
+
+    fn input_check(inputs: &Vec<usize>) -> Result<(), KdezeroError> {
+        if inputs.len() != 1 {
+            return Err(KdezeroError::InvalidInputCount { function: "Var".to_string(), expected: 1, actual: inputs.len() });
+        }
+        Ok(())
+    }
+
+    fn output_check(outputs: &Vec<usize>) -> Result<(), KdezeroError> {
+        if outputs.len() != 1 {
+            return Err(KdezeroError::InvalidOutputCount { function: "Var".to_string(), expected: 1, actual: outputs.len() });
+        }
+        Ok(())
+    }
+}
+
+impl FunctionContents for Var {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "Var"
+    }
+
+    fn forward(&self, info: &crate::function::FunctionInfo, inputs: &Vec<usize>, variable_table: &mut VariableTable) -> Vec<usize> {
+        self.try_forward(info, inputs, variable_table).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    fn try_forward(&self, _info: &crate::function::FunctionInfo, inputs: &Vec<usize>, variable_table: &mut VariableTable) -> Result<Vec<usize>, KdezeroError> {
+        let [input] = get_inputs_f64(self.name(), inputs, variable_table)?;
+
+        let output = input.try_var(self.axis.as_deref().unwrap_or(&[]), self.keepdims, self.ddof)
+            .map_err(|e| KdezeroError::invalid_input(self.name(), 0, input.shape(), e))?;
+
+        let output_id = variable_table.generate_variable_from_f64_tensor(output, "");
+        Ok(vec![output_id])
+    }
+
+    fn get_backward(&self) -> fn(usize, &mut Graph) -> Result<Vec<usize>, KdezeroError> {
+        |function_id, graph| {
+            let function = graph.get_function(function_id).ok_or(KdezeroError::InvalidFunctionId { id: function_id })?;
+            let function_contents = function.get_function_contents::<Var>().ok_or(KdezeroError::InvalidFunctionId { id: function_id })?;
+            let axis = function_contents.get_axis().cloned();
+            let keepdims = function_contents.get_keepdims();
+            let ddof = function_contents.get_ddof();
+
+            let inputs = function.try_get_inputs()?;
+            let outputs = function.try_get_outputs()?;
+            Var::input_check(inputs)?;
+            Var::output_check(outputs)?;
+            let input_id = inputs[0];
+            let output_id = outputs[0];
+            let output_grad_id = graph.get_variable_grad_id(output_id).ok_or_else(|| KdezeroError::GradNotFound { function: "Var".to_string(), id: output_id })?;
+            let input_shape = graph.get_variable(input_id).ok_or(KdezeroError::InvalidVariableId { id: input_id })?.shape().clone();
+            let count = Mean::reduce_count(&input_shape, axis.as_ref()) - ddof;
+
+            let mean_id = graph.generate_function_from_function_contents(Box::new(Mean::new(axis.clone(), true)));
+            let mean_id = graph.try_forward(mean_id, vec![input_id])?[0];
+            let sub_id = graph.generate_function_from_function_contents(Box::new(Sub::new()));
+            let diff_id = graph.try_forward(sub_id, vec![input_id, mean_id])?[0];
+            let scale_id = graph.generate_variable_from_f64_tensor(Tensor::full(2.0 / count as f64, vec![]), "");
+            let mul_id = graph.generate_function_from_function_contents(Box::new(Mul::new()));
+            let diff_id = graph.try_forward(mul_id, vec![diff_id, scale_id])?[0];
+
+            let grad_id = Sum::expand_grad(graph, output_grad_id, input_id, axis.as_ref(), keepdims)?;
+            let mul_id = graph.generate_function_from_function_contents(Box::new(Mul::new()));
+            let grad_id = graph.try_forward(mul_id, vec![grad_id, diff_id])?[0];
+
+            graph.try_update_grad(input_id, grad_id)?;
+
+            Ok(vec![input_id])
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ktensor::TensorError;
+
+    #[test]
+    fn forward_normal() {
+        let mut graph = Graph::new();
+
+        let var_id = graph.generate_function_from_function_contents(Box::new(Var::new(Some([1]), false, 1)));
+        let x_id = graph.generate_variable_from_f64_tensor(
+            Tensor::new_from_num_vec(vec![1.0, 2.0, 3.0, 4.0, 6.0, 8.0], vec![2, 3]), "x");
+        let y_id = graph.forward(var_id, vec![x_id]);
+
+        let y = graph.get_variable_contents_f64(y_id[0]).unwrap();
+        assert_eq!(y, &Tensor::new_from_num_vec(vec![1.0, 4.0], vec![2]));
+    }
+
+    #[test]
+    fn backward_normal() {
+        let mut graph = Graph::new();
+
+        let var_id = graph.generate_function_from_function_contents(Box::new(Var::new(Some([1]), false, 0)));
+        let x_id = graph.generate_variable_from_f64_tensor(
+            Tensor::new_from_num_vec(vec![1.0, 2.0, 3.0, 4.0, 6.0, 8.0], vec![2, 3]), "x");
+        let y_id = graph.forward(var_id, vec![x_id]);
+
+        graph.backward(y_id, false, false);
+
+        let x_grad = graph.get_variable_grad_contents_f64(x_id).unwrap();
+        let expected = Tensor::new_from_num_vec(vec![-1.0, 0.0, 1.0, -2.0, 0.0, 2.0], vec![2, 3])
+            .scalar_mul((2.0 / 3.0).into());
+        assert!(x_grad.iter().zip(expected.iter()).all(|(x, y)| (x.data() - y.data()).abs() < 1e-12));
+    }
+
+    #[test]
+    fn backward_backward_normal() {
+        let mut graph = Graph::new();
+
+        let var_id = graph.generate_function_from_function_contents(Box::new(Var::new::<&[usize]>(None, false, 0)));
+        let x_id = graph.generate_variable_from_f64_tensor(
+            Tensor::new_from_num_vec(vec![1.0, 3.0], vec![2]), "x");
+        let y_id = graph.forward(var_id, vec![x_id]);
+
+        graph.backward(y_id, false, true);
+
+        let x_grad_id = graph.get_variable_grad_id(x_id).unwrap();
+        assert_eq!(graph.get_variable_contents_f64(x_grad_id).unwrap(),
+            &Tensor::new_from_num_vec(vec![-1.0, 1.0], vec![2]));
+        graph.clear_grad(x_id);
+
+        let sum_id = graph.generate_function_from_function_contents(Box::new(Sum::new::<&[usize]>(None, false)));
+        let s_id = graph.forward(sum_id, vec![x_grad_id]);
+        graph.backward(s_id, false, false);
+
+        let x_grad = graph.get_variable_grad_contents_f64(x_id).unwrap();
+        assert_eq!(x_grad, &Tensor::new_from_num_vec(vec![0.0, 0.0], vec![2]));
+    }
+
+    #[test]
+    fn try_forward_error_axis() {
+        let mut graph = Graph::new();
+
+        let var_id = graph.generate_function_from_function_contents(Box::new(Var::new(Some([3]), false, 0)));
+        let x_id = graph.generate_variable_from_f64_tensor(Tensor::arrange([2, 3]), "x");
+        let error = graph.try_forward(var_id, vec![x_id]).unwrap_err();
+
+        assert_eq!(error, KdezeroError::invalid_input("Var", 0, &[2, 3],
+            TensorError::InvalidAxis { axis: 3, ndim: 2 }));
+    }
+}
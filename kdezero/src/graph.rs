@@ -0,0 +1,799 @@
+mod function_generation_priority_queue;
+pub mod var;
+pub mod no_grad;
+
+pub use var::Var;
+pub use no_grad::{NoGradGuard, NoGradScope};
+
+use std::fs::File;
+use std::io::prelude::*;
+use std::process::Command;
+use std::collections::HashSet;
+use ktensor::Tensor;
+use function_generation_priority_queue::FunctionGenerationPriorityQueue;
+use crate::variable::{Variable, VariableTable};
+use crate::function::{Function, FunctionContents, FunctionTable, operator::Add};
+use crate::error::KdezeroError;
+
+/// Computation graph
+/// 
+/// Owns the variable table and the function table of one computation,
+/// so that every forward and backward pass works on a consistent pair.
+/// Variable and function ids are unique across all graphs,
+/// so an id taken from another graph is never found in this one.
+/// 
+/// # Fields
+/// 
+/// * `variable_table` - Variable table
+/// * `function_table` - Function table
+/// * `enable_backprop` - Whether forward records creators and generations
+pub struct Graph {
+    variable_table: VariableTable,
+    function_table: FunctionTable,
+    enable_backprop: bool,
+}
+
+impl Graph {
+    /// Create a new Graph instance.
+    pub fn new() -> Self {
+        Self { variable_table: VariableTable::new(), function_table: FunctionTable::new(), enable_backprop: true }
+    }
+
+    /// Get the variable table.
+    pub fn variable_table(&self) -> &VariableTable {
+        &self.variable_table
+    }
+
+    /// Get the mutable variable table.
+    pub fn variable_table_mut(&mut self) -> &mut VariableTable {
+        &mut self.variable_table
+    }
+
+    /// Get the function table.
+    pub fn function_table(&self) -> &FunctionTable {
+        &self.function_table
+    }
+
+    /// Whether forward records creators and generations.
+    pub fn is_enable_backprop(&self) -> bool {
+        self.enable_backprop
+    }
+
+    /// Set whether forward records creators and generations.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `enable_backprop` - New mode
+    /// 
+    /// # Returns
+    /// 
+    /// * Previous mode
+    pub fn set_enable_backprop(&mut self, enable_backprop: bool) -> bool {
+        std::mem::replace(&mut self.enable_backprop, enable_backprop)
+    }
+
+    /// Disable backpropagation until the returned guard is dropped.
+    /// 
+    /// Every forward through the guard, including composite functions,
+    /// does not record creators or generations.
+    pub fn no_grad(&mut self) -> NoGradGuard<'_> {
+        NoGradGuard::new(self)
+    }
+
+    /// Get the variable with the specified id.
+    pub fn get_variable(&self, id: usize) -> Option<&Variable> {
+        self.variable_table.get(id)
+    }
+
+    /// Get the function with the specified id.
+    pub fn get_function(&self, id: usize) -> Option<&Function> {
+        self.function_table.get(id)
+    }
+
+    /// Generate a new variable from the specified tensor and insert it into the graph.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `tensor` - Tensor
+    /// * `name` - Variable name
+    /// 
+    /// # Returns
+    /// 
+    /// * Variable ID
+    pub fn generate_variable_from_f64_tensor(&mut self, tensor: Tensor<f64>, name: &str) -> usize {
+        self.variable_table.generate_variable_from_f64_tensor(tensor, name)
+    }
+
+    /// Generate a new function from the specified contents and insert it into the graph.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `function_contents` - Function contents
+    /// 
+    /// # Returns
+    /// 
+    /// * Function ID
+    pub fn generate_function_from_function_contents(&mut self, function_contents: Box<dyn FunctionContents>) -> usize {
+        self.function_table.generate_function_from_function_contents(function_contents)
+    }
+
+    /// Get the variable f64 contents of the specified variable id.
+    pub fn get_variable_contents_f64(&self, id: usize) -> Option<&Tensor<f64>> {
+        self.variable_table.get_variable_contents_f64(id)
+    }
+
+    /// Set the variable f64 contents of the specified variable id.
+    pub fn set_variable_contents_f64(&mut self, id: usize, tensor: Tensor<f64>) {
+        self.variable_table.set_variable_contents_f64(id, tensor);
+    }
+
+    /// Get the variable grad id of the specified variable id.
+    pub fn get_variable_grad_id(&self, id: usize) -> Option<usize> {
+        self.variable_table.get_variable_grad_id(id)
+    }
+
+    /// Get the variable grad f64 contents of the specified variable id.
+    pub fn get_variable_grad_contents_f64(&self, id: usize) -> Option<&Tensor<f64>> {
+        self.variable_table.get_variable_grad_contents_f64(id)
+    }
+
+    /// Set the name of the specified variable id.
+    pub fn set_variable_name(&mut self, id: usize, name: &str) {
+        self.variable_table.set_variable_name(id, name);
+    }
+
+    /// Clear the grad of the specified variable id.
+    pub fn clear_grad(&mut self, id: usize) {
+        self.variable_table.clear_grad(id);
+    }
+
+    /// Clear the grads of the specified variable ids.
+    pub fn clear_grads(&mut self, ids: &Vec<usize>) {
+        self.variable_table.clear_grads(ids);
+    }
+
+    /// Forward the function with the specified id.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `id` - Function ID
+    /// * `inputs` - Input variable IDs
+    /// 
+    /// The creators and generations of the outputs are not recorded
+    /// while backpropagation is disabled by `no_grad`
+    /// or while backward runs without `create_graph`.
+    /// 
+    /// # Returns
+    /// 
+    /// * Output variable IDs
+    /// 
+    /// # Panics
+    /// 
+    /// * `Invalid function id` - If the function is not in this graph
+    /// * `Invalid variable id` - If an input variable is not in this graph
+    pub fn forward(&mut self, id: usize, inputs: Vec<usize>) -> Vec<usize> {
+        self.try_forward(id, inputs).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Fallible forward of the function with the specified id.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `id` - Function ID
+    /// * `inputs` - Input variable IDs
+    /// 
+    /// # Returns
+    /// 
+    /// * Output variable IDs
+    /// 
+    /// # Errors
+    /// 
+    /// * `InvalidFunctionId` - If the function is not in this graph
+    /// * `InvalidInputId` - If an input variable is not in this graph
+    /// * `InvalidInputCount` - If the number of inputs is wrong
+    /// * `InvalidInput` - If the function cannot be applied to the shape of an input
+    pub fn try_forward(&mut self, id: usize, inputs: Vec<usize>) -> Result<Vec<usize>, KdezeroError> {
+        let function = self.function_table.get_mut(id).ok_or(KdezeroError::InvalidFunctionId { id })?;
+        function.try_forward(inputs, &mut self.variable_table, !self.enable_backprop)
+    }
+
+    /// Backward the function with the specified id.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `id` - Function ID
+    /// 
+    /// # Returns
+    /// 
+    /// * Input variable IDs
+    /// 
+    /// # Errors
+    /// 
+    /// * `InvalidFunctionId` - If the function is not in this graph
+    /// * `BackwardFailed` - If the backward function of the function fails
+    fn backward_function(&mut self, id: usize) -> Result<Vec<usize>, KdezeroError> {
+        let function = self.function_table.get(id).ok_or(KdezeroError::InvalidFunctionId { id })?;
+        let name = function.get_name().to_string();
+        let backward = function.get_backward();
+        backward(id, self).map_err(|error| KdezeroError::BackwardFailed { function: name, error: Box::new(error) })
+    }
+
+    /// Update the grad id of the specified variable id.
+    /// 
+    /// If the variable already has a gradient, the new gradient is added to it.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `variable_id` - Variable ID
+    /// * `grad_id` - Grad ID
+    /// 
+    /// # Panics
+    /// 
+    /// * `Invalid variable id` - If the variable is not in this graph
+    /// * If the new gradient cannot be added to the gradient of the variable
+    pub fn update_grad(&mut self, variable_id: usize, grad_id: usize) {
+        self.try_update_grad(variable_id, grad_id).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Fallible update of the grad id of the specified variable id.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `variable_id` - Variable ID
+    /// * `grad_id` - Grad ID
+    /// 
+    /// # Errors
+    /// 
+    /// * `InvalidVariableId` - If the variable is not in this graph
+    /// * `InvalidInput` - If the new gradient cannot be added to the gradient of the variable
+    pub fn try_update_grad(&mut self, variable_id: usize, grad_id: usize) -> Result<(), KdezeroError> {
+        let variable = self.variable_table.get(variable_id).ok_or(KdezeroError::InvalidVariableId { id: variable_id })?;
+        let new_grad_id = match variable.get_grad_id() {
+            Some(id) => {
+                let add_id = self.generate_function_from_function_contents(Box::new(Add::new()));
+                self.try_forward(add_id, vec![id, grad_id])?[0]
+            },
+            None => grad_id,
+        };
+        self.variable_table.set_grad(variable_id, new_grad_id);
+        Ok(())
+    }
+
+    /// Add function to queue for backward propagation.
+    fn add_function_to_queue(&self, variable_id: usize, priority_queue: &mut FunctionGenerationPriorityQueue) {
+        let variable = self.variable_table.get(variable_id).expect("Invalid variable id");
+        let function_id = match variable.get_creator() {
+            Some(id) => id,
+            None => return,
+        };
+        let function_generation = self.function_table.get(function_id).expect("Invalid function id")
+            .get_generation();
+        priority_queue.push(function_id, function_generation);
+    }
+
+    /// Add functions to queue for backward propagation.
+    fn add_functions_to_queue(&self, variable_ids: &Vec<usize>, priority_queue: &mut FunctionGenerationPriorityQueue) {
+        for &variable_id in variable_ids {
+            self.add_function_to_queue(variable_id, priority_queue);
+        }
+    }
+
+    /// Backward propagation.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `ids` - Variable IDs
+    /// * `retain_grad` - Whether to retain the grad
+    /// * `create_graph` - Whether to record the backward computation,
+    ///   so that the gradients can be differentiated again.
+    ///   It is ignored while backpropagation is disabled by `no_grad`
+    /// 
+    /// # Panics
+    /// 
+    /// * `Invalid function id` - If the function id is invalid
+    /// * `Output not found` - If the output is not found
+    /// * `Invalid variable id` - If the variable id is invalid
+    pub fn backward(&mut self, ids: Vec<usize>, retain_grad: bool, create_graph: bool) {
+        self.try_backward(ids, retain_grad, create_graph).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Fallible backward propagation.
+    /// 
+    /// An error stops the propagation
+    /// and leaves the gradients of the functions that have already run.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `ids` - Variable IDs
+    /// * `retain_grad` - Whether to retain the grad
+    /// * `create_graph` - Whether to record the backward computation,
+    ///   unless backpropagation is disabled by `no_grad`
+    /// 
+    /// # Errors
+    /// 
+    /// * `InvalidVariableId` - If a variable is not in this graph
+    /// * `InvalidFunctionId` - If a creator function is not in this graph
+    /// * `OutputsNotFound` - If a creator function has not recorded its outputs
+    /// * `GradNotFound` - If no output of a creator function has a gradient
+    /// * `BackwardFailed` - If the backward function of a creator function fails,
+    ///   for example when its input has been released or its gradient has the wrong shape
+    pub fn try_backward(&mut self, ids: Vec<usize>, retain_grad: bool, create_graph: bool) -> Result<(), KdezeroError> {
+        for &id in ids.iter() {
+            let variable = self.variable_table.get(id).ok_or(KdezeroError::InvalidVariableId { id })?;
+            if let Some(function_id) = variable.get_creator() {
+                self.function_table.get(function_id).ok_or(KdezeroError::InvalidFunctionId { id: function_id })?;
+            }
+        }
+        let mut graph = NoGradGuard::with_enable_backprop(self, create_graph);
+        graph.variable_table.sets_grad_default(&ids);
+
+        let mut function_queue = FunctionGenerationPriorityQueue::new();
+        graph.add_functions_to_queue(&ids, &mut function_queue);
+
+        graph.run_backward(function_queue, retain_grad)
+    }
+
+    /// Run the backward functions in the queue and the functions they reach.
+    fn run_backward(&mut self, mut function_queue: FunctionGenerationPriorityQueue, retain_grad: bool) -> Result<(), KdezeroError> {
+        while !function_queue.is_empty() {
+            let function_id = function_queue.pop().unwrap();
+            let output_ids = self.check_backward_function(function_id)?;
+            let input_ids = self.backward_function(function_id)?;
+            for &input_id in input_ids.iter() {
+                let variable = self.variable_table.get(input_id)
+                    .ok_or(KdezeroError::InvalidVariableId { id: input_id })?;
+                if let Some(creator_id) = variable.get_creator() {
+                    self.function_table.get(creator_id).ok_or(KdezeroError::InvalidFunctionId { id: creator_id })?;
+                }
+            }
+            self.add_functions_to_queue(&input_ids, &mut function_queue);
+
+            if !retain_grad {
+                self.variable_table.clear_grads(&output_ids);
+            }
+        }
+        Ok(())
+    }
+
+    /// Check that the function can run its backward and get its outputs.
+    /// 
+    /// An output without a gradient gets zeros as its gradient
+    /// if another output of the function has a gradient,
+    /// as when only some outputs of a function with several outputs are used.
+    fn check_backward_function(&mut self, id: usize) -> Result<Vec<usize>, KdezeroError> {
+        let function = self.function_table.get(id).ok_or(KdezeroError::InvalidFunctionId { id })?;
+        let name = || function.get_name().to_string();
+        let output_ids = function.get_outputs()
+            .ok_or_else(|| KdezeroError::OutputsNotFound { function: name() })?.clone();
+        let mut missing = Vec::new();
+        for &output_id in output_ids.iter() {
+            let variable = self.variable_table.get(output_id)
+                .ok_or(KdezeroError::InvalidVariableId { id: output_id })?;
+            if variable.get_grad_id().is_none() {
+                missing.push((output_id, variable.shape().clone()));
+            }
+        }
+        if missing.len() == output_ids.len() {
+            if let Some((output_id, _)) = missing.first() {
+                return Err(KdezeroError::GradNotFound { function: name(), id: *output_id });
+            }
+        }
+        for (output_id, shape) in missing {
+            self.variable_table.set_grad_from_f64_tensor(output_id, Tensor::full(0.0, shape));
+        }
+        Ok(output_ids)
+    }
+
+    /// Release every variable and function that is not reachable from the root variables.
+    /// 
+    /// A variable reaches its gradient and its creator,
+    /// and a function reaches its inputs and outputs.
+    /// Ids of released variables and functions become invalid.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `root_ids` - Variable IDs to keep
+    /// 
+    /// # Panics
+    /// 
+    /// * `Invalid variable id` - If a root variable is not in this graph
+    /// * `Invalid function id` - If a creator function is not in this graph
+    pub fn release_unreachable(&mut self, root_ids: &Vec<usize>) {
+        let mut variable_ids = HashSet::new();
+        let mut function_ids = HashSet::new();
+        let mut stack = root_ids.clone();
+
+        while let Some(id) = stack.pop() {
+            if !variable_ids.insert(id) {
+                continue;
+            }
+            let variable = self.variable_table.get(id).expect("Invalid variable id");
+            if let Some(grad_id) = variable.get_grad_id() {
+                stack.push(grad_id);
+            }
+            let function_id = match variable.get_creator() {
+                Some(function_id) => function_id,
+                None => continue,
+            };
+            if !function_ids.insert(function_id) {
+                continue;
+            }
+            let function = self.function_table.get(function_id).expect("Invalid function id");
+            if let Some(inputs) = function.get_inputs() {
+                stack.extend(inputs);
+            }
+            if let Some(outputs) = function.get_outputs() {
+                stack.extend(outputs);
+            }
+        }
+
+        self.variable_table.retain(|id| variable_ids.contains(&id));
+        self.function_table.retain(|id| function_ids.contains(&id));
+    }
+
+    /// Add a function to the function queue for dot graph
+    fn add_function_for_dot_graph(&self, function_ids: &mut Vec<usize>, function_ids_set: &mut HashSet<usize>, id: usize) {
+        let y = self.variable_table.get(id).expect("Variable not found");
+        let f_id = y.get_creator();
+        let f_id = match f_id {
+            Some(f_id) => f_id,
+            None => return,
+        };
+        if function_ids_set.contains(&f_id) {
+            return;
+        }
+        function_ids.push(f_id);
+        function_ids_set.insert(f_id);
+    }
+
+    /// Add a variable to the variable queue for dot graph
+    fn add_variable_for_dot_graph(&self, variable_ids_set: &mut HashSet<usize>, text: &mut String, id: usize) {
+        if variable_ids_set.contains(&id) {
+            return;
+        }
+        variable_ids_set.insert(id);
+        let var = self.variable_table.get(id).expect("Variable not found");
+        text.push_str(&var.to_dot_string());
+    }
+
+    /// Get a dot graph
+    /// 
+    /// # Arguments
+    /// 
+    /// * `ids` - ID
+    /// 
+    /// # Returns
+    /// 
+    /// * `String` - Dot graph
+    /// 
+    /// # Panics
+    /// 
+    /// * `Function not found` - If the function is not found
+    pub fn get_dot_graph(&self, ids: Vec<usize>) -> String {
+        let mut text = String::new();
+        let mut function_ids = vec![];
+        let mut function_ids_set = HashSet::new();
+        let mut variable_ids_set = HashSet::new();
+
+        for id in &ids {
+            self.add_variable_for_dot_graph(&mut variable_ids_set, &mut text, *id);
+            self.add_function_for_dot_graph(&mut function_ids, &mut function_ids_set, *id);
+        }
+
+        while !function_ids.is_empty() {
+            let f_id = function_ids.pop().unwrap();
+            let f = self.function_table.get(f_id).expect("Function not found");
+            text.push_str(&f.to_dot_string());
+            let input_ids = f.get_inputs();
+            if let Some(input_ids) = input_ids {
+                for id in input_ids {
+                    self.add_variable_for_dot_graph(&mut variable_ids_set, &mut text, *id);
+                    self.add_function_for_dot_graph(&mut function_ids, &mut function_ids_set, *id);
+                }
+            }
+        }
+        format!("digraph g {{\n{}}}", text)
+    }
+
+    /// Plot a dot graph
+    /// 
+    /// # Arguments
+    /// 
+    /// * `ids` - ID
+    /// * `path` - path without extension
+    /// * `to_png` - If true, convert to png
+    /// 
+    /// # Panics
+    /// 
+    /// * `Failed to create file` - If failed to create file
+    /// * `Failed to write file` - If failed to write file
+    /// * `Failed to execute dot` - If failed to execute dot command
+    pub fn plot_dot_graph(&self, ids: Vec<usize>, path: &str, to_png: bool) {
+        let dot_path = format!("{}.dot", path);
+        {
+            let text = self.get_dot_graph(ids);
+            let mut file = File::create(&dot_path).expect("Failed to create file");
+            file.write_all(text.as_bytes()).expect("Failed to write file");
+        }
+        if to_png {
+            Command::new("dot")
+                .args([dot_path.as_str(), "-T", "png", "-o", format!("{}.png", path).as_str()])
+                .status()
+                .expect("Failed to execute dot");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::function::operator::{Mul, Exp};
+    use crate::variable::VariableContents;
+
+    #[test]
+    fn forward_normal() {
+        let mut graph = Graph::new();
+        let x_id = graph.generate_variable_from_f64_tensor(
+            Tensor::new_from_num_vec(vec![1.0, 2.0, 3.0], vec![3]), "x");
+        let mul_id = graph.generate_function_from_function_contents(Box::new(Mul::new()));
+        let y_id = graph.forward(mul_id, vec![x_id, x_id])[0];
+
+        let y = graph.get_variable_contents_f64(y_id).unwrap();
+        assert_eq!(y, &Tensor::new_from_num_vec(vec![1.0, 4.0, 9.0], vec![3]));
+        assert_eq!(graph.get_variable(y_id).unwrap().get_creator(), Some(mul_id));
+    }
+
+    #[test]
+    #[should_panic]
+    fn forward_error_other_graph_variable() {
+        let mut graph0 = Graph::new();
+        let mut graph1 = Graph::new();
+        let x_id = graph0.generate_variable_from_f64_tensor(
+            Tensor::new_from_num_vec(vec![1.0], vec![]), "x");
+        let _ = graph1.generate_variable_from_f64_tensor(
+            Tensor::new_from_num_vec(vec![1.0], vec![]), "x");
+        let mul_id = graph1.generate_function_from_function_contents(Box::new(Mul::new()));
+        graph1.forward(mul_id, vec![x_id, x_id]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn forward_error_other_graph_function() {
+        let mut graph0 = Graph::new();
+        let mut graph1 = Graph::new();
+        let mul_id = graph0.generate_function_from_function_contents(Box::new(Mul::new()));
+        let _ = graph1.generate_function_from_function_contents(Box::new(Mul::new()));
+        let x_id = graph1.generate_variable_from_f64_tensor(
+            Tensor::new_from_num_vec(vec![1.0], vec![]), "x");
+        graph1.forward(mul_id, vec![x_id, x_id]);
+    }
+
+    #[test]
+    fn backward_normal() {
+        let mut graph = Graph::new();
+        let x_id = graph.generate_variable_from_f64_tensor(
+            Tensor::new_from_num_vec(vec![1.0, 2.0, 3.0], vec![3]), "x");
+        let mul_id = graph.generate_function_from_function_contents(Box::new(Mul::new()));
+        let y_id = graph.forward(mul_id, vec![x_id, x_id])[0];
+
+        graph.backward(vec![y_id], false, false);
+
+        let grad = graph.get_variable_grad_contents_f64(x_id).unwrap();
+        assert_eq!(grad, &Tensor::new_from_num_vec(vec![2.0, 4.0, 6.0], vec![3]));
+        assert_eq!(graph.get_variable_grad_id(y_id), None);
+    }
+
+    #[test]
+    fn backward_create_graph() {
+        let mut graph = Graph::new();
+        let x_id = graph.generate_variable_from_f64_tensor(
+            Tensor::new_from_num_vec(vec![1.0, 2.0, 3.0], vec![3]), "x");
+        let mul_id = graph.generate_function_from_function_contents(Box::new(Mul::new()));
+        let y_id = graph.forward(mul_id, vec![x_id, x_id])[0];
+
+        graph.backward(vec![y_id], false, false);
+        let grad_id = graph.get_variable_grad_id(x_id).unwrap();
+        assert!(graph.get_variable(grad_id).unwrap().get_creator().is_none());
+
+        graph.clear_grad(x_id);
+        graph.backward(vec![y_id], false, true);
+        let grad_id = graph.get_variable_grad_id(x_id).unwrap();
+        assert!(graph.get_variable(grad_id).unwrap().get_creator().is_some());
+
+        graph.clear_grad(x_id);
+        graph.backward(vec![grad_id], false, false);
+        let grad = graph.get_variable_grad_contents_f64(x_id).unwrap();
+        assert_eq!(grad, &Tensor::new_from_num_vec(vec![2.0, 2.0, 2.0], vec![3]));
+    }
+
+    #[test]
+    fn backward_create_graph_no_grad() {
+        let mut graph = Graph::new();
+        let x_id = graph.generate_variable_from_f64_tensor(
+            Tensor::new_from_num_vec(vec![1.0, 2.0, 3.0], vec![3]), "x");
+        let mul_id = graph.generate_function_from_function_contents(Box::new(Mul::new()));
+        let y_id = graph.forward(mul_id, vec![x_id, x_id])[0];
+
+        {
+            let mut graph = graph.no_grad();
+            graph.backward(vec![y_id], false, true);
+            assert!(!graph.is_enable_backprop());
+        }
+        let grad_id = graph.get_variable_grad_id(x_id).unwrap();
+        assert!(graph.get_variable(grad_id).unwrap().get_creator().is_none());
+        assert!(graph.is_enable_backprop());
+    }
+
+    #[test]
+    fn release_unreachable_normal() {
+        let mut graph = Graph::new();
+        let x_id = graph.generate_variable_from_f64_tensor(
+            Tensor::new_from_num_vec(vec![1.0, 2.0, 3.0], vec![3]), "x");
+        let mul_id = graph.generate_function_from_function_contents(Box::new(Mul::new()));
+        let y_id = graph.forward(mul_id, vec![x_id, x_id])[0];
+        let mul_id = graph.generate_function_from_function_contents(Box::new(Mul::new()));
+        let z_id = graph.forward(mul_id, vec![y_id, y_id])[0];
+
+        graph.release_unreachable(&vec![y_id]);
+
+        assert_eq!(graph.variable_table().len(), 2);
+        assert_eq!(graph.function_table().len(), 1);
+        assert!(graph.get_variable(x_id).is_some());
+        assert!(graph.get_variable(z_id).is_none());
+
+        graph.backward(vec![y_id], false, false);
+        let grad = graph.get_variable_grad_contents_f64(x_id).unwrap();
+        assert_eq!(grad, &Tensor::new_from_num_vec(vec![2.0, 4.0, 6.0], vec![3]));
+    }
+
+    #[test]
+    fn release_unreachable_keep_grad() {
+        let mut graph = Graph::new();
+        let x_id = graph.generate_variable_from_f64_tensor(
+            Tensor::new_from_num_vec(vec![3.0], vec![]), "x");
+        let mul_id = graph.generate_function_from_function_contents(Box::new(Mul::new()));
+        let y_id = graph.forward(mul_id, vec![x_id, x_id])[0];
+        graph.backward(vec![y_id], false, true);
+        let gx_id = graph.get_variable_grad_id(x_id).unwrap();
+
+        graph.release_unreachable(&vec![gx_id]);
+
+        assert!(graph.get_variable(y_id).is_none());
+        graph.clear_grad(x_id);
+        graph.backward(vec![gx_id], false, false);
+        let grad = graph.get_variable_grad_contents_f64(x_id).unwrap();
+        assert_eq!(grad, &Tensor::new_from_num_vec(vec![2.0], vec![]));
+    }
+
+    #[test]
+    fn release_unreachable_training_loop() {
+        let mut graph = Graph::new();
+        let w_id = graph.generate_variable_from_f64_tensor(
+            Tensor::new_from_num_vec(vec![2.0], vec![]), "w");
+
+        for _ in 0..10 {
+            let x_id = graph.generate_variable_from_f64_tensor(
+                Tensor::new_from_num_vec(vec![1.0], vec![]), "x");
+            let mul_id = graph.generate_function_from_function_contents(Box::new(Mul::new()));
+            let y_id = graph.forward(mul_id, vec![w_id, x_id])[0];
+            let mul_id = graph.generate_function_from_function_contents(Box::new(Mul::new()));
+            let loss_id = graph.forward(mul_id, vec![y_id, y_id])[0];
+            graph.backward(vec![loss_id], false, false);
+
+            let w = graph.get_variable_contents_f64(w_id).unwrap();
+            let w_grad = graph.get_variable_grad_contents_f64(w_id).unwrap();
+            let w = w - &w_grad.scalar_mul(0.1.into());
+            *graph.variable_table_mut().get_mut(w_id).unwrap().get_mut_data() = VariableContents::F64(Box::new(w));
+            graph.clear_grad(w_id);
+
+            graph.release_unreachable(&vec![w_id]);
+            assert_eq!(graph.variable_table().len(), 1);
+            assert!(graph.function_table().is_empty());
+        }
+
+        let w = graph.get_variable_contents_f64(w_id).unwrap();
+        assert!(*w.at(&[]).data() < 2.0);
+    }
+
+    #[test]
+    fn get_dot_graph_normal() {
+        let mut graph = Graph::new();
+        let x_id = graph.generate_variable_from_f64_tensor(
+            Tensor::new_from_num_vec(vec![1.0], vec![]), "x");
+        let mul_id = graph.generate_function_from_function_contents(Box::new(Mul::new()));
+        let y_id = graph.forward(mul_id, vec![x_id, x_id])[0];
+
+        let text = graph.get_dot_graph(vec![y_id]);
+        assert!(text.starts_with("digraph g {\n"));
+        assert!(text.contains(&format!("var_{} -> func_{};", x_id, mul_id)));
+        assert!(text.contains(&format!("func_{} -> var_{};", mul_id, y_id)));
+    }
+
+    #[test]
+    fn try_forward_error() {
+        let mut graph0 = Graph::new();
+        let mut graph1 = Graph::new();
+        let x_id = graph0.generate_variable_from_f64_tensor(
+            Tensor::new_from_num_vec(vec![1.0, 2.0, 3.0], vec![3]), "x");
+        let y_id = graph1.generate_variable_from_f64_tensor(
+            Tensor::new_from_num_vec(vec![1.0, 2.0], vec![2]), "y");
+        let z_id = graph1.generate_variable_from_f64_tensor(
+            Tensor::new_from_num_vec(vec![1.0, 2.0, 3.0], vec![3]), "z");
+        let mul_id = graph1.generate_function_from_function_contents(Box::new(Mul::new()));
+        let other_mul_id = graph0.generate_function_from_function_contents(Box::new(Mul::new()));
+
+        assert_eq!(graph1.try_forward(other_mul_id, vec![y_id, y_id]).unwrap_err(),
+            KdezeroError::InvalidFunctionId { id: other_mul_id });
+        assert_eq!(graph1.try_forward(mul_id, vec![y_id, x_id]).unwrap_err(),
+            KdezeroError::InvalidInputId { function: "Mul".to_string(), index: 1, id: x_id });
+        assert!(matches!(graph1.try_forward(mul_id, vec![z_id, y_id]).unwrap_err(),
+            KdezeroError::InvalidInput { index: 1, ref shape, .. } if shape == &vec![2]));
+        assert!(graph1.try_forward(mul_id, vec![z_id, z_id]).is_ok());
+    }
+
+    #[test]
+    fn try_backward_normal() {
+        let mut graph = Graph::new();
+        let x_id = graph.generate_variable_from_f64_tensor(
+            Tensor::new_from_num_vec(vec![1.0, 2.0, 3.0], vec![3]), "x");
+        let mul_id = graph.generate_function_from_function_contents(Box::new(Mul::new()));
+        let y_id = graph.forward(mul_id, vec![x_id, x_id])[0];
+
+        graph.try_backward(vec![y_id], false, false).unwrap();
+
+        let grad = graph.get_variable_grad_contents_f64(x_id).unwrap();
+        assert_eq!(grad, &Tensor::new_from_num_vec(vec![2.0, 4.0, 6.0], vec![3]));
+        assert!(graph.is_enable_backprop());
+    }
+
+    #[test]
+    fn try_backward_error_other_graph_variable() {
+        let mut graph0 = Graph::new();
+        let mut graph1 = Graph::new();
+        let x_id = graph0.generate_variable_from_f64_tensor(
+            Tensor::new_from_num_vec(vec![1.0], vec![]), "x");
+
+        assert_eq!(graph1.try_backward(vec![x_id], false, false).unwrap_err(),
+            KdezeroError::InvalidVariableId { id: x_id });
+        assert!(graph1.is_enable_backprop());
+    }
+
+    #[test]
+    fn try_backward_error_grad_shape() {
+        let mut graph = Graph::new();
+        let x_id = graph.generate_variable_from_f64_tensor(
+            Tensor::new_from_num_vec(vec![1.0, 2.0, 3.0], vec![3]), "x");
+        let mul_id = graph.generate_function_from_function_contents(Box::new(Mul::new()));
+        let y_id = graph.forward(mul_id, vec![x_id, x_id])[0];
+        graph.variable_table_mut().set_grad_from_f64_tensor(y_id, Tensor::full(1.0, vec![2]));
+
+        let error = graph.try_backward(vec![y_id], false, false).unwrap_err();
+
+        match error {
+            KdezeroError::BackwardFailed { function, error } => {
+                assert_eq!(function, "Mul");
+                assert!(matches!(*error, KdezeroError::InvalidInput { index: 1, ref shape, .. } if shape == &vec![3]));
+            },
+            _ => panic!("Unexpected error: {}", error),
+        }
+        assert!(graph.get_variable_grad_id(x_id).is_none());
+        assert!(graph.is_enable_backprop());
+    }
+
+    #[test]
+    fn try_backward_error_released_input() {
+        let mut graph = Graph::new();
+        let x_id = graph.generate_variable_from_f64_tensor(
+            Tensor::new_from_num_vec(vec![1.0], vec![]), "x");
+        let exp_id = graph.generate_function_from_function_contents(Box::new(Exp::new()));
+        let y_id = graph.forward(exp_id, vec![x_id])[0];
+        let exp_id = graph.generate_function_from_function_contents(Box::new(Exp::new()));
+        let z_id = graph.forward(exp_id, vec![y_id])[0];
+        graph.variable_table_mut().retain(|id| id != y_id);
+
+        assert_eq!(graph.try_backward(vec![z_id], false, true).unwrap_err(),
+            KdezeroError::BackwardFailed { function: "Exp".to_string(),
+                error: Box::new(KdezeroError::InvalidInputId { function: "Exp".to_string(), index: 0, id: y_id }) });
+        assert!(graph.is_enable_backprop());
+    }
+}
@@ -0,0 +1,161 @@
+use std::rc::Rc;
+use std::cell::RefCell;
+use std::ops::{Deref, DerefMut};
+use super::Graph;
+
+/// Guard that disables backpropagation of a borrowed Graph
+/// 
+/// While the guard is alive, forward does not record creators or generations.
+/// The previous mode is restored when the guard is dropped.
+/// 
+/// # Fields
+/// 
+/// * `graph` - Graph
+/// * `enable_backprop` - Mode before the guard was created
+pub struct NoGradGuard<'a> {
+    graph: &'a mut Graph,
+    enable_backprop: bool,
+}
+
+impl<'a> NoGradGuard<'a> {
+    /// Create a new NoGradGuard instance.
+    pub(crate) fn new(graph: &'a mut Graph) -> Self {
+        Self::with_enable_backprop(graph, false)
+    }
+
+    /// Create a new NoGradGuard instance that keeps backpropagation if it is requested and enabled.
+    /// 
+    /// The mode is never enabled by the guard, so an active no_grad scope is respected.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `graph` - Graph
+    /// * `enable_backprop` - Whether to keep backpropagation
+    pub(crate) fn with_enable_backprop(graph: &'a mut Graph, enable_backprop: bool) -> Self {
+        let mode = enable_backprop && graph.is_enable_backprop();
+        let enable_backprop = graph.set_enable_backprop(mode);
+        Self { graph, enable_backprop }
+    }
+}
+
+impl Deref for NoGradGuard<'_> {
+    type Target = Graph;
+
+    fn deref(&self) -> &Graph {
+        self.graph
+    }
+}
+
+impl DerefMut for NoGradGuard<'_> {
+    fn deref_mut(&mut self) -> &mut Graph {
+        self.graph
+    }
+}
+
+impl Drop for NoGradGuard<'_> {
+    fn drop(&mut self) {
+        self.graph.set_enable_backprop(self.enable_backprop);
+    }
+}
+
+/// Guard that disables backpropagation of a shared Graph
+/// 
+/// Unlike NoGradGuard, the graph is not borrowed while the guard is alive,
+/// so Var handles of the graph can be used in the scope.
+/// 
+/// # Fields
+/// 
+/// * `graph` - Shared graph
+/// * `enable_backprop` - Mode before the guard was created
+pub struct NoGradScope {
+    graph: Rc<RefCell<Graph>>,
+    enable_backprop: bool,
+}
+
+impl NoGradScope {
+    /// Create a new NoGradScope instance.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `graph` - Shared graph
+    pub fn new(graph: &Rc<RefCell<Graph>>) -> Self {
+        let enable_backprop = graph.borrow_mut().set_enable_backprop(false);
+        Self { graph: graph.clone(), enable_backprop }
+    }
+}
+
+impl Drop for NoGradScope {
+    fn drop(&mut self) {
+        self.graph.borrow_mut().set_enable_backprop(self.enable_backprop);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ktensor::Tensor;
+    use crate::function::operator::Square;
+
+    #[test]
+    fn no_grad_guard_normal() {
+        let mut graph = Graph::new();
+        let x_id = graph.generate_variable_from_f64_tensor(
+            Tensor::new_from_num_vec(vec![2.0], vec![]), "x");
+        let square_id = graph.generate_function_from_function_contents(Box::new(Square::new()));
+
+        let y_id = {
+            let mut graph = graph.no_grad();
+            assert!(!graph.is_enable_backprop());
+            graph.forward(square_id, vec![x_id])[0]
+        };
+
+        assert!(graph.is_enable_backprop());
+        assert_eq!(graph.get_variable_contents_f64(y_id).unwrap(), &Tensor::new_from_num_vec(vec![4.0], vec![]));
+        assert!(graph.get_variable(y_id).unwrap().get_creator().is_none());
+    }
+
+    #[test]
+    fn no_grad_guard_nested() {
+        let mut graph = Graph::new();
+        {
+            let mut graph = graph.no_grad();
+            {
+                let graph = graph.no_grad();
+                assert!(!graph.is_enable_backprop());
+            }
+            assert!(!graph.is_enable_backprop());
+        }
+        assert!(graph.is_enable_backprop());
+    }
+
+    #[test]
+    fn with_enable_backprop_normal() {
+        let mut graph = Graph::new();
+        {
+            let mut graph = NoGradGuard::with_enable_backprop(&mut graph, true);
+            assert!(graph.is_enable_backprop());
+            {
+                let mut graph = graph.no_grad();
+                let graph = NoGradGuard::with_enable_backprop(&mut graph, true);
+                assert!(!graph.is_enable_backprop());
+            }
+            assert!(graph.is_enable_backprop());
+        }
+        assert!(graph.is_enable_backprop());
+    }
+
+    #[test]
+    fn no_grad_scope_normal() {
+        let graph = Rc::new(RefCell::new(Graph::new()));
+        let x = super::super::Var::new(&graph, Tensor::new_from_num_vec(vec![2.0], vec![]), "x");
+
+        let y = {
+            let _scope = NoGradScope::new(&graph);
+            x.square()
+        };
+        let z = x.square();
+
+        assert!(graph.borrow().get_variable(y.id()).unwrap().get_creator().is_none());
+        assert!(graph.borrow().get_variable(z.id()).unwrap().get_creator().is_some());
+    }
+}
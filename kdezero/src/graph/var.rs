@@ -0,0 +1,723 @@
+use std::rc::Rc;
+use std::cell::RefCell;
+use ktensor::{Tensor, SliceIndex};
+use super::Graph;
+use crate::function::{FunctionContents, operator::{
+    Add, Sub, Mul, Div, Neg, Square, Exp, Sin, Cos, Tanh, Pow,
+    Log, Sqrt, Abs, Clip, Maximum, Minimum,
+    Sigmoid, ReLU, LeakyReLU, GELU, SiLU, ELU, Softplus,
+    Softmax, LogSoftmax, SoftmaxCrossEntropy, BinaryCrossEntropyWithLogits,
+    MatMul, Reshape, Transpose, Permute, Sum, BroadcastTo, SumTo,
+    Mean, Max, Min, Var as Variance,
+    GetItem, IndexSelect, Gather, Concat, Stack, Split,
+}};
+
+/// Handle of a variable in a shared Graph
+/// 
+/// # Fields
+/// 
+/// * `id` - Variable ID
+/// * `graph` - Graph that owns the variable
+#[derive(Clone)]
+pub struct Var {
+    id: usize,
+    graph: Rc<RefCell<Graph>>,
+}
+
+impl Var {
+    /// Create a new variable in the graph and return its handle.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `graph` - Graph that owns the variable
+    /// * `tensor` - Tensor
+    /// * `name` - Variable name
+    pub fn new(graph: &Rc<RefCell<Graph>>, tensor: Tensor<f64>, name: &str) -> Self {
+        let id = graph.borrow_mut().generate_variable_from_f64_tensor(tensor, name);
+        Self { id, graph: graph.clone() }
+    }
+
+    /// Create a handle of an existing variable.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `graph` - Graph that owns the variable
+    /// * `id` - Variable ID
+    /// 
+    /// # Panics
+    /// 
+    /// * `Invalid variable id` - If the variable is not in the graph
+    pub fn from_id(graph: &Rc<RefCell<Graph>>, id: usize) -> Self {
+        graph.borrow().get_variable(id).expect("Invalid variable id");
+        Self { id, graph: graph.clone() }
+    }
+
+    /// Get the variable ID.
+    pub fn id(&self) -> usize {
+        self.id
+    }
+
+    /// Get the graph that owns the variable.
+    pub fn graph(&self) -> &Rc<RefCell<Graph>> {
+        &self.graph
+    }
+
+    /// Get a copy of the variable data.
+    pub fn data(&self) -> Tensor<f64> {
+        self.graph.borrow()
+            .get_variable_contents_f64(self.id).expect("Invalid variable id")
+            .clone()
+    }
+
+    /// Replace the variable data.
+    pub fn set_data(&self, tensor: Tensor<f64>) {
+        self.graph.borrow_mut().set_variable_contents_f64(self.id, tensor);
+    }
+
+    /// Get the shape of the variable.
+    pub fn shape(&self) -> Vec<usize> {
+        self.graph.borrow()
+            .get_variable(self.id).expect("Invalid variable id")
+            .shape().clone()
+    }
+
+    /// Set the name of the variable.
+    pub fn set_name(&self, name: &str) {
+        self.graph.borrow_mut().set_variable_name(self.id, name);
+    }
+
+    /// Get the gradient of the variable.
+    pub fn grad(&self) -> Option<Var> {
+        self.graph.borrow()
+            .get_variable_grad_id(self.id)
+            .map(|id| Self { id, graph: self.graph.clone() })
+    }
+
+    /// Clear the gradient of the variable.
+    pub fn clear_grad(&self) {
+        self.graph.borrow_mut().clear_grad(self.id);
+    }
+
+    /// Backward propagation from this variable.
+    pub fn backward(&self) {
+        self.backward_with(false, false);
+    }
+
+    /// Backward propagation from this variable with options.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `retain_grad` - Keep the gradients of intermediate variables
+    /// * `create_graph` - Record the backward computation so that the gradients can be differentiated again
+    pub fn backward_with(&self, retain_grad: bool, create_graph: bool) {
+        self.graph.borrow_mut().backward(vec![self.id], retain_grad, create_graph);
+    }
+
+    /// Forward a new function with the specified inputs and return the first output.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `function_contents` - Function contents
+    /// * `inputs` - Input variables
+    /// 
+    /// # Panics
+    /// 
+    /// * `Variables belong to different graphs` - If an input is not in this graph
+    pub fn apply(&self, function_contents: Box<dyn FunctionContents>, inputs: &[&Var]) -> Var {
+        self.apply_all(function_contents, inputs).swap_remove(0)
+    }
+
+    /// Forward a new function with the specified inputs and return all the outputs.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `function_contents` - Function contents
+    /// * `inputs` - Input variables
+    /// 
+    /// # Panics
+    /// 
+    /// * `Variables belong to different graphs` - If an input is not in this graph
+    pub fn apply_all(&self, function_contents: Box<dyn FunctionContents>, inputs: &[&Var]) -> Vec<Var> {
+        for input in inputs {
+            assert!(Rc::ptr_eq(&self.graph, &input.graph), "Variables belong to different graphs");
+        }
+        let input_ids = inputs.iter().map(|input| input.id).collect();
+        let mut graph = self.graph.borrow_mut();
+        let function_id = graph.generate_function_from_function_contents(function_contents);
+        graph.forward(function_id, input_ids).into_iter()
+            .map(|id| Self { id, graph: self.graph.clone() })
+            .collect()
+    }
+
+    /// Square of the variable.
+    pub fn square(&self) -> Var {
+        self.apply(Box::new(Square::new()), &[self])
+    }
+
+    /// Exponential of the variable.
+    pub fn exp(&self) -> Var {
+        self.apply(Box::new(Exp::new()), &[self])
+    }
+
+    /// Sin of the variable.
+    pub fn sin(&self) -> Var {
+        self.apply(Box::new(Sin::new()), &[self])
+    }
+
+    /// Cos of the variable.
+    pub fn cos(&self) -> Var {
+        self.apply(Box::new(Cos::new()), &[self])
+    }
+
+    /// Tanh of the variable.
+    pub fn tanh(&self) -> Var {
+        self.apply(Box::new(Tanh::new()), &[self])
+    }
+
+    /// Natural logarithm of the variable.
+    pub fn log(&self) -> Var {
+        self.apply(Box::new(Log::new()), &[self])
+    }
+
+    /// Square root of the variable.
+    pub fn sqrt(&self) -> Var {
+        self.apply(Box::new(Sqrt::new()), &[self])
+    }
+
+    /// Absolute value of the variable.
+    pub fn abs(&self) -> Var {
+        self.apply(Box::new(Abs::new()), &[self])
+    }
+
+    /// Variable limited to the range from min to max.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `min` - Lower bound
+    /// * `max` - Upper bound
+    pub fn clip(&self, min: f64, max: f64) -> Var {
+        self.apply(Box::new(Clip::new(min, max)), &[self])
+    }
+
+    /// Elementwise maximum of this variable and other.
+    pub fn maximum(&self, other: &Var) -> Var {
+        self.apply(Box::new(Maximum::new()), &[self, other])
+    }
+
+    /// Elementwise minimum of this variable and other.
+    pub fn minimum(&self, other: &Var) -> Var {
+        self.apply(Box::new(Minimum::new()), &[self, other])
+    }
+
+    /// Logistic sigmoid of the variable.
+    pub fn sigmoid(&self) -> Var {
+        self.apply(Box::new(Sigmoid::new()), &[self])
+    }
+
+    /// Rectified linear unit of the variable.
+    pub fn relu(&self) -> Var {
+        self.apply(Box::new(ReLU::new()), &[self])
+    }
+
+    /// Leaky rectified linear unit of the variable.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `slope` - Slope for the non-positive elements
+    pub fn leaky_relu(&self, slope: f64) -> Var {
+        self.apply(Box::new(LeakyReLU::new(slope)), &[self])
+    }
+
+    /// Gaussian error linear unit of the variable.
+    pub fn gelu(&self) -> Var {
+        self.apply(Box::new(GELU::new()), &[self])
+    }
+
+    /// Sigmoid linear unit of the variable.
+    pub fn silu(&self) -> Var {
+        self.apply(Box::new(SiLU::new()), &[self])
+    }
+
+    /// Exponential linear unit of the variable.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `alpha` - Scale of the negative part
+    pub fn elu(&self, alpha: f64) -> Var {
+        self.apply(Box::new(ELU::new(alpha)), &[self])
+    }
+
+    /// Softplus of the variable.
+    pub fn softplus(&self) -> Var {
+        self.apply(Box::new(Softplus::new()), &[self])
+    }
+
+    /// Softmax of the variable along the axis.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `axis` - Axis to normalize
+    pub fn softmax(&self, axis: usize) -> Var {
+        self.apply(Box::new(Softmax::new(axis)), &[self])
+    }
+
+    /// Logarithm of the softmax of the variable along the axis.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `axis` - Axis to normalize
+    pub fn log_softmax(&self, axis: usize) -> Var {
+        self.apply(Box::new(LogSoftmax::new(axis)), &[self])
+    }
+
+    /// Mean cross entropy of the softmax of this variable and the class labels.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `t` - Class labels with the shape (N)
+    pub fn softmax_cross_entropy(&self, t: &Var) -> Var {
+        self.apply(Box::new(SoftmaxCrossEntropy::new()), &[self, t])
+    }
+
+    /// Mean binary cross entropy of the sigmoid of this variable and the targets.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `t` - Targets of the same shape
+    pub fn binary_cross_entropy_with_logits(&self, t: &Var) -> Var {
+        self.apply(Box::new(BinaryCrossEntropyWithLogits::new()), &[self, t])
+    }
+
+    /// Power of the variable.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `c` - Exponent
+    pub fn pow(&self, c: f64) -> Var {
+        self.apply(Box::new(Pow::new(c)), &[self])
+    }
+
+    /// Matrix product of this variable and other.
+    pub fn matmul(&self, other: &Var) -> Var {
+        self.apply(Box::new(MatMul::new()), &[self, other])
+    }
+
+    /// Reshape the variable.
+    pub fn reshape(&self, shape: Vec<usize>) -> Var {
+        self.apply(Box::new(Reshape::new(shape)), &[self])
+    }
+
+    /// Transpose the variable.
+    pub fn transpose(&self) -> Var {
+        self.apply(Box::new(Transpose::new()), &[self])
+    }
+
+    /// Permute the axes of the variable.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `axes` - New order of the axes
+    pub fn permute(&self, axes: Vec<usize>) -> Var {
+        self.apply(Box::new(Permute::new(axes)), &[self])
+    }
+
+    /// Swap two axes of the variable.
+    pub fn swapaxes(&self, axis1: usize, axis2: usize) -> Var {
+        let ndim = self.shape().len();
+        self.apply(Box::new(Permute::swapaxes(ndim, axis1, axis2)), &[self])
+    }
+
+    /// Sum the variable along the given axis.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `axis` - Axis to sum along. If None, sum all elements
+    /// * `keepdims` - Keep the dimensions
+    pub fn sum<T: AsRef<[usize]>>(&self, axis: Option<T>, keepdims: bool) -> Var {
+        self.apply(Box::new(Sum::new(axis, keepdims)), &[self])
+    }
+
+    /// Take the mean of the variable along the given axis.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `axis` - Axis to reduce. If None, reduce all elements
+    /// * `keepdims` - Keep the dimensions
+    pub fn mean<T: AsRef<[usize]>>(&self, axis: Option<T>, keepdims: bool) -> Var {
+        self.apply(Box::new(Mean::new(axis, keepdims)), &[self])
+    }
+
+    /// Take the maximum of the variable along the given axis.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `axis` - Axis to reduce. If None, reduce all elements
+    /// * `keepdims` - Keep the dimensions
+    pub fn max<T: AsRef<[usize]>>(&self, axis: Option<T>, keepdims: bool) -> Var {
+        self.apply(Box::new(Max::new(axis, keepdims)), &[self])
+    }
+
+    /// Take the minimum of the variable along the given axis.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `axis` - Axis to reduce. If None, reduce all elements
+    /// * `keepdims` - Keep the dimensions
+    pub fn min<T: AsRef<[usize]>>(&self, axis: Option<T>, keepdims: bool) -> Var {
+        self.apply(Box::new(Min::new(axis, keepdims)), &[self])
+    }
+
+    /// Take the variance of the variable along the given axis.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `axis` - Axis to reduce. If None, reduce all elements
+    /// * `keepdims` - Keep the dimensions
+    /// * `ddof` - Delta degrees of freedom
+    pub fn var<T: AsRef<[usize]>>(&self, axis: Option<T>, keepdims: bool, ddof: usize) -> Var {
+        self.apply(Box::new(Variance::new(axis, keepdims, ddof)), &[self])
+    }
+
+    /// Broadcast the variable to the given shape.
+    pub fn broadcast_to(&self, shape: Vec<usize>) -> Var {
+        self.apply(Box::new(BroadcastTo::new(shape)), &[self])
+    }
+
+    /// Sum the variable to the given shape.
+    pub fn sum_to(&self, shape: Vec<usize>) -> Var {
+        self.apply(Box::new(SumTo::new(shape)), &[self])
+    }
+
+    /// Slice the variable.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `indexes` - Index, range or array of indexes of each leading axis
+    pub fn get_item(&self, indexes: Vec<SliceIndex>) -> Var {
+        self.apply(Box::new(GetItem::new(indexes)), &[self])
+    }
+
+    /// Select the indexes of the variable along the axis.
+    pub fn index_select(&self, axis: usize, indexes: Vec<usize>) -> Var {
+        self.apply(Box::new(IndexSelect::new(axis, indexes)), &[self])
+    }
+
+    /// Gather the values of the variable along the axis.
+    pub fn gather(&self, axis: usize, indexes: Tensor<usize>) -> Var {
+        self.apply(Box::new(Gather::new(axis, indexes)), &[self])
+    }
+
+    /// Concatenate the variables along the axis.
+    /// 
+    /// # Panics
+    /// 
+    /// * `No variables to join` - If `vars` is empty
+    pub fn concat(vars: &[&Var], axis: usize) -> Var {
+        let first = vars.first().expect("No variables to join");
+        first.apply(Box::new(Concat::new(axis)), vars)
+    }
+
+    /// Stack the variables along a new axis.
+    /// 
+    /// # Panics
+    /// 
+    /// * `No variables to join` - If `vars` is empty
+    pub fn stack(vars: &[&Var], axis: usize) -> Var {
+        let first = vars.first().expect("No variables to join");
+        first.apply(Box::new(Stack::new(axis)), vars)
+    }
+
+    /// Split the variable along the axis into parts of the sizes.
+    pub fn split(&self, axis: usize, sizes: Vec<usize>) -> Vec<Var> {
+        self.apply_all(Box::new(Split::new(axis, sizes)), &[self])
+    }
+
+    /// Split the variable along the axis into `chunks` parts,
+    /// whose sizes differ by at most one.
+    pub fn chunk(&self, axis: usize, chunks: usize) -> Vec<Var> {
+        let sizes = self.data().chunk(axis, chunks).iter().map(|part| part.shape()[axis]).collect();
+        self.split(axis, sizes)
+    }
+}
+
+impl std::ops::Add for &Var {
+    type Output = Var;
+
+    fn add(self, other: Self) -> Var {
+        self.apply(Box::new(Add::new()), &[self, other])
+    }
+}
+
+impl std::ops::Add for Var {
+    type Output = Var;
+
+    fn add(self, other: Self) -> Var {
+        &self + &other
+    }
+}
+
+impl std::ops::Sub for &Var {
+    type Output = Var;
+
+    fn sub(self, other: Self) -> Var {
+        self.apply(Box::new(Sub::new()), &[self, other])
+    }
+}
+
+impl std::ops::Sub for Var {
+    type Output = Var;
+
+    fn sub(self, other: Self) -> Var {
+        &self - &other
+    }
+}
+
+impl std::ops::Mul for &Var {
+    type Output = Var;
+
+    fn mul(self, other: Self) -> Var {
+        self.apply(Box::new(Mul::new()), &[self, other])
+    }
+}
+
+impl std::ops::Mul for Var {
+    type Output = Var;
+
+    fn mul(self, other: Self) -> Var {
+        &self * &other
+    }
+}
+
+impl std::ops::Div for &Var {
+    type Output = Var;
+
+    fn div(self, other: Self) -> Var {
+        self.apply(Box::new(Div::new()), &[self, other])
+    }
+}
+
+impl std::ops::Div for Var {
+    type Output = Var;
+
+    fn div(self, other: Self) -> Var {
+        &self / &other
+    }
+}
+
+impl std::ops::Neg for &Var {
+    type Output = Var;
+
+    fn neg(self) -> Var {
+        self.apply(Box::new(Neg::new()), &[self])
+    }
+}
+
+impl std::ops::Neg for Var {
+    type Output = Var;
+
+    fn neg(self) -> Var {
+        -&self
+    }
+}
+
+impl std::fmt::Debug for Var {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Var").field("id", &self.id).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn new_graph() -> Rc<RefCell<Graph>> {
+        Rc::new(RefCell::new(Graph::new()))
+    }
+
+    #[test]
+    fn new_normal() {
+        let graph = new_graph();
+        let x = Var::new(&graph, Tensor::new_from_num_vec(vec![1.0, 2.0, 3.0], vec![3]), "x");
+        assert_eq!(x.data(), Tensor::new_from_num_vec(vec![1.0, 2.0, 3.0], vec![3]));
+        assert_eq!(x.shape(), vec![3]);
+        assert_eq!(graph.borrow().get_variable(x.id()).unwrap().get_name(), "x");
+    }
+
+    #[test]
+    fn operator_normal() {
+        let graph = new_graph();
+        let x = Var::new(&graph, Tensor::new_from_num_vec(vec![1.0, 2.0], vec![2]), "x");
+        let y = Var::new(&graph, Tensor::new_from_num_vec(vec![4.0, 8.0], vec![2]), "y");
+
+        assert_eq!((&x + &y).data(), Tensor::new_from_num_vec(vec![5.0, 10.0], vec![2]));
+        assert_eq!((&x - &y).data(), Tensor::new_from_num_vec(vec![-3.0, -6.0], vec![2]));
+        assert_eq!((&x * &y).data(), Tensor::new_from_num_vec(vec![4.0, 16.0], vec![2]));
+        assert_eq!((&y / &x).data(), Tensor::new_from_num_vec(vec![4.0, 4.0], vec![2]));
+        assert_eq!((-x).data(), Tensor::new_from_num_vec(vec![-1.0, -2.0], vec![2]));
+    }
+
+    #[test]
+    #[should_panic]
+    fn operator_error_different_graph() {
+        let x = Var::new(&new_graph(), Tensor::new_from_num_vec(vec![1.0], vec![]), "x");
+        let y = Var::new(&new_graph(), Tensor::new_from_num_vec(vec![2.0], vec![]), "y");
+        let _ = &x + &y;
+    }
+
+    #[test]
+    fn backward_normal() {
+        let graph = new_graph();
+        let x = Var::new(&graph, Tensor::new_from_num_vec(vec![2.0], vec![]), "x");
+        let y = Var::new(&graph, Tensor::new_from_num_vec(vec![3.0], vec![]), "y");
+
+        let z = &(&x * &y) + &x.exp();
+        z.backward();
+
+        assert_eq!(x.grad().unwrap().data(), Tensor::new_from_num_vec(vec![3.0 + 2.0f64.exp()], vec![]));
+        assert_eq!(y.grad().unwrap().data(), Tensor::new_from_num_vec(vec![2.0], vec![]));
+    }
+
+    #[test]
+    fn swapaxes_backward() {
+        let graph = new_graph();
+        let x = Var::new(&graph, Tensor::arrange([2, 3, 4]), "x");
+        let w = Var::new(&graph, Tensor::arrange([2, 3, 4]).swapaxes(0, 1), "w");
+
+        let y = &x.swapaxes(0, 1) * &w;
+        y.backward();
+
+        assert_eq!(y.shape(), vec![3, 2, 4]);
+        assert_eq!(x.grad().unwrap().data(), Tensor::arrange([2, 3, 4]));
+    }
+
+    #[test]
+    fn reduction_backward() {
+        let graph = new_graph();
+        let x = Var::new(&graph, Tensor::new_from_num_vec(vec![1.0, 5.0, 3.0, 4.0, 2.0, 6.0], vec![2, 3]), "x");
+
+        let y = &(&x.max(Some([1]), false) + &x.min(Some([1]), false)) + &x.mean(Some([1]), false);
+        let z = x.var::<&[usize]>(None, false, 0);
+        assert_eq!(y.data(), Tensor::new_from_num_vec(vec![9.0, 12.0], vec![2]));
+        assert_eq!(z.shape(), Vec::<usize>::new());
+
+        y.sum::<&[usize]>(None, false).backward();
+        let expected = Tensor::new_from_num_vec(vec![4.0, 4.0, 1.0, 1.0, 4.0, 4.0], vec![2, 3])
+            .scalar_div(3.0.into());
+        assert_eq!(x.grad().unwrap().data(), expected);
+    }
+
+    #[test]
+    fn elementwise_backward() {
+        let graph = new_graph();
+        let x = Var::new(&graph, Tensor::new_from_num_vec(vec![-4.0, 1.0, 4.0], vec![3]), "x");
+        let c = Var::new(&graph, Tensor::new_from_num_vec(vec![2.0], vec![]), "c");
+
+        let y = &(&x.abs().sqrt() + &x.abs().log()) + &(&x.clip(-2.0, 2.0) + &x.maximum(&c).minimum(&x));
+        assert_eq!(y.data(), Tensor::new_from_num_vec(
+            vec![2.0 + 4.0_f64.ln() - 2.0 - 4.0, 1.0 + 0.0 + 1.0 + 1.0, 2.0 + 4.0_f64.ln() + 2.0 + 4.0], vec![3]));
+
+        y.sum::<&[usize]>(None, false).backward();
+        assert_eq!(x.grad().unwrap().data(), Tensor::new_from_num_vec(
+            vec![-0.25 - 0.25 + 1.0, 0.5 + 1.0 + 1.0 + 1.0, 0.25 + 0.25 + 1.0], vec![3]));
+        assert_eq!(c.grad().unwrap().data(), Tensor::new_from_num_vec(vec![0.0], vec![]));
+    }
+
+    #[test]
+    fn activation_backward() {
+        let graph = new_graph();
+        let x = Var::new(&graph, Tensor::new_from_num_vec(vec![-1.0, 2.0], vec![2]), "x");
+
+        let y = &(&x.relu() + &x.leaky_relu(0.5)) + &x.elu(1.0);
+        assert_eq!(y.data(), Tensor::new_from_num_vec(vec![-0.5 + (-1.0_f64).exp_m1(), 6.0], vec![2]));
+
+        y.sum::<&[usize]>(None, false).backward();
+        assert_eq!(x.grad().unwrap().data(), Tensor::new_from_num_vec(vec![0.5 + (-1.0_f64).exp(), 3.0], vec![2]));
+
+        let z = &(&x.sigmoid() + &x.gelu()) + &(&x.silu() + &x.softplus());
+        assert_eq!(z.shape(), vec![2]);
+    }
+
+    #[test]
+    fn classification_backward() {
+        let graph = new_graph();
+        let x = Var::new(&graph, Tensor::new_from_num_vec(vec![0.0, 0.0, 1000.0, 0.0], vec![2, 2]), "x");
+        let t = Var::new(&graph, Tensor::new_from_num_vec(vec![1.0, 0.0], vec![2]), "t");
+
+        assert_eq!(x.softmax(1).data(), Tensor::new_from_num_vec(vec![0.5, 0.5, 1.0, 0.0], vec![2, 2]));
+        assert_eq!(x.log_softmax(1).data(), Tensor::new_from_num_vec(
+            vec![-2.0_f64.ln(), -2.0_f64.ln(), 0.0, -1000.0], vec![2, 2]));
+
+        let loss = x.softmax_cross_entropy(&t);
+        assert_eq!(loss.data(), Tensor::full(2.0_f64.ln() / 2.0, vec![]));
+
+        loss.backward();
+        assert_eq!(x.grad().unwrap().data(), Tensor::new_from_num_vec(vec![0.25, -0.25, 0.0, 0.0], vec![2, 2]));
+        assert!(t.grad().is_none());
+    }
+
+    #[test]
+    fn indexing_backward() {
+        let graph = new_graph();
+        let x = Var::new(&graph, Tensor::arrange([3, 2]), "x");
+
+        let y = x.get_item(vec![(1..).into(), 0.into()]);
+        assert_eq!(y.data(), Tensor::new_from_num_vec(vec![2.0, 4.0], vec![2]));
+        let z = x.index_select(0, vec![0, 0]);
+        assert_eq!(z.data(), Tensor::new_from_num_vec(vec![0.0, 1.0, 0.0, 1.0], vec![2, 2]));
+        let w = x.gather(1, Tensor::new_from_num_vec(vec![1, 1, 0], vec![3, 1]));
+        assert_eq!(w.data(), Tensor::new_from_num_vec(vec![1.0, 3.0, 4.0], vec![3, 1]));
+
+        let loss = &(&y.sum::<&[usize]>(None, false) + &z.sum::<&[usize]>(None, false)) + &w.sum::<&[usize]>(None, false);
+        loss.backward();
+        assert_eq!(x.grad().unwrap().data(), Tensor::new_from_num_vec(vec![2.0, 3.0, 1.0, 1.0, 2.0, 0.0], vec![3, 2]));
+    }
+
+    #[test]
+    fn join_backward() {
+        let graph = new_graph();
+        let x = Var::new(&graph, Tensor::arrange([2, 3]), "x");
+
+        let parts = x.chunk(1, 2);
+        assert_eq!(parts[1].data(), Tensor::new_from_num_vec(vec![2.0, 5.0], vec![2, 1]));
+        let y = Var::concat(&[&parts[1], &parts[1]], 1);
+        assert_eq!(y.shape(), vec![2, 2]);
+        let z = Var::stack(&[&y, &y, &y], 0);
+        assert_eq!(z.shape(), vec![3, 2, 2]);
+
+        z.sum::<&[usize]>(None, false).backward();
+        assert_eq!(x.grad().unwrap().data(), Tensor::new_from_num_vec(vec![0.0, 0.0, 6.0, 0.0, 0.0, 6.0], vec![2, 3]));
+    }
+
+    #[test]
+    fn backward_backward_normal() {
+        let graph = new_graph();
+        let x = Var::new(&graph, Tensor::new_from_num_vec(vec![2.0], vec![]), "x");
+
+        let y = x.pow(4.0);
+        y.backward_with(false, true);
+
+        let gx = x.grad().unwrap();
+        assert_eq!(gx.data(), Tensor::new_from_num_vec(vec![32.0], vec![]));
+        x.clear_grad();
+        gx.backward();
+
+        assert_eq!(x.grad().unwrap().data(), Tensor::new_from_num_vec(vec![48.0], vec![]));
+    }
+
+    #[test]
+    fn backward_mlp() {
+        let graph = new_graph();
+        let x = Var::new(&graph, Tensor::new_from_num_vec(vec![0.0, 1.0, 2.0, 3.0, 4.0, 5.0], vec![3, 2]), "x");
+        let w = Var::new(&graph, Tensor::new_from_num_vec(vec![0.0, 1.0, 2.0, 3.0], vec![2, 2]), "w");
+        let b = Var::new(&graph, Tensor::new_from_num_vec(vec![0.0, 1.0], vec![2]), "b");
+
+        let y = &x.matmul(&w) + &b.broadcast_to(vec![3, 2]);
+        let loss = y.tanh().sum::<&[usize]>(None, false);
+        loss.backward();
+
+        assert_eq!(loss.shape(), Vec::<usize>::new());
+        assert_eq!(w.grad().unwrap().shape(), vec![2, 2]);
+        assert_eq!(b.grad().unwrap().shape(), vec![2]);
+        assert_eq!(x.reshape(vec![6]).transpose().sum_to(vec![1]).data(), Tensor::new_from_num_vec(vec![15.0], vec![1]));
+    }
+}
@@ -0,0 +1,83 @@
+pub mod linear;
+pub mod mlp;
+
+pub use linear::Linear;
+pub use mlp::MLP;
+
+use crate::graph::Graph;
+
+/// Layer that owns named parameter variables of a graph
+/// 
+/// A model is a layer made of sublayers,
+/// and its parameters are listed with the names of the sublayers as prefixes.
+/// Parameters are created by `build` or lazily by the first forward,
+/// so they are listed only after they exist.
+/// Call `build` before creating an optimizer from `param_ids`.
+pub trait Layer {
+    /// Create the parameters for inputs whose last axis has the size `in_size`.
+    /// 
+    /// Parameters that already exist are kept.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `in_size` - Size of the last axis of the input
+    /// * `graph` - Graph that owns the parameters
+    /// 
+    /// # Returns
+    /// 
+    /// * Size of the last axis of the output
+    fn build(&mut self, in_size: usize, graph: &mut Graph) -> usize;
+
+    /// Forward the layer.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `x_id` - Input variable ID
+    /// * `graph` - Graph that owns the parameters
+    /// 
+    /// # Returns
+    /// 
+    /// * Output variable ID
+    fn forward(&mut self, x_id: usize, graph: &mut Graph) -> usize;
+
+    /// Get the parameters of this layer, not including those of the sublayers.
+    /// 
+    /// # Returns
+    /// 
+    /// * Pairs of the parameter name and the variable ID
+    fn params(&self) -> Vec<(String, usize)> {
+        Vec::new()
+    }
+
+    /// Get the sublayers of this layer.
+    /// 
+    /// # Returns
+    /// 
+    /// * Pairs of the sublayer name and the sublayer
+    fn sublayers(&self) -> Vec<(String, &dyn Layer)> {
+        Vec::new()
+    }
+
+    /// Get the parameters of this layer and all of its sublayers.
+    /// 
+    /// The name of a sublayer parameter is `sublayer_name.param_name`.
+    fn named_params(&self) -> Vec<(String, usize)> {
+        let mut params = self.params();
+        for (layer_name, layer) in self.sublayers() {
+            for (name, id) in layer.named_params() {
+                params.push((format!("{}.{}", layer_name, name), id));
+            }
+        }
+        params
+    }
+
+    /// Get the variable IDs of all parameters, for example to build an optimizer.
+    fn param_ids(&self) -> Vec<usize> {
+        self.named_params().into_iter().map(|(_, id)| id).collect()
+    }
+
+    /// Clear the gradients of all parameters.
+    fn clear_grads(&self, graph: &mut Graph) {
+        graph.clear_grads(&self.param_ids());
+    }
+}
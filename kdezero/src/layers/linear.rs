@@ -0,0 +1,236 @@
+use ktensor::{Tensor, tensor::{random::TensorRng, initializer::Initializer}};
+use super::Layer;
+use crate::graph::Graph;
+use crate::function::function::linear;
+
+/// Fully connected layer
+/// 
+/// `y = x @ w + b`, where `w` has the shape `[in_size, out_size]`
+/// and `b` has the shape `[out_size]`.
+/// If `in_size` is not given, it is taken from the last axis of the first input.
+/// 
+/// # Fields
+/// 
+/// * `in_size` - Input size
+/// * `out_size` - Output size
+/// * `nobias` - Whether to omit the bias
+/// * `initializer` - Initializer of the weight
+/// * `rng` - Random number generator of the initializer
+/// * `w_id` - Variable ID of the weight
+/// * `b_id` - Variable ID of the bias
+#[derive(Debug, Clone)]
+pub struct Linear {
+    in_size: Option<usize>,
+    out_size: usize,
+    nobias: bool,
+    initializer: Initializer,
+    rng: TensorRng,
+    w_id: Option<usize>,
+    b_id: Option<usize>,
+}
+
+impl Linear {
+    /// Create a new Linear instance.
+    /// 
+    /// The parameters are created in the graph by `build` or the first forward.
+    /// The random number generator is seeded from the operating system,
+    /// use `set_rng` for reproducible parameters.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `out_size` - Output size
+    /// * `nobias` - Whether to omit the bias
+    /// * `in_size` - Input size. If None, it is taken from the first input
+    /// * `initializer` - Initializer of the weight
+    pub fn new(out_size: usize, nobias: bool, in_size: Option<usize>, initializer: Initializer) -> Self {
+        Self { in_size, out_size, nobias, initializer, rng: TensorRng::new(), w_id: None, b_id: None }
+    }
+
+    /// Set the random number generator of the initializer.
+    pub fn set_rng(&mut self, rng: TensorRng) {
+        self.rng = rng;
+    }
+
+    /// Get the variable ID of the weight, if it has been created.
+    pub fn w_id(&self) -> Option<usize> {
+        self.w_id
+    }
+
+    /// Get the variable ID of the bias, if it has been created.
+    pub fn b_id(&self) -> Option<usize> {
+        self.b_id
+    }
+
+    /// Create the parameters in the graph.
+    /// 
+    /// The weight is drawn by the initializer and the bias is zero.
+    fn init_params(&mut self, in_size: usize, graph: &mut Graph) {
+        let w = self.initializer.init_f64([in_size, self.out_size], &mut self.rng);
+        self.in_size = Some(in_size);
+        self.w_id = Some(graph.generate_variable_from_f64_tensor(w, "w"));
+        if !self.nobias {
+            self.b_id = Some(graph.generate_variable_from_f64_tensor(
+                Tensor::full(0.0, vec![self.out_size]), "b"));
+        }
+    }
+}
+
+impl Layer for Linear {
+    /// Forward the layer.
+    /// 
+    /// # Panics
+    /// 
+    /// * `Invalid variable id` - If the input is not in the graph
+    /// * `Input size mismatch` - If the last axis of the input is not `in_size`
+    fn forward(&mut self, x_id: usize, graph: &mut Graph) -> usize {
+        let x_in_size = *graph.get_variable(x_id).expect("Invalid variable id")
+            .shape().last().expect("Input must have at least one axis");
+        self.build(x_in_size, graph);
+        linear(x_id, self.w_id.unwrap(), self.b_id, graph)
+    }
+
+    /// Create the weight and the bias, unless they exist.
+    /// 
+    /// # Panics
+    /// 
+    /// * `Input size mismatch` - If `in_size` is not the input size of the layer
+    fn build(&mut self, in_size: usize, graph: &mut Graph) -> usize {
+        assert_eq!(in_size, self.in_size.unwrap_or(in_size), "Input size mismatch");
+        if self.w_id.is_none() {
+            self.init_params(in_size, graph);
+        }
+        self.out_size
+    }
+
+    fn params(&self) -> Vec<(String, usize)> {
+        let mut params = Vec::new();
+        if let Some(w_id) = self.w_id {
+            params.push(("w".to_string(), w_id));
+        }
+        if let Some(b_id) = self.b_id {
+            params.push(("b".to_string(), b_id));
+        }
+        params
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn forward_normal() {
+        let mut graph = Graph::new();
+        let x_id = graph.generate_variable_from_f64_tensor(
+            Tensor::new_from_num_vec(vec![0.0, 1.0, 2.0, 3.0, 4.0, 5.0], vec![3, 2]), "x");
+
+        let mut layer = Linear::new(4, false, None, Initializer::XavierUniform { gain: 1.0 });
+        assert!(layer.named_params().is_empty());
+
+        let y_id = layer.forward(x_id, &mut graph);
+
+        assert_eq!(graph.get_variable(y_id).unwrap().shape(), &vec![3, 4]);
+        let w_id = layer.w_id().unwrap();
+        let b_id = layer.b_id().unwrap();
+        assert_eq!(graph.get_variable(w_id).unwrap().shape(), &vec![2, 4]);
+        assert_eq!(graph.get_variable(b_id).unwrap().shape(), &vec![4]);
+        assert_eq!(layer.named_params(), vec![("w".to_string(), w_id), ("b".to_string(), b_id)]);
+
+        let w = graph.get_variable_contents_f64(w_id).unwrap();
+        assert!(w.data().iter().all(|x| x.data().abs() <= 1.0));
+    }
+
+    #[test]
+    fn build_normal() {
+        let mut graph = Graph::new();
+        let x_id = graph.generate_variable_from_f64_tensor(
+            Tensor::new_from_num_vec(vec![0.0, 1.0], vec![1, 2]), "x");
+
+        let mut layer = Linear::new(3, false, None, Initializer::HeNormal);
+        assert_eq!(layer.build(2, &mut graph), 3);
+        let params = layer.param_ids();
+        assert_eq!(params.len(), 2);
+        assert_eq!(graph.get_variable(params[0]).unwrap().shape(), &vec![2, 3]);
+
+        layer.forward(x_id, &mut graph);
+        assert_eq!(layer.param_ids(), params);
+    }
+
+    #[test]
+    fn forward_reuse_params() {
+        let mut graph = Graph::new();
+        let x_id = graph.generate_variable_from_f64_tensor(
+            Tensor::new_from_num_vec(vec![0.0, 1.0], vec![1, 2]), "x");
+
+        let mut layer = Linear::new(3, true, Some(2), Initializer::HeNormal);
+        layer.forward(x_id, &mut graph);
+        let params = layer.param_ids();
+        layer.forward(x_id, &mut graph);
+
+        assert_eq!(layer.param_ids(), params);
+        assert_eq!(params.len(), 1);
+    }
+
+    #[test]
+    fn backward_normal() {
+        let mut graph = Graph::new();
+        let x_id = graph.generate_variable_from_f64_tensor(
+            Tensor::new_from_num_vec(vec![0.0, 1.0, 2.0, 3.0, 4.0, 5.0], vec![3, 2]), "x");
+
+        let mut layer = Linear::new(4, false, None, Initializer::XavierUniform { gain: 1.0 });
+        let y_id = layer.forward(x_id, &mut graph);
+        graph.backward(vec![y_id], false, false);
+
+        let b_grad = graph.get_variable_grad_contents_f64(layer.b_id().unwrap()).unwrap();
+        assert_eq!(b_grad, &Tensor::new_from_num_vec(vec![3.0, 3.0, 3.0, 3.0], vec![4]));
+
+        layer.clear_grads(&mut graph);
+        assert!(graph.get_variable_grad_id(layer.w_id().unwrap()).is_none());
+    }
+
+    #[test]
+    fn set_rng_normal() {
+        let mut graph = Graph::new();
+        let x_id = graph.generate_variable_from_f64_tensor(
+            Tensor::new_from_num_vec(vec![0.0, 1.0], vec![1, 2]), "x");
+
+        let mut layers = [
+            Linear::new(3, true, None, Initializer::HeNormal),
+            Linear::new(3, true, None, Initializer::HeNormal),
+        ];
+        let mut ws = Vec::new();
+        for layer in layers.iter_mut() {
+            layer.set_rng(TensorRng::seed_from_u64(0));
+            layer.forward(x_id, &mut graph);
+            ws.push(graph.get_variable_contents_f64(layer.w_id().unwrap()).unwrap().clone());
+        }
+
+        assert_eq!(ws[0], ws[1]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Input size mismatch")]
+    fn forward_error_in_size() {
+        let mut graph = Graph::new();
+        let x_id = graph.generate_variable_from_f64_tensor(
+            Tensor::new_from_num_vec(vec![0.0, 1.0], vec![1, 2]), "x");
+
+        let mut layer = Linear::new(3, false, Some(4), Initializer::HeNormal);
+        layer.forward(x_id, &mut graph);
+    }
+
+    #[test]
+    fn forward_error_in_size_keeps_graph() {
+        let mut graph = Graph::new();
+        let x_id = graph.generate_variable_from_f64_tensor(
+            Tensor::new_from_num_vec(vec![0.0, 1.0], vec![1, 2]), "x");
+        let variable_count = graph.variable_table().len();
+
+        let mut layer = Linear::new(3, false, Some(4), Initializer::HeNormal);
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| layer.forward(x_id, &mut graph)));
+
+        assert!(result.is_err());
+        assert!(layer.w_id().is_none());
+        assert_eq!(graph.variable_table().len(), variable_count);
+    }
+}
@@ -0,0 +1,139 @@
+use ktensor::tensor::{random::TensorRng, initializer::Initializer};
+use super::{Layer, Linear};
+use crate::graph::Graph;
+
+/// Multi-layer perceptron
+/// 
+/// A stack of Linear layers with the activation between them.
+/// The activation is not applied to the output of the last layer.
+/// The sublayers are named `l0`, `l1`, ...
+/// 
+/// # Fields
+/// 
+/// * `layers` - Linear layers
+/// * `activation` - Activation function
+#[derive(Debug, Clone)]
+pub struct MLP {
+    layers: Vec<Linear>,
+    activation: fn(usize, &mut Graph) -> usize,
+}
+
+impl MLP {
+    /// Create a new MLP instance.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `fc_output_sizes` - Output size of each Linear layer
+    /// * `activation` - Activation function, for example `function::function::sigmoid`
+    /// * `initializer` - Initializer of the weights
+    pub fn new(fc_output_sizes: &[usize], activation: fn(usize, &mut Graph) -> usize, initializer: Initializer) -> Self {
+        let layers = fc_output_sizes.iter()
+            .map(|&out_size| Linear::new(out_size, false, None, initializer.clone()))
+            .collect();
+        Self { layers, activation }
+    }
+
+    /// Set the random number generators of the layers.
+    /// 
+    /// Each layer gets a child stream of the generator.
+    pub fn set_rng(&mut self, mut rng: TensorRng) {
+        for layer in self.layers.iter_mut() {
+            layer.set_rng(rng.child());
+        }
+    }
+
+    /// Get the Linear layers.
+    pub fn layers(&self) -> &Vec<Linear> {
+        &self.layers
+    }
+}
+
+impl Layer for MLP {
+    fn forward(&mut self, x_id: usize, graph: &mut Graph) -> usize {
+        let last = self.layers.len().saturating_sub(1);
+        let mut x_id = x_id;
+        for (i, layer) in self.layers.iter_mut().enumerate() {
+            x_id = layer.forward(x_id, graph);
+            if i != last {
+                x_id = (self.activation)(x_id, graph);
+            }
+        }
+        x_id
+    }
+
+    fn build(&mut self, in_size: usize, graph: &mut Graph) -> usize {
+        self.layers.iter_mut().fold(in_size, |in_size, layer| layer.build(in_size, graph))
+    }
+
+    fn sublayers(&self) -> Vec<(String, &dyn Layer)> {
+        self.layers.iter().enumerate()
+            .map(|(i, layer)| (format!("l{}", i), layer as &dyn Layer))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ktensor::Tensor;
+    use crate::function::{function::sigmoid, operator::MeanSquaredError};
+    use crate::optimizers::{Optimizer, SGD};
+
+    #[test]
+    fn forward_normal() {
+        let mut graph = Graph::new();
+        let x_id = graph.generate_variable_from_f64_tensor(
+            Tensor::new_from_num_vec(vec![0.0, 1.0, 2.0, 3.0, 4.0, 5.0], vec![3, 2]), "x");
+
+        let mut model = MLP::new(&[10, 5, 1], sigmoid, Initializer::XavierUniform { gain: 1.0 });
+        let y_id = model.forward(x_id, &mut graph);
+
+        assert_eq!(graph.get_variable(y_id).unwrap().shape(), &vec![3, 1]);
+        let names = model.named_params().into_iter().map(|(name, _)| name).collect::<Vec<_>>();
+        assert_eq!(names, vec!["l0.w", "l0.b", "l1.w", "l1.b", "l2.w", "l2.b"]);
+        let w_id = model.layers()[1].w_id().unwrap();
+        assert_eq!(graph.get_variable(w_id).unwrap().shape(), &vec![10, 5]);
+    }
+
+    #[test]
+    fn build_normal() {
+        let mut graph = Graph::new();
+
+        let mut model = MLP::new(&[10, 5, 1], sigmoid, Initializer::XavierUniform { gain: 1.0 });
+        assert_eq!(model.build(2, &mut graph), 1);
+
+        assert_eq!(model.param_ids().len(), 6);
+        let w_id = model.layers()[2].w_id().unwrap();
+        assert_eq!(graph.get_variable(w_id).unwrap().shape(), &vec![5, 1]);
+    }
+
+    #[test]
+    fn train_normal() {
+        let mut graph = Graph::new();
+        let x = Tensor::new_from_num_vec(vec![0.0, 0.25, 0.5, 0.75, 1.0], vec![5, 1]);
+        let y = x.scalar_mul(2.0.into()).scalar_add(1.0.into());
+
+        let mut model = MLP::new(&[8, 1], sigmoid, Initializer::XavierNormal { gain: 1.0 });
+        model.set_rng(TensorRng::seed_from_u64(0));
+        model.build(1, &mut graph);
+        let mut optimizer = SGD::new(model.param_ids(), 0.1, 0.0);
+        let mut losses = Vec::new();
+
+        for _ in 0..200 {
+            let x_id = graph.generate_variable_from_f64_tensor(x.clone(), "x");
+            let y_id = graph.generate_variable_from_f64_tensor(y.clone(), "y");
+            let pred_id = model.forward(x_id, &mut graph);
+            let mse_id = graph.generate_function_from_function_contents(Box::new(MeanSquaredError::new()));
+            let loss_id = graph.forward(mse_id, vec![y_id, pred_id])[0];
+            losses.push(*graph.get_variable_contents_f64(loss_id).unwrap().data()[0].data());
+
+            graph.backward(vec![loss_id], false, false);
+            optimizer.step(&mut graph);
+            optimizer.zero_grad(&mut graph);
+            graph.release_unreachable(&model.param_ids());
+        }
+
+        assert!(losses.last().unwrap() < &(losses[0] * 0.1));
+        assert_eq!(graph.variable_table().len(), 4);
+    }
+}
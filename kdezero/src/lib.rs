@@ -1,2 +1,6 @@
 pub mod variable;
 pub mod function;
+pub mod graph;
+pub mod optimizers;
+pub mod layers;
+pub mod error;
@@ -0,0 +1,82 @@
+pub mod sgd;
+pub mod momentum_sgd;
+pub mod adagrad;
+pub mod rmsprop;
+pub mod adam;
+pub mod adamw;
+
+pub use sgd::SGD;
+pub use momentum_sgd::MomentumSGD;
+pub use adagrad::AdaGrad;
+pub use rmsprop::RMSProp;
+pub use adam::Adam;
+pub use adamw::AdamW;
+
+use ktensor::Tensor;
+use crate::graph::Graph;
+
+/// Optimizer of parameter variables
+/// 
+/// An optimizer holds the parameter variable IDs of one graph
+/// and the per-parameter state, keyed by the parameter ID.
+/// A training iteration is `zero_grad`, backward, then `step`.
+pub trait Optimizer {
+    /// Get the parameter variable IDs.
+    fn params(&self) -> &Vec<usize>;
+
+    /// Compute the updated parameter from the parameter and its gradient.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `id` - Parameter variable ID
+    /// * `param` - Parameter data
+    /// * `grad` - Gradient data
+    /// 
+    /// # Returns
+    /// 
+    /// * Updated parameter data
+    fn update_one(&mut self, id: usize, param: &Tensor<f64>, grad: &Tensor<f64>) -> Tensor<f64>;
+
+    /// Update every parameter that has a gradient.
+    /// 
+    /// The data of the parameters is replaced, so no function is recorded in the graph.
+    /// 
+    /// # Panics
+    /// 
+    /// * `Invalid variable id` - If a parameter is not in the graph
+    fn step(&mut self, graph: &mut Graph) {
+        for id in self.params().clone() {
+            let grad = match graph.get_variable_grad_contents_f64(id) {
+                Some(grad) => grad.clone(),
+                None => continue,
+            };
+            let param = graph.get_variable_contents_f64(id).expect("Invalid variable id");
+            let new_param = self.update_one(id, param, &grad);
+            graph.set_variable_contents_f64(id, new_param);
+        }
+    }
+
+    /// Clear the gradients of the parameters.
+    /// 
+    /// # Panics
+    /// 
+    /// * `Invalid variable id` - If a parameter is not in the graph
+    fn zero_grad(&self, graph: &mut Graph) {
+        graph.clear_grads(self.params());
+    }
+}
+
+/// Add the L2 penalty of the parameter to the gradient.
+/// 
+/// # Arguments
+/// 
+/// * `param` - Parameter data
+/// * `grad` - Gradient data
+/// * `weight_decay` - Coefficient of the L2 penalty
+pub(crate) fn weight_decayed_grad(param: &Tensor<f64>, grad: &Tensor<f64>, weight_decay: f64) -> Tensor<f64> {
+    if weight_decay == 0.0 {
+        grad.clone()
+    } else {
+        grad.clone() + param.scalar_mul(weight_decay.into())
+    }
+}
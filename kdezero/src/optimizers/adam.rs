@@ -0,0 +1,139 @@
+use std::collections::HashMap;
+use ktensor::Tensor;
+use super::{Optimizer, weight_decayed_grad};
+
+/// State of one parameter of Adam and AdamW
+/// 
+/// # Fields
+/// 
+/// * `m` - Moving average of the gradients
+/// * `v` - Moving average of the squared gradients
+/// * `t` - Number of updates of the parameter
+#[derive(Debug, Clone)]
+pub(crate) struct AdamState {
+    m: Tensor<f64>,
+    v: Tensor<f64>,
+    t: i32,
+}
+
+impl AdamState {
+    fn new(param: &Tensor<f64>) -> Self {
+        Self { m: Tensor::full_like(param, 0.0), v: Tensor::full_like(param, 0.0), t: 0 }
+    }
+
+    /// Update the moments with the gradient and return the bias corrected step direction.
+    /// 
+    /// `m_hat / (sqrt(v_hat) + eps)`
+    pub(crate) fn direction(
+        states: &mut HashMap<usize, AdamState>, id: usize, param: &Tensor<f64>, grad: &Tensor<f64>,
+        beta1: f64, beta2: f64, eps: f64,
+    ) -> Tensor<f64> {
+        let state = states.entry(id).or_insert_with(|| AdamState::new(param));
+        state.t += 1;
+        state.m = state.m.scalar_mul(beta1.into()) + grad.scalar_mul((1.0 - beta1).into());
+        state.v = state.v.scalar_mul(beta2.into())
+            + (grad.clone() * grad.clone()).scalar_mul((1.0 - beta2).into());
+        let m_hat = state.m.scalar_div((1.0 - beta1.powi(state.t)).into());
+        let v_hat = state.v.scalar_div((1.0 - beta2.powi(state.t)).into());
+        m_hat / v_hat.powf(0.5).scalar_add(eps.into())
+    }
+}
+
+/// Adam
+/// 
+/// The weight decay is added to the gradient as an L2 penalty.
+/// Use AdamW for the decoupled weight decay.
+/// 
+/// # Fields
+/// 
+/// * `params` - Parameter variable IDs
+/// * `lr` - Learning rate
+/// * `beta1` - Decay rate of the first moment
+/// * `beta2` - Decay rate of the second moment
+/// * `eps` - Small value to avoid division by zero
+/// * `weight_decay` - Coefficient of the L2 penalty
+/// * `states` - Moments of each parameter
+#[derive(Debug, Clone)]
+pub struct Adam {
+    params: Vec<usize>,
+    lr: f64,
+    beta1: f64,
+    beta2: f64,
+    eps: f64,
+    weight_decay: f64,
+    states: HashMap<usize, AdamState>,
+}
+
+impl Adam {
+    /// Create a new Adam instance.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `params` - Parameter variable IDs
+    /// * `lr` - Learning rate
+    /// * `beta1` - Decay rate of the first moment
+    /// * `beta2` - Decay rate of the second moment
+    /// * `eps` - Small value to avoid division by zero
+    /// * `weight_decay` - Coefficient of the L2 penalty
+    pub fn new(params: Vec<usize>, lr: f64, beta1: f64, beta2: f64, eps: f64, weight_decay: f64) -> Self {
+        Self { params, lr, beta1, beta2, eps, weight_decay, states: HashMap::new() }
+    }
+}
+
+impl Optimizer for Adam {
+    fn params(&self) -> &Vec<usize> {
+        &self.params
+    }
+
+    fn update_one(&mut self, id: usize, param: &Tensor<f64>, grad: &Tensor<f64>) -> Tensor<f64> {
+        let grad = weight_decayed_grad(param, grad, self.weight_decay);
+        let direction = AdamState::direction(
+            &mut self.states, id, param, &grad, self.beta1, self.beta2, self.eps);
+        param.clone() - direction.scalar_mul(self.lr.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ktensor::utility::assert_approx_eq;
+    use crate::graph::Graph;
+    use crate::function::operator::Square;
+
+    #[test]
+    fn step_normal() {
+        let mut graph = Graph::new();
+        let x_id = graph.generate_variable_from_f64_tensor(
+            Tensor::new_from_num_vec(vec![1.0, -2.0], vec![2]), "x");
+        graph.variable_table_mut().set_grad_from_f64_tensor(
+            x_id, Tensor::new_from_num_vec(vec![0.5, -3.0], vec![2]));
+
+        let mut optimizer = Adam::new(vec![x_id], 0.1, 0.9, 0.999, 1e-8, 0.0);
+        optimizer.step(&mut graph);
+
+        // The first bias corrected step is lr * sign(grad)
+        let x = graph.get_variable_contents_f64(x_id).unwrap();
+        assert_approx_eq(*x.data()[0].data(), 0.9, 1e-6);
+        assert_approx_eq(*x.data()[1].data(), -1.9, 1e-6);
+    }
+
+    #[test]
+    fn step_converge() {
+        let mut graph = Graph::new();
+        let x_id = graph.generate_variable_from_f64_tensor(
+            Tensor::new_from_num_vec(vec![3.0], vec![]), "x");
+        let mut optimizer = Adam::new(vec![x_id], 0.1, 0.9, 0.999, 1e-8, 0.0);
+
+        for _ in 0..300 {
+            let square_id = graph.generate_function_from_function_contents(Box::new(Square::new()));
+            let y_id = graph.forward(square_id, vec![x_id])[0];
+            optimizer.zero_grad(&mut graph);
+            graph.backward(vec![y_id], false, false);
+            optimizer.step(&mut graph);
+            graph.release_unreachable(&vec![x_id]);
+        }
+
+        let x = graph.get_variable_contents_f64(x_id).unwrap();
+        assert_approx_eq(*x.data()[0].data(), 0.0, 1e-2);
+    }
+}
@@ -0,0 +1,82 @@
+use std::collections::HashMap;
+use ktensor::Tensor;
+use super::Optimizer;
+use super::adam::AdamState;
+
+/// AdamW
+/// 
+/// Adam with the decoupled weight decay:
+/// `param -= lr * (m_hat / (sqrt(v_hat) + eps) + weight_decay * param)`
+/// 
+/// # Fields
+/// 
+/// * `params` - Parameter variable IDs
+/// * `lr` - Learning rate
+/// * `beta1` - Decay rate of the first moment
+/// * `beta2` - Decay rate of the second moment
+/// * `eps` - Small value to avoid division by zero
+/// * `weight_decay` - Coefficient of the decoupled weight decay
+/// * `states` - Moments of each parameter
+#[derive(Debug, Clone)]
+pub struct AdamW {
+    params: Vec<usize>,
+    lr: f64,
+    beta1: f64,
+    beta2: f64,
+    eps: f64,
+    weight_decay: f64,
+    states: HashMap<usize, AdamState>,
+}
+
+impl AdamW {
+    /// Create a new AdamW instance.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `params` - Parameter variable IDs
+    /// * `lr` - Learning rate
+    /// * `beta1` - Decay rate of the first moment
+    /// * `beta2` - Decay rate of the second moment
+    /// * `eps` - Small value to avoid division by zero
+    /// * `weight_decay` - Coefficient of the decoupled weight decay
+    pub fn new(params: Vec<usize>, lr: f64, beta1: f64, beta2: f64, eps: f64, weight_decay: f64) -> Self {
+        Self { params, lr, beta1, beta2, eps, weight_decay, states: HashMap::new() }
+    }
+}
+
+impl Optimizer for AdamW {
+    fn params(&self) -> &Vec<usize> {
+        &self.params
+    }
+
+    fn update_one(&mut self, id: usize, param: &Tensor<f64>, grad: &Tensor<f64>) -> Tensor<f64> {
+        let direction = AdamState::direction(
+            &mut self.states, id, param, grad, self.beta1, self.beta2, self.eps);
+        let direction = direction + param.scalar_mul(self.weight_decay.into());
+        param.clone() - direction.scalar_mul(self.lr.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ktensor::utility::assert_approx_eq;
+    use crate::graph::Graph;
+
+    #[test]
+    fn step_normal() {
+        let mut graph = Graph::new();
+        let x_id = graph.generate_variable_from_f64_tensor(
+            Tensor::new_from_num_vec(vec![1.0, -2.0], vec![2]), "x");
+        graph.variable_table_mut().set_grad_from_f64_tensor(
+            x_id, Tensor::new_from_num_vec(vec![0.5, -3.0], vec![2]));
+
+        let mut optimizer = AdamW::new(vec![x_id], 0.1, 0.9, 0.999, 1e-8, 0.5);
+        optimizer.step(&mut graph);
+
+        // lr * (sign(grad) + weight_decay * param)
+        let x = graph.get_variable_contents_f64(x_id).unwrap();
+        assert_approx_eq(*x.data()[0].data(), 1.0 - 0.1 * (1.0 + 0.5), 1e-6);
+        assert_approx_eq(*x.data()[1].data(), -2.0 - 0.1 * (-1.0 - 1.0), 1e-6);
+    }
+}
@@ -0,0 +1,78 @@
+use std::collections::HashMap;
+use ktensor::Tensor;
+use super::{Optimizer, weight_decayed_grad};
+
+/// Stochastic gradient descent with momentum
+/// 
+/// `v = momentum * v - lr * (grad + weight_decay * param)`,
+/// `param += v`
+/// 
+/// # Fields
+/// 
+/// * `params` - Parameter variable IDs
+/// * `lr` - Learning rate
+/// * `momentum` - Momentum coefficient
+/// * `weight_decay` - Coefficient of the L2 penalty
+/// * `velocities` - Velocity of each parameter
+#[derive(Debug, Clone)]
+pub struct MomentumSGD {
+    params: Vec<usize>,
+    lr: f64,
+    momentum: f64,
+    weight_decay: f64,
+    velocities: HashMap<usize, Tensor<f64>>,
+}
+
+impl MomentumSGD {
+    /// Create a new MomentumSGD instance.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `params` - Parameter variable IDs
+    /// * `lr` - Learning rate
+    /// * `momentum` - Momentum coefficient
+    /// * `weight_decay` - Coefficient of the L2 penalty
+    pub fn new(params: Vec<usize>, lr: f64, momentum: f64, weight_decay: f64) -> Self {
+        Self { params, lr, momentum, weight_decay, velocities: HashMap::new() }
+    }
+}
+
+impl Optimizer for MomentumSGD {
+    fn params(&self) -> &Vec<usize> {
+        &self.params
+    }
+
+    fn update_one(&mut self, id: usize, param: &Tensor<f64>, grad: &Tensor<f64>) -> Tensor<f64> {
+        let grad = weight_decayed_grad(param, grad, self.weight_decay);
+        let velocity = self.velocities.entry(id)
+            .or_insert_with(|| Tensor::full_like(param, 0.0));
+        *velocity = velocity.scalar_mul(self.momentum.into()) - grad.scalar_mul(self.lr.into());
+        param.clone() + velocity.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ktensor::utility::assert_approx_eq;
+    use crate::graph::Graph;
+
+    #[test]
+    fn step_normal() {
+        let mut graph = Graph::new();
+        let x_id = graph.generate_variable_from_f64_tensor(
+            Tensor::new_from_num_vec(vec![1.0], vec![1]), "x");
+        let mut optimizer = MomentumSGD::new(vec![x_id], 0.1, 0.9, 0.0);
+
+        for _ in 0..2 {
+            graph.variable_table_mut().set_grad_from_f64_tensor(
+                x_id, Tensor::new_from_num_vec(vec![1.0], vec![1]));
+            optimizer.step(&mut graph);
+            optimizer.zero_grad(&mut graph);
+        }
+
+        // v1 = -0.1, v2 = 0.9 * -0.1 - 0.1 = -0.19
+        let x = graph.get_variable_contents_f64(x_id).unwrap();
+        assert_approx_eq(*x.data()[0].data(), 1.0 - 0.1 - 0.19, 1e-12);
+    }
+}
@@ -0,0 +1,86 @@
+use std::collections::HashMap;
+use ktensor::Tensor;
+use super::{Optimizer, weight_decayed_grad};
+
+/// RMSProp
+/// 
+/// `h = decay * h + (1 - decay) * grad * grad`,
+/// `param -= lr * grad / (sqrt(h) + eps)`
+/// 
+/// # Fields
+/// 
+/// * `params` - Parameter variable IDs
+/// * `lr` - Learning rate
+/// * `decay` - Decay rate of the moving average
+/// * `eps` - Small value to avoid division by zero
+/// * `weight_decay` - Coefficient of the L2 penalty
+/// * `hs` - Moving average of the squared gradients of each parameter
+#[derive(Debug, Clone)]
+pub struct RMSProp {
+    params: Vec<usize>,
+    lr: f64,
+    decay: f64,
+    eps: f64,
+    weight_decay: f64,
+    hs: HashMap<usize, Tensor<f64>>,
+}
+
+impl RMSProp {
+    /// Create a new RMSProp instance.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `params` - Parameter variable IDs
+    /// * `lr` - Learning rate
+    /// * `decay` - Decay rate of the moving average
+    /// * `eps` - Small value to avoid division by zero
+    /// * `weight_decay` - Coefficient of the L2 penalty
+    pub fn new(params: Vec<usize>, lr: f64, decay: f64, eps: f64, weight_decay: f64) -> Self {
+        Self { params, lr, decay, eps, weight_decay, hs: HashMap::new() }
+    }
+}
+
+impl Optimizer for RMSProp {
+    fn params(&self) -> &Vec<usize> {
+        &self.params
+    }
+
+    fn update_one(&mut self, id: usize, param: &Tensor<f64>, grad: &Tensor<f64>) -> Tensor<f64> {
+        let grad = weight_decayed_grad(param, grad, self.weight_decay);
+        let h = self.hs.entry(id)
+            .or_insert_with(|| Tensor::full_like(param, 0.0));
+        *h = h.scalar_mul(self.decay.into())
+            + (grad.clone() * grad.clone()).scalar_mul((1.0 - self.decay).into());
+        let denominator = h.powf(0.5).scalar_add(self.eps.into());
+        param.clone() - grad.scalar_mul(self.lr.into()) / denominator
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ktensor::utility::assert_approx_eq;
+    use crate::graph::Graph;
+
+    #[test]
+    fn step_normal() {
+        let mut graph = Graph::new();
+        let x_id = graph.generate_variable_from_f64_tensor(
+            Tensor::new_from_num_vec(vec![1.0], vec![1]), "x");
+        let mut optimizer = RMSProp::new(vec![x_id], 0.01, 0.9, 0.0, 0.0);
+
+        let mut expected = 1.0;
+        let mut h = 0.0;
+        for grad in [2.0, 1.0] {
+            graph.variable_table_mut().set_grad_from_f64_tensor(
+                x_id, Tensor::new_from_num_vec(vec![grad], vec![1]));
+            optimizer.step(&mut graph);
+            optimizer.zero_grad(&mut graph);
+            h = 0.9 * h + 0.1 * grad * grad;
+            expected -= 0.01 * grad / f64::sqrt(h);
+        }
+
+        let x = graph.get_variable_contents_f64(x_id).unwrap();
+        assert_approx_eq(*x.data()[0].data(), expected, 1e-12);
+    }
+}
@@ -0,0 +1,96 @@
+use ktensor::Tensor;
+use super::{Optimizer, weight_decayed_grad};
+
+/// Stochastic gradient descent
+/// 
+/// `param -= lr * (grad + weight_decay * param)`
+/// 
+/// # Fields
+/// 
+/// * `params` - Parameter variable IDs
+/// * `lr` - Learning rate
+/// * `weight_decay` - Coefficient of the L2 penalty
+#[derive(Debug, Clone)]
+pub struct SGD {
+    params: Vec<usize>,
+    lr: f64,
+    weight_decay: f64,
+}
+
+impl SGD {
+    /// Create a new SGD instance.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `params` - Parameter variable IDs
+    /// * `lr` - Learning rate
+    /// * `weight_decay` - Coefficient of the L2 penalty
+    pub fn new(params: Vec<usize>, lr: f64, weight_decay: f64) -> Self {
+        Self { params, lr, weight_decay }
+    }
+}
+
+impl Optimizer for SGD {
+    fn params(&self) -> &Vec<usize> {
+        &self.params
+    }
+
+    fn update_one(&mut self, _id: usize, param: &Tensor<f64>, grad: &Tensor<f64>) -> Tensor<f64> {
+        let grad = weight_decayed_grad(param, grad, self.weight_decay);
+        param.clone() - grad.scalar_mul(self.lr.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::Graph;
+    use crate::function::operator::Square;
+
+    #[test]
+    fn step_normal() {
+        let mut graph = Graph::new();
+        let x_id = graph.generate_variable_from_f64_tensor(
+            Tensor::new_from_num_vec(vec![1.0, 2.0], vec![2]), "x");
+        let square_id = graph.generate_function_from_function_contents(Box::new(Square::new()));
+        let y_id = graph.forward(square_id, vec![x_id])[0];
+        graph.backward(vec![y_id], false, false);
+
+        let mut optimizer = SGD::new(vec![x_id], 0.1, 0.0);
+        optimizer.step(&mut graph);
+
+        let x = graph.get_variable_contents_f64(x_id).unwrap();
+        assert_eq!(x, &Tensor::new_from_num_vec(vec![0.8, 1.6], vec![2]));
+
+        optimizer.zero_grad(&mut graph);
+        assert!(graph.get_variable_grad_id(x_id).is_none());
+    }
+
+    #[test]
+    fn step_weight_decay() {
+        let mut graph = Graph::new();
+        let x_id = graph.generate_variable_from_f64_tensor(
+            Tensor::new_from_num_vec(vec![1.0, 2.0], vec![2]), "x");
+        graph.variable_table_mut().set_grad_from_f64_tensor(
+            x_id, Tensor::new_from_num_vec(vec![0.0, 0.0], vec![2]));
+
+        let mut optimizer = SGD::new(vec![x_id], 0.5, 0.5);
+        optimizer.step(&mut graph);
+
+        let x = graph.get_variable_contents_f64(x_id).unwrap();
+        assert_eq!(x, &Tensor::new_from_num_vec(vec![0.75, 1.5], vec![2]));
+    }
+
+    #[test]
+    fn step_without_grad() {
+        let mut graph = Graph::new();
+        let x_id = graph.generate_variable_from_f64_tensor(
+            Tensor::new_from_num_vec(vec![1.0, 2.0], vec![2]), "x");
+
+        let mut optimizer = SGD::new(vec![x_id], 0.1, 0.0);
+        optimizer.step(&mut graph);
+
+        let x = graph.get_variable_contents_f64(x_id).unwrap();
+        assert_eq!(x, &Tensor::new_from_num_vec(vec![1.0, 2.0], vec![2]));
+    }
+}
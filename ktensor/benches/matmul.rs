@@ -0,0 +1,53 @@
+//! Benchmark of `Tensor::matmul` against the previous naive implementation.
+//!
+//! Run with `cargo bench -p ktensor --bench matmul`.
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+use ktensor::{Tensor, Scaler};
+
+/// The previous implementation: an `i-j-k` triple loop over 2-D matrices
+fn naive_matmul(x: &Tensor<f64>, y: &Tensor<f64>) -> Tensor<f64> {
+    let (m, k, n) = (x.shape()[0], x.shape()[1], y.shape()[1]);
+    let (a, b) = (x.data(), y.data());
+    let mut data = vec![Scaler::from(0.0); m * n];
+    for i in 0..m {
+        for j in 0..n {
+            for l in 0..k {
+                data[i * n + j] += a[i * k + l] * b[l * n + j];
+            }
+        }
+    }
+    Tensor::new(data, [m, n])
+}
+
+/// Average time of `f` over enough iterations to take about `budget`
+fn measure<F: FnMut() -> Tensor<f64>>(mut f: F, budget: Duration) -> Duration {
+    black_box(f());
+    let mut iterations = 0;
+    let start = Instant::now();
+    while iterations == 0 || start.elapsed() < budget {
+        black_box(f());
+        iterations += 1;
+    }
+    start.elapsed() / iterations
+}
+
+fn main() {
+    let budget = Duration::from_millis(500);
+    println!("{:>16} {:>14} {:>14} {:>8}", "shape", "naive", "matmul", "speedup");
+    // (m, k, n), including the 100x10 MLP of step43
+    let shapes = [(100, 1, 10), (100, 10, 1), (64, 64, 64), (128, 128, 128), (256, 256, 256), (512, 512, 512)];
+    for (m, k, n) in shapes {
+        let x = Tensor::<f64>::arrange([m, k]).scalar_mul(1e-3.into()).sin();
+        let y = Tensor::<f64>::arrange([k, n]).scalar_mul(1e-3.into()).cos();
+        assert_eq!(naive_matmul(&x, &y), x.matmul(&y));
+
+        let naive = measure(|| naive_matmul(black_box(&x), black_box(&y)), budget);
+        let blocked = measure(|| black_box(&x).matmul(black_box(&y)), budget);
+        println!(
+            "{:>16} {:>14?} {:>14?} {:>7.2}x",
+            format!("{}x{}x{}", m, k, n), naive, blocked, naive.as_secs_f64() / blocked.as_secs_f64(),
+        );
+    }
+}
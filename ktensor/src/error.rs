@@ -0,0 +1,87 @@
+/// Error of the fallible `try_*` functions of Tensor
+/// 
+/// The panicking functions panic with the message of this error.
+/// 
+/// # Variants
+/// 
+/// * `SizeMismatch` - The number of elements does not match the shape
+/// * `ShapeMismatch` - Two shapes that must agree do not
+/// * `BroadcastMismatch` - Two shapes cannot be broadcast together
+/// * `NdimMismatch` - The number of dimensions is not the expected one
+/// * `IndexOutOfRange` - An index is not less than the size of its axis
+/// * `InvalidAxis` - An axis is not less than the number of dimensions
+/// * `RepeatedAxis` - An axis appears twice where each axis must appear once
+/// * `InvalidStep` - The step of a slice is 0
+/// * `InvalidShape` - The shape is not allowed for the operation
+/// * `DTypeMismatch` - The data type is not the expected one
+/// * `InvalidRange` - The lower bound is greater than the upper bound or a bound is NaN
+#[derive(Debug, Clone, PartialEq)]
+pub enum TensorError {
+    SizeMismatch { size: usize, shape: Vec<usize> },
+    ShapeMismatch { left: Vec<usize>, right: Vec<usize> },
+    BroadcastMismatch { left: Vec<usize>, right: Vec<usize> },
+    NdimMismatch { expected: usize, actual: usize },
+    IndexOutOfRange { index: Vec<usize>, shape: Vec<usize> },
+    InvalidAxis { axis: usize, ndim: usize },
+    RepeatedAxis { axis: usize },
+    InvalidStep { axis: usize },
+    InvalidShape { shape: Vec<usize>, reason: &'static str },
+    DTypeMismatch { expected: String, actual: String },
+    InvalidRange { min: f64, max: f64 },
+}
+
+impl std::fmt::Display for TensorError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TensorError::SizeMismatch { size, shape } =>
+                write!(f, "Shape mismatch: {} elements cannot have the shape {:?}", size, shape),
+            TensorError::ShapeMismatch { left, right } =>
+                write!(f, "Shape mismatch: {:?} and {:?}", left, right),
+            TensorError::BroadcastMismatch { left, right } =>
+                write!(f, "Shape mismatch: {:?} and {:?} cannot be broadcast", left, right),
+            TensorError::NdimMismatch { expected, actual } =>
+                write!(f, "Shape mismatch: expected {} dimensions, but got {}", expected, actual),
+            TensorError::IndexOutOfRange { index, shape } =>
+                write!(f, "Index out of range: {:?} for the shape {:?}", index, shape),
+            TensorError::InvalidAxis { axis, ndim } =>
+                write!(f, "Invalid axis: {} for {} dimensions", axis, ndim),
+            TensorError::RepeatedAxis { axis } =>
+                write!(f, "Repeated axis: {}", axis),
+            TensorError::InvalidStep { axis } =>
+                write!(f, "Invalid step: the step of the axis {} must be positive", axis),
+            TensorError::InvalidShape { shape, reason } =>
+                write!(f, "Invalid shape {:?}: {}", shape, reason),
+            TensorError::DTypeMismatch { expected, actual } =>
+                write!(f, "DType mismatch: expected {}, but got {}", expected, actual),
+            TensorError::InvalidRange { min, max } =>
+                write!(f, "Invalid range: min {} must not be greater than max {}", min, max),
+        }
+    }
+}
+
+impl std::error::Error for TensorError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn display_normal() {
+        let error = TensorError::ShapeMismatch { left: vec![2, 3], right: vec![4] };
+        assert_eq!(error.to_string(), "Shape mismatch: [2, 3] and [4]");
+        let error = TensorError::InvalidAxis { axis: 2, ndim: 2 };
+        assert_eq!(error.to_string(), "Invalid axis: 2 for 2 dimensions");
+        let error = TensorError::InvalidStep { axis: 1 };
+        assert_eq!(error.to_string(), "Invalid step: the step of the axis 1 must be positive");
+        let error = TensorError::DTypeMismatch { expected: "f64".to_string(), actual: "f32".to_string() };
+        assert_eq!(error.to_string(), "DType mismatch: expected f64, but got f32");
+        let error = TensorError::InvalidRange { min: 1.0, max: -1.0 };
+        assert_eq!(error.to_string(), "Invalid range: min 1 must not be greater than max -1");
+    }
+
+    #[test]
+    fn error_normal() {
+        let error: Box<dyn std::error::Error> = Box::new(TensorError::RepeatedAxis { axis: 0 });
+        assert_eq!(error.to_string(), "Repeated axis: 0");
+    }
+}
@@ -1,5 +1,7 @@
 pub mod tensor;
 pub mod num;
 pub mod utility;
+pub mod error;
 
-pub use crate::tensor::{Tensor, Scaler};
+pub use crate::tensor::{Tensor, Scaler, SliceIndex};
+pub use crate::error::TensorError;
@@ -1,3 +1,7 @@
 mod from_usize;
+mod from_f64;
+mod sqrt;
 
 pub use from_usize::FromUsize;
+pub use from_f64::FromF64;
+pub use sqrt::Sqrt;
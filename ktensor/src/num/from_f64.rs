@@ -0,0 +1,15 @@
+pub trait FromF64: Sized {
+    fn from_f64(x: f64) -> Self;
+}
+
+impl FromF64 for f32 {
+    fn from_f64(x: f64) -> Self {
+        x as f32
+    }
+}
+
+impl FromF64 for f64 {
+    fn from_f64(x: f64) -> Self {
+        x
+    }
+}
@@ -1,22 +1,78 @@
 mod scaler;
 mod specialize;
 pub mod random;
+pub mod initializer;
+mod reduce;
+mod index;
+mod join;
 
+use std::borrow::Cow;
+use std::sync::Arc;
 use crate::num::FromUsize;
+use crate::error::TensorError;
 
 pub use self::scaler::Scaler;
+pub use self::index::SliceIndex;
+
+/// Compute the shape that two shapes broadcast to
+/// 
+/// The shapes are aligned at the last axis,
+/// and each pair of sizes must be equal or one of them must be 1.
+/// 
+/// # Arguments
+/// 
+/// * `shape0` - First shape
+/// * `shape1` - Second shape
+/// 
+/// # Panics
+/// 
+/// Panics if the shapes cannot be broadcast.
+pub fn broadcast_shapes(shape0: &[usize], shape1: &[usize]) -> Vec<usize> {
+    try_broadcast_shapes(shape0, shape1).unwrap_or_else(|e| panic!("{}", e))
+}
+
+/// Compute the shape that two shapes broadcast to
+/// 
+/// # Arguments
+/// 
+/// * `shape0` - First shape
+/// * `shape1` - Second shape
+/// 
+/// # Errors
+/// 
+/// `BroadcastMismatch` if the shapes cannot be broadcast.
+pub fn try_broadcast_shapes(shape0: &[usize], shape1: &[usize]) -> Result<Vec<usize>, TensorError> {
+    let ndim = shape0.len().max(shape1.len());
+    (0..ndim).map(|i| {
+        let size0 = (i + shape0.len()).checked_sub(ndim).map_or(1, |j| shape0[j]);
+        let size1 = (i + shape1.len()).checked_sub(ndim).map_or(1, |j| shape1[j]);
+        match (size0, size1) {
+            (size0, size1) if size0 == size1 => Ok(size0),
+            (1, size) | (size, 1) => Ok(size),
+            _ => Err(TensorError::BroadcastMismatch { left: shape0.to_vec(), right: shape1.to_vec() }),
+        }
+    }).collect()
+}
 
 /// Struct to perform calculations
 /// 
+/// The contents are stored in a buffer that is shared between views,
+/// so reshape, transpose, permute, slice and broadcast do not copy the contents.
+/// The element at `indexes` is `data[offset + sum(indexes[i] * strides[i])]`.
+/// 
 /// # Fields
 /// 
-/// * `data` - Contents of Tensor
+/// * `data` - Shared buffer of the contents
 /// * `shape` - Tensor shape
-#[derive(Debug, Clone, PartialEq)]
+/// * `strides` - Step in the buffer for each axis
+/// * `offset` - Position of the first element in the buffer
+#[derive(Clone)]
 pub struct Tensor<T>
 {
-    data: Vec<Scaler<T>>,
+    data: Arc<Vec<Scaler<T>>>,
     shape: Vec<usize>,
+    strides: Vec<usize>,
+    offset: usize,
 }
 
 impl<T> Tensor<T>
@@ -32,17 +88,71 @@ impl<T> Tensor<T>
     /// 
     /// Panics if the shape is not correct.
     fn check_shape(data: &Vec<Scaler<T>>, shape: &Vec<usize>) {
-        if shape.len() == 0 {
-            assert_eq!(data.len(), 1, "Shape mismatch");
-            return;
+        Self::try_check_shape(data, shape).unwrap_or_else(|e| panic!("{}", e));
+    }
+
+    /// Check the shape
+    /// 
+    /// # Errors
+    /// 
+    /// `SizeMismatch` if the number of elements is not the size of the shape.
+    fn try_check_shape(data: &[Scaler<T>], shape: &[usize]) -> Result<(), TensorError> {
+        if data.len() != shape.iter().product::<usize>() {
+            return Err(TensorError::SizeMismatch { size: data.len(), shape: shape.to_vec() });
         }
-        let size = shape.iter().product();
-        assert_eq!(data.len(), size, "Shape mismatch");
+        Ok(())
+    }
+
+    /// Check that every axis to reduce is in range and appears once
+    /// 
+    /// # Errors
+    /// 
+    /// * `InvalidAxis` - If an axis is out of range
+    /// * `RepeatedAxis` - If an axis appears twice
+    fn check_reduce_axis(&self, axis: &[usize]) -> Result<(), TensorError> {
+        for (i, &a) in axis.iter().enumerate() {
+            if a >= self.ndim() {
+                return Err(TensorError::InvalidAxis { axis: a, ndim: self.ndim() });
+            }
+            if axis[..i].contains(&a) {
+                return Err(TensorError::RepeatedAxis { axis: a });
+            }
+        }
+        Ok(())
     }
 
-    /// Get the data
-    pub fn data(&self) -> &Vec<Scaler<T>> {
-        &self.data
+    /// Make the strides of a contiguous Tensor
+    fn contiguous_strides(shape: &[usize]) -> Vec<usize> {
+        let mut strides = vec![0; shape.len()];
+        let mut size = 1;
+        for i in (0..shape.len()).rev() {
+            strides[i] = size;
+            size *= shape[i];
+        }
+        strides
+    }
+
+    /// Create a contiguous Tensor from the contents in row-major order
+    fn from_vec(data: Vec<Scaler<T>>, shape: Vec<usize>) -> Self {
+        Self::check_shape(&data, &shape);
+        let strides = Self::contiguous_strides(&shape);
+        Self { data: Arc::new(data), shape, strides, offset: 0 }
+    }
+
+    /// Create a contiguous Tensor from the contents in row-major order
+    /// 
+    /// # Errors
+    /// 
+    /// `SizeMismatch` if the number of elements is not the size of the shape.
+    fn try_from_vec(data: Vec<Scaler<T>>, shape: Vec<usize>) -> Result<Self, TensorError> {
+        Self::try_check_shape(&data, &shape)?;
+        let strides = Self::contiguous_strides(&shape);
+        Ok(Self { data: Arc::new(data), shape, strides, offset: 0 })
+    }
+
+    /// Create a view of the same buffer
+    fn view(&self, shape: Vec<usize>, strides: Vec<usize>, offset: usize) -> Self {
+        Self { data: self.data.clone(), shape, strides, offset }
     }
 
     /// Get the shape
@@ -50,6 +160,16 @@ impl<T> Tensor<T>
         &self.shape
     }
 
+    /// Get the strides
+    pub fn strides(&self) -> &Vec<usize> {
+        &self.strides
+    }
+
+    /// Get the offset in the buffer
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
     /// Get the number of dimensions
     pub fn ndim(&self) -> usize {
         self.shape.len()
@@ -57,7 +177,7 @@ impl<T> Tensor<T>
 
     /// Get the size of the Tensor
     pub fn size(&self) -> usize {
-        self.data.len()
+        self.shape.iter().product()
     }
 
     /// Get the data type
@@ -70,22 +190,31 @@ impl<T> Tensor<T>
         self.ndim() == 0
     }
 
-    fn calc_at_index(&self, indexes: &[usize]) -> usize {
-        if self.is_scalar() {
-            assert!(indexes.len() == 0, "Shape mismatch");
-            return 0;
-        }
-        assert_eq!(indexes.len(), self.ndim(), "Shape mismatch");
-        for i in 0..self.ndim() {
-            assert!(indexes[i] < self.shape[i], "Index out of range");
-        }
-        let mut index = 0;
+    /// Check if the elements are laid out in row-major order without gaps
+    pub fn is_contiguous(&self) -> bool {
         let mut size = 1;
         for i in (0..self.ndim()).rev() {
-            index += indexes[i] * size;
+            if self.shape[i] != 1 && self.strides[i] != size {
+                return false;
+            }
             size *= self.shape[i];
         }
-        index
+        true
+    }
+
+    /// Check if two Tensors are views of the same buffer
+    pub fn shares_data(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.data, &other.data)
+    }
+
+    fn try_calc_at_index(&self, indexes: &[usize]) -> Result<usize, TensorError> {
+        if indexes.len() != self.ndim() {
+            return Err(TensorError::NdimMismatch { expected: self.ndim(), actual: indexes.len() });
+        }
+        if indexes.iter().zip(self.shape.iter()).any(|(i, s)| i >= s) {
+            return Err(TensorError::IndexOutOfRange { index: indexes.to_vec(), shape: self.shape.clone() });
+        }
+        Ok(self.offset + indexes.iter().zip(self.strides.iter()).map(|(i, s)| i * s).sum::<usize>())
     }
 
     /// Get the value of the Tensor
@@ -98,25 +227,264 @@ impl<T> Tensor<T>
     /// 
     /// Panics if the shape is not correct.
     pub fn at(&self, indexes: &[usize]) -> &Scaler<T> {
-        let index = self.calc_at_index(indexes);
-        &self.data[index]
+        self.try_at(indexes).unwrap_or_else(|e| panic!("{}", e))
     }
 
-    /// Get the value of a Tensor that can be changed
+    /// Get the value of the Tensor
     /// 
     /// # Arguments
     /// 
     /// * `indexes` - Indexes of the Tensor
     /// 
+    /// # Errors
+    /// 
+    /// * `NdimMismatch` - If the number of indexes is not the number of dimensions
+    /// * `IndexOutOfRange` - If an index is not less than the size of its axis
+    pub fn try_at(&self, indexes: &[usize]) -> Result<&Scaler<T>, TensorError> {
+        let index = self.try_calc_at_index(indexes)?;
+        Ok(&self.data[index])
+    }
+
+    /// Iterate over the elements in row-major order
+    pub fn iter(&self) -> TensorIter<'_, T> {
+        TensorIter { tensor: self, index: vec![0; self.ndim()], remaining: self.size() }
+    }
+
+    /// Reshape the Tensor without copying, if it is contiguous
+    fn reshape_view(&self, shape: Vec<usize>) -> Option<Self> {
+        if !self.is_contiguous() {
+            return None;
+        }
+        let strides = Self::contiguous_strides(&shape);
+        Some(self.view(shape, strides, self.offset))
+    }
+
+    /// Transpose the Tensor
+    /// 
+    /// The order of the axes is reversed. The contents are not copied.
+    pub fn transpose(&self) -> Self {
+        let mut shape = self.shape.clone();
+        let mut strides = self.strides.clone();
+        shape.reverse();
+        strides.reverse();
+        self.view(shape, strides, self.offset)
+    }
+
+    /// Permute the axes of the Tensor
+    /// 
+    /// The axis `i` of the result is the axis `axes[i]` of the Tensor.
+    /// The contents are not copied.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `axes` - New order of the axes
+    /// 
+    /// # Panics
+    /// 
+    /// Panics if `axes` is not a permutation of the axes.
+    pub fn permute(&self, axes: &[usize]) -> Self {
+        self.try_permute(axes).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Permute the axes of the Tensor
+    /// 
+    /// # Arguments
+    /// 
+    /// * `axes` - New order of the axes
+    /// 
+    /// # Errors
+    /// 
+    /// * `NdimMismatch` - If the length of `axes` is not the number of dimensions
+    /// * `InvalidAxis` - If an axis is out of range
+    /// * `RepeatedAxis` - If an axis appears twice
+    pub fn try_permute(&self, axes: &[usize]) -> Result<Self, TensorError> {
+        if axes.len() != self.ndim() {
+            return Err(TensorError::NdimMismatch { expected: self.ndim(), actual: axes.len() });
+        }
+        let mut seen = vec![false; self.ndim()];
+        for &axis in axes {
+            if axis >= self.ndim() {
+                return Err(TensorError::InvalidAxis { axis, ndim: self.ndim() });
+            }
+            if seen[axis] {
+                return Err(TensorError::RepeatedAxis { axis });
+            }
+            seen[axis] = true;
+        }
+        let shape = axes.iter().map(|&axis| self.shape[axis]).collect();
+        let strides = axes.iter().map(|&axis| self.strides[axis]).collect();
+        Ok(self.view(shape, strides, self.offset))
+    }
+
+    /// Swap two axes of the Tensor
+    /// 
+    /// The contents are not copied.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `axis1` - First axis
+    /// * `axis2` - Second axis
+    /// 
+    /// # Panics
+    /// 
+    /// Panics if an axis is out of range.
+    pub fn swapaxes(&self, axis1: usize, axis2: usize) -> Self {
+        self.try_swapaxes(axis1, axis2).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Swap two axes of the Tensor
+    /// 
+    /// # Arguments
+    /// 
+    /// * `axis1` - First axis
+    /// * `axis2` - Second axis
+    /// 
+    /// # Errors
+    /// 
+    /// `InvalidAxis` if an axis is out of range.
+    pub fn try_swapaxes(&self, axis1: usize, axis2: usize) -> Result<Self, TensorError> {
+        if let Some(&axis) = [axis1, axis2].iter().find(|&&axis| axis >= self.ndim()) {
+            return Err(TensorError::InvalidAxis { axis, ndim: self.ndim() });
+        }
+        let mut axes = (0..self.ndim()).collect::<Vec<usize>>();
+        axes.swap(axis1, axis2);
+        self.try_permute(&axes)
+    }
+
+    /// Slice the Tensor along the axis
+    /// 
+    /// The contents are not copied.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `axis` - Axis to slice
+    /// * `start` - First index, inclusive
+    /// * `end` - Last index, exclusive
+    /// 
+    /// # Panics
+    /// 
+    /// Panics if the axis or the range is out of range.
+    pub fn slice_axis(&self, axis: usize, start: usize, end: usize) -> Self {
+        self.try_slice_axis(axis, start, end).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Slice the Tensor along the axis
+    /// 
+    /// # Arguments
+    /// 
+    /// * `axis` - Axis to slice
+    /// * `start` - First index, inclusive
+    /// * `end` - Last index, exclusive
+    /// 
+    /// # Errors
+    /// 
+    /// * `InvalidAxis` - If the axis is out of range
+    /// * `IndexOutOfRange` - If the range is out of range
+    pub fn try_slice_axis(&self, axis: usize, start: usize, end: usize) -> Result<Self, TensorError> {
+        if axis >= self.ndim() {
+            return Err(TensorError::InvalidAxis { axis, ndim: self.ndim() });
+        }
+        if start > end || end > self.shape[axis] {
+            let mut index = vec![0; self.ndim()];
+            index[axis] = end;
+            return Err(TensorError::IndexOutOfRange { index, shape: self.shape.clone() });
+        }
+        let mut shape = self.shape.clone();
+        shape[axis] = end - start;
+        let offset = if start < self.shape[axis] {
+            self.offset + start * self.strides[axis]
+        } else {
+            self.offset
+        };
+        Ok(self.view(shape, self.strides.clone(), offset))
+    }
+
+    /// Broadcast the Tensor
+    /// 
+    /// The broadcast axes have the stride 0, so the contents are not copied.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `shape` - Tensor shape
+    /// 
     /// # Panics
     /// 
     /// Panics if the shape is not correct.
-    pub fn at_mut(&mut self, indexes: &[usize]) -> &mut Scaler<T> {
-        let index = self.calc_at_index(indexes);
-        &mut self.data[index]
+    pub fn broadcast_to(&self, shape: &[usize]) -> Self {
+        self.try_broadcast_to(shape).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Broadcast the Tensor
+    /// 
+    /// # Arguments
+    /// 
+    /// * `shape` - Tensor shape
+    /// 
+    /// # Errors
+    /// 
+    /// `BroadcastMismatch` if the Tensor cannot be broadcast to the shape.
+    pub fn try_broadcast_to(&self, shape: &[usize]) -> Result<Self, TensorError> {
+        let error = || TensorError::BroadcastMismatch { left: self.shape.clone(), right: shape.to_vec() };
+        let diff = shape.len().checked_sub(self.ndim()).ok_or_else(error)?;
+        let mut strides = vec![0; shape.len()];
+        for i in 0..shape.len() {
+            let (self_size, self_stride) = match i.checked_sub(diff) {
+                Some(j) => (self.shape[j], self.strides[j]),
+                None => (1, 0),
+            };
+            if shape[i] == self_size {
+                strides[i] = self_stride;
+                continue;
+            }
+            if shape[i] == 0 || self_size != 1 {
+                return Err(error());
+            }
+        }
+        Ok(self.view(shape.to_vec(), strides, self.offset))
     }
 }
 
+/// Iterator over the elements of a Tensor in row-major order
+/// 
+/// # Fields
+/// 
+/// * `tensor` - Tensor
+/// * `index` - Indexes of the next element
+/// * `remaining` - Number of the remaining elements
+pub struct TensorIter<'a, T> {
+    tensor: &'a Tensor<T>,
+    index: Vec<usize>,
+    remaining: usize,
+}
+
+impl<'a, T> Iterator for TensorIter<'a, T> {
+    type Item = &'a Scaler<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+        self.remaining -= 1;
+        let tensor = self.tensor;
+        let position = tensor.offset + self.index.iter()
+            .zip(tensor.strides.iter()).map(|(i, s)| i * s).sum::<usize>();
+        for axis in (0..self.index.len()).rev() {
+            self.index[axis] += 1;
+            if self.index[axis] < tensor.shape[axis] {
+                break;
+            }
+            self.index[axis] = 0;
+        }
+        Some(&tensor.data[position])
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<T> ExactSizeIterator for TensorIter<'_, T> {}
+
 impl<T> Tensor<T>
 where
     T: Clone
@@ -132,10 +500,21 @@ where
     /// 
     /// Panics if the shape is not correct.
     pub fn new<U: AsRef<[Scaler<T>]>, V: AsRef<[usize]>>(data: U, shape: V) -> Self {
-        let data = data.as_ref().to_vec();
-        let shape = shape.as_ref().to_owned();
-        Self::check_shape(&data, &shape);
-        Self { data, shape }
+        Self::try_new(data, shape).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Create a new Tensor
+    /// 
+    /// # Arguments
+    /// 
+    /// * `data` - Contents of Tensor
+    /// * `shape` - Tensor shape
+    /// 
+    /// # Errors
+    /// 
+    /// `SizeMismatch` if the number of elements is not the size of the shape.
+    pub fn try_new<U: AsRef<[Scaler<T>]>, V: AsRef<[usize]>>(data: U, shape: V) -> Result<Self, TensorError> {
+        Self::try_from_vec(data.as_ref().to_vec(), shape.as_ref().to_owned())
     }
 
     /// Create a new Tensor from numbers
@@ -149,51 +528,163 @@ where
     /// 
     /// Panics if the shape is not correct.
     pub fn new_from_num_vec<U: IntoIterator<Item = T>, V: AsRef<[usize]>>(data: U, shape: V) -> Self {
-        let data: Vec<Scaler<T>> = data.into_iter().map(Scaler::from).collect();
-        Tensor::new(data, shape)
+        Self::try_new_from_num_vec(data, shape).unwrap_or_else(|e| panic!("{}", e))
     }
 
-    /// Reshape the Tensor
+    /// Create a new Tensor from numbers
     /// 
     /// # Arguments
     /// 
+    /// * `data` - Contents of Tensor
     /// * `shape` - Tensor shape
     /// 
+    /// # Errors
+    /// 
+    /// `SizeMismatch` if the number of elements is not the size of the shape.
+    pub fn try_new_from_num_vec<U: IntoIterator<Item = T>, V: AsRef<[usize]>>(data: U, shape: V) -> Result<Self, TensorError> {
+        let data: Vec<Scaler<T>> = data.into_iter().map(Scaler::from).collect();
+        Self::try_from_vec(data, shape.as_ref().to_owned())
+    }
+
+    /// Get the data in row-major order
+    /// 
+    /// The buffer is borrowed if the Tensor is contiguous,
+    /// otherwise the elements are copied.
+    pub fn data(&self) -> Cow<'_, [Scaler<T>]> {
+        if self.is_contiguous() {
+            Cow::Borrowed(&self.data[self.offset..self.offset + self.size()])
+        } else {
+            Cow::Owned(self.iter().cloned().collect())
+        }
+    }
+
+    /// Get the data in row-major order as an owned vector
+    /// 
+    /// The buffer is moved without copying if it is not shared and is contiguous.
+    pub fn into_data(self) -> Vec<Scaler<T>> {
+        let size = self.size();
+        if self.is_contiguous() && self.offset == 0 && self.data.len() == size {
+            Arc::try_unwrap(self.data).unwrap_or_else(|data| data.as_ref().clone())
+        } else {
+            self.data().into_owned()
+        }
+    }
+
+    /// Make a contiguous Tensor
+    /// 
+    /// The contents are copied into a new buffer unless the Tensor is already contiguous.
+    pub fn contiguous(&self) -> Self {
+        if self.is_contiguous() {
+            return self.clone();
+        }
+        Self::from_vec(self.iter().cloned().collect(), self.shape.clone())
+    }
+
+    /// Apply the function to each element
+    /// 
+    /// # Arguments
+    /// 
+    /// * `f` - Function to apply
+    pub fn map<U, F: FnMut(&Scaler<T>) -> Scaler<U>>(&self, f: F) -> Tensor<U> {
+        Tensor::from_vec(self.iter().map(f).collect(), self.shape.clone())
+    }
+
+    /// Apply the function to each pair of elements of the same index
+    /// 
+    /// The Tensors are broadcast to the same shape by the rules of numpy.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `other` - Other Tensor
+    /// * `f` - Function to apply
+    /// 
     /// # Panics
     /// 
-    /// Panics if the shape is not correct.
-    pub fn reshape<U: AsRef<[usize]>>(&self, shape: U) -> Self {
-        let shape = shape.as_ref().to_owned();
-        Self::check_shape(&self.data, &shape);
-        Self { data: self.data.clone(), shape }
+    /// Panics if the shapes cannot be broadcast.
+    pub fn zip_map<U: Clone, V, F: FnMut(&Scaler<T>, &Scaler<U>) -> Scaler<V>>(&self, other: &Tensor<U>, f: F) -> Tensor<V> {
+        self.try_zip_map(other, f).unwrap_or_else(|e| panic!("{}", e))
     }
 
-    /// Transpose the Tensor
-    pub fn transpose(&self) -> Self {
-        let mut shape = self.shape.clone();
-        shape.reverse();
-        let mut new_tensor = Self::new(self.data.clone(), shape);
-
-        let mut index = vec![0; self.ndim()];
-        for _ in 0..self.data.len() {
-            let value = self.at(&index);
-            let mut new_index = index.clone();
-            new_index.reverse();
-            *new_tensor.at_mut(&new_index) = value.clone();
-
-            for j in 0..self.ndim() {
-                index[j] += 1;
-                if index[j] < self.shape[j] {
-                    break;
-                }
-                index[j] = 0;
-            }
+    /// Apply the function to each pair of elements of the same index
+    /// 
+    /// # Arguments
+    /// 
+    /// * `other` - Other Tensor
+    /// * `f` - Function to apply
+    /// 
+    /// # Errors
+    /// 
+    /// `BroadcastMismatch` if the shapes cannot be broadcast.
+    pub fn try_zip_map<U: Clone, V, F: FnMut(&Scaler<T>, &Scaler<U>) -> Scaler<V>>(&self, other: &Tensor<U>, mut f: F) -> Result<Tensor<V>, TensorError> {
+        if self.shape == other.shape {
+            let data = self.iter().zip(other.iter()).map(|(x, y)| f(x, y)).collect();
+            return Ok(Tensor::from_vec(data, self.shape.clone()));
         }
+        let shape = try_broadcast_shapes(&self.shape, &other.shape)?;
+        let (x, y) = (self.broadcast_to(&shape), other.broadcast_to(&shape));
+        let data = x.iter().zip(y.iter()).map(|(x, y)| f(x, y)).collect();
+        Ok(Tensor::from_vec(data, shape))
+    }
 
-        new_tensor
+    /// Broadcast the other Tensor to the shape of this Tensor for an in-place operation
+    /// 
+    /// # Panics
+    /// 
+    /// Panics if the broadcast shape is not the shape of this Tensor.
+    fn broadcast_other<'a>(&self, other: &'a Self) -> Cow<'a, Self> {
+        if self.shape == other.shape {
+            return Cow::Borrowed(other);
+        }
+        Cow::Owned(other.try_broadcast_to(&self.shape).unwrap_or_else(|_| panic!("{}", TensorError::BroadcastMismatch {
+            left: self.shape.clone(), right: other.shape.clone() })))
     }
 
-    /// Broadcast the Tensor
+    /// Get the contents that can be changed in row-major order
+    /// 
+    /// The buffer is copied first if it is shared or the Tensor is not contiguous.
+    fn data_mut(&mut self) -> &mut [Scaler<T>] {
+        let size = self.size();
+        if !(self.is_contiguous() && self.offset == 0 && self.data.len() == size) {
+            *self = Self::from_vec(self.iter().cloned().collect(), self.shape.clone());
+        }
+        &mut Arc::make_mut(&mut self.data)[..]
+    }
+
+    /// Get the value of a Tensor that can be changed
+    /// 
+    /// The buffer is copied first if it is shared with another view.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `indexes` - Indexes of the Tensor
+    /// 
+    /// # Panics
+    /// 
+    /// Panics if the shape is not correct.
+    pub fn at_mut(&mut self, indexes: &[usize]) -> &mut Scaler<T> {
+        self.try_at_mut(indexes).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Get the value of a Tensor that can be changed
+    /// 
+    /// # Arguments
+    /// 
+    /// * `indexes` - Indexes of the Tensor
+    /// 
+    /// # Errors
+    /// 
+    /// * `NdimMismatch` - If the number of indexes is not the number of dimensions
+    /// * `IndexOutOfRange` - If an index is not less than the size of its axis
+    pub fn try_at_mut(&mut self, indexes: &[usize]) -> Result<&mut Scaler<T>, TensorError> {
+        self.try_calc_at_index(indexes)?;
+        let index = Self::contiguous_strides(&self.shape).iter()
+            .zip(indexes.iter()).map(|(s, i)| s * i).sum::<usize>();
+        Ok(&mut self.data_mut()[index])
+    }
+
+    /// Reshape the Tensor
+    /// 
+    /// The contents are copied only if the Tensor is not contiguous.
     /// 
     /// # Arguments
     /// 
@@ -202,33 +693,49 @@ where
     /// # Panics
     /// 
     /// Panics if the shape is not correct.
-    pub fn broadcast_to(&self, shape: &[usize]) -> Self {
-        let mut self_shape = self.shape().clone();
-        let mut data = self.data.clone();
-        assert!(self_shape.len() <= shape.len(), "shape len is too small");
-        while self_shape.len() < shape.len() {
-            self_shape.insert(0, 1);
-        }
-        let mut size = 1;
-        for i in (0..self_shape.len()).rev() {
-            if shape[i] == 1 || shape[i] == self_shape[i] {
-                size *= shape[i];
-                continue;
-            }
-            assert!(shape[i] != 0, "shape is 0");
-            assert_eq!(self_shape[i], 1, "self shape is not 1");
-            let mut new_data = Vec::new();
-            let mut index = 0;
-            while index < data.len() {
-                for _ in 0..shape[i] {
-                    new_data.extend(data[index..index + size].iter().map(|x| x.clone()))
-                }
-                index += size;
-            }
-            size *= shape[i];
-            data = new_data;
+    pub fn reshape<U: AsRef<[usize]>>(&self, shape: U) -> Self {
+        self.try_reshape(shape).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Reshape the Tensor
+    /// 
+    /// # Arguments
+    /// 
+    /// * `shape` - Tensor shape
+    /// 
+    /// # Errors
+    /// 
+    /// `SizeMismatch` if the size of the shape is not the size of the Tensor.
+    pub fn try_reshape<U: AsRef<[usize]>>(&self, shape: U) -> Result<Self, TensorError> {
+        let shape = shape.as_ref().to_owned();
+        if self.size() != shape.iter().product::<usize>() {
+            return Err(TensorError::SizeMismatch { size: self.size(), shape });
         }
-        Self::new(data, shape)
+        Ok(match self.reshape_view(shape.clone()) {
+            Some(tensor) => tensor,
+            None => Self::from_vec(self.iter().cloned().collect(), shape),
+        })
+    }
+}
+
+impl<T> std::fmt::Debug for Tensor<T>
+where
+    T: std::fmt::Debug
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Tensor")
+            .field("data", &self.iter().collect::<Vec<_>>())
+            .field("shape", &self.shape)
+            .finish()
+    }
+}
+
+impl<T> PartialEq for Tensor<T>
+where
+    T: PartialEq
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.shape == other.shape && self.iter().eq(other.iter())
     }
 }
 
@@ -251,7 +758,7 @@ where
         let data = (0..size)
             .map(|x| Scaler::from(T::from_usize(x)))
             .collect();
-        Self { data, shape }
+        Self::from_vec(data, shape)
     }
 }
 
@@ -261,7 +768,7 @@ where
 {
     /// Sum all the values in the Tensor
     pub fn sum_all(&self) -> Scaler<T> {
-        self.data.iter().sum()
+        self.iter().sum()
     }
 
     fn make_sum_axis(&self, axis: &[usize]) -> Vec<usize> {
@@ -293,13 +800,33 @@ where
     /// 
     /// # Arguments
     /// 
-    /// * `axis` - Axis to sum along
+    /// * `axis` - Axis to sum along. If empty, all the axes are summed
     /// * `keepdims` - Keep the dimensions
     /// 
     /// # Returns
     /// 
     /// A new Tensor with the summed values
+    /// 
+    /// # Panics
+    /// 
+    /// Panics if an axis is out of range or appears twice.
     pub fn sum<U: AsRef<[usize]>>(&self, axis: U, keepdims: bool) -> Self {
+        self.try_sum(axis, keepdims).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Sum the values in the Tensor along the given axis
+    /// 
+    /// # Arguments
+    /// 
+    /// * `axis` - Axis to sum along. If empty, all the axes are summed
+    /// * `keepdims` - Keep the dimensions
+    /// 
+    /// # Errors
+    /// 
+    /// * `InvalidAxis` - If an axis is out of range
+    /// * `RepeatedAxis` - If an axis appears twice
+    pub fn try_sum<U: AsRef<[usize]>>(&self, axis: U, keepdims: bool) -> Result<Self, TensorError> {
+        self.check_reduce_axis(axis.as_ref())?;
         let axis = self.make_sum_axis(axis.as_ref());
         let new_shape = self.make_sum_new_shape(&axis, true);
         let mut data = vec![Scaler::from(T::default()); new_shape.iter().product()];
@@ -323,7 +850,7 @@ where
         }
 
         let new_shape = self.make_sum_new_shape(&axis, keepdims);
-        Self::new(data, new_shape)
+        Ok(Self::new(data, new_shape))
     }
 
     /// Sum the values for the given shape
@@ -336,56 +863,220 @@ where
     /// 
     /// Panics if the shape is not correct.
     pub fn sum_to(&self, shape: &[usize]) -> Self {
-        assert!(shape.len() <= self.ndim(), "shape len is too big");
+        self.try_sum_to(shape).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Sum the values for the given shape
+    /// 
+    /// # Arguments
+    /// 
+    /// * `shape` - Shape to sum to
+    /// 
+    /// # Errors
+    /// 
+    /// `BroadcastMismatch` if the shape cannot be broadcast to the shape of the Tensor.
+    pub fn try_sum_to(&self, shape: &[usize]) -> Result<Self, TensorError> {
+        let error = || TensorError::BroadcastMismatch { left: self.shape.clone(), right: shape.to_vec() };
+        let index = self.ndim().checked_sub(shape.len()).ok_or_else(error)?;
         let mut axis = Vec::new();
-        let index = self.ndim() - shape.len();
         for i in 0..index {
             axis.push(i);
         }
         for i in 0..shape.len() {
             if shape[i] == 1 {
                 axis.push(i + index);
-            } else {
-                assert_eq!(shape[i], self.shape[i + index], "shape mismatch");
+            } else if shape[i] != self.shape[i + index] {
+                return Err(error());
             }
         }
-        let mut tensor = self.sum(axis, true);
-        tensor.shape = shape.to_vec();
-        tensor
+        self.sum(axis, true).try_reshape(shape)
+    }
+}
+
+/// Number of multiply-adds from which matmul splits the rows between threads
+const MATMUL_PARALLEL_THRESHOLD: usize = 1 << 18;
+
+/// Block sizes of the matmul kernel along the rows, the inner axis and the columns
+const MATMUL_BLOCK_ROWS: usize = 32;
+const MATMUL_BLOCK_INNER: usize = 128;
+const MATMUL_BLOCK_COLS: usize = 256;
+
+/// Matrix product of contiguous batches of row-major matrices
+/// 
+/// The rows of all batches are numbered in order, so a range of rows can be given to each thread.
+/// 
+/// # Fields
+/// 
+/// * `a` - Left matrices of the shape `[m, k]`
+/// * `b` - Right matrices of the shape `[k, n]`
+/// * `a_batch_stride` - Distance between the left matrices, 0 if shared by all batches
+/// * `b_batch_stride` - Distance between the right matrices, 0 if shared by all batches
+/// * `m`, `k`, `n` - Matrix sizes
+struct MatmulKernel<'a, T> {
+    a: &'a [Scaler<T>],
+    b: &'a [Scaler<T>],
+    a_batch_stride: usize,
+    b_batch_stride: usize,
+    m: usize,
+    k: usize,
+    n: usize,
+}
+
+impl<T> MatmulKernel<'_, T>
+where
+    T: std::ops::Mul<Output = T> + Copy + std::ops::AddAssign
+{
+    /// Compute the output rows from `row_start` into `out`, which holds whole rows.
+    /// 
+    /// The loops are in `i-k-j` order so that the innermost loop walks along the rows of `b` and `out`,
+    /// and they are blocked so that a block of `b` stays in the cache while it is reused by a block of rows.
+    /// Each output element is accumulated in the same order regardless of the blocks.
+    fn compute_rows(&self, row_start: usize, out: &mut [Scaler<T>]) {
+        let (m, k, n) = (self.m, self.k, self.n);
+        let row_end = row_start + out.len() / n;
+        let mut block_start = row_start;
+        while block_start < row_end {
+            // A block of rows does not cross the boundary of a batch
+            let batch = block_start / m;
+            let block_end = row_end.min((batch + 1) * m).min(block_start + MATMUL_BLOCK_ROWS);
+            let b = &self.b[batch * self.b_batch_stride..][..k * n];
+            for col_start in (0..n).step_by(MATMUL_BLOCK_COLS) {
+                let col_end = n.min(col_start + MATMUL_BLOCK_COLS);
+                for inner_start in (0..k).step_by(MATMUL_BLOCK_INNER) {
+                    let inner_end = k.min(inner_start + MATMUL_BLOCK_INNER);
+                    for row in block_start..block_end {
+                        let a = &self.a[batch * self.a_batch_stride + (row % m) * k..][..k];
+                        let out_row = &mut out[(row - row_start) * n..][col_start..col_end];
+                        for l in inner_start..inner_end {
+                            let x = a[l];
+                            let b_row = &b[l * n..][col_start..col_end];
+                            for (y, z) in out_row.iter_mut().zip(b_row.iter()) {
+                                *y += x * *z;
+                            }
+                        }
+                    }
+                }
+            }
+            block_start = block_end;
+        }
     }
 }
 
 impl<T> Tensor<T>
 where
-    T: std::ops::Add<Output = T> + std::ops::Mul<Output = T> + Copy + Default + std::ops::AddAssign
+    T: std::ops::Add<Output = T> + std::ops::Mul<Output = T> + Copy + Default + std::ops::AddAssign + Send + Sync
 {
+    /// Get the matrices of an operand of matmul for every batch and the distance between them
+    /// 
+    /// An operand without batch axes is shared by every batch instead of being broadcast.
+    /// The contents are borrowed if the operand is contiguous and already has the batch shape,
+    /// and copied only for a broadcast or strided operand.
+    fn matmul_batch_data<'a>(x: &'a Self, batch_shape: &[usize], matrix_shape: [usize; 2]) -> (Cow<'a, [Scaler<T>]>, usize) {
+        if x.ndim() == 2 {
+            return (x.data(), 0);
+        }
+        let matrix_size = matrix_shape[0] * matrix_shape[1];
+        let shape = [batch_shape, &matrix_shape].concat();
+        if x.shape == shape {
+            return (x.data(), matrix_size);
+        }
+        let x = x.broadcast_to(&shape);
+        (Cow::Owned(x.data().into_owned()), matrix_size)
+    }
+
     /// Multiply matrixes
     /// 
+    /// Follows the semantics of numpy's matmul.
+    /// A 1-D left operand is treated as a row vector and a 1-D right operand as a column vector,
+    /// and the added axis is removed from the result.
+    /// The axes before the last two are batch axes and are broadcast against each other.
+    /// Large products are split by rows between threads.
+    /// 
     /// # Arguments
     /// 
     /// * `other` - Other matrix to multiply
     /// 
     /// # Panics
     /// 
-    /// * Panics if the ndim is not 2
-    /// * Panics if self.shape[1] != other.shape[0]
-    /// * Panics if any shape is 0
+    /// * Panics if either operand is a scalar
+    /// * Panics if the last axis of self does not match the second to last axis of other
+    /// * Panics if the batch axes cannot be broadcast
+    /// * Panics if any matrix shape is 0
     pub fn matmul(&self, other: &Self) -> Self {
-        assert_eq!(self.ndim(), 2, "ndim is not 2");
-        assert_eq!(other.ndim(), 2, "ndim is not 2");
-        assert_eq!(self.shape[1], other.shape[0], "Shape mismatch");
-        assert!(self.shape[0] != 0, "Shape is 0");
-        assert!(self.shape[1] != 0, "Shape is 0");
-        assert!(other.shape[1] != 0, "Shape is 0");
-        let mut data = vec![Scaler::from(T::default()); self.shape[0] * other.shape[1]];
-        for i in 0..self.shape[0] {
-            for j in 0..other.shape[1] {
-                for k in 0..self.shape[1] {
-                    data[i * other.shape[1] + j] += self.data[i * self.shape[1] + k] * other.data[k * other.shape[1] + j];
-                }
+        self.try_matmul(other).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Multiply matrixes
+    /// 
+    /// # Arguments
+    /// 
+    /// * `other` - Other matrix to multiply
+    /// 
+    /// # Errors
+    /// 
+    /// * `InvalidShape` - If either operand is a scalar or any matrix shape is 0
+    /// * `ShapeMismatch` - If the last axis of self does not match the second to last axis of other
+    /// * `BroadcastMismatch` - If the batch axes cannot be broadcast
+    pub fn try_matmul(&self, other: &Self) -> Result<Self, TensorError> {
+        self.try_matmul_with_threads(other, None)
+    }
+
+    /// Multiply matrixes with the given number of threads, or with the automatic number if None
+    fn try_matmul_with_threads(&self, other: &Self, threads: Option<usize>) -> Result<Self, TensorError> {
+        for x in [self, other] {
+            if x.ndim() == 0 {
+                return Err(TensorError::InvalidShape { shape: x.shape.clone(), reason: "matmul needs at least one axis" });
+            }
+        }
+        let a = if self.ndim() == 1 { self.reshape([1, self.shape[0]]) } else { self.clone() };
+        let b = if other.ndim() == 1 { other.reshape([other.shape[0], 1]) } else { other.clone() };
+        let (m, k) = (a.shape[a.ndim() - 2], a.shape[a.ndim() - 1]);
+        let n = b.shape[b.ndim() - 1];
+        if k != b.shape[b.ndim() - 2] {
+            return Err(TensorError::ShapeMismatch { left: self.shape.clone(), right: other.shape.clone() });
+        }
+        if m == 0 || k == 0 || n == 0 {
+            let shape = if m == 0 || k == 0 { self.shape.clone() } else { other.shape.clone() };
+            return Err(TensorError::InvalidShape { shape, reason: "matmul needs non-empty matrices" });
+        }
+
+        let batch_shape = try_broadcast_shapes(&a.shape[..a.ndim() - 2], &b.shape[..b.ndim() - 2])?;
+        let batch_size = batch_shape.iter().product::<usize>();
+        let (a_data, a_batch_stride) = Self::matmul_batch_data(&a, &batch_shape, [m, k]);
+        let (b_data, b_batch_stride) = Self::matmul_batch_data(&b, &batch_shape, [k, n]);
+        let kernel = MatmulKernel {
+            a: &a_data, b: &b_data, a_batch_stride, b_batch_stride, m, k, n,
+        };
+
+        let rows = batch_size * m;
+        let mut data = vec![Scaler::from(T::default()); rows * n];
+        let threads = threads.unwrap_or_else(|| {
+            if rows * k * n >= MATMUL_PARALLEL_THRESHOLD {
+                std::thread::available_parallelism().map_or(1, |x| x.get())
+            } else {
+                1
             }
+        }).min(rows);
+        if threads <= 1 {
+            kernel.compute_rows(0, &mut data);
+        } else {
+            let rows_per_thread = rows.div_ceil(threads);
+            std::thread::scope(|scope| {
+                for (i, chunk) in data.chunks_mut(rows_per_thread * n).enumerate() {
+                    let kernel = &kernel;
+                    scope.spawn(move || kernel.compute_rows(i * rows_per_thread, chunk));
+                }
+            });
+        }
+
+        let mut shape = batch_shape;
+        if self.ndim() != 1 {
+            shape.push(m);
+        }
+        if other.ndim() != 1 {
+            shape.push(n);
         }
-        Self::new(data, vec![self.shape[0], other.shape[1]])
+        Ok(Self::from_vec(data, shape))
     }
 }
 
@@ -396,13 +1087,16 @@ where
     type Output = Self;
 
     fn add(self, other: Self) -> Self {
-        assert_eq!(self.shape, other.shape, "Shape mismatch");
+        if self.shape != other.shape {
+            return self.zip_map(&other, |x, y| x.clone() + y.clone());
+        }
+        let shape = self.shape.clone();
         let data =
-            self.data
+            self.into_data()
             .into_iter()
-            .zip(other.data.into_iter()).map(|(x, y)| x + y)
+            .zip(other.into_data()).map(|(x, y)| x + y)
             .collect();
-        Self { data, shape: self.shape }
+        Self::from_vec(data, shape)
     }
 }
 
@@ -413,13 +1107,7 @@ where
     type Output = Tensor<T>;
 
     fn add(self, other: Self) -> Self::Output {
-        assert_eq!(self.shape, other.shape, "Shape mismatch");
-        let data =
-            self.data
-            .iter()
-            .zip(other.data.iter()).map(|(x, y)| *x + *y)
-            .collect();
-        Tensor { data, shape: self.shape.clone() }
+        self.zip_map(other, |x, y| *x + *y)
     }
 }
 
@@ -430,13 +1118,16 @@ where
     type Output = Self;
 
     fn sub(self, other: Self) -> Self {
-        assert_eq!(self.shape, other.shape, "Shape mismatch");
+        if self.shape != other.shape {
+            return self.zip_map(&other, |x, y| x.clone() - y.clone());
+        }
+        let shape = self.shape.clone();
         let data =
-            self.data
+            self.into_data()
             .into_iter()
-            .zip(other.data.into_iter()).map(|(x, y)| x - y)
+            .zip(other.into_data()).map(|(x, y)| x - y)
             .collect();
-        Self { data, shape: self.shape }
+        Self::from_vec(data, shape)
     }
 }
 
@@ -447,13 +1138,7 @@ where
     type Output = Tensor<T>;
 
     fn sub(self, other: Self) -> Self::Output {
-        assert_eq!(self.shape, other.shape, "Shape mismatch");
-        let data =
-            self.data
-            .iter()
-            .zip(other.data.iter()).map(|(x, y)| *x - *y)
-            .collect();
-        Tensor { data, shape: self.shape.clone() }
+        self.zip_map(other, |x, y| *x - *y)
     }
 }
 
@@ -464,13 +1149,16 @@ where
     type Output = Self;
 
     fn mul(self, other: Self) -> Self {
-        assert_eq!(self.shape, other.shape, "Shape mismatch");
+        if self.shape != other.shape {
+            return self.zip_map(&other, |x, y| x.clone() * y.clone());
+        }
+        let shape = self.shape.clone();
         let data =
-            self.data
+            self.into_data()
             .into_iter()
-            .zip(other.data.into_iter()).map(|(x, y)| x * y)
+            .zip(other.into_data()).map(|(x, y)| x * y)
             .collect();
-        Self { data, shape: self.shape }
+        Self::from_vec(data, shape)
     }
 }
 
@@ -481,13 +1169,7 @@ where
     type Output = Tensor<T>;
 
     fn mul(self, other: Self) -> Self::Output {
-        assert_eq!(self.shape, other.shape, "Shape mismatch");
-        let data =
-            self.data
-            .iter()
-            .zip(other.data.iter()).map(|(x, y)| *x * *y)
-            .collect();
-        Tensor { data, shape: self.shape.clone() }
+        self.zip_map(other, |x, y| *x * *y)
     }
 }
 
@@ -498,13 +1180,16 @@ where
     type Output = Self;
 
     fn div(self, other: Self) -> Self {
-        assert_eq!(self.shape, other.shape, "Shape mismatch");
+        if self.shape != other.shape {
+            return self.zip_map(&other, |x, y| x.clone() / y.clone());
+        }
+        let shape = self.shape.clone();
         let data =
-            self.data
+            self.into_data()
             .into_iter()
-            .zip(other.data.into_iter()).map(|(x, y)| x / y)
+            .zip(other.into_data()).map(|(x, y)| x / y)
             .collect();
-        Self { data, shape: self.shape }
+        Self::from_vec(data, shape)
     }
 }
 
@@ -515,13 +1200,7 @@ where
     type Output = Tensor<T>;
 
     fn div(self, other: Self) -> Self::Output {
-        assert_eq!(self.shape, other.shape, "Shape mismatch");
-        let data =
-            self.data
-            .iter()
-            .zip(other.data.iter()).map(|(x, y)| *x / *y)
-            .collect();
-        Tensor { data, shape: self.shape.clone() }
+        self.zip_map(other, |x, y| *x / *y)
     }
 }
 
@@ -532,8 +1211,9 @@ where
     type Output = Self;
 
     fn neg(self) -> Self {
-        let data = self.data.into_iter().map(|x| -x).collect();
-        Self { data, shape: self.shape }
+        let shape = self.shape.clone();
+        let data = self.into_data().into_iter().map(|x| -x).collect();
+        Self::from_vec(data, shape)
     }
 }
 
@@ -544,8 +1224,7 @@ where
     type Output = Tensor<T>;
 
     fn neg(self) -> Self::Output {
-        let data = self.data.iter().map(|x| -*x).collect();
-        Tensor { data, shape: self.shape.clone() }
+        self.map(|x| -*x)
     }
 }
 
@@ -554,8 +1233,8 @@ where
     T: std::ops::AddAssign + Copy
 {
     fn add_assign(&mut self, other: &Self) {
-        assert_eq!(self.shape, other.shape, "Shape mismatch");
-        for (x, y) in self.data.iter_mut().zip(other.data.iter()) {
+        let other = self.broadcast_other(other);
+        for (x, y) in self.data_mut().iter_mut().zip(other.iter()) {
             *x += *y;
         }
     }
@@ -566,8 +1245,8 @@ where
     T: std::ops::SubAssign + Copy
 {
     fn sub_assign(&mut self, other: &Self) {
-        assert_eq!(self.shape, other.shape, "Shape mismatch");
-        for (x, y) in self.data.iter_mut().zip(other.data.iter()) {
+        let other = self.broadcast_other(other);
+        for (x, y) in self.data_mut().iter_mut().zip(other.iter()) {
             *x -= *y;
         }
     }
@@ -578,8 +1257,8 @@ where
     T: std::ops::MulAssign + Copy
 {
     fn mul_assign(&mut self, other: &Self) {
-        assert_eq!(self.shape, other.shape, "Shape mismatch");
-        for (x, y) in self.data.iter_mut().zip(other.data.iter()) {
+        let other = self.broadcast_other(other);
+        for (x, y) in self.data_mut().iter_mut().zip(other.iter()) {
             *x *= *y;
         }
     }
@@ -590,8 +1269,8 @@ where
     T: std::ops::DivAssign + Copy
 {
     fn div_assign(&mut self, other: &Tensor<T>) {
-        assert_eq!(self.shape, other.shape, "Shape mismatch");
-        for (x, y) in self.data.iter_mut().zip(other.data.iter()) {
+        let other = self.broadcast_other(other);
+        for (x, y) in self.data_mut().iter_mut().zip(other.iter()) {
             *x /= *y;
         }
     }
@@ -607,8 +1286,7 @@ where
     /// 
     /// * `scalar` - Scalar to add
     pub fn scalar_add(&self, scalar: Scaler<T>) -> Self {
-        let data = self.data.iter().map(|x| *x + scalar).collect();
-        Self { data, shape: self.shape.clone() }
+        self.map(|x| *x + scalar)
     }
 }
 
@@ -622,8 +1300,7 @@ where
     /// 
     /// * `scalar` - Scalar to subtract
     pub fn scalar_sub(&self, scalar: Scaler<T>) -> Self {
-        let data = self.data.iter().map(|x| *x - scalar).collect();
-        Self { data, shape: self.shape.clone() }
+        self.map(|x| *x - scalar)
     }
 }
 
@@ -637,8 +1314,7 @@ where
     /// 
     /// * `scalar` - Scalar to multiply by
     pub fn scalar_mul(&self, scalar: Scaler<T>) -> Self {
-        let data = self.data.iter().map(|x| *x * scalar).collect();
-        Self { data, shape: self.shape.clone() }
+        self.map(|x| *x * scalar)
     }
 }
 
@@ -652,8 +1328,7 @@ where
     /// 
     /// * `scalar` - Scalar to divide by
     pub fn scalar_div(&self, scalar: Scaler<T>) -> Self {
-        let data = self.data.iter().map(|x| *x / scalar).collect();
-        Self { data, shape: self.shape.clone() }
+        self.map(|x| *x / scalar)
     }
 }
 
@@ -697,14 +1372,14 @@ mod tests {
     #[test]
     fn new_normal() {
         let x = Tensor::new([0.0.into(), 1.0.into(), 2.0.into()], [3,]);
-        assert_eq!(x.data(), &vec![0.0.into(), 1.0.into(), 2.0.into()]);
+        assert_eq!(x.data(), vec![0.0.into(), 1.0.into(), 2.0.into()]);
         assert_eq!(x.shape(), &vec![3]);
     }
 
     #[test]
     fn new_zero_dim() {
         let x = Tensor::new([1.0.into()], []);
-        assert_eq!(x.data(), &vec![1.0.into()]);
+        assert_eq!(x.data(), vec![1.0.into()]);
         assert_eq!(x.shape(), &vec![]);
     }
 
@@ -717,14 +1392,14 @@ mod tests {
     #[test]
     fn new_from_num_vec_normal() {
         let x = Tensor::new_from_num_vec([0.0, 1.0, 2.0], [3,]);
-        assert_eq!(x.data(), &vec![0.0.into(), 1.0.into(), 2.0.into()]);
+        assert_eq!(x.data(), vec![0.0.into(), 1.0.into(), 2.0.into()]);
         assert_eq!(x.shape(), &vec![3]);
     }
 
     #[test]
     fn new_from_num_vec_zero_dim() {
         let x = Tensor::new_from_num_vec([1.0], []);
-        assert_eq!(x.data(), &vec![1.0.into()]);
+        assert_eq!(x.data(), vec![1.0.into()]);
         assert_eq!(x.shape(), &vec![]);
     }
 
@@ -738,7 +1413,7 @@ mod tests {
     fn reshape_normal() {
         let x = Tensor::new_from_num_vec([0.0, 1.0, 2.0, 3.0], [4,]);
         let x = x.reshape([2, 2]);
-        assert_eq!(x.data(), &vec![0.0.into(), 1.0.into(), 2.0.into(), 3.0.into()]);
+        assert_eq!(x.data(), vec![0.0.into(), 1.0.into(), 2.0.into(), 3.0.into()]);
         assert_eq!(x.shape(), &vec![2, 2]);
     }
 
@@ -746,7 +1421,7 @@ mod tests {
     fn reshape_zero_dim() {
         let x = Tensor::new_from_num_vec([1.0], []);
         let x = x.reshape([1, 1]);
-        assert_eq!(x.data(), &vec![1.0.into()]);
+        assert_eq!(x.data(), vec![1.0.into()]);
         assert_eq!(x.shape(), &vec![1, 1]);
     }
 
@@ -761,7 +1436,7 @@ mod tests {
     fn transpose_normal() {
         let x = Tensor::new_from_num_vec([0.0, 1.0, 2.0, 3.0, 4.0, 5.0], [3, 2]);
         let x = x.transpose();
-        assert_eq!(x.data(), &vec![0.0.into(), 2.0.into(), 4.0.into(), 1.0.into(), 3.0.into(), 5.0.into()]);
+        assert_eq!(x.data(), vec![0.0.into(), 2.0.into(), 4.0.into(), 1.0.into(), 3.0.into(), 5.0.into()]);
         assert_eq!(x.shape(), &vec![2, 3]);
     }
 
@@ -769,7 +1444,7 @@ mod tests {
     fn transpose_zero_dim() {
         let x = Tensor::new_from_num_vec([1.0], []);
         let x = x.transpose();
-        assert_eq!(x.data(), &vec![1.0.into()]);
+        assert_eq!(x.data(), vec![1.0.into()]);
         assert_eq!(x.shape(), &vec![]);
     }
 
@@ -777,7 +1452,7 @@ mod tests {
     fn transpose_1d() {
         let x = Tensor::new_from_num_vec([0.0, 1.0, 2.0], [3,]);
         let x = x.transpose();
-        assert_eq!(x.data(), &vec![0.0.into(), 1.0.into(), 2.0.into()]);
+        assert_eq!(x.data(), vec![0.0.into(), 1.0.into(), 2.0.into()]);
         assert_eq!(x.shape(), &vec![3]);
     }
 
@@ -839,7 +1514,7 @@ mod tests {
         *x.at_mut(&[1, 0]) = 13.0.into();
         *x.at_mut(&[1, 1]) = 14.0.into();
         *x.at_mut(&[1, 2]) = 15.0.into();
-        assert_eq!(x.data(), &vec![10.0.into(), 11.0.into(), 12.0.into(), 13.0.into(), 14.0.into(), 15.0.into()]);
+        assert_eq!(x.data(), vec![10.0.into(), 11.0.into(), 12.0.into(), 13.0.into(), 14.0.into(), 15.0.into()]);
         assert_eq!(x.shape(), &vec![2, 3]);
     }
 
@@ -866,21 +1541,21 @@ mod tests {
     #[test]
     fn arrange_normal() {
         let x = Tensor::<f32>::arrange([2, 3]);
-        assert_eq!(x.data(), &vec![0.0.into(), 1.0.into(), 2.0.into(), 3.0.into(), 4.0.into(), 5.0.into()]);
+        assert_eq!(x.data(), vec![0.0.into(), 1.0.into(), 2.0.into(), 3.0.into(), 4.0.into(), 5.0.into()]);
         assert_eq!(x.shape(), &vec![2, 3]);
     }
 
     #[test]
     fn arrange_zero_dim() {
         let x = Tensor::<f32>::arrange([]);
-        assert_eq!(x.data(), &vec![0.0.into()]);
+        assert_eq!(x.data(), vec![0.0.into()]);
         assert_eq!(x.shape(), &vec![]);
     }
 
     #[test]
     fn arrange_error_zero_shape() {
         let x = Tensor::<f32>::arrange([1, 0]);
-        assert_eq!(x.data(), &vec![]);
+        assert_eq!(x.data(), vec![]);
         assert_eq!(x.shape(), &vec![1, 0]);
     }
 
@@ -991,9 +1666,17 @@ mod tests {
     }
 
     #[test]
-    fn sum_unrelated_axis() {
+    fn try_sum_error_axis() {
         let x = Tensor::<f32>::arrange([2, 3, 4]);
-        assert_eq!(x.sum([1, 2, 3], false), Tensor::new([66.0.into(), 210.0.into()], [2,]));
+        assert_eq!(x.try_sum([1, 2, 3], false), Err(TensorError::InvalidAxis { axis: 3, ndim: 3 }));
+        assert_eq!(x.try_sum([1, 1], false), Err(TensorError::RepeatedAxis { axis: 1 }));
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid axis")]
+    fn sum_error_axis() {
+        let x = Tensor::<f32>::arrange([2, 3]);
+        x.sum([2], false);
     }
 
     #[test]
@@ -1052,7 +1735,7 @@ mod tests {
         let x = Tensor::new([0.0.into(), 1.0.into(), 2.0.into()], [3, 1]);
         let y = Tensor::new([3.0.into(), 4.0.into(), 5.0.into()], [1, 3]);
         let z = x.matmul(&y);
-        assert_eq!(z.data(), &vec![0.0.into(), 0.0.into(), 0.0.into(), 3.0.into(), 4.0.into(), 5.0.into(), 6.0.into(), 8.0.into(), 10.0.into()]);
+        assert_eq!(z.data(), vec![0.0.into(), 0.0.into(), 0.0.into(), 3.0.into(), 4.0.into(), 5.0.into(), 6.0.into(), 8.0.into(), 10.0.into()]);
         assert_eq!(z.shape(), &vec![3, 3]);
     }
 
@@ -1061,7 +1744,7 @@ mod tests {
         let x = Tensor::new([0.0.into(), 1.0.into(), 2.0.into(), 3.0.into(), 4.0.into(), 5.0.into()], [3, 2]);
         let y = Tensor::new([0.0.into(), 1.0.into(), 2.0.into(), 3.0.into()], [2, 2]);
         let z = x.matmul(&y);
-        assert_eq!(z.data(), &vec![2.0.into(), 3.0.into(), 6.0.into(), 11.0.into(), 10.0.into(), 19.0.into()]);
+        assert_eq!(z.data(), vec![2.0.into(), 3.0.into(), 6.0.into(), 11.0.into(), 10.0.into(), 19.0.into()]);
         assert_eq!(z.shape(), &vec![3, 2]);
     }
 
@@ -1097,12 +1780,125 @@ mod tests {
         let _ = x.matmul(&y);
     }
 
+    #[test]
+    fn matmul_vector() {
+        let x = Tensor::<f64>::arrange([3]);
+        let y = Tensor::<f64>::arrange([3, 2]);
+        let z = x.matmul(&y);
+        assert_eq!(z, Tensor::new_from_num_vec(vec![10.0, 13.0], [2]));
+
+        let z = y.transpose().matmul(&x);
+        assert_eq!(z, Tensor::new_from_num_vec(vec![10.0, 13.0], [2]));
+
+        let z = x.matmul(&x);
+        assert_eq!(z, Tensor::new_from_num_vec(vec![5.0], []));
+    }
+
+    #[test]
+    fn matmul_batch() {
+        let x = Tensor::<f64>::arrange([2, 2, 3]);
+        let y = Tensor::<f64>::arrange([2, 3, 1]);
+        let z = x.matmul(&y);
+        assert_eq!(z.shape(), &vec![2, 2, 1]);
+        assert_eq!(z.data(), vec![5.0.into(), 14.0.into(), 86.0.into(), 122.0.into()]);
+    }
+
+    #[test]
+    fn matmul_broadcast_batch() {
+        let x = Tensor::<f64>::arrange([2, 1, 2, 3]);
+        let y = Tensor::<f64>::arrange([3, 3, 2]);
+        let z = x.matmul(&y);
+        assert_eq!(z.shape(), &vec![2, 3, 2, 2]);
+        for i in 0..2 {
+            for j in 0..3 {
+                let x_ij = x.slice_axis(0, i, i + 1).reshape([2, 3]);
+                let y_ij = y.slice_axis(0, j, j + 1).reshape([3, 2]);
+                let z_ij = z.slice_axis(0, i, i + 1).slice_axis(1, j, j + 1).reshape([2, 2]);
+                assert_eq!(z_ij, x_ij.matmul(&y_ij));
+            }
+        }
+
+        let z = Tensor::<f64>::arrange([3]).matmul(&y);
+        assert_eq!(z.shape(), &vec![3, 2]);
+    }
+
+    #[test]
+    fn matmul_batch_data_borrow() {
+        let x = Tensor::<f64>::arrange([2, 2, 3]);
+        let (data, stride) = Tensor::matmul_batch_data(&x, &[2], [2, 3]);
+        assert!(matches!(data, Cow::Borrowed(_)));
+        assert_eq!(stride, 6);
+
+        let (data, _) = Tensor::matmul_batch_data(&x, &[4, 2], [2, 3]);
+        assert!(matches!(data, Cow::Owned(_)));
+        assert_eq!(data.len(), 48);
+
+        let x_t = x.permute(&[0, 2, 1]);
+        let (data, _) = Tensor::matmul_batch_data(&x_t, &[2], [3, 2]);
+        assert!(matches!(data, Cow::Owned(_)));
+        assert_eq!(&data[..], &x_t.data()[..]);
+    }
+
+    #[test]
+    #[should_panic(expected = "cannot be broadcast")]
+    fn matmul_error_batch_mismatch() {
+        let x = Tensor::<f64>::arrange([2, 2, 3]);
+        let y = Tensor::<f64>::arrange([3, 3, 2]);
+        let _ = x.matmul(&y);
+    }
+
+    /// Matrix product by the definition, as a reference for the blocked kernel
+    fn naive_matmul(x: &Tensor<f64>, y: &Tensor<f64>) -> Tensor<f64> {
+        let (m, k, n) = (x.shape()[0], x.shape()[1], y.shape()[1]);
+        let mut data = Vec::new();
+        for i in 0..m {
+            for j in 0..n {
+                data.push((0..k).map(|l| x.at(&[i, l]).data() * y.at(&[l, j]).data()).sum::<f64>());
+            }
+        }
+        Tensor::new_from_num_vec(data, [m, n])
+    }
+
+    #[test]
+    fn matmul_blocked() {
+        // Larger than the blocks, with sizes that are not multiples of them
+        let x = Tensor::<f64>::arrange([37, 300]).scalar_mul(0.01.into()).sin();
+        let y = Tensor::<f64>::arrange([300, 261]).scalar_mul(0.02.into()).cos();
+        assert_eq!(x.matmul(&y), naive_matmul(&x, &y));
+    }
+
+    #[test]
+    fn matmul_parallel() {
+        // Rows of a batch are split between several threads
+        let x = Tensor::<f64>::arrange([3, 70, 70]).scalar_mul(0.01.into()).sin();
+        let y = Tensor::<f64>::arrange([70, 70]).scalar_mul(0.02.into()).cos();
+        let z = x.try_matmul_with_threads(&y, Some(4)).unwrap();
+        assert_eq!(x.try_matmul_with_threads(&y, Some(1)).unwrap(), z);
+        for i in 0..3 {
+            let x_i = x.slice_axis(0, i, i + 1).reshape([70, 70]);
+            assert_eq!(z.slice_axis(0, i, i + 1).reshape([70, 70]), naive_matmul(&x_i, &y));
+        }
+    }
+
+    #[test]
+    fn broadcast_shapes_normal() {
+        assert_eq!(broadcast_shapes(&[2, 1, 3], &[4, 1]), vec![2, 4, 3]);
+        assert_eq!(broadcast_shapes(&[], &[2]), vec![2]);
+        assert_eq!(broadcast_shapes(&[5, 0], &[1]), vec![5, 0]);
+    }
+
+    #[test]
+    #[should_panic(expected = "cannot be broadcast")]
+    fn broadcast_shapes_error() {
+        broadcast_shapes(&[2, 3], &[2]);
+    }
+
     #[test]
     fn add_normal() {
         let x = Tensor::new([0.0.into(), 1.0.into(), 2.0.into()], [3,]);
         let y = Tensor::new([3.0.into(), 4.0.into(), 5.0.into()], [3,]);
         let z = x + y;
-        assert_eq!(z.data(), &vec![3.0.into(), 5.0.into(), 7.0.into()]);
+        assert_eq!(z.data(), vec![3.0.into(), 5.0.into(), 7.0.into()]);
         assert_eq!(z.shape(), &vec![3]);
     }
 
@@ -1119,7 +1915,7 @@ mod tests {
         let x = Tensor::new([0.0.into(), 1.0.into(), 2.0.into()], [3,]);
         let y = Tensor::new([3.0.into(), 4.0.into(), 5.0.into()], [3,]);
         let z = &x + &y;
-        assert_eq!(z.data(), &vec![3.0.into(), 5.0.into(), 7.0.into()]);
+        assert_eq!(z.data(), vec![3.0.into(), 5.0.into(), 7.0.into()]);
         assert_eq!(z.shape(), &vec![3]);
     }
 
@@ -1131,12 +1927,52 @@ mod tests {
         let _ = &x + &y;
     }
 
+    #[test]
+    fn add_broadcast() {
+        let x = Tensor::<f64>::arrange([2, 3]);
+        let y = Tensor::new_from_num_vec(vec![10.0, 20.0, 30.0], [3]);
+        let z = &x + &y;
+        assert_eq!(z, Tensor::new_from_num_vec(vec![10.0, 21.0, 32.0, 13.0, 24.0, 35.0], [2, 3]));
+        assert_eq!(y + x, z);
+    }
+
+    #[test]
+    fn binary_broadcast_both() {
+        let x = Tensor::<f64>::arrange([2, 1]);
+        let y = Tensor::new_from_num_vec(vec![1.0, 2.0, 4.0], [1, 3]);
+        assert_eq!(&x - &y, Tensor::new_from_num_vec(vec![-1.0, -2.0, -4.0, 0.0, -1.0, -3.0], [2, 3]));
+        assert_eq!(&x * &y, Tensor::new_from_num_vec(vec![0.0, 0.0, 0.0, 1.0, 2.0, 4.0], [2, 3]));
+        assert_eq!(&x / &y, Tensor::new_from_num_vec(vec![0.0, 0.0, 0.0, 1.0, 0.5, 0.25], [2, 3]));
+    }
+
+    #[test]
+    fn binary_broadcast_scalar() {
+        let x = Tensor::<f64>::arrange([2, 2]);
+        let y = Tensor::new_from_num_vec(vec![2.0], []);
+        assert_eq!(x.clone() * y.clone(), x.scalar_mul(2.0.into()));
+        assert_eq!(y - x.clone(), x.scalar_mul((-1.0).into()).scalar_add(2.0.into()));
+    }
+
+    #[test]
+    fn add_assign_broadcast() {
+        let mut x = Tensor::<f64>::arrange([2, 3]);
+        x += &Tensor::new_from_num_vec(vec![10.0, 20.0], [2, 1]);
+        assert_eq!(x, Tensor::new_from_num_vec(vec![10.0, 11.0, 12.0, 23.0, 24.0, 25.0], [2, 3]));
+    }
+
+    #[test]
+    #[should_panic(expected = "Shape mismatch")]
+    fn add_assign_error_broadcast_self() {
+        let mut x = Tensor::<f64>::arrange([3]);
+        x += &Tensor::<f64>::arrange([2, 3]);
+    }
+
     #[test]
     fn sub_normal() {
         let x = Tensor::new([0.0.into(), 1.0.into(), 2.0.into()], [3,]);
         let y = Tensor::new([3.0.into(), 4.0.into(), 5.0.into()], [3,]);
         let z = x - y;
-        assert_eq!(z.data(), &vec![(-3.0).into(), (-3.0).into(), (-3.0).into()]);
+        assert_eq!(z.data(), vec![(-3.0).into(), (-3.0).into(), (-3.0).into()]);
         assert_eq!(z.shape(), &vec![3]);
     }
 
@@ -1153,7 +1989,7 @@ mod tests {
         let x = Tensor::new([0.0.into(), 1.0.into(), 2.0.into()], [3,]);
         let y = Tensor::new([3.0.into(), 4.0.into(), 5.0.into()], [3,]);
         let z = &x - &y;
-        assert_eq!(z.data(), &vec![(-3.0).into(), (-3.0).into(), (-3.0).into()]);
+        assert_eq!(z.data(), vec![(-3.0).into(), (-3.0).into(), (-3.0).into()]);
         assert_eq!(z.shape(), &vec![3]);
     }
 
@@ -1170,7 +2006,7 @@ mod tests {
         let x = Tensor::new([0.0.into(), 1.0.into(), 2.0.into()], [3,]);
         let y = Tensor::new([3.0.into(), 4.0.into(), 5.0.into()], [3,]);
         let z = x * y;
-        assert_eq!(z.data(), &vec![0.0.into(), 4.0.into(), 10.0.into()]);
+        assert_eq!(z.data(), vec![0.0.into(), 4.0.into(), 10.0.into()]);
         assert_eq!(z.shape(), &vec![3]);
     }
 
@@ -1187,7 +2023,7 @@ mod tests {
         let x = Tensor::new([0.0.into(), 1.0.into(), 2.0.into()], [3,]);
         let y = Tensor::new([3.0.into(), 4.0.into(), 5.0.into()], [3,]);
         let z = &x * &y;
-        assert_eq!(z.data(), &vec![0.0.into(), 4.0.into(), 10.0.into()]);
+        assert_eq!(z.data(), vec![0.0.into(), 4.0.into(), 10.0.into()]);
         assert_eq!(z.shape(), &vec![3]);
     }
 
@@ -1204,7 +2040,7 @@ mod tests {
         let x = Tensor::new([0.0.into(), 1.0.into(), 2.0.into()], [3,]);
         let y = Tensor::new([3.0.into(), 4.0.into(), 5.0.into()], [3,]);
         let z = x / y;
-        assert_eq!(z.data(), &vec![0.0.into(), 0.25.into(), 0.4.into()]);
+        assert_eq!(z.data(), vec![0.0.into(), 0.25.into(), 0.4.into()]);
         assert_eq!(z.shape(), &vec![3]);
     }
 
@@ -1221,7 +2057,7 @@ mod tests {
         let x = Tensor::new([0.0.into(), 1.0.into(), 2.0.into()], [3,]);
         let y = Tensor::new([3.0.into(), 4.0.into(), 5.0.into()], [3,]);
         let z = &x / &y;
-        assert_eq!(z.data(), &vec![0.0.into(), 0.25.into(), 0.4.into()]);
+        assert_eq!(z.data(), vec![0.0.into(), 0.25.into(), 0.4.into()]);
         assert_eq!(z.shape(), &vec![3]);
     }
 
@@ -1237,7 +2073,7 @@ mod tests {
     fn neg_normal() {
         let x = Tensor::new([0.0.into(), 1.0.into(), 2.0.into()], [3,]);
         let y = -x;
-        assert_eq!(y.data(), &vec![0.0.into(), (-1.0).into(), (-2.0).into()]);
+        assert_eq!(y.data(), vec![0.0.into(), (-1.0).into(), (-2.0).into()]);
         assert_eq!(y.shape(), &vec![3]);
     }
 
@@ -1245,7 +2081,7 @@ mod tests {
     fn neg_reference_normal() {
         let x = Tensor::new([0.0.into(), 1.0.into(), 2.0.into()], [3,]);
         let y = -&x;
-        assert_eq!(y.data(), &vec![0.0.into(), (-1.0).into(), (-2.0).into()]);
+        assert_eq!(y.data(), vec![0.0.into(), (-1.0).into(), (-2.0).into()]);
         assert_eq!(y.shape(), &vec![3]);
     }
 
@@ -1254,7 +2090,7 @@ mod tests {
         let mut x = Tensor::new([0.0.into(), 1.0.into(), 2.0.into()], [3,]);
         let y = Tensor::new([3.0.into(), 4.0.into(), 5.0.into()], [3,]);
         x += &y;
-        assert_eq!(x.data(), &vec![3.0.into(), 5.0.into(), 7.0.into()]);
+        assert_eq!(x.data(), vec![3.0.into(), 5.0.into(), 7.0.into()]);
         assert_eq!(x.shape(), &vec![3]);
     }
 
@@ -1271,7 +2107,7 @@ mod tests {
         let mut x = Tensor::new([0.0.into(), 1.0.into(), 2.0.into()], [3,]);
         let y = Tensor::new([3.0.into(), 4.0.into(), 5.0.into()], [3,]);
         x -= &y;
-        assert_eq!(x.data(), &vec![(-3.0).into(), (-3.0).into(), (-3.0).into()]);
+        assert_eq!(x.data(), vec![(-3.0).into(), (-3.0).into(), (-3.0).into()]);
         assert_eq!(x.shape(), &vec![3]);
     }
 
@@ -1288,7 +2124,7 @@ mod tests {
         let mut x = Tensor::new([0.0.into(), 1.0.into(), 2.0.into()], [3,]);
         let y = Tensor::new([3.0.into(), 4.0.into(), 5.0.into()], [3,]);
         x *= &y;
-        assert_eq!(x.data(), &vec![0.0.into(), 4.0.into(), 10.0.into()]);
+        assert_eq!(x.data(), vec![0.0.into(), 4.0.into(), 10.0.into()]);
         assert_eq!(x.shape(), &vec![3]);
     }
 
@@ -1305,7 +2141,7 @@ mod tests {
         let mut x = Tensor::new([0.0.into(), 1.0.into(), 2.0.into()], [3,]);
         let y = Tensor::new([3.0.into(), 4.0.into(), 5.0.into()], [3,]);
         x /= &y;
-        assert_eq!(x.data(), &vec![0.0.into(), 0.25.into(), 0.4.into()]);
+        assert_eq!(x.data(), vec![0.0.into(), 0.25.into(), 0.4.into()]);
         assert_eq!(x.shape(), &vec![3]);
     }
 
@@ -1321,7 +2157,7 @@ mod tests {
     fn scalar_add_normal() {
         let x = Tensor::new([0.0.into(), 1.0.into(), 2.0.into()], [3,]);
         let y = x.scalar_add(3.0.into());
-        assert_eq!(y.data(), &vec![3.0.into(), 4.0.into(), 5.0.into()]);
+        assert_eq!(y.data(), vec![3.0.into(), 4.0.into(), 5.0.into()]);
         assert_eq!(y.shape(), &vec![3]);
     }
 
@@ -1329,7 +2165,7 @@ mod tests {
     fn scalar_sub_normal() {
         let x = Tensor::new([0.0.into(), 1.0.into(), 2.0.into()], [3,]);
         let y = x.scalar_sub(3.0.into());
-        assert_eq!(y.data(), &vec![(-3.0).into(), (-2.0).into(), (-1.0).into()]);
+        assert_eq!(y.data(), vec![(-3.0).into(), (-2.0).into(), (-1.0).into()]);
         assert_eq!(y.shape(), &vec![3]);
     }
 
@@ -1337,7 +2173,7 @@ mod tests {
     fn scalar_mul_normal() {
         let x = Tensor::new([0.0.into(), 1.0.into(), 2.0.into()], [3,]);
         let y = x.scalar_mul(3.0.into());
-        assert_eq!(y.data(), &vec![0.0.into(), 3.0.into(), 6.0.into()]);
+        assert_eq!(y.data(), vec![0.0.into(), 3.0.into(), 6.0.into()]);
         assert_eq!(y.shape(), &vec![3]);
     }
 
@@ -1345,7 +2181,232 @@ mod tests {
     fn scalar_div_normal() {
         let x = Tensor::new([0.0.into(), 1.0.into(), 2.0.into()], [3,]);
         let y = x.scalar_div(2.0.into());
-        assert_eq!(y.data(), &vec![0.0.into(), 0.5.into(), 1.0.into()]);
+        assert_eq!(y.data(), vec![0.0.into(), 0.5.into(), 1.0.into()]);
         assert_eq!(y.shape(), &vec![3]);
     }
+
+    #[test]
+    fn reshape_view_normal() {
+        let x = Tensor::<f64>::arrange([2, 3]);
+        let y = x.reshape([3, 2]);
+        assert!(y.shares_data(&x));
+        assert_eq!(y.strides(), &vec![2, 1]);
+    }
+
+    #[test]
+    fn reshape_not_contiguous() {
+        let x = Tensor::<f64>::arrange([2, 3]).transpose();
+        let y = x.reshape([6]);
+        assert!(!y.shares_data(&x));
+        assert_eq!(y.data(), vec![0.0.into(), 3.0.into(), 1.0.into(), 4.0.into(), 2.0.into(), 5.0.into()]);
+    }
+
+    #[test]
+    fn transpose_view() {
+        let x = Tensor::<f64>::arrange([2, 3]);
+        let y = x.transpose();
+        assert!(y.shares_data(&x));
+        assert_eq!(y.strides(), &vec![1, 3]);
+        assert!(!y.is_contiguous());
+        assert_eq!(y.at(&[2, 1]), &5.0.into());
+    }
+
+    #[test]
+    fn permute_normal() {
+        let x = Tensor::<f64>::arrange([2, 3, 4]);
+        let y = x.permute(&[2, 0, 1]);
+        assert!(y.shares_data(&x));
+        assert_eq!(y.shape(), &vec![4, 2, 3]);
+        assert_eq!(y.strides(), &vec![1, 12, 4]);
+        assert_eq!(y.at(&[3, 1, 2]), x.at(&[1, 2, 3]));
+    }
+
+    #[test]
+    #[should_panic(expected = "Repeated axis")]
+    fn permute_error_repeated_axis() {
+        let x = Tensor::<f64>::arrange([2, 3]);
+        let _ = x.permute(&[0, 0]);
+    }
+
+    #[test]
+    fn swapaxes_normal() {
+        let x = Tensor::<f64>::arrange([2, 3, 4]);
+        let y = x.swapaxes(0, 2);
+        assert!(y.shares_data(&x));
+        assert_eq!(y.shape(), &vec![4, 3, 2]);
+        assert_eq!(y.at(&[3, 1, 0]), x.at(&[0, 1, 3]));
+        assert_eq!(x.swapaxes(1, 1), x);
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid axis")]
+    fn swapaxes_error_invalid_axis() {
+        let x = Tensor::<f64>::arrange([2, 3]);
+        let _ = x.swapaxes(0, 2);
+    }
+
+    #[test]
+    fn slice_axis_normal() {
+        let x = Tensor::<f64>::arrange([3, 4]);
+        let y = x.slice_axis(1, 1, 3);
+        assert!(y.shares_data(&x));
+        assert_eq!(y.shape(), &vec![3, 2]);
+        assert_eq!(y.offset(), 1);
+        assert_eq!(y.data(), vec![1.0.into(), 2.0.into(), 5.0.into(), 6.0.into(), 9.0.into(), 10.0.into()]);
+
+        let z = x.slice_axis(0, 1, 2);
+        assert!(z.is_contiguous());
+        assert_eq!(z.data(), vec![4.0.into(), 5.0.into(), 6.0.into(), 7.0.into()]);
+    }
+
+    #[test]
+    fn slice_axis_empty() {
+        let x = Tensor::<f64>::arrange([3, 4]);
+        let y = x.slice_axis(0, 3, 3);
+        assert_eq!(y.shape(), &vec![0, 4]);
+        assert_eq!(y.size(), 0);
+        assert_eq!(y.data(), vec![]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Index out of range")]
+    fn slice_axis_error_out_of_range() {
+        let x = Tensor::<f64>::arrange([3, 4]);
+        let _ = x.slice_axis(1, 2, 5);
+    }
+
+    #[test]
+    fn broadcast_to_view() {
+        let x = Tensor::<f64>::arrange([3, 1]);
+        let y = x.broadcast_to(&[2, 3, 4]);
+        assert!(y.shares_data(&x));
+        assert_eq!(y.strides(), &vec![0, 1, 0]);
+        assert_eq!(y.at(&[1, 2, 3]), &2.0.into());
+    }
+
+    #[test]
+    fn contiguous_normal() {
+        let x = Tensor::<f64>::arrange([2, 3]);
+        assert!(x.contiguous().shares_data(&x));
+
+        let y = x.transpose().contiguous();
+        assert!(!y.shares_data(&x));
+        assert!(y.is_contiguous());
+        assert_eq!(y.strides(), &vec![2, 1]);
+        assert_eq!(y, x.transpose());
+    }
+
+    #[test]
+    fn at_mut_shared() {
+        let x = Tensor::<f64>::arrange([2, 3]);
+        let mut y = x.transpose();
+        *y.at_mut(&[2, 0]) = 10.0.into();
+        assert!(!y.shares_data(&x));
+        assert_eq!(y.at(&[2, 0]), &10.0.into());
+        assert_eq!(x.at(&[0, 2]), &2.0.into());
+    }
+
+    #[test]
+    fn add_assign_view() {
+        let mut x = Tensor::<f64>::arrange([2, 2]).transpose();
+        let y = Tensor::<f64>::arrange([2, 2]);
+        x += &y;
+        assert_eq!(x.data(), vec![0.0.into(), 3.0.into(), 3.0.into(), 6.0.into()]);
+    }
+
+    #[test]
+    fn add_view() {
+        let x = Tensor::<f64>::arrange([2, 3]).transpose();
+        let y = Tensor::<f64>::arrange([3]).reshape([3, 1]).broadcast_to(&[3, 2]);
+        let z = &x + &y;
+        assert_eq!(z.data(), vec![0.0.into(), 3.0.into(), 2.0.into(), 5.0.into(), 4.0.into(), 7.0.into()]);
+        assert_eq!(x + y, z);
+    }
+
+    #[test]
+    fn try_new_normal() {
+        let x = Tensor::try_new_from_num_vec([0.0, 1.0, 2.0, 3.0], [2, 2]).unwrap();
+        assert_eq!(x, Tensor::arrange([2, 2]));
+        let e = Tensor::try_new_from_num_vec([0.0, 1.0, 2.0], [2, 2]).unwrap_err();
+        assert_eq!(e, TensorError::SizeMismatch { size: 3, shape: vec![2, 2] });
+        let e = Tensor::<f64>::try_new([], []).unwrap_err();
+        assert_eq!(e, TensorError::SizeMismatch { size: 0, shape: vec![] });
+    }
+
+    #[test]
+    fn try_reshape_normal() {
+        let x = Tensor::<f64>::arrange([2, 3]);
+        assert_eq!(x.try_reshape([3, 2]).unwrap(), x.reshape([3, 2]));
+        assert_eq!(x.try_reshape([4]).unwrap_err(), TensorError::SizeMismatch { size: 6, shape: vec![4] });
+    }
+
+    #[test]
+    fn try_at_normal() {
+        let mut x = Tensor::<f64>::arrange([2, 3]);
+        assert_eq!(x.try_at(&[1, 2]).unwrap(), &5.0.into());
+        assert_eq!(x.try_at(&[2, 0]).unwrap_err(),
+            TensorError::IndexOutOfRange { index: vec![2, 0], shape: vec![2, 3] });
+        assert_eq!(x.try_at(&[0]).unwrap_err(), TensorError::NdimMismatch { expected: 2, actual: 1 });
+        *x.try_at_mut(&[0, 1]).unwrap() = 7.0.into();
+        assert_eq!(x.at(&[0, 1]), &7.0.into());
+        assert!(x.try_at_mut(&[0, 3]).is_err());
+    }
+
+    #[test]
+    fn try_permute_normal() {
+        let x = Tensor::<f64>::arrange([2, 3, 4]);
+        assert_eq!(x.try_permute(&[2, 0, 1]).unwrap(), x.permute(&[2, 0, 1]));
+        assert_eq!(x.try_permute(&[0, 0, 1]).unwrap_err(), TensorError::RepeatedAxis { axis: 0 });
+        assert_eq!(x.try_permute(&[0, 3, 1]).unwrap_err(), TensorError::InvalidAxis { axis: 3, ndim: 3 });
+        assert_eq!(x.try_permute(&[0, 1]).unwrap_err(), TensorError::NdimMismatch { expected: 3, actual: 2 });
+        assert_eq!(x.try_swapaxes(0, 3).unwrap_err(), TensorError::InvalidAxis { axis: 3, ndim: 3 });
+        assert_eq!(x.try_slice_axis(3, 0, 1).unwrap_err(), TensorError::InvalidAxis { axis: 3, ndim: 3 });
+        assert!(matches!(x.try_slice_axis(1, 2, 4), Err(TensorError::IndexOutOfRange { .. })));
+    }
+
+    #[test]
+    fn try_broadcast_to_normal() {
+        let x = Tensor::<f64>::arrange([3, 1]);
+        assert_eq!(x.try_broadcast_to(&[2, 3, 4]).unwrap().shape(), &vec![2, 3, 4]);
+        let error = |right: Vec<usize>| TensorError::BroadcastMismatch { left: vec![3, 1], right };
+        assert_eq!(x.try_broadcast_to(&[2, 4]).unwrap_err(), error(vec![2, 4]));
+        assert_eq!(x.try_broadcast_to(&[3]).unwrap_err(), error(vec![3]));
+        assert_eq!(x.try_broadcast_to(&[3, 0]).unwrap_err(), error(vec![3, 0]));
+        assert_eq!(try_broadcast_shapes(&[2, 3], &[3]).unwrap(), vec![2, 3]);
+        assert_eq!(try_broadcast_shapes(&[2, 3], &[2]).unwrap_err(),
+            TensorError::BroadcastMismatch { left: vec![2, 3], right: vec![2] });
+    }
+
+    #[test]
+    fn try_sum_to_normal() {
+        let x = Tensor::<f64>::arrange([2, 3]);
+        assert_eq!(x.try_sum_to(&[1, 3]).unwrap(), x.sum_to(&[1, 3]));
+        assert_eq!(x.try_sum_to(&[2]).unwrap_err(),
+            TensorError::BroadcastMismatch { left: vec![2, 3], right: vec![2] });
+        assert!(x.try_sum_to(&[1, 2, 3]).is_err());
+    }
+
+    #[test]
+    fn try_matmul_normal() {
+        let x = Tensor::<f64>::arrange([2, 3]);
+        let y = Tensor::<f64>::arrange([3, 4]);
+        assert_eq!(x.try_matmul(&y).unwrap(), x.matmul(&y));
+        assert_eq!(y.try_matmul(&x).unwrap_err(),
+            TensorError::ShapeMismatch { left: vec![3, 4], right: vec![2, 3] });
+        let z = Tensor::<f64>::arrange([2, 3, 4]);
+        assert_eq!(Tensor::<f64>::arrange([3, 2, 3]).try_matmul(&z).unwrap_err(),
+            TensorError::BroadcastMismatch { left: vec![3], right: vec![2] });
+        assert!(matches!(Tensor::<f64>::arrange([]).try_matmul(&y), Err(TensorError::InvalidShape { .. })));
+        assert!(matches!(Tensor::<f64>::arrange([2, 0]).try_matmul(&Tensor::arrange([0, 4])),
+            Err(TensorError::InvalidShape { .. })));
+    }
+
+    #[test]
+    fn try_zip_map_normal() {
+        let x = Tensor::<f64>::arrange([2, 3]);
+        let y = Tensor::<f64>::arrange([3]);
+        assert_eq!(x.try_zip_map(&y, |a, b| *a + *b).unwrap(), &x + &y);
+        assert!(matches!(x.try_zip_map(&Tensor::<f64>::arrange([2]), |a, b| *a + *b),
+            Err(TensorError::BroadcastMismatch { .. })));
+    }
 }
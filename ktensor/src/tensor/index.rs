@@ -0,0 +1,507 @@
+use std::ops::{Range, RangeFrom, RangeTo, RangeFull};
+use super::Tensor;
+use crate::error::TensorError;
+
+/// Index of an axis for `slice`
+/// 
+/// # Variants
+/// 
+/// * `Index` - Select one index and remove the axis
+/// * `Range` - Select every `step` index from `start` to `end`, exclusive. If `end` is None, to the end of the axis
+/// * `Array` - Select the indexes in the order of the array, which may repeat
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SliceIndex {
+    Index(usize),
+    Range { start: usize, end: Option<usize>, step: usize },
+    Array(Vec<usize>),
+}
+
+impl SliceIndex {
+    /// Select the whole axis.
+    pub fn full() -> Self {
+        SliceIndex::Range { start: 0, end: None, step: 1 }
+    }
+
+    /// Select every `step` index from `start` to `end`, exclusive.
+    pub fn step(start: usize, end: usize, step: usize) -> Self {
+        SliceIndex::Range { start, end: Some(end), step }
+    }
+}
+
+impl From<usize> for SliceIndex {
+    fn from(index: usize) -> Self {
+        SliceIndex::Index(index)
+    }
+}
+
+impl From<Range<usize>> for SliceIndex {
+    fn from(range: Range<usize>) -> Self {
+        SliceIndex::Range { start: range.start, end: Some(range.end), step: 1 }
+    }
+}
+
+impl From<RangeFrom<usize>> for SliceIndex {
+    fn from(range: RangeFrom<usize>) -> Self {
+        SliceIndex::Range { start: range.start, end: None, step: 1 }
+    }
+}
+
+impl From<RangeTo<usize>> for SliceIndex {
+    fn from(range: RangeTo<usize>) -> Self {
+        SliceIndex::Range { start: 0, end: Some(range.end), step: 1 }
+    }
+}
+
+impl From<RangeFull> for SliceIndex {
+    fn from(_: RangeFull) -> Self {
+        SliceIndex::full()
+    }
+}
+
+impl From<Vec<usize>> for SliceIndex {
+    fn from(indexes: Vec<usize>) -> Self {
+        SliceIndex::Array(indexes)
+    }
+}
+
+impl From<&[usize]> for SliceIndex {
+    fn from(indexes: &[usize]) -> Self {
+        SliceIndex::Array(indexes.to_vec())
+    }
+}
+
+/// Move the indexes to the next element in row-major order
+fn next_index(index: &mut [usize], shape: &[usize]) {
+    for axis in (0..index.len()).rev() {
+        index[axis] += 1;
+        if index[axis] < shape[axis] {
+            break;
+        }
+        index[axis] = 0;
+    }
+}
+
+impl<T> Tensor<T>
+{
+    /// Get the indexes that each axis selects and whether the axis is kept
+    /// 
+    /// The axes after the given indexes select the whole axis.
+    fn resolve_slice(&self, indexes: &[SliceIndex]) -> Result<Vec<(Vec<usize>, bool)>, TensorError> {
+        if indexes.len() > self.ndim() {
+            return Err(TensorError::NdimMismatch { expected: self.ndim(), actual: indexes.len() });
+        }
+        let out_of_range = |axis: usize, i: usize| {
+            let mut index = vec![0; self.ndim()];
+            index[axis] = i;
+            TensorError::IndexOutOfRange { index, shape: self.shape.clone() }
+        };
+        (0..self.ndim()).map(|axis| {
+            let size = self.shape[axis];
+            match indexes.get(axis).cloned().unwrap_or_else(SliceIndex::full) {
+                SliceIndex::Index(i) if i < size => Ok((vec![i], false)),
+                SliceIndex::Index(i) => Err(out_of_range(axis, i)),
+                SliceIndex::Range { step: 0, .. } => Err(TensorError::InvalidStep { axis }),
+                SliceIndex::Range { start, end, step } => {
+                    let end = end.unwrap_or(size);
+                    if start > end || end > size {
+                        return Err(out_of_range(axis, end.max(start)));
+                    }
+                    Ok(((start..end).step_by(step).collect(), true))
+                },
+                SliceIndex::Array(array) => match array.iter().find(|&&i| i >= size) {
+                    Some(&i) => Err(out_of_range(axis, i)),
+                    None => Ok((array, true)),
+                },
+            }
+        }).collect()
+    }
+
+    /// Get the positions in the row-major order of this Tensor of the elements of a slice
+    fn slice_positions(&self, selected: &[(Vec<usize>, bool)]) -> (Vec<usize>, Vec<usize>) {
+        let all_shape = selected.iter().map(|(s, _)| s.len()).collect::<Vec<usize>>();
+        let out_shape = selected.iter().filter(|(_, kept)| *kept).map(|(s, _)| s.len()).collect();
+        let strides = Self::contiguous_strides(&self.shape);
+        let size = all_shape.iter().product();
+        let mut index = vec![0; self.ndim()];
+        let mut positions = Vec::with_capacity(size);
+        for _ in 0..size {
+            positions.push(index.iter().zip(selected.iter()).zip(strides.iter())
+                .map(|((&i, (s, _)), stride)| s[i] * stride).sum());
+            next_index(&mut index, &all_shape);
+        }
+        (positions, out_shape)
+    }
+
+    /// Get the positions in the row-major order of this Tensor of the elements of `gather`
+    fn gather_positions(&self, axis: usize, indexes: &Tensor<usize>) -> Result<Vec<usize>, TensorError> {
+        if axis >= self.ndim() {
+            return Err(TensorError::InvalidAxis { axis, ndim: self.ndim() });
+        }
+        if indexes.ndim() != self.ndim()
+            || (0..self.ndim()).any(|i| i != axis && indexes.shape[i] != self.shape[i]) {
+            return Err(TensorError::ShapeMismatch { left: self.shape.clone(), right: indexes.shape.clone() });
+        }
+        let strides = Self::contiguous_strides(&self.shape);
+        let mut index = vec![0; self.ndim()];
+        let mut positions = Vec::with_capacity(indexes.size());
+        for i in indexes.iter() {
+            let i = *i.data();
+            if i >= self.shape[axis] {
+                let mut index = index.clone();
+                index[axis] = i;
+                return Err(TensorError::IndexOutOfRange { index, shape: self.shape.clone() });
+            }
+            positions.push(index.iter().zip(strides.iter()).enumerate()
+                .map(|(j, (&k, stride))| if j == axis { i * stride } else { k * stride }).sum());
+            next_index(&mut index, &indexes.shape);
+        }
+        Ok(positions)
+    }
+}
+
+impl<T> Tensor<T>
+where
+    T: Clone
+{
+    /// Slice the Tensor with an index for each axis
+    /// 
+    /// The axes after the given indexes are kept whole.
+    /// Integer arrays select along each axis independently.
+    /// The contents are not copied unless an integer array is used.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `indexes` - Index of each axis
+    /// 
+    /// # Panics
+    /// 
+    /// Panics if there are more indexes than axes, an index is out of range or a step is 0.
+    pub fn slice(&self, indexes: &[SliceIndex]) -> Self {
+        self.try_slice(indexes).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Slice the Tensor with an index for each axis
+    /// 
+    /// # Arguments
+    /// 
+    /// * `indexes` - Index of each axis
+    /// 
+    /// # Errors
+    /// 
+    /// * `NdimMismatch` - If there are more indexes than axes
+    /// * `IndexOutOfRange` - If an index is out of range
+    /// * `InvalidStep` - If a step is 0
+    pub fn try_slice(&self, indexes: &[SliceIndex]) -> Result<Self, TensorError> {
+        let selected = self.resolve_slice(indexes)?;
+        if indexes.iter().any(|index| matches!(index, SliceIndex::Array(_))) {
+            let (positions, shape) = self.slice_positions(&selected);
+            let data = self.data();
+            return Ok(Self::from_vec(positions.into_iter().map(|i| data[i].clone()).collect(), shape));
+        }
+        let mut shape = Vec::new();
+        let mut strides = Vec::new();
+        let mut offset = self.offset;
+        for (axis, (s, kept)) in selected.iter().enumerate() {
+            if let Some(first) = s.first() {
+                offset += first * self.strides[axis];
+            }
+            if *kept {
+                let step = match indexes.get(axis) {
+                    Some(SliceIndex::Range { step, .. }) => *step,
+                    _ => 1,
+                };
+                shape.push(s.len());
+                strides.push(self.strides[axis] * step);
+            }
+        }
+        if shape.contains(&0) {
+            offset = self.offset;
+        }
+        Ok(self.view(shape, strides, offset))
+    }
+
+    /// Select the indexes along the axis
+    /// 
+    /// # Arguments
+    /// 
+    /// * `axis` - Axis to select along
+    /// * `indexes` - Indexes to select, which may repeat
+    /// 
+    /// # Panics
+    /// 
+    /// Panics if the axis or an index is out of range.
+    pub fn index_select(&self, axis: usize, indexes: &[usize]) -> Self {
+        self.try_index_select(axis, indexes).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Select the indexes along the axis
+    /// 
+    /// # Arguments
+    /// 
+    /// * `axis` - Axis to select along
+    /// * `indexes` - Indexes to select, which may repeat
+    /// 
+    /// # Errors
+    /// 
+    /// * `InvalidAxis` - If the axis is out of range
+    /// * `IndexOutOfRange` - If an index is out of range
+    pub fn try_index_select(&self, axis: usize, indexes: &[usize]) -> Result<Self, TensorError> {
+        self.try_slice(&Self::axis_slice(self.ndim(), axis, indexes)?)
+    }
+
+    /// Make the slice that selects the indexes along the axis
+    fn axis_slice(ndim: usize, axis: usize, indexes: &[usize]) -> Result<Vec<SliceIndex>, TensorError> {
+        if axis >= ndim {
+            return Err(TensorError::InvalidAxis { axis, ndim });
+        }
+        let mut slice = vec![SliceIndex::full(); axis];
+        slice.push(SliceIndex::Array(indexes.to_vec()));
+        Ok(slice)
+    }
+
+    /// Gather the values along the axis
+    /// 
+    /// The result has the shape of `indexes`, and the value at `i` is this Tensor at `i`
+    /// with the index of the axis replaced by `indexes[i]`.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `axis` - Axis to gather along
+    /// * `indexes` - Indexes with the shape of this Tensor except for the axis
+    /// 
+    /// # Panics
+    /// 
+    /// Panics if the axis or an index is out of range or the shapes do not match.
+    pub fn gather(&self, axis: usize, indexes: &Tensor<usize>) -> Self {
+        self.try_gather(axis, indexes).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Gather the values along the axis
+    /// 
+    /// # Arguments
+    /// 
+    /// * `axis` - Axis to gather along
+    /// * `indexes` - Indexes with the shape of this Tensor except for the axis
+    /// 
+    /// # Errors
+    /// 
+    /// * `InvalidAxis` - If the axis is out of range
+    /// * `ShapeMismatch` - If the shape of `indexes` differs from this Tensor except for the axis
+    /// * `IndexOutOfRange` - If an index is out of range
+    pub fn try_gather(&self, axis: usize, indexes: &Tensor<usize>) -> Result<Self, TensorError> {
+        let positions = self.gather_positions(axis, indexes)?;
+        let data = self.data();
+        Ok(Self::from_vec(positions.into_iter().map(|i| data[i].clone()).collect(), indexes.shape.clone()))
+    }
+}
+
+impl<T> Tensor<T>
+where
+    T: std::ops::AddAssign + Copy
+{
+    /// Add the values of `src` to the positions in the row-major order of a copy of this Tensor
+    fn add_at(&self, positions: &[usize], src: &Self) -> Self {
+        let mut result = self.clone();
+        let data = result.data_mut();
+        for (&i, x) in positions.iter().zip(src.iter()) {
+            data[i] += *x;
+        }
+        result
+    }
+
+    /// Add the Tensor to the slice of a copy of this Tensor
+    /// 
+    /// The values for a repeated index of an integer array are all added.
+    /// This is the scatter-add that reverses `slice`.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `indexes` - Index of each axis
+    /// * `src` - Tensor broadcast to the shape of the slice
+    /// 
+    /// # Panics
+    /// 
+    /// Panics if the slice is not valid or `src` cannot be broadcast to it.
+    pub fn slice_add(&self, indexes: &[SliceIndex], src: &Self) -> Self {
+        self.try_slice_add(indexes, src).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Add the Tensor to the slice of a copy of this Tensor
+    /// 
+    /// # Arguments
+    /// 
+    /// * `indexes` - Index of each axis
+    /// * `src` - Tensor broadcast to the shape of the slice
+    /// 
+    /// # Errors
+    /// 
+    /// * `NdimMismatch` - If there are more indexes than axes
+    /// * `IndexOutOfRange` - If an index is out of range
+    /// * `InvalidStep` - If a step is 0
+    /// * `BroadcastMismatch` - If `src` cannot be broadcast to the shape of the slice
+    pub fn try_slice_add(&self, indexes: &[SliceIndex], src: &Self) -> Result<Self, TensorError> {
+        let selected = self.resolve_slice(indexes)?;
+        let (positions, shape) = self.slice_positions(&selected);
+        let src = src.try_broadcast_to(&shape)?;
+        Ok(self.add_at(&positions, &src))
+    }
+
+    /// Add the Tensor to the indexes along the axis of a copy of this Tensor
+    /// 
+    /// The values for a repeated index are all added.
+    /// This is the scatter-add that reverses `index_select`.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `axis` - Axis to add along
+    /// * `indexes` - Indexes to add to
+    /// * `src` - Tensor broadcast to the shape of `index_select`
+    /// 
+    /// # Panics
+    /// 
+    /// Panics if the axis or an index is out of range or `src` cannot be broadcast.
+    pub fn index_add(&self, axis: usize, indexes: &[usize], src: &Self) -> Self {
+        self.try_index_add(axis, indexes, src).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Add the Tensor to the indexes along the axis of a copy of this Tensor
+    /// 
+    /// # Arguments
+    /// 
+    /// * `axis` - Axis to add along
+    /// * `indexes` - Indexes to add to
+    /// * `src` - Tensor broadcast to the shape of `index_select`
+    /// 
+    /// # Errors
+    /// 
+    /// * `InvalidAxis` - If the axis is out of range
+    /// * `IndexOutOfRange` - If an index is out of range
+    /// * `BroadcastMismatch` - If `src` cannot be broadcast to the shape of `index_select`
+    pub fn try_index_add(&self, axis: usize, indexes: &[usize], src: &Self) -> Result<Self, TensorError> {
+        self.try_slice_add(&Self::axis_slice(self.ndim(), axis, indexes)?, src)
+    }
+
+    /// Add the values along the axis of a copy of this Tensor
+    /// 
+    /// The value of `src` at `i` is added to this Tensor at `i`
+    /// with the index of the axis replaced by `indexes[i]`.
+    /// This is the scatter-add that reverses `gather`.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `axis` - Axis to add along
+    /// * `indexes` - Indexes with the shape of this Tensor except for the axis
+    /// * `src` - Tensor with the shape of `indexes`
+    /// 
+    /// # Panics
+    /// 
+    /// Panics if the axis or an index is out of range or the shapes do not match.
+    pub fn scatter_add(&self, axis: usize, indexes: &Tensor<usize>, src: &Self) -> Self {
+        self.try_scatter_add(axis, indexes, src).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Add the values along the axis of a copy of this Tensor
+    /// 
+    /// # Arguments
+    /// 
+    /// * `axis` - Axis to add along
+    /// * `indexes` - Indexes with the shape of this Tensor except for the axis
+    /// * `src` - Tensor with the shape of `indexes`
+    /// 
+    /// # Errors
+    /// 
+    /// * `InvalidAxis` - If the axis is out of range
+    /// * `ShapeMismatch` - If the shapes of `indexes` and `src` do not match
+    /// * `IndexOutOfRange` - If an index is out of range
+    pub fn try_scatter_add(&self, axis: usize, indexes: &Tensor<usize>, src: &Self) -> Result<Self, TensorError> {
+        if src.shape != indexes.shape {
+            return Err(TensorError::ShapeMismatch { left: indexes.shape.clone(), right: src.shape.clone() });
+        }
+        let positions = self.gather_positions(axis, indexes)?;
+        Ok(self.add_at(&positions, src))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn slice_normal() {
+        let x = Tensor::<f64>::arrange([3, 4]);
+
+        let y = x.slice(&[1.into()]);
+        assert_eq!(y, Tensor::new_from_num_vec([4.0, 5.0, 6.0, 7.0], [4]));
+        let y = x.slice(&[(1..3).into(), SliceIndex::step(0, 4, 2)]);
+        assert_eq!(y, Tensor::new_from_num_vec([4.0, 6.0, 8.0, 10.0], [2, 2]));
+        assert!(y.shares_data(&x));
+        let y = x.slice(&[(..).into(), 3.into()]);
+        assert_eq!(y, Tensor::new_from_num_vec([3.0, 7.0, 11.0], [3]));
+        let y = x.slice(&[(2..).into(), (..0).into()]);
+        assert_eq!(y.shape(), &vec![1, 0]);
+    }
+
+    #[test]
+    fn slice_array() {
+        let x = Tensor::<f64>::arrange([3, 4]);
+
+        let y = x.slice(&[vec![2, 0, 2].into(), (1..3).into()]);
+        assert_eq!(y, Tensor::new_from_num_vec([9.0, 10.0, 1.0, 2.0, 9.0, 10.0], [3, 2]));
+        let y = x.slice(&[1.into(), vec![3, 0].into()]);
+        assert_eq!(y, Tensor::new_from_num_vec([7.0, 4.0], [2]));
+        let y = x.transpose().slice(&[vec![1].into()]);
+        assert_eq!(y, Tensor::new_from_num_vec([1.0, 5.0, 9.0], [1, 3]));
+    }
+
+    #[test]
+    fn try_slice_error() {
+        let x = Tensor::<f64>::arrange([3, 4]);
+
+        assert_eq!(x.try_slice(&[3.into()]),
+            Err(TensorError::IndexOutOfRange { index: vec![3, 0], shape: vec![3, 4] }));
+        assert_eq!(x.try_slice(&[(..).into(), (1..5).into()]),
+            Err(TensorError::IndexOutOfRange { index: vec![0, 5], shape: vec![3, 4] }));
+        assert_eq!(x.try_slice(&[(..).into(), vec![0, 4].into()]),
+            Err(TensorError::IndexOutOfRange { index: vec![0, 4], shape: vec![3, 4] }));
+        assert_eq!(x.try_slice(&[SliceIndex::step(0, 3, 0)]), Err(TensorError::InvalidStep { axis: 0 }));
+        assert_eq!(x.try_slice(&[0.into(), 0.into(), 0.into()]),
+            Err(TensorError::NdimMismatch { expected: 2, actual: 3 }));
+    }
+
+    #[test]
+    fn slice_add_normal() {
+        let x = Tensor::<f64>::full(0.0, vec![3, 2]);
+
+        let y = x.slice_add(&[vec![2, 0, 2].into()], &Tensor::new_from_num_vec([1.0, 2.0], [2]));
+        assert_eq!(y, Tensor::new_from_num_vec([1.0, 2.0, 0.0, 0.0, 2.0, 4.0], [3, 2]));
+        let y = x.slice_add(&[SliceIndex::step(0, 3, 2), 1.into()], &Tensor::new_from_num_vec([1.0, 2.0], [2]));
+        assert_eq!(y, Tensor::new_from_num_vec([0.0, 1.0, 0.0, 0.0, 0.0, 2.0], [3, 2]));
+        assert_eq!(x, Tensor::full(0.0, vec![3, 2]));
+    }
+
+    #[test]
+    fn index_select_normal() {
+        let x = Tensor::<f64>::arrange([2, 3]);
+
+        assert_eq!(x.index_select(1, &[2, 2, 0]), Tensor::new_from_num_vec([2.0, 2.0, 0.0, 5.0, 5.0, 3.0], [2, 3]));
+        assert_eq!(x.try_index_select(2, &[0]), Err(TensorError::InvalidAxis { axis: 2, ndim: 2 }));
+
+        let y = x.index_add(0, &[1, 1], &Tensor::full(1.0, vec![2, 3]));
+        assert_eq!(y, Tensor::new_from_num_vec([0.0, 1.0, 2.0, 5.0, 6.0, 7.0], [2, 3]));
+    }
+
+    #[test]
+    fn gather_normal() {
+        let x = Tensor::<f64>::arrange([2, 3]);
+        let indexes = Tensor::new_from_num_vec([2, 0, 1, 1], [2, 2]);
+
+        assert_eq!(x.gather(1, &indexes), Tensor::new_from_num_vec([2.0, 0.0, 4.0, 4.0], [2, 2]));
+        assert_eq!(x.try_gather(1, &Tensor::new_from_num_vec([3], [1, 1])),
+            Err(TensorError::ShapeMismatch { left: vec![2, 3], right: vec![1, 1] }));
+        assert_eq!(x.try_gather(1, &Tensor::new_from_num_vec([0, 3], [2, 1])),
+            Err(TensorError::IndexOutOfRange { index: vec![1, 3], shape: vec![2, 3] }));
+
+        let y = Tensor::full(0.0, vec![2, 3]).scatter_add(1, &indexes, &Tensor::full(1.0, vec![2, 2]));
+        assert_eq!(y, Tensor::new_from_num_vec([1.0, 0.0, 1.0, 0.0, 2.0, 0.0], [2, 3]));
+    }
+}
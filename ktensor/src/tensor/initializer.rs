@@ -0,0 +1,259 @@
+use super::Tensor;
+use super::random::TensorRng;
+
+/// Initializer of parameter tensors
+/// 
+/// The fans of a shape are computed with the first axis as the input axis
+/// and the second axis as the output axis, which matches the `[in, out]` weight of Linear.
+/// The remaining axes are multiplied to both fans as the receptive field.
+/// A 1-D shape uses its length for both fans.
+/// 
+/// # Variants
+/// 
+/// * `Zeros` - All zeros
+/// * `Ones` - All ones
+/// * `Constant` - All the given value
+/// * `Uniform` - `U(low, high)`
+/// * `Normal` - `N(mean, std^2)`
+/// * `TruncatedNormal` - `N(mean, std^2)` redrawn until within two standard deviations
+/// * `XavierUniform` - `U(-a, a)` with `a = gain * sqrt(6 / (fan_in + fan_out))`
+/// * `XavierNormal` - `N(0, s^2)` with `s = gain * sqrt(2 / (fan_in + fan_out))`
+/// * `HeUniform` - `U(-a, a)` with `a = sqrt(6 / fan_in)`
+/// * `HeNormal` - `N(0, s^2)` with `s = sqrt(2 / fan_in)`
+/// * `Orthogonal` - (Semi) orthogonal matrix of the shape flattened to `[shape[0], rest]`, scaled by gain
+#[derive(Debug, Clone, PartialEq)]
+pub enum Initializer {
+    Zeros,
+    Ones,
+    Constant(f64),
+    Uniform { low: f64, high: f64 },
+    Normal { mean: f64, std: f64 },
+    TruncatedNormal { mean: f64, std: f64 },
+    XavierUniform { gain: f64 },
+    XavierNormal { gain: f64 },
+    HeUniform,
+    HeNormal,
+    Orthogonal { gain: f64 },
+}
+
+impl Initializer {
+    /// Compute the fan in and the fan out of the shape.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `shape` - Tensor shape
+    pub fn fans(shape: &[usize]) -> (usize, usize) {
+        match shape.len() {
+            0 => (1, 1),
+            1 => (shape[0], shape[0]),
+            _ => {
+                let receptive_field = shape[2..].iter().product::<usize>();
+                (shape[0] * receptive_field, shape[1] * receptive_field)
+            }
+        }
+    }
+
+    /// Generate the values in row-major order.
+    fn values(&self, shape: &[usize], rng: &mut TensorRng) -> Vec<f64> {
+        let size = shape.iter().product::<usize>();
+        let (fan_in, fan_out) = Self::fans(shape);
+        let uniform = |rng: &mut TensorRng, bound: f64| -> Vec<f64> {
+            (0..size).map(|_| (rng.next_uniform_f64() * 2.0 - 1.0) * bound).collect()
+        };
+        let normal = |rng: &mut TensorRng, mean: f64, std: f64| -> Vec<f64> {
+            (0..size).map(|_| mean + std * rng.next_standard_normal_f64()).collect()
+        };
+        match *self {
+            Initializer::Zeros => vec![0.0; size],
+            Initializer::Ones => vec![1.0; size],
+            Initializer::Constant(value) => vec![value; size],
+            Initializer::Uniform { low, high } => {
+                assert!(low <= high, "Invalid range");
+                (0..size).map(|_| low + (high - low) * rng.next_uniform_f64()).collect()
+            },
+            Initializer::Normal { mean, std } => normal(rng, mean, std),
+            Initializer::TruncatedNormal { mean, std } => {
+                (0..size).map(|_| loop {
+                    let x = rng.next_standard_normal_f64();
+                    if x.abs() <= 2.0 {
+                        break mean + std * x;
+                    }
+                }).collect()
+            },
+            Initializer::XavierUniform { gain } =>
+                uniform(rng, gain * (6.0 / (fan_in + fan_out) as f64).sqrt()),
+            Initializer::XavierNormal { gain } =>
+                normal(rng, 0.0, gain * (2.0 / (fan_in + fan_out) as f64).sqrt()),
+            Initializer::HeUniform => uniform(rng, (6.0 / fan_in as f64).sqrt()),
+            Initializer::HeNormal => normal(rng, 0.0, (2.0 / fan_in as f64).sqrt()),
+            Initializer::Orthogonal { gain } => Self::orthogonal(shape, gain, rng),
+        }
+    }
+
+    /// Generate a (semi) orthogonal matrix by the Gram-Schmidt process on a normal matrix.
+    /// 
+    /// # Panics
+    /// 
+    /// * `Orthogonal initializer needs at least 2 dimensions` - If the shape has less than 2 axes
+    fn orthogonal(shape: &[usize], gain: f64, rng: &mut TensorRng) -> Vec<f64> {
+        assert!(shape.len() >= 2, "Orthogonal initializer needs at least 2 dimensions");
+        let rows = shape[0];
+        let cols = shape[1..].iter().product::<usize>();
+        // Orthonormal columns of a `long x short` matrix, stored column by column
+        let (long, short) = if rows >= cols { (rows, cols) } else { (cols, rows) };
+        let mut columns: Vec<Vec<f64>> = Vec::with_capacity(short);
+        while columns.len() < short {
+            let mut v = (0..long).map(|_| rng.next_standard_normal_f64()).collect::<Vec<f64>>();
+            for q in columns.iter() {
+                let dot = v.iter().zip(q.iter()).map(|(a, b)| a * b).sum::<f64>();
+                v.iter_mut().zip(q.iter()).for_each(|(a, b)| *a -= dot * b);
+            }
+            let norm = v.iter().map(|a| a * a).sum::<f64>().sqrt();
+            if norm < 1e-10 {
+                continue;
+            }
+            v.iter_mut().for_each(|a| *a /= norm);
+            columns.push(v);
+        }
+        (0..rows * cols).map(|index| {
+            let (i, j) = (index / cols, index % cols);
+            let value = if rows >= cols { columns[j][i] } else { columns[i][j] };
+            gain * value
+        }).collect()
+    }
+
+    /// Generate a f64 tensor.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `shape` - Tensor shape
+    /// * `rng` - Random number generator
+    pub fn init_f64<U: AsRef<[usize]>>(&self, shape: U, rng: &mut TensorRng) -> Tensor<f64> {
+        let shape = shape.as_ref();
+        Tensor::new_from_num_vec(self.values(shape, rng), shape)
+    }
+
+    /// Generate a f32 tensor.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `shape` - Tensor shape
+    /// * `rng` - Random number generator
+    pub fn init_f32<U: AsRef<[usize]>>(&self, shape: U, rng: &mut TensorRng) -> Tensor<f32> {
+        let shape = shape.as_ref();
+        Tensor::new_from_num_vec(self.values(shape, rng).into_iter().map(|x| x as f32), shape)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utility::assert_approx_eq;
+
+    fn mean_std(x: &Tensor<f64>) -> (f64, f64) {
+        let n = x.size() as f64;
+        let mean = x.data().iter().map(|x| x.data()).sum::<f64>() / n;
+        let var = x.data().iter().map(|x| (x.data() - mean).powi(2)).sum::<f64>() / n;
+        (mean, var.sqrt())
+    }
+
+    #[test]
+    fn fans_normal() {
+        assert_eq!(Initializer::fans(&[]), (1, 1));
+        assert_eq!(Initializer::fans(&[5]), (5, 5));
+        assert_eq!(Initializer::fans(&[3, 4]), (3, 4));
+        assert_eq!(Initializer::fans(&[3, 4, 2, 2]), (12, 16));
+    }
+
+    #[test]
+    fn constant_normal() {
+        let mut rng = TensorRng::seed_from_u64(0);
+        assert_eq!(Initializer::Zeros.init_f64([2, 3], &mut rng), Tensor::full(0.0, vec![2, 3]));
+        assert_eq!(Initializer::Ones.init_f64([2, 3], &mut rng), Tensor::full(1.0, vec![2, 3]));
+        assert_eq!(Initializer::Constant(0.5).init_f32([2], &mut rng), Tensor::new_from_num_vec(vec![0.5f32, 0.5], vec![2]));
+    }
+
+    #[test]
+    fn uniform_normal() {
+        let mut rng = TensorRng::seed_from_u64(0);
+        let x = Initializer::Uniform { low: -2.0, high: 3.0 }.init_f64([100, 100], &mut rng);
+        assert!(x.data().iter().all(|x| (-2.0..3.0).contains(x.data())));
+        let (mean, _) = mean_std(&x);
+        assert_approx_eq(mean, 0.5, 0.1);
+    }
+
+    #[test]
+    fn normal_normal() {
+        let mut rng = TensorRng::seed_from_u64(0);
+        let x = Initializer::Normal { mean: 1.0, std: 2.0 }.init_f64([100, 100], &mut rng);
+        let (mean, std) = mean_std(&x);
+        assert_approx_eq(mean, 1.0, 0.1);
+        assert_approx_eq(std, 2.0, 0.1);
+    }
+
+    #[test]
+    fn truncated_normal_normal() {
+        let mut rng = TensorRng::seed_from_u64(0);
+        let x = Initializer::TruncatedNormal { mean: 1.0, std: 0.5 }.init_f64([100, 100], &mut rng);
+        assert!(x.data().iter().all(|x| (x.data() - 1.0).abs() <= 1.0));
+        let (mean, _) = mean_std(&x);
+        assert_approx_eq(mean, 1.0, 0.05);
+    }
+
+    #[test]
+    fn xavier_normal() {
+        let mut rng = TensorRng::seed_from_u64(0);
+        let bound = (6.0f64 / 300.0).sqrt();
+        let x = Initializer::XavierUniform { gain: 1.0 }.init_f64([100, 200], &mut rng);
+        assert!(x.data().iter().all(|x| x.data().abs() <= bound));
+        let (_, std) = mean_std(&x);
+        assert_approx_eq(std, bound / 3.0f64.sqrt(), 0.01);
+
+        let x = Initializer::XavierNormal { gain: 2.0 }.init_f64([100, 200], &mut rng);
+        let (_, std) = mean_std(&x);
+        assert_approx_eq(std, 2.0 * (2.0f64 / 300.0).sqrt(), 0.01);
+    }
+
+    #[test]
+    fn he_normal() {
+        let mut rng = TensorRng::seed_from_u64(0);
+        let bound = (6.0f64 / 100.0).sqrt();
+        let x = Initializer::HeUniform.init_f64([100, 200], &mut rng);
+        assert!(x.data().iter().all(|x| x.data().abs() <= bound));
+
+        let x = Initializer::HeNormal.init_f32([100, 200], &mut rng);
+        assert_eq!(x.data_type(), "f32");
+        let n = x.size() as f32;
+        let std = (x.data().iter().map(|x| x.data().powi(2)).sum::<f32>() / n).sqrt();
+        assert!((std - (2.0f32 / 100.0).sqrt()).abs() < 0.01);
+    }
+
+    #[test]
+    fn orthogonal_normal() {
+        let mut rng = TensorRng::seed_from_u64(0);
+        for shape in [[4, 3], [3, 4], [5, 5]] {
+            let x = Initializer::Orthogonal { gain: 1.0 }.init_f64(shape, &mut rng);
+            assert_eq!(x.shape(), &shape);
+            // The smaller gram matrix is the identity
+            let gram = if shape[0] >= shape[1] {
+                x.transpose().matmul(&x)
+            } else {
+                x.matmul(&x.transpose())
+            };
+            let n = shape[0].min(shape[1]);
+            for i in 0..n {
+                for j in 0..n {
+                    let expected = if i == j { 1.0 } else { 0.0 };
+                    assert_approx_eq(*gram.at(&[i, j]).data(), expected, 1e-10);
+                }
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn orthogonal_error_1d() {
+        let mut rng = TensorRng::seed_from_u64(0);
+        Initializer::Orthogonal { gain: 1.0 }.init_f64([4], &mut rng);
+    }
+}
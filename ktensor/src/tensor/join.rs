@@ -0,0 +1,253 @@
+use super::{Tensor, SliceIndex};
+use crate::error::TensorError;
+
+impl<T> Tensor<T>
+{
+    /// Check that the Tensors can be joined along the axis and get the size of the axis of the result
+    fn join_check(tensors: &[&Self], axis: usize) -> Result<usize, TensorError> {
+        let first = tensors.first()
+            .ok_or(TensorError::InvalidShape { shape: vec![], reason: "at least one Tensor is needed to join" })?;
+        if axis >= first.ndim() {
+            return Err(TensorError::InvalidAxis { axis, ndim: first.ndim() });
+        }
+        for tensor in tensors.iter().skip(1) {
+            if tensor.ndim() != first.ndim()
+                || (0..first.ndim()).any(|i| i != axis && tensor.shape[i] != first.shape[i]) {
+                return Err(TensorError::ShapeMismatch { left: first.shape.clone(), right: tensor.shape.clone() });
+            }
+        }
+        Ok(tensors.iter().map(|tensor| tensor.shape[axis]).sum())
+    }
+
+    /// Get the sizes of `chunks` parts of the size, which differ by at most one
+    fn chunk_sizes(size: usize, chunks: usize) -> Vec<usize> {
+        (0..chunks).map(|i| size / chunks + if i < size % chunks { 1 } else { 0 }).collect()
+    }
+}
+
+impl<T> Tensor<T>
+where
+    T: Clone
+{
+    /// Concatenate the Tensors along the axis
+    /// 
+    /// # Arguments
+    /// 
+    /// * `tensors` - Tensors with the same shape except for the axis
+    /// * `axis` - Axis to concatenate along
+    /// 
+    /// # Panics
+    /// 
+    /// Panics if there are no Tensors, the axis is out of range or the shapes do not match.
+    pub fn concat(tensors: &[&Self], axis: usize) -> Self {
+        Self::try_concat(tensors, axis).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Concatenate the Tensors along the axis
+    /// 
+    /// # Arguments
+    /// 
+    /// * `tensors` - Tensors with the same shape except for the axis
+    /// * `axis` - Axis to concatenate along
+    /// 
+    /// # Errors
+    /// 
+    /// * `InvalidShape` - If there are no Tensors
+    /// * `InvalidAxis` - If the axis is out of range
+    /// * `ShapeMismatch` - If the shapes differ except for the axis
+    pub fn try_concat(tensors: &[&Self], axis: usize) -> Result<Self, TensorError> {
+        let size = Self::join_check(tensors, axis)?;
+        let mut shape = tensors[0].shape.clone();
+        shape[axis] = size;
+        let outer = shape[..axis].iter().product::<usize>();
+        let data = tensors.iter().map(|tensor| tensor.data()).collect::<Vec<_>>();
+        let blocks = tensors.iter().map(|tensor| tensor.shape[axis..].iter().product::<usize>()).collect::<Vec<_>>();
+        let mut result = Vec::with_capacity(shape.iter().product());
+        for i in 0..outer {
+            for (data, &block) in data.iter().zip(blocks.iter()) {
+                result.extend_from_slice(&data[i * block..(i + 1) * block]);
+            }
+        }
+        Ok(Self::from_vec(result, shape))
+    }
+
+    /// Stack the Tensors along a new axis
+    /// 
+    /// # Arguments
+    /// 
+    /// * `tensors` - Tensors with the same shape
+    /// * `axis` - Position of the new axis in the result
+    /// 
+    /// # Panics
+    /// 
+    /// Panics if there are no Tensors, the axis is out of range or the shapes do not match.
+    pub fn stack(tensors: &[&Self], axis: usize) -> Self {
+        Self::try_stack(tensors, axis).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Stack the Tensors along a new axis
+    /// 
+    /// # Arguments
+    /// 
+    /// * `tensors` - Tensors with the same shape
+    /// * `axis` - Position of the new axis in the result
+    /// 
+    /// # Errors
+    /// 
+    /// * `InvalidShape` - If there are no Tensors
+    /// * `InvalidAxis` - If the axis is greater than the number of dimensions
+    /// * `ShapeMismatch` - If the shapes differ
+    pub fn try_stack(tensors: &[&Self], axis: usize) -> Result<Self, TensorError> {
+        let first = tensors.first()
+            .ok_or(TensorError::InvalidShape { shape: vec![], reason: "at least one Tensor is needed to join" })?;
+        if axis > first.ndim() {
+            return Err(TensorError::InvalidAxis { axis, ndim: first.ndim() + 1 });
+        }
+        if let Some(tensor) = tensors.iter().find(|tensor| tensor.shape != first.shape) {
+            return Err(TensorError::ShapeMismatch { left: first.shape.clone(), right: tensor.shape.clone() });
+        }
+        let mut shape = first.shape.clone();
+        shape.insert(axis, 1);
+        let expanded = tensors.iter()
+            .map(|tensor| tensor.try_reshape(&shape))
+            .collect::<Result<Vec<_>, _>>()?;
+        Self::try_concat(&expanded.iter().collect::<Vec<_>>(), axis)
+    }
+
+    /// Split the Tensor along the axis into parts of the sizes
+    /// 
+    /// The parts share the contents of this Tensor.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `axis` - Axis to split along
+    /// * `sizes` - Size of the axis of each part
+    /// 
+    /// # Panics
+    /// 
+    /// Panics if the axis is out of range or the sizes do not add up to the size of the axis.
+    pub fn split(&self, axis: usize, sizes: &[usize]) -> Vec<Self> {
+        self.try_split(axis, sizes).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Split the Tensor along the axis into parts of the sizes
+    /// 
+    /// # Arguments
+    /// 
+    /// * `axis` - Axis to split along
+    /// * `sizes` - Size of the axis of each part
+    /// 
+    /// # Errors
+    /// 
+    /// * `InvalidAxis` - If the axis is out of range
+    /// * `InvalidShape` - If the sizes do not add up to the size of the axis
+    pub fn try_split(&self, axis: usize, sizes: &[usize]) -> Result<Vec<Self>, TensorError> {
+        if axis >= self.ndim() {
+            return Err(TensorError::InvalidAxis { axis, ndim: self.ndim() });
+        }
+        if sizes.iter().sum::<usize>() != self.shape[axis] {
+            return Err(TensorError::InvalidShape {
+                shape: self.shape.clone(), reason: "the sizes of the parts must add up to the size of the axis" });
+        }
+        let mut start = 0;
+        sizes.iter().map(|&size| {
+            let mut indexes = vec![SliceIndex::full(); axis];
+            indexes.push((start..start + size).into());
+            start += size;
+            self.try_slice(&indexes)
+        }).collect()
+    }
+
+    /// Split the Tensor along the axis into `chunks` parts
+    /// 
+    /// The sizes of the parts differ by at most one, and the first parts are the larger.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `axis` - Axis to split along
+    /// * `chunks` - Number of the parts
+    /// 
+    /// # Panics
+    /// 
+    /// Panics if the axis is out of range or `chunks` is 0.
+    pub fn chunk(&self, axis: usize, chunks: usize) -> Vec<Self> {
+        self.try_chunk(axis, chunks).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Split the Tensor along the axis into `chunks` parts
+    /// 
+    /// # Arguments
+    /// 
+    /// * `axis` - Axis to split along
+    /// * `chunks` - Number of the parts
+    /// 
+    /// # Errors
+    /// 
+    /// * `InvalidAxis` - If the axis is out of range
+    /// * `InvalidShape` - If `chunks` is 0
+    pub fn try_chunk(&self, axis: usize, chunks: usize) -> Result<Vec<Self>, TensorError> {
+        if axis >= self.ndim() {
+            return Err(TensorError::InvalidAxis { axis, ndim: self.ndim() });
+        }
+        if chunks == 0 {
+            return Err(TensorError::InvalidShape { shape: self.shape.clone(), reason: "the number of chunks must be positive" });
+        }
+        self.try_split(axis, &Self::chunk_sizes(self.shape[axis], chunks))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn concat_normal() {
+        let x = Tensor::<f64>::arrange([2, 2]);
+        let y = Tensor::<f64>::arrange([2, 1]);
+        let z = Tensor::concat(&[&x, &y], 1);
+        assert_eq!(z, Tensor::new_from_num_vec([0.0, 1.0, 0.0, 2.0, 3.0, 1.0], [2, 3]));
+
+        let z = Tensor::concat(&[&x, &x.transpose()], 0);
+        assert_eq!(z, Tensor::new_from_num_vec([0.0, 1.0, 2.0, 3.0, 0.0, 2.0, 1.0, 3.0], [4, 2]));
+    }
+
+    #[test]
+    fn try_concat_error() {
+        let x = Tensor::<f64>::arrange([2, 2]);
+        let y = Tensor::<f64>::arrange([1, 3]);
+        assert_eq!(Tensor::try_concat(&[&x, &y], 1),
+            Err(TensorError::ShapeMismatch { left: vec![2, 2], right: vec![1, 3] }));
+        assert_eq!(Tensor::try_concat(&[&x], 2), Err(TensorError::InvalidAxis { axis: 2, ndim: 2 }));
+        assert!(matches!(Tensor::<f64>::try_concat(&[], 0), Err(TensorError::InvalidShape { .. })));
+    }
+
+    #[test]
+    fn stack_normal() {
+        let x = Tensor::<f64>::arrange([2]);
+        let y = Tensor::new_from_num_vec([5.0, 6.0], [2]);
+        assert_eq!(Tensor::stack(&[&x, &y], 0), Tensor::new_from_num_vec([0.0, 1.0, 5.0, 6.0], [2, 2]));
+        assert_eq!(Tensor::stack(&[&x, &y], 1), Tensor::new_from_num_vec([0.0, 5.0, 1.0, 6.0], [2, 2]));
+        assert_eq!(Tensor::try_stack(&[&x, &y], 2), Err(TensorError::InvalidAxis { axis: 2, ndim: 2 }));
+    }
+
+    #[test]
+    fn split_normal() {
+        let x = Tensor::<f64>::arrange([2, 3]);
+        let parts = x.split(1, &[1, 2]);
+        assert_eq!(parts[0], Tensor::new_from_num_vec([0.0, 3.0], [2, 1]));
+        assert_eq!(parts[1], Tensor::new_from_num_vec([1.0, 2.0, 4.0, 5.0], [2, 2]));
+        assert_eq!(Tensor::concat(&parts.iter().collect::<Vec<_>>(), 1), x);
+        assert!(matches!(x.try_split(1, &[1, 1]), Err(TensorError::InvalidShape { .. })));
+    }
+
+    #[test]
+    fn chunk_normal() {
+        let x = Tensor::<f64>::arrange([5]);
+        let parts = x.chunk(0, 3);
+        assert_eq!(parts.iter().map(|part| part.shape()[0]).collect::<Vec<_>>(), vec![2, 2, 1]);
+        assert_eq!(parts[2], Tensor::new_from_num_vec([4.0], [1]));
+        assert_eq!(x.chunk(0, 7).len(), 7);
+        assert!(matches!(x.try_chunk(0, 0), Err(TensorError::InvalidShape { .. })));
+        assert_eq!(x.try_chunk(1, 2), Err(TensorError::InvalidAxis { axis: 1, ndim: 1 }));
+    }
+}
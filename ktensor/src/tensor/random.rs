@@ -1,24 +1,87 @@
 extern crate rand;
+extern crate rand_chacha;
 
-use rand::Rng;
-use rand::distributions::{Distribution, Standard};
+use rand::{Rng, SeedableRng};
+use rand::distributions::{Distribution, Standard, uniform::SampleUniform};
+use rand_chacha::ChaCha8Rng;
 
 use super::{Tensor, Scaler};
+use crate::num::{FromF64, FromUsize};
 
 /// Random number generator for Tensor.
 /// 
+/// The generator is a deterministic ChaCha8 stream,
+/// so the same seed always gives the same tensors.
+/// 
 /// # Fields
 /// 
 /// * `rng` - Random number generator.
+#[derive(Debug, Clone, PartialEq)]
 pub struct TensorRng {
-    rng: rand::rngs::ThreadRng,
+    rng: ChaCha8Rng,
+}
+
+/// Snapshot of the state of TensorRng.
+/// 
+/// # Fields
+/// 
+/// * `seed` - Seed of the stream.
+/// * `stream` - Stream number.
+/// * `word_pos` - Position in the stream.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TensorRngState {
+    pub seed: [u8; 32],
+    pub stream: u64,
+    pub word_pos: u128,
 }
 
 impl TensorRng {
-    /// Create a new TensorRng.
+    /// Create a new TensorRng seeded from the operating system.
     pub fn new() -> Self {
         Self {
-            rng: rand::thread_rng(),
+            rng: ChaCha8Rng::from_entropy(),
+        }
+    }
+
+    /// Create a new TensorRng from a seed.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `seed` - Seed.
+    pub fn seed_from_u64(seed: u64) -> Self {
+        Self {
+            rng: ChaCha8Rng::seed_from_u64(seed),
+        }
+    }
+
+    /// Get a snapshot of the state.
+    pub fn state(&self) -> TensorRngState {
+        TensorRngState {
+            seed: self.rng.get_seed(),
+            stream: self.rng.get_stream(),
+            word_pos: self.rng.get_word_pos(),
+        }
+    }
+
+    /// Restore the state from a snapshot.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `state` - Snapshot taken by `state`.
+    pub fn set_state(&mut self, state: &TensorRngState) {
+        let mut rng = ChaCha8Rng::from_seed(state.seed);
+        rng.set_stream(state.stream);
+        rng.set_word_pos(state.word_pos);
+        self.rng = rng;
+    }
+
+    /// Derive an independent child generator.
+    /// 
+    /// The seed of the child is drawn from this generator,
+    /// so the children are reproducible when this generator is seeded.
+    pub fn child(&mut self) -> Self {
+        Self {
+            rng: ChaCha8Rng::from_seed(self.rng.gen::<[u8; 32]>()),
         }
     }
 
@@ -39,6 +102,203 @@ impl TensorRng {
         }
         Tensor::new(data, shape)
     }
+
+    /// Generate a Tensor from the standard normal distribution.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `shape` - Shape of the Tensor.
+    pub fn standard_normal<T, U>(&mut self, shape: U) -> Tensor<T>
+    where
+        T: FromF64 + Clone,
+        U: AsRef<[usize]>
+    {
+        let size = shape.as_ref().iter().product::<usize>();
+        let data = (0..size)
+            .map(|_| Scaler::new(T::from_f64(self.next_standard_normal_f64())))
+            .collect::<Vec<_>>();
+        Tensor::new(data, shape)
+    }
+
+    /// Generate a Tensor from the normal distribution.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `mean` - Mean of the distribution.
+    /// * `std` - Standard deviation of the distribution.
+    /// * `shape` - Shape of the Tensor.
+    /// 
+    /// # Panics
+    /// 
+    /// * `Standard deviation must be non-negative` - If `std` is negative.
+    pub fn normal<T, U>(&mut self, mean: T, std: T, shape: U) -> Tensor<T>
+    where
+        T: FromF64 + Into<f64> + Clone,
+        U: AsRef<[usize]>
+    {
+        let (mean, std) = (mean.into(), std.into());
+        assert!(std >= 0.0, "Standard deviation must be non-negative");
+        let size = shape.as_ref().iter().product::<usize>();
+        let data = (0..size)
+            .map(|_| Scaler::new(T::from_f64(mean + std * self.next_standard_normal_f64())))
+            .collect::<Vec<_>>();
+        Tensor::new(data, shape)
+    }
+
+    /// Generate a Tensor from the uniform distribution on [low, high).
+    /// 
+    /// Integer types give integer ranges, for example class labels.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `low` - Lower bound, inclusive.
+    /// * `high` - Upper bound, exclusive.
+    /// * `shape` - Shape of the Tensor.
+    /// 
+    /// # Panics
+    /// 
+    /// * `Invalid range` - If `low` is not less than `high`.
+    pub fn uniform<T, U>(&mut self, low: T, high: T, shape: U) -> Tensor<T>
+    where
+        T: SampleUniform + PartialOrd + Clone,
+        U: AsRef<[usize]>
+    {
+        assert!(low < high, "Invalid range");
+        let size = shape.as_ref().iter().product::<usize>();
+        let data = (0..size)
+            .map(|_| Scaler::new(self.rng.gen_range(low.clone()..high.clone())))
+            .collect::<Vec<_>>();
+        Tensor::new(data, shape)
+    }
+
+    /// Generate a mask Tensor whose elements are 1 with probability p and 0 otherwise.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `p` - Probability of 1.
+    /// * `shape` - Shape of the Tensor.
+    /// 
+    /// # Panics
+    /// 
+    /// * `Probability must be in [0, 1]` - If `p` is out of range.
+    pub fn bernoulli<T, U>(&mut self, p: f64, shape: U) -> Tensor<T>
+    where
+        T: FromUsize + Clone,
+        U: AsRef<[usize]>
+    {
+        assert!((0.0..=1.0).contains(&p), "Probability must be in [0, 1]");
+        let size = shape.as_ref().iter().product::<usize>();
+        let data = (0..size)
+            .map(|_| Scaler::new(T::from_usize(self.rng.gen_bool(p) as usize)))
+            .collect::<Vec<_>>();
+        Tensor::new(data, shape)
+    }
+
+    /// Generate a random permutation of the indices 0..n.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `n` - Number of indices.
+    pub fn permutation(&mut self, n: usize) -> Vec<usize> {
+        let mut indices = (0..n).collect::<Vec<usize>>();
+        for i in (1..n).rev() {
+            let j = self.rng.gen_range(0..=i);
+            indices.swap(i, j);
+        }
+        indices
+    }
+
+    /// Shuffle the Tensor in place along the axis.
+    /// 
+    /// The subtensors along the axis are moved together,
+    /// for example `axis = 0` shuffles the rows of a matrix.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `tensor` - Tensor to shuffle.
+    /// * `axis` - Axis to shuffle along.
+    /// 
+    /// # Panics
+    /// 
+    /// * `Invalid axis` - If the axis is out of range.
+    pub fn shuffle<T: Clone>(&mut self, tensor: &mut Tensor<T>, axis: usize) {
+        assert!(axis < tensor.ndim(), "Invalid axis");
+        let shape = tensor.shape().clone();
+        let len = shape[axis];
+        let inner = shape[axis + 1..].iter().product::<usize>();
+        let outer = shape[..axis].iter().product::<usize>();
+        let permutation = self.permutation(len);
+        let source = tensor.data();
+        let mut data = Vec::with_capacity(source.len());
+        for o in 0..outer {
+            for &p in permutation.iter() {
+                let start = (o * len + p) * inner;
+                data.extend_from_slice(&source[start..start + inner]);
+            }
+        }
+        *tensor = Tensor::new(data, shape);
+    }
+
+    /// Sample subtensors along the first axis.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `tensor` - Tensor to sample from.
+    /// * `size` - Number of samples.
+    /// * `replace` - Whether to sample with replacement.
+    /// 
+    /// # Returns
+    /// 
+    /// * Tensor of the shape `[size, tensor.shape()[1..]]`.
+    /// 
+    /// # Panics
+    /// 
+    /// * `Scalar tensor cannot be sampled` - If the tensor is a scalar.
+    /// * `Sample size is larger than the population` - If `replace` is false and `size` is larger than the first axis.
+    pub fn choice<T: Clone>(&mut self, tensor: &Tensor<T>, size: usize, replace: bool) -> Tensor<T> {
+        assert!(!tensor.is_scalar(), "Scalar tensor cannot be sampled");
+        let len = tensor.shape()[0];
+        let indices = if replace {
+            assert!(len > 0 || size == 0, "Sample size is larger than the population");
+            (0..size).map(|_| self.rng.gen_range(0..len)).collect::<Vec<usize>>()
+        } else {
+            assert!(size <= len, "Sample size is larger than the population");
+            let mut indices = (0..len).collect::<Vec<usize>>();
+            for i in 0..size {
+                let j = self.rng.gen_range(i..len);
+                indices.swap(i, j);
+            }
+            indices.truncate(size);
+            indices
+        };
+        let inner = tensor.shape()[1..].iter().product::<usize>();
+        let source = tensor.data();
+        let mut data = Vec::with_capacity(size * inner);
+        for i in indices {
+            data.extend_from_slice(&source[i * inner..(i + 1) * inner]);
+        }
+        let mut shape = tensor.shape().clone();
+        shape[0] = size;
+        Tensor::new(data, shape)
+    }
+
+    /// Draw a sample from the uniform distribution on [0, 1).
+    pub(crate) fn next_uniform_f64(&mut self) -> f64 {
+        self.rng.gen::<f64>()
+    }
+
+    /// Draw a sample from the standard normal distribution by the Box-Muller transform.
+    pub(crate) fn next_standard_normal_f64(&mut self) -> f64 {
+        let u1 = 1.0 - self.rng.gen::<f64>();
+        let u2 = self.rng.gen::<f64>();
+        (-2.0 * u1.ln()).sqrt() * (2.0 * std::f64::consts::PI * u2).cos()
+    }
+}
+
+impl Default for TensorRng {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 #[cfg(test)]
@@ -52,4 +312,154 @@ mod tests {
         assert_eq!(x.data_type(), "f32");
         assert_eq!(x.shape(), &[2, 3]);
     }
+
+    #[test]
+    fn seed_from_u64_normal() {
+        let mut rng0 = TensorRng::seed_from_u64(42);
+        let mut rng1 = TensorRng::seed_from_u64(42);
+        let mut rng2 = TensorRng::seed_from_u64(43);
+        let x0 = rng0.gen::<f64, _>([2, 3]);
+        assert_eq!(x0, rng1.gen::<f64, _>([2, 3]));
+        assert_ne!(x0, rng2.gen::<f64, _>([2, 3]));
+    }
+
+    #[test]
+    fn state_normal() {
+        let mut rng = TensorRng::seed_from_u64(0);
+        rng.gen::<f64, _>([5]);
+        let state = rng.state();
+        let x0 = rng.gen::<f32, _>([2, 3]);
+        rng.gen::<f64, _>([7]);
+
+        rng.set_state(&state);
+        assert_eq!(rng.state(), state);
+        assert_eq!(rng.gen::<f32, _>([2, 3]), x0);
+
+        let mut other = TensorRng::new();
+        other.set_state(&state);
+        assert_eq!(other.gen::<f32, _>([2, 3]), x0);
+    }
+
+    #[test]
+    fn child_normal() {
+        let mut rng0 = TensorRng::seed_from_u64(0);
+        let mut rng1 = TensorRng::seed_from_u64(0);
+        let mut child0 = rng0.child();
+        let mut child1 = rng1.child();
+        let mut child2 = rng0.child();
+
+        let x = child0.gen::<f64, _>([4]);
+        assert_eq!(x, child1.gen::<f64, _>([4]));
+        assert_ne!(x, child2.gen::<f64, _>([4]));
+        assert_ne!(x, rng0.gen::<f64, _>([4]));
+    }
+
+    #[test]
+    fn standard_normal_normal() {
+        let mut rng = TensorRng::seed_from_u64(0);
+        let x = rng.standard_normal::<f32, _>([100, 100]);
+        assert_eq!(x.data_type(), "f32");
+        let n = x.size() as f32;
+        let mean = x.data().iter().map(|x| x.data()).sum::<f32>() / n;
+        assert!(mean.abs() < 0.05);
+    }
+
+    #[test]
+    fn normal_normal() {
+        let mut rng = TensorRng::seed_from_u64(0);
+        let x = rng.normal(3.0, 0.5, [100, 100]);
+        let n = x.size() as f64;
+        let mean = x.data().iter().map(|x| x.data()).sum::<f64>() / n;
+        let std = (x.data().iter().map(|x| (x.data() - mean).powi(2)).sum::<f64>() / n).sqrt();
+        assert!((mean - 3.0).abs() < 0.05);
+        assert!((std - 0.5).abs() < 0.05);
+    }
+
+    #[test]
+    fn uniform_normal() {
+        let mut rng = TensorRng::seed_from_u64(0);
+        let x = rng.uniform(-1.0f32, 2.0, [100]);
+        assert!(x.data().iter().all(|x| (-1.0..2.0).contains(x.data())));
+
+        let x = rng.uniform(3, 6, [1000]);
+        assert!(x.data().iter().all(|x| (3..6).contains(x.data())));
+        for i in 3..6 {
+            assert!(x.data().iter().any(|x| *x.data() == i));
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn uniform_error_range() {
+        let mut rng = TensorRng::seed_from_u64(0);
+        rng.uniform(1.0, 1.0, [2]);
+    }
+
+    #[test]
+    fn bernoulli_normal() {
+        let mut rng = TensorRng::seed_from_u64(0);
+        let x = rng.bernoulli::<f64, _>(0.3, [100, 100]);
+        assert!(x.data().iter().all(|x| *x.data() == 0.0 || *x.data() == 1.0));
+        let mean = x.data().iter().map(|x| x.data()).sum::<f64>() / x.size() as f64;
+        assert!((mean - 0.3).abs() < 0.05);
+
+        let x = rng.bernoulli::<i32, _>(1.0, [3]);
+        assert_eq!(x, Tensor::new_from_num_vec(vec![1, 1, 1], vec![3]));
+    }
+
+    #[test]
+    fn permutation_normal() {
+        let mut rng = TensorRng::seed_from_u64(0);
+        let mut p = rng.permutation(10);
+        assert_ne!(p, (0..10).collect::<Vec<usize>>());
+        p.sort();
+        assert_eq!(p, (0..10).collect::<Vec<usize>>());
+        assert_eq!(rng.permutation(0), Vec::<usize>::new());
+    }
+
+    #[test]
+    fn shuffle_normal() {
+        let mut rng = TensorRng::seed_from_u64(0);
+        let mut x = Tensor::<f64>::arrange([4, 3]);
+        rng.shuffle(&mut x, 0);
+        assert_eq!(x.shape(), &[4, 3]);
+        let mut rows = (0..4)
+            .map(|i| (0..3).map(|j| *x.at(&[i, j]).data()).collect::<Vec<f64>>())
+            .collect::<Vec<_>>();
+        for row in rows.iter() {
+            assert_eq!(row[1] - row[0], 1.0);
+            assert_eq!(row[2] - row[1], 1.0);
+        }
+        rows.sort_by(|a, b| a[0].partial_cmp(&b[0]).unwrap());
+        assert_eq!(rows[3], vec![9.0, 10.0, 11.0]);
+
+        let mut x = Tensor::<f64>::arrange([2, 5]);
+        rng.shuffle(&mut x, 1);
+        for i in 0..2 {
+            assert_eq!(*x.at(&[1, i]).data() - *x.at(&[0, i]).data(), 5.0);
+        }
+    }
+
+    #[test]
+    fn choice_normal() {
+        let mut rng = TensorRng::seed_from_u64(0);
+        let x = Tensor::<f64>::arrange([5, 2]);
+
+        let y = rng.choice(&x, 5, false);
+        assert_eq!(y.shape(), &[5, 2]);
+        let mut firsts = (0..5).map(|i| *y.at(&[i, 0]).data() as usize).collect::<Vec<usize>>();
+        firsts.sort();
+        assert_eq!(firsts, vec![0, 2, 4, 6, 8]);
+
+        let y = rng.choice(&x, 20, true);
+        assert_eq!(y.shape(), &[20, 2]);
+        assert!((0..20).all(|i| *y.at(&[i, 1]).data() - *y.at(&[i, 0]).data() == 1.0));
+    }
+
+    #[test]
+    #[should_panic]
+    fn choice_error_size() {
+        let mut rng = TensorRng::seed_from_u64(0);
+        rng.choice(&Tensor::<f64>::arrange([3]), 4, false);
+    }
 }
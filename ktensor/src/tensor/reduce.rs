@@ -0,0 +1,459 @@
+use super::{Tensor, Scaler};
+use crate::num::{FromUsize, Sqrt};
+use crate::error::TensorError;
+
+impl<T> Tensor<T>
+{
+    /// Check if the axis is reduced
+    /// 
+    /// An empty `axis` reduces all the axes, as in `sum`.
+    fn is_reduced_axis(axis: &[usize], i: usize) -> bool {
+        axis.is_empty() || axis.contains(&i)
+    }
+
+    /// Make the shape of a reduction
+    fn make_reduce_shape(&self, axis: &[usize], keepdims: bool) -> Vec<usize> {
+        (0..self.ndim())
+            .filter_map(|i| match Self::is_reduced_axis(axis, i) {
+                true if keepdims => Some(1),
+                true => None,
+                false => Some(self.shape[i]),
+            })
+            .collect()
+    }
+
+    /// Get the number of elements reduced into each element of a reduction
+    fn reduce_count(&self, axis: &[usize]) -> usize {
+        (0..self.ndim())
+            .filter(|&i| Self::is_reduced_axis(axis, i))
+            .map(|i| self.shape[i])
+            .product()
+    }
+
+    /// Reduce the values along the given axis
+    /// 
+    /// Each accumulator starts from `init` and is updated by `f`
+    /// with every element of its axes, in row-major order,
+    /// together with the indexes of the element.
+    /// 
+    /// # Returns
+    /// 
+    /// The accumulators in row-major order and the shape of the result
+    /// 
+    /// # Errors
+    /// 
+    /// * `InvalidAxis` - If an axis is out of range
+    /// * `RepeatedAxis` - If an axis appears twice
+    fn reduce<A, F>(&self, axis: &[usize], keepdims: bool, init: A, mut f: F) -> Result<(Vec<A>, Vec<usize>), TensorError>
+    where
+        A: Clone,
+        F: FnMut(&mut A, &Scaler<T>, &[usize]),
+    {
+        self.check_reduce_axis(axis)?;
+        let kept_shape = self.make_reduce_shape(axis, true);
+        let strides = Self::contiguous_strides(&kept_shape).into_iter().enumerate()
+            .map(|(i, stride)| if Self::is_reduced_axis(axis, i) { 0 } else { stride })
+            .collect::<Vec<usize>>();
+        let mut accumulators = vec![init; kept_shape.iter().product()];
+        let mut indexes = vec![0; self.ndim()];
+        for value in self.iter() {
+            let index = indexes.iter().zip(strides.iter()).map(|(i, s)| i * s).sum::<usize>();
+            f(&mut accumulators[index], value, &indexes);
+            for j in (0..self.ndim()).rev() {
+                indexes[j] += 1;
+                if indexes[j] < self.shape[j] {
+                    break;
+                }
+                indexes[j] = 0;
+            }
+        }
+        Ok((accumulators, self.make_reduce_shape(axis, keepdims)))
+    }
+}
+
+impl<T> Tensor<T>
+where
+    T: Copy + PartialOrd
+{
+    /// Reduce the values to the one that `select` prefers
+    fn reduce_select<F: Fn(&Scaler<T>, &Scaler<T>) -> bool>(&self, axis: &[usize], keepdims: bool, select: F) -> Result<Self, TensorError> {
+        let (data, shape) = self.reduce(axis, keepdims, None, |acc: &mut Option<Scaler<T>>, x, _| {
+            if acc.is_none_or(|y| select(x, &y)) {
+                *acc = Some(*x);
+            }
+        })?;
+        let data = data.into_iter()
+            .map(|x| x.ok_or_else(|| TensorError::InvalidShape {
+                shape: self.shape.clone(), reason: "cannot reduce an empty axis" }))
+            .collect::<Result<_, _>>()?;
+        Ok(Self::from_vec(data, shape))
+    }
+
+    /// Get the position of the value that `select` prefers along the axis
+    /// 
+    /// If `axis` is None, the position is in the flattened Tensor.
+    fn arg_select<F: Fn(&Scaler<T>, &Scaler<T>) -> bool>(&self, axis: Option<usize>, keepdims: bool, select: F) -> Tensor<usize> {
+        if let Some(axis) = axis {
+            if axis >= self.ndim() {
+                panic!("{}", TensorError::InvalidAxis { axis, ndim: self.ndim() });
+            }
+        }
+        let strides = Self::contiguous_strides(&self.shape);
+        let axes = axis.map_or(Vec::new(), |axis| vec![axis]);
+        let (data, shape) = self.reduce(&axes, keepdims, None, |acc: &mut Option<(usize, Scaler<T>)>, x, indexes| {
+            if acc.is_none_or(|(_, y)| select(x, &y)) {
+                let position = match axis {
+                    Some(axis) => indexes[axis],
+                    None => indexes.iter().zip(strides.iter()).map(|(i, s)| i * s).sum(),
+                };
+                *acc = Some((position, *x));
+            }
+        }).unwrap_or_else(|e| panic!("{}", e));
+        let data = data.into_iter()
+            .map(|x| x.map(|(position, _)| Scaler::new(position)).unwrap_or_else(|| panic!("{}", TensorError::InvalidShape {
+                shape: self.shape.clone(), reason: "cannot reduce an empty axis" })))
+            .collect();
+        Tensor::from_vec(data, shape)
+    }
+
+    /// Get the maximum values along the given axis
+    /// 
+    /// # Arguments
+    /// 
+    /// * `axis` - Axes to reduce. If empty, all the axes are reduced
+    /// * `keepdims` - Keep the reduced axes with the size 1
+    /// 
+    /// # Panics
+    /// 
+    /// Panics if an axis is out of range or appears twice, or a reduced axis has the size 0.
+    pub fn max<U: AsRef<[usize]>>(&self, axis: U, keepdims: bool) -> Self {
+        self.try_max(axis, keepdims).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Get the maximum values along the given axis
+    /// 
+    /// # Arguments
+    /// 
+    /// * `axis` - Axes to reduce. If empty, all the axes are reduced
+    /// * `keepdims` - Keep the reduced axes with the size 1
+    /// 
+    /// # Errors
+    /// 
+    /// * `InvalidAxis` - If an axis is out of range
+    /// * `RepeatedAxis` - If an axis appears twice
+    /// * `InvalidShape` - If a reduced axis has the size 0
+    pub fn try_max<U: AsRef<[usize]>>(&self, axis: U, keepdims: bool) -> Result<Self, TensorError> {
+        self.reduce_select(axis.as_ref(), keepdims, |x, y| x > y)
+    }
+
+    /// Get the minimum values along the given axis
+    /// 
+    /// # Arguments
+    /// 
+    /// * `axis` - Axes to reduce. If empty, all the axes are reduced
+    /// * `keepdims` - Keep the reduced axes with the size 1
+    /// 
+    /// # Panics
+    /// 
+    /// Panics if an axis is out of range or appears twice, or a reduced axis has the size 0.
+    pub fn min<U: AsRef<[usize]>>(&self, axis: U, keepdims: bool) -> Self {
+        self.try_min(axis, keepdims).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Get the minimum values along the given axis
+    /// 
+    /// # Arguments
+    /// 
+    /// * `axis` - Axes to reduce. If empty, all the axes are reduced
+    /// * `keepdims` - Keep the reduced axes with the size 1
+    /// 
+    /// # Errors
+    /// 
+    /// * `InvalidAxis` - If an axis is out of range
+    /// * `RepeatedAxis` - If an axis appears twice
+    /// * `InvalidShape` - If a reduced axis has the size 0
+    pub fn try_min<U: AsRef<[usize]>>(&self, axis: U, keepdims: bool) -> Result<Self, TensorError> {
+        self.reduce_select(axis.as_ref(), keepdims, |x, y| x < y)
+    }
+
+    /// Get the indexes of the maximum values along the axis
+    /// 
+    /// The first index is taken when the maximum appears more than once.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `axis` - Axis to reduce. If None, the index is in the flattened Tensor
+    /// * `keepdims` - Keep the reduced axes with the size 1
+    /// 
+    /// # Panics
+    /// 
+    /// Panics if the axis is out of range or a reduced axis has the size 0.
+    pub fn argmax(&self, axis: Option<usize>, keepdims: bool) -> Tensor<usize> {
+        self.arg_select(axis, keepdims, |x, y| x > y)
+    }
+
+    /// Get the indexes of the minimum values along the axis
+    /// 
+    /// The first index is taken when the minimum appears more than once.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `axis` - Axis to reduce. If None, the index is in the flattened Tensor
+    /// * `keepdims` - Keep the reduced axes with the size 1
+    /// 
+    /// # Panics
+    /// 
+    /// Panics if the axis is out of range or a reduced axis has the size 0.
+    pub fn argmin(&self, axis: Option<usize>, keepdims: bool) -> Tensor<usize> {
+        self.arg_select(axis, keepdims, |x, y| x < y)
+    }
+}
+
+impl<T> Tensor<T>
+where
+    T: std::ops::Mul<Output = T> + Copy + FromUsize
+{
+    /// Multiply the values along the given axis
+    /// 
+    /// # Arguments
+    /// 
+    /// * `axis` - Axes to reduce. If empty, all the axes are reduced
+    /// * `keepdims` - Keep the reduced axes with the size 1
+    /// 
+    /// # Panics
+    /// 
+    /// Panics if an axis is out of range or appears twice.
+    pub fn prod<U: AsRef<[usize]>>(&self, axis: U, keepdims: bool) -> Self {
+        let one = Scaler::new(T::from_usize(1));
+        let (data, shape) = self.reduce(axis.as_ref(), keepdims, one, |acc, x, _| *acc = *acc * *x)
+            .unwrap_or_else(|e| panic!("{}", e));
+        Self::from_vec(data, shape)
+    }
+}
+
+impl<T> Tensor<T>
+where
+    T: std::ops::Add<Output = T> + std::ops::Sub<Output = T> + std::ops::Mul<Output = T>
+        + std::ops::Div<Output = T> + Copy + Default + std::ops::AddAssign + FromUsize
+{
+    /// Get the mean values along the given axis
+    /// 
+    /// # Arguments
+    /// 
+    /// * `axis` - Axes to reduce. If empty, all the axes are reduced
+    /// * `keepdims` - Keep the reduced axes with the size 1
+    /// 
+    /// # Panics
+    /// 
+    /// Panics if an axis is out of range or appears twice.
+    pub fn mean<U: AsRef<[usize]>>(&self, axis: U, keepdims: bool) -> Self {
+        self.try_mean(axis, keepdims).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Get the mean values along the given axis
+    /// 
+    /// # Arguments
+    /// 
+    /// * `axis` - Axes to reduce. If empty, all the axes are reduced
+    /// * `keepdims` - Keep the reduced axes with the size 1
+    /// 
+    /// # Errors
+    /// 
+    /// * `InvalidAxis` - If an axis is out of range
+    /// * `RepeatedAxis` - If an axis appears twice
+    pub fn try_mean<U: AsRef<[usize]>>(&self, axis: U, keepdims: bool) -> Result<Self, TensorError> {
+        let sum = self.try_sum(axis.as_ref(), keepdims)?;
+        let count = self.reduce_count(axis.as_ref());
+        Ok(sum.scalar_div(T::from_usize(count).into()))
+    }
+
+    /// Get the variance along the given axis
+    /// 
+    /// The sum of the squared deviations is divided by `N - ddof`,
+    /// where `N` is the number of the reduced elements.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `axis` - Axes to reduce. If empty, all the axes are reduced
+    /// * `keepdims` - Keep the reduced axes with the size 1
+    /// * `ddof` - Delta degrees of freedom
+    /// 
+    /// # Panics
+    /// 
+    /// Panics if an axis is out of range or appears twice,
+    /// or `ddof` is not less than the number of the reduced elements.
+    pub fn var<U: AsRef<[usize]>>(&self, axis: U, keepdims: bool, ddof: usize) -> Self {
+        self.try_var(axis, keepdims, ddof).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Get the variance along the given axis
+    /// 
+    /// # Arguments
+    /// 
+    /// * `axis` - Axes to reduce. If empty, all the axes are reduced
+    /// * `keepdims` - Keep the reduced axes with the size 1
+    /// * `ddof` - Delta degrees of freedom
+    /// 
+    /// # Errors
+    /// 
+    /// * `InvalidAxis` - If an axis is out of range
+    /// * `RepeatedAxis` - If an axis appears twice
+    /// * `InvalidShape` - If `ddof` is not less than the number of the reduced elements
+    pub fn try_var<U: AsRef<[usize]>>(&self, axis: U, keepdims: bool, ddof: usize) -> Result<Self, TensorError> {
+        let axis = axis.as_ref();
+        let diff = self - &self.try_mean(axis, true)?;
+        let count = self.reduce_count(axis).checked_sub(ddof).filter(|&x| x != 0)
+            .ok_or_else(|| TensorError::InvalidShape { shape: self.shape.clone(), reason: "ddof is too big" })?;
+        Ok((&diff * &diff).sum(axis, keepdims).scalar_div(T::from_usize(count).into()))
+    }
+}
+
+impl<T> Tensor<T>
+where
+    T: std::ops::Add<Output = T> + std::ops::Sub<Output = T> + std::ops::Mul<Output = T>
+        + std::ops::Div<Output = T> + Copy + Default + std::ops::AddAssign + FromUsize + Sqrt
+{
+    /// Get the standard deviation along the given axis
+    /// 
+    /// # Arguments
+    /// 
+    /// * `axis` - Axes to reduce. If empty, all the axes are reduced
+    /// * `keepdims` - Keep the reduced axes with the size 1
+    /// * `ddof` - Delta degrees of freedom of the variance
+    /// 
+    /// # Panics
+    /// 
+    /// Panics if an axis is out of range or appears twice,
+    /// or `ddof` is not less than the number of the reduced elements.
+    pub fn std<U: AsRef<[usize]>>(&self, axis: U, keepdims: bool, ddof: usize) -> Self {
+        self.try_std(axis, keepdims, ddof).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Get the standard deviation along the given axis
+    /// 
+    /// # Arguments
+    /// 
+    /// * `axis` - Axes to reduce. If empty, all the axes are reduced
+    /// * `keepdims` - Keep the reduced axes with the size 1
+    /// * `ddof` - Delta degrees of freedom of the variance
+    /// 
+    /// # Errors
+    /// 
+    /// * `InvalidAxis` - If an axis is out of range
+    /// * `RepeatedAxis` - If an axis appears twice
+    /// * `InvalidShape` - If `ddof` is not less than the number of the reduced elements
+    pub fn try_std<U: AsRef<[usize]>>(&self, axis: U, keepdims: bool, ddof: usize) -> Result<Self, TensorError> {
+        Ok(self.try_var(axis, keepdims, ddof)?.map(|x| x.data().sqrt().into()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn max_normal() {
+        let x = Tensor::new_from_num_vec([1.0, 5.0, 3.0, 4.0, 2.0, 6.0], [2, 3]);
+        assert_eq!(x.max([0], false), Tensor::new_from_num_vec([4.0, 5.0, 6.0], [3]));
+        assert_eq!(x.max([1], true), Tensor::new_from_num_vec([5.0, 6.0], [2, 1]));
+        assert_eq!(x.max([], false), Tensor::new_from_num_vec([6.0], []));
+        assert_eq!(x.max([], true), Tensor::new_from_num_vec([6.0], [1, 1]));
+    }
+
+    #[test]
+    fn min_normal() {
+        let x = Tensor::new_from_num_vec([1.0, 5.0, 3.0, 4.0, 2.0, 6.0], [2, 3]);
+        assert_eq!(x.min([0], false), Tensor::new_from_num_vec([1.0, 2.0, 3.0], [3]));
+        assert_eq!(x.min([0, 1], false), Tensor::new_from_num_vec([1.0], []));
+    }
+
+    #[test]
+    fn max_view() {
+        let x = Tensor::<f64>::arrange([2, 3, 4]).permute(&[2, 0, 1]);
+        assert_eq!(x.max([1, 2], false), Tensor::new_from_num_vec([20.0, 21.0, 22.0, 23.0], [4]));
+    }
+
+    #[test]
+    #[should_panic]
+    fn max_error_empty() {
+        let x = Tensor::<f64>::new([], [2, 0]);
+        let _ = x.max([1], false);
+    }
+
+    #[test]
+    fn argmax_normal() {
+        let x = Tensor::new_from_num_vec([1.0, 5.0, 3.0, 4.0, 2.0, 6.0, 6.0, 0.0], [2, 4]);
+        assert_eq!(x.argmax(Some(1), false), Tensor::new_from_num_vec([1, 1], [2]));
+        assert_eq!(x.argmax(Some(0), true), Tensor::new_from_num_vec([1, 1, 1, 0], [1, 4]));
+        assert_eq!(x.argmax(None, false), Tensor::new_from_num_vec([5], []));
+    }
+
+    #[test]
+    fn argmin_normal() {
+        let x = Tensor::new_from_num_vec([1.0, 5.0, 3.0, 4.0, 2.0, 6.0], [2, 3]);
+        assert_eq!(x.argmin(Some(0), false), Tensor::new_from_num_vec([0, 1, 0], [3]));
+        assert_eq!(x.argmin(None, true), Tensor::new_from_num_vec([0], [1, 1]));
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid axis")]
+    fn argmax_error_invalid_axis() {
+        let x = Tensor::<f64>::arrange([2, 3]);
+        let _ = x.argmax(Some(2), false);
+    }
+
+    #[test]
+    fn prod_normal() {
+        let x = Tensor::<f64>::new_from_num_vec([1.0, 2.0, 3.0, 4.0, 5.0, 6.0], [2, 3]);
+        assert_eq!(x.prod([1], false), Tensor::new_from_num_vec([6.0, 120.0], [2]));
+        assert_eq!(x.prod([], false), Tensor::new_from_num_vec([720.0], []));
+    }
+
+    #[test]
+    fn mean_normal() {
+        let x = Tensor::<f64>::arrange([2, 3]);
+        assert_eq!(x.mean([1], false), Tensor::new_from_num_vec([1.0, 4.0], [2]));
+        assert_eq!(x.mean([0], true), Tensor::new_from_num_vec([1.5, 2.5, 3.5], [1, 3]));
+        assert_eq!(x.mean([], false), Tensor::new_from_num_vec([2.5], []));
+    }
+
+    #[test]
+    fn var_normal() {
+        let x = Tensor::<f64>::new_from_num_vec([1.0, 2.0, 3.0, 4.0, 6.0, 8.0], [2, 3]);
+        assert_eq!(x.var([1], false, 0), Tensor::new_from_num_vec([2.0 / 3.0, 8.0 / 3.0], [2]));
+        assert_eq!(x.var([1], true, 1), Tensor::new_from_num_vec([1.0, 4.0], [2, 1]));
+    }
+
+    #[test]
+    #[should_panic(expected = "ddof is too big")]
+    fn var_error_ddof() {
+        let x = Tensor::<f64>::arrange([2, 3]);
+        let _ = x.var([1], false, 3);
+    }
+
+    #[test]
+    fn try_reduce_error_axis() {
+        let x = Tensor::<f64>::arrange([2, 3]);
+        assert_eq!(x.try_max([7], false), Err(TensorError::InvalidAxis { axis: 7, ndim: 2 }));
+        assert_eq!(x.try_min([0, 0], false), Err(TensorError::RepeatedAxis { axis: 0 }));
+        assert_eq!(x.try_mean([5], false), Err(TensorError::InvalidAxis { axis: 5, ndim: 2 }));
+        assert_eq!(x.try_var([2], false, 0), Err(TensorError::InvalidAxis { axis: 2, ndim: 2 }));
+        assert!(matches!(x.try_var([1], false, 3), Err(TensorError::InvalidShape { .. })));
+        assert_eq!(x.try_std([1, 1], false, 0), Err(TensorError::RepeatedAxis { axis: 1 }));
+        assert!(matches!(x.try_std([1], false, 3), Err(TensorError::InvalidShape { .. })));
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid axis")]
+    fn mean_error_invalid_axis() {
+        let x = Tensor::<f64>::arrange([2, 3]);
+        let _ = x.mean([2], false);
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid axis")]
+    fn std_error_invalid_axis() {
+        let x = Tensor::<f64>::arrange([2, 3]);
+        let _ = x.std([2], false, 0);
+    }
+}
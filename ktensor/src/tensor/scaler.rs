@@ -231,6 +231,35 @@ where
     }
 }
 
+/// Error function of the number
+/// 
+/// The Maclaurin series is used for a small number and the continued fraction
+/// of the complementary error function for a large one.
+fn erf(x: f64) -> f64 {
+    if x.is_nan() {
+        return x;
+    }
+    let a = x.abs();
+    let y = if a < 3.0 {
+        let mut term = a;
+        let mut sum = a;
+        let mut n = 0.0;
+        while term.abs() > 1e-17 * sum.abs() {
+            n += 1.0;
+            term *= -a * a / n;
+            sum += term / (2.0 * n + 1.0);
+        }
+        sum * 2.0 / std::f64::consts::PI.sqrt()
+    } else {
+        let mut fraction = a;
+        for k in (1..=60).rev() {
+            fraction = a + k as f64 / 2.0 / fraction;
+        }
+        1.0 - (-a * a).exp() / std::f64::consts::PI.sqrt() / fraction
+    };
+    y.copysign(x)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
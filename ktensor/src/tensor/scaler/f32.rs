@@ -15,6 +15,158 @@ impl Scaler<f32> {
     pub fn exp(&self) -> Self {
         Self { data: self.data.exp() }
     }
+
+    /// Returns sin of the number
+    pub fn sin(&self) -> Self {
+        Self { data: self.data.sin() }
+    }
+
+    /// Returns cos of the number
+    pub fn cos(&self) -> Self {
+        Self { data: self.data.cos() }
+    }
+
+    /// Returns tanh of the number
+    pub fn tanh(&self) -> Self {
+        Self { data: self.data.tanh() }
+    }
+
+    /// Returns natural logarithm of the number
+    pub fn ln(&self) -> Self {
+        Self { data: self.data.ln() }
+    }
+
+    /// Returns base 2 logarithm of the number
+    pub fn log2(&self) -> Self {
+        Self { data: self.data.log2() }
+    }
+
+    /// Returns base 10 logarithm of the number
+    pub fn log10(&self) -> Self {
+        Self { data: self.data.log10() }
+    }
+
+    /// Returns square root of the number
+    pub fn sqrt(&self) -> Self {
+        Self { data: self.data.sqrt() }
+    }
+
+    /// Returns reciprocal of the square root of the number
+    pub fn rsqrt(&self) -> Self {
+        Self { data: 1.0 / self.data.sqrt() }
+    }
+
+    /// Returns absolute value of the number
+    pub fn abs(&self) -> Self {
+        Self { data: self.data.abs() }
+    }
+
+    /// Returns sign of the number
+    /// 
+    /// The sign is 1 for a positive number, -1 for a negative number and the number itself for zero and NaN.
+    pub fn sign(&self) -> Self {
+        let data = if self.data > 0.0 {
+            1.0
+        } else if self.data < 0.0 {
+            -1.0
+        } else {
+            self.data
+        };
+        Self { data }
+    }
+
+    /// Returns largest integer less than or equal to the number
+    pub fn floor(&self) -> Self {
+        Self { data: self.data.floor() }
+    }
+
+    /// Returns smallest integer greater than or equal to the number
+    pub fn ceil(&self) -> Self {
+        Self { data: self.data.ceil() }
+    }
+
+    /// Returns nearest integer to the number, rounding half-way cases away from zero
+    pub fn round(&self) -> Self {
+        Self { data: self.data.round() }
+    }
+
+    /// Returns tan of the number
+    pub fn tan(&self) -> Self {
+        Self { data: self.data.tan() }
+    }
+
+    /// Returns arcsine of the number
+    pub fn asin(&self) -> Self {
+        Self { data: self.data.asin() }
+    }
+
+    /// Returns arccosine of the number
+    pub fn acos(&self) -> Self {
+        Self { data: self.data.acos() }
+    }
+
+    /// Returns arctangent of the number
+    pub fn atan(&self) -> Self {
+        Self { data: self.data.atan() }
+    }
+
+    /// Returns sinh of the number
+    pub fn sinh(&self) -> Self {
+        Self { data: self.data.sinh() }
+    }
+
+    /// Returns cosh of the number
+    pub fn cosh(&self) -> Self {
+        Self { data: self.data.cosh() }
+    }
+
+    /// Returns logistic sigmoid of the number
+    /// 
+    /// The exponential is always taken of a non-positive number so that it does not overflow.
+    pub fn sigmoid(&self) -> Self {
+        let data = if self.data >= 0.0 {
+            1.0 / (1.0 + (-self.data).exp())
+        } else {
+            let e = self.data.exp();
+            e / (1.0 + e)
+        };
+        Self { data }
+    }
+
+    /// Returns error function of the number
+    pub fn erf(&self) -> Self {
+        Self { data: super::erf(self.data as f64) as f32 }
+    }
+
+    /// Returns the number limited to the range from `min` to `max`
+    /// 
+    /// # Arguments
+    /// 
+    /// * `min` - Lower bound
+    /// * `max` - Upper bound
+    /// 
+    /// # Panics
+    /// 
+    /// Panics if `min` is greater than `max` or either bound is NaN.
+    pub fn clip(&self, min: f32, max: f32) -> Self {
+        Self { data: self.data.clamp(min, max) }
+    }
+
+    /// Returns the maximum of the two numbers
+    /// 
+    /// If either number is NaN, NaN is returned.
+    pub fn maximum(&self, other: &Self) -> Self {
+        let data = if self.data.is_nan() || self.data > other.data { self.data } else { other.data };
+        Self { data }
+    }
+
+    /// Returns the minimum of the two numbers
+    /// 
+    /// If either number is NaN, NaN is returned.
+    pub fn minimum(&self, other: &Self) -> Self {
+        let data = if self.data.is_nan() || self.data < other.data { self.data } else { other.data };
+        Self { data }
+    }
 }
 
 #[cfg(test)]
@@ -38,4 +190,159 @@ mod tests {
         let x = Scaler::<f32>::new(2.0);
         assert_eq!(x.exp(), Scaler::<f32>::new((2.0 as f32).exp()));
     }
+
+    #[test]
+    fn sin_normal() {
+        let x = Scaler::<f32>::new(0.5);
+        assert_eq!(x.sin(), Scaler::<f32>::new((0.5 as f32).sin()));
+    }
+
+    #[test]
+    fn cos_normal() {
+        let x = Scaler::<f32>::new(0.5);
+        assert_eq!(x.cos(), Scaler::<f32>::new((0.5 as f32).cos()));
+    }
+
+    #[test]
+    fn tanh_normal() {
+        let x = Scaler::<f32>::new(0.5);
+        assert_eq!(x.tanh(), Scaler::<f32>::new((0.5 as f32).tanh()));
+    }
+
+    #[test]
+    fn ln_normal() {
+        let x = Scaler::<f32>::new(2.0);
+        assert_eq!(x.ln(), Scaler::<f32>::new((2.0 as f32).ln()));
+    }
+
+    #[test]
+    fn log2_normal() {
+        let x = Scaler::<f32>::new(2.0);
+        assert_eq!(x.log2(), Scaler::<f32>::new((2.0 as f32).log2()));
+    }
+
+    #[test]
+    fn log10_normal() {
+        let x = Scaler::<f32>::new(2.0);
+        assert_eq!(x.log10(), Scaler::<f32>::new((2.0 as f32).log10()));
+    }
+
+    #[test]
+    fn sqrt_normal() {
+        let x = Scaler::<f32>::new(2.0);
+        assert_eq!(x.sqrt(), Scaler::<f32>::new((2.0 as f32).sqrt()));
+    }
+
+    #[test]
+    fn abs_normal() {
+        let x = Scaler::<f32>::new(0.5);
+        assert_eq!(x.abs(), Scaler::<f32>::new((0.5 as f32).abs()));
+    }
+
+    #[test]
+    fn floor_normal() {
+        let x = Scaler::<f32>::new(0.5);
+        assert_eq!(x.floor(), Scaler::<f32>::new((0.5 as f32).floor()));
+    }
+
+    #[test]
+    fn ceil_normal() {
+        let x = Scaler::<f32>::new(0.5);
+        assert_eq!(x.ceil(), Scaler::<f32>::new((0.5 as f32).ceil()));
+    }
+
+    #[test]
+    fn round_normal() {
+        let x = Scaler::<f32>::new(0.5);
+        assert_eq!(x.round(), Scaler::<f32>::new((0.5 as f32).round()));
+    }
+
+    #[test]
+    fn tan_normal() {
+        let x = Scaler::<f32>::new(0.5);
+        assert_eq!(x.tan(), Scaler::<f32>::new((0.5 as f32).tan()));
+    }
+
+    #[test]
+    fn asin_normal() {
+        let x = Scaler::<f32>::new(0.5);
+        assert_eq!(x.asin(), Scaler::<f32>::new((0.5 as f32).asin()));
+    }
+
+    #[test]
+    fn acos_normal() {
+        let x = Scaler::<f32>::new(0.5);
+        assert_eq!(x.acos(), Scaler::<f32>::new((0.5 as f32).acos()));
+    }
+
+    #[test]
+    fn atan_normal() {
+        let x = Scaler::<f32>::new(0.5);
+        assert_eq!(x.atan(), Scaler::<f32>::new((0.5 as f32).atan()));
+    }
+
+    #[test]
+    fn sinh_normal() {
+        let x = Scaler::<f32>::new(0.5);
+        assert_eq!(x.sinh(), Scaler::<f32>::new((0.5 as f32).sinh()));
+    }
+
+    #[test]
+    fn cosh_normal() {
+        let x = Scaler::<f32>::new(0.5);
+        assert_eq!(x.cosh(), Scaler::<f32>::new((0.5 as f32).cosh()));
+    }
+
+    #[test]
+    fn rsqrt_normal() {
+        let x = Scaler::<f32>::new(4.0);
+        assert_eq!(x.rsqrt(), Scaler::<f32>::new(0.5));
+    }
+
+    #[test]
+    fn sign_normal() {
+        assert_eq!(Scaler::<f32>::new(2.5).sign(), Scaler::<f32>::new(1.0));
+        assert_eq!(Scaler::<f32>::new(-0.5).sign(), Scaler::<f32>::new(-1.0));
+        assert_eq!(Scaler::<f32>::new(0.0).sign(), Scaler::<f32>::new(0.0));
+        assert!(Scaler::<f32>::new(f32::NAN).sign().data().is_nan());
+    }
+
+    #[test]
+    fn sigmoid_normal() {
+        assert_eq!(Scaler::<f32>::new(0.0).sigmoid(), Scaler::<f32>::new(0.5));
+        assert_eq!(Scaler::<f32>::new(-1000.0).sigmoid(), Scaler::<f32>::new(0.0));
+        assert_eq!(Scaler::<f32>::new(1000.0).sigmoid(), Scaler::<f32>::new(1.0));
+    }
+
+    #[test]
+    fn erf_normal() {
+        assert_eq!(Scaler::<f32>::new(0.0).erf(), Scaler::<f32>::new(0.0));
+        assert!((Scaler::<f32>::new(1.0).erf().data() - 0.8427008).abs() < 1e-6);
+        assert!((Scaler::<f32>::new(-0.5).erf().data() + 0.5204999).abs() < 1e-6);
+    }
+
+    #[test]
+    fn clip_normal() {
+        assert_eq!(Scaler::<f32>::new(2.0).clip(-1.0, 1.0), Scaler::<f32>::new(1.0));
+        assert_eq!(Scaler::<f32>::new(-2.0).clip(-1.0, 1.0), Scaler::<f32>::new(-1.0));
+        assert_eq!(Scaler::<f32>::new(0.5).clip(-1.0, 1.0), Scaler::<f32>::new(0.5));
+    }
+
+    #[test]
+    fn maximum_minimum_normal() {
+        let x = Scaler::<f32>::new(2.0);
+        let y = Scaler::<f32>::new(3.0);
+        assert_eq!(x.maximum(&y), y);
+        assert_eq!(x.minimum(&y), x);
+    }
+
+    #[test]
+    fn maximum_minimum_nan() {
+        let x = Scaler::<f32>::new(f32::NAN);
+        let y = Scaler::<f32>::new(1.0);
+        assert!(x.maximum(&y).data().is_nan());
+        assert!(y.maximum(&x).data().is_nan());
+        assert!(x.minimum(&y).data().is_nan());
+        assert!(y.minimum(&x).data().is_nan());
+    }
 }
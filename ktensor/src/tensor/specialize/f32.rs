@@ -1,43 +1,189 @@
 use super::super::Tensor;
+use crate::error::TensorError;
 
 impl Tensor<f32> {
     /// Returns the result of performing an integer power over the value of each element
     pub fn powi(&self, n: i32) -> Self {
-        Self {
-            data: self.data
-                .iter()
-                .map(|x| x.powi(n))
-                .collect(),
-            shape: self.shape.clone(),
-        }
+        self.map(|x| x.powi(n))
     }
 
     /// Returns the result of performing a floating point power over the value of each element
     pub fn powf(&self, n: f32) -> Self {
-        Self {
-            data: self.data
-                .iter()
-                .map(|x| x.powf(n))
-                .collect(),
-            shape: self.shape.clone(),
-        }
+        self.map(|x| x.powf(n))
     }
 
     /// Returns the exponential of each element
     pub fn exp(&self) -> Self {
-        Self {
-            data: self.data
-                .iter()
-                .map(|x| x.exp())
-                .collect(),
-            shape: self.shape.clone(),
+        self.map(|x| x.exp())
+    }
+
+    /// Returns the sin of each element
+    pub fn sin(&self) -> Self {
+        self.map(|x| x.sin())
+    }
+
+    /// Returns the cos of each element
+    pub fn cos(&self) -> Self {
+        self.map(|x| x.cos())
+    }
+
+    /// Returns the tanh of each element
+    pub fn tanh(&self) -> Self {
+        self.map(|x| x.tanh())
+    }
+
+    /// Returns the natural logarithm of each element
+    pub fn ln(&self) -> Self {
+        self.map(|x| x.ln())
+    }
+
+    /// Returns the base 2 logarithm of each element
+    pub fn log2(&self) -> Self {
+        self.map(|x| x.log2())
+    }
+
+    /// Returns the base 10 logarithm of each element
+    pub fn log10(&self) -> Self {
+        self.map(|x| x.log10())
+    }
+
+    /// Returns the square root of each element
+    pub fn sqrt(&self) -> Self {
+        self.map(|x| x.sqrt())
+    }
+
+    /// Returns the reciprocal of the square root of each element
+    pub fn rsqrt(&self) -> Self {
+        self.map(|x| x.rsqrt())
+    }
+
+    /// Returns the absolute value of each element
+    pub fn abs(&self) -> Self {
+        self.map(|x| x.abs())
+    }
+
+    /// Returns the sign of each element
+    /// 
+    /// The sign is 1 for a positive element, -1 for a negative element and the element itself for zero and NaN.
+    pub fn sign(&self) -> Self {
+        self.map(|x| x.sign())
+    }
+
+    /// Returns the largest integer less than or equal to each element
+    pub fn floor(&self) -> Self {
+        self.map(|x| x.floor())
+    }
+
+    /// Returns the smallest integer greater than or equal to each element
+    pub fn ceil(&self) -> Self {
+        self.map(|x| x.ceil())
+    }
+
+    /// Returns the nearest integer to each element, rounding half-way cases away from zero
+    pub fn round(&self) -> Self {
+        self.map(|x| x.round())
+    }
+
+    /// Returns the tan of each element
+    pub fn tan(&self) -> Self {
+        self.map(|x| x.tan())
+    }
+
+    /// Returns the arcsine of each element
+    pub fn asin(&self) -> Self {
+        self.map(|x| x.asin())
+    }
+
+    /// Returns the arccosine of each element
+    pub fn acos(&self) -> Self {
+        self.map(|x| x.acos())
+    }
+
+    /// Returns the arctangent of each element
+    pub fn atan(&self) -> Self {
+        self.map(|x| x.atan())
+    }
+
+    /// Returns the sinh of each element
+    pub fn sinh(&self) -> Self {
+        self.map(|x| x.sinh())
+    }
+
+    /// Returns the cosh of each element
+    pub fn cosh(&self) -> Self {
+        self.map(|x| x.cosh())
+    }
+
+    /// Returns the logistic sigmoid of each element
+    pub fn sigmoid(&self) -> Self {
+        self.map(|x| x.sigmoid())
+    }
+
+    /// Returns the error function of each element
+    pub fn erf(&self) -> Self {
+        self.map(|x| x.erf())
+    }
+
+    /// Returns each element limited to the range from `min` to `max`
+    /// 
+    /// # Arguments
+    /// 
+    /// * `min` - Lower bound
+    /// * `max` - Upper bound
+    /// 
+    /// # Panics
+    /// 
+    /// Panics if `min` is greater than `max` or either bound is NaN.
+    pub fn clip(&self, min: f32, max: f32) -> Self {
+        self.try_clip(min, max).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Returns each element limited to the range from `min` to `max`
+    /// 
+    /// # Arguments
+    /// 
+    /// * `min` - Lower bound
+    /// * `max` - Upper bound
+    /// 
+    /// # Errors
+    /// 
+    /// * `InvalidRange` - If `min` is greater than `max` or either bound is NaN
+    pub fn try_clip(&self, min: f32, max: f32) -> Result<Self, TensorError> {
+        if min.is_nan() || max.is_nan() || min > max {
+            return Err(TensorError::InvalidRange { min: min as f64, max: max as f64 });
         }
+        Ok(self.map(|x| x.clip(min, max)))
+    }
+
+    /// Returns the elementwise maximum of the two tensors
+    /// 
+    /// The tensors are broadcast to the same shape by the rules of numpy.
+    /// If either element is NaN, the result is NaN.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `other` - Other tensor
+    pub fn maximum(&self, other: &Self) -> Self {
+        self.zip_map(other, |x, y| x.maximum(y))
+    }
+
+    /// Returns the elementwise minimum of the two tensors
+    /// 
+    /// The tensors are broadcast to the same shape by the rules of numpy.
+    /// If either element is NaN, the result is NaN.
+    /// 
+    /// # Arguments
+    /// 
+    /// * `other` - Other tensor
+    pub fn minimum(&self, other: &Self) -> Self {
+        self.zip_map(other, |x, y| x.minimum(y))
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use super::super::super::Scaler;
 
     #[test]
     fn powi_normal() {
@@ -57,4 +203,193 @@ mod tests {
         let x = Tensor::<f32>::new_from_num_vec(data.clone(), vec![3]);
         assert_eq!(x.exp(), Tensor::<f32>::new_from_num_vec(data.iter().map(|x| x.exp()), vec![3]));
     }
+
+    #[test]
+    fn std_normal() {
+        let x = Tensor::<f32>::new_from_num_vec(vec![1.0, 3.0, 2.0, 6.0], vec![2, 2]);
+        assert_eq!(x.std([1], false, 0), Tensor::<f32>::new_from_num_vec(vec![1.0, 2.0], vec![2]));
+    }
+
+    #[test]
+    fn sin_normal() {
+        let data = vec![-1.5, 0.0, 2.5];
+        let x = Tensor::<f32>::new_from_num_vec(data.clone(), vec![3]);
+        assert_eq!(x.sin(), Tensor::<f32>::new_from_num_vec(data.iter().map(|x| *Scaler::new(*x).sin().data()), vec![3]));
+    }
+
+    #[test]
+    fn cos_normal() {
+        let data = vec![-1.5, 0.0, 2.5];
+        let x = Tensor::<f32>::new_from_num_vec(data.clone(), vec![3]);
+        assert_eq!(x.cos(), Tensor::<f32>::new_from_num_vec(data.iter().map(|x| *Scaler::new(*x).cos().data()), vec![3]));
+    }
+
+    #[test]
+    fn tanh_normal() {
+        let data = vec![-1.5, 0.0, 2.5];
+        let x = Tensor::<f32>::new_from_num_vec(data.clone(), vec![3]);
+        assert_eq!(x.tanh(), Tensor::<f32>::new_from_num_vec(data.iter().map(|x| *Scaler::new(*x).tanh().data()), vec![3]));
+    }
+
+    #[test]
+    fn ln_normal() {
+        let data = vec![1.0, 2.0, 3.0];
+        let x = Tensor::<f32>::new_from_num_vec(data.clone(), vec![3]);
+        assert_eq!(x.ln(), Tensor::<f32>::new_from_num_vec(data.iter().map(|x| *Scaler::new(*x).ln().data()), vec![3]));
+    }
+
+    #[test]
+    fn log2_normal() {
+        let data = vec![1.0, 2.0, 3.0];
+        let x = Tensor::<f32>::new_from_num_vec(data.clone(), vec![3]);
+        assert_eq!(x.log2(), Tensor::<f32>::new_from_num_vec(data.iter().map(|x| *Scaler::new(*x).log2().data()), vec![3]));
+    }
+
+    #[test]
+    fn log10_normal() {
+        let data = vec![1.0, 2.0, 3.0];
+        let x = Tensor::<f32>::new_from_num_vec(data.clone(), vec![3]);
+        assert_eq!(x.log10(), Tensor::<f32>::new_from_num_vec(data.iter().map(|x| *Scaler::new(*x).log10().data()), vec![3]));
+    }
+
+    #[test]
+    fn sqrt_normal() {
+        let data = vec![1.0, 2.0, 3.0];
+        let x = Tensor::<f32>::new_from_num_vec(data.clone(), vec![3]);
+        assert_eq!(x.sqrt(), Tensor::<f32>::new_from_num_vec(data.iter().map(|x| *Scaler::new(*x).sqrt().data()), vec![3]));
+    }
+
+    #[test]
+    fn rsqrt_normal() {
+        let data = vec![1.0, 2.0, 3.0];
+        let x = Tensor::<f32>::new_from_num_vec(data.clone(), vec![3]);
+        assert_eq!(x.rsqrt(), Tensor::<f32>::new_from_num_vec(data.iter().map(|x| *Scaler::new(*x).rsqrt().data()), vec![3]));
+    }
+
+    #[test]
+    fn abs_normal() {
+        let data = vec![-1.5, 0.0, 2.5];
+        let x = Tensor::<f32>::new_from_num_vec(data.clone(), vec![3]);
+        assert_eq!(x.abs(), Tensor::<f32>::new_from_num_vec(data.iter().map(|x| *Scaler::new(*x).abs().data()), vec![3]));
+    }
+
+    #[test]
+    fn sign_normal() {
+        let data = vec![-1.5, 0.0, 2.5];
+        let x = Tensor::<f32>::new_from_num_vec(data.clone(), vec![3]);
+        assert_eq!(x.sign(), Tensor::<f32>::new_from_num_vec(data.iter().map(|x| *Scaler::new(*x).sign().data()), vec![3]));
+    }
+
+    #[test]
+    fn floor_normal() {
+        let data = vec![-1.5, 0.0, 2.5];
+        let x = Tensor::<f32>::new_from_num_vec(data.clone(), vec![3]);
+        assert_eq!(x.floor(), Tensor::<f32>::new_from_num_vec(data.iter().map(|x| *Scaler::new(*x).floor().data()), vec![3]));
+    }
+
+    #[test]
+    fn ceil_normal() {
+        let data = vec![-1.5, 0.0, 2.5];
+        let x = Tensor::<f32>::new_from_num_vec(data.clone(), vec![3]);
+        assert_eq!(x.ceil(), Tensor::<f32>::new_from_num_vec(data.iter().map(|x| *Scaler::new(*x).ceil().data()), vec![3]));
+    }
+
+    #[test]
+    fn round_normal() {
+        let data = vec![-1.5, 0.0, 2.5];
+        let x = Tensor::<f32>::new_from_num_vec(data.clone(), vec![3]);
+        assert_eq!(x.round(), Tensor::<f32>::new_from_num_vec(data.iter().map(|x| *Scaler::new(*x).round().data()), vec![3]));
+    }
+
+    #[test]
+    fn tan_normal() {
+        let data = vec![-1.5, 0.0, 2.5];
+        let x = Tensor::<f32>::new_from_num_vec(data.clone(), vec![3]);
+        assert_eq!(x.tan(), Tensor::<f32>::new_from_num_vec(data.iter().map(|x| *Scaler::new(*x).tan().data()), vec![3]));
+    }
+
+    #[test]
+    fn asin_normal() {
+        let data = vec![-0.5, 0.0, 0.5];
+        let x = Tensor::<f32>::new_from_num_vec(data.clone(), vec![3]);
+        assert_eq!(x.asin(), Tensor::<f32>::new_from_num_vec(data.iter().map(|x| *Scaler::new(*x).asin().data()), vec![3]));
+    }
+
+    #[test]
+    fn acos_normal() {
+        let data = vec![-0.5, 0.0, 0.5];
+        let x = Tensor::<f32>::new_from_num_vec(data.clone(), vec![3]);
+        assert_eq!(x.acos(), Tensor::<f32>::new_from_num_vec(data.iter().map(|x| *Scaler::new(*x).acos().data()), vec![3]));
+    }
+
+    #[test]
+    fn atan_normal() {
+        let data = vec![-1.5, 0.0, 2.5];
+        let x = Tensor::<f32>::new_from_num_vec(data.clone(), vec![3]);
+        assert_eq!(x.atan(), Tensor::<f32>::new_from_num_vec(data.iter().map(|x| *Scaler::new(*x).atan().data()), vec![3]));
+    }
+
+    #[test]
+    fn sinh_normal() {
+        let data = vec![-1.5, 0.0, 2.5];
+        let x = Tensor::<f32>::new_from_num_vec(data.clone(), vec![3]);
+        assert_eq!(x.sinh(), Tensor::<f32>::new_from_num_vec(data.iter().map(|x| *Scaler::new(*x).sinh().data()), vec![3]));
+    }
+
+    #[test]
+    fn cosh_normal() {
+        let data = vec![-1.5, 0.0, 2.5];
+        let x = Tensor::<f32>::new_from_num_vec(data.clone(), vec![3]);
+        assert_eq!(x.cosh(), Tensor::<f32>::new_from_num_vec(data.iter().map(|x| *Scaler::new(*x).cosh().data()), vec![3]));
+    }
+
+    #[test]
+    fn sigmoid_normal() {
+        let data = vec![-1.5, 0.0, 2.5];
+        let x = Tensor::<f32>::new_from_num_vec(data.clone(), vec![3]);
+        assert_eq!(x.sigmoid(), Tensor::<f32>::new_from_num_vec(data.iter().map(|x| *Scaler::new(*x).sigmoid().data()), vec![3]));
+    }
+
+    #[test]
+    fn erf_normal() {
+        let data = vec![-1.5, 0.0, 2.5];
+        let x = Tensor::<f32>::new_from_num_vec(data.clone(), vec![3]);
+        assert_eq!(x.erf(), Tensor::<f32>::new_from_num_vec(data.iter().map(|x| *Scaler::new(*x).erf().data()), vec![3]));
+    }
+
+    #[test]
+    fn clip_normal() {
+        let x = Tensor::<f32>::new_from_num_vec(vec![-2.0, 0.5, 2.0], vec![3]);
+        assert_eq!(x.clip(-1.0, 1.0), Tensor::<f32>::new_from_num_vec(vec![-1.0, 0.5, 1.0], vec![3]));
+    }
+
+    #[test]
+    fn try_clip_error_range() {
+        let x = Tensor::<f32>::new_from_num_vec(vec![-2.0, 0.5, 3.0], vec![3]);
+        assert_eq!(x.try_clip(1.0, -1.0), Err(TensorError::InvalidRange { min: 1.0, max: -1.0 }));
+        assert!(matches!(x.try_clip(f32::NAN, 1.0), Err(TensorError::InvalidRange { .. })));
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid range")]
+    fn clip_error_range() {
+        let x = Tensor::<f32>::new_from_num_vec(vec![-2.0, 0.5, 3.0], vec![3]);
+        let _ = x.clip(1.0, -1.0);
+    }
+
+    #[test]
+    fn maximum_minimum_normal() {
+        let x = Tensor::<f32>::new_from_num_vec(vec![1.0, 4.0, 2.0, 5.0], vec![2, 2]);
+        let y = Tensor::<f32>::new_from_num_vec(vec![3.0, 3.0], vec![2]);
+        assert_eq!(x.maximum(&y), Tensor::<f32>::new_from_num_vec(vec![3.0, 4.0, 3.0, 5.0], vec![2, 2]));
+        assert_eq!(x.minimum(&y), Tensor::<f32>::new_from_num_vec(vec![1.0, 3.0, 2.0, 3.0], vec![2, 2]));
+    }
+
+    #[test]
+    fn maximum_minimum_nan() {
+        let x = Tensor::<f32>::new_from_num_vec(vec![f32::NAN, 1.0], vec![2]);
+        let y = Tensor::<f32>::new_from_num_vec(vec![0.0, f32::NAN], vec![2]);
+        assert!(x.maximum(&y).data().iter().all(|x| x.data().is_nan()));
+        assert!(x.minimum(&y).data().iter().all(|x| x.data().is_nan()));
+    }
 }
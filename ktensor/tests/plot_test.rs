@@ -47,7 +47,7 @@ fn toy_dataset_plot() {
         Err(_) => {},
     }
 
-    let mut rng = TensorRng::new();
+    let mut rng = TensorRng::seed_from_u64(0);
 
     let x = rng.gen::<f64, _>(&[100, 1]);
     let y = (